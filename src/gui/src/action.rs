@@ -0,0 +1,193 @@
+use std::process::Command;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::protocol::{HelperRequest, ValidationError, TOOLS_DIR};
+
+// What an action runs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActionKind {
+    // Script relative to TOOLS_DIR, e.g. "ufw.sh" or "stig/grub.sh"
+    ToolScript(String),
+    // `hardn [options] <subcommand> [args]`; options are hardn's global flags
+    Hardn { options: Vec<String>, subcommand: String },
+    // Any other operation of the privileged helper
+    Privileged(HelperRequest),
+}
+
+// A GUI operation. Every button builds one of these and hands it to the dispatcher,
+// which decides on elevation from `needs_root` alone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HardnAction {
+    pub label: String,
+    pub kind: ActionKind,
+    pub args: Vec<String>,
+    pub needs_root: bool,
+    pub expected_duration: Duration,
+    pub destructive: bool,
+}
+
+impl HardnAction {
+    // Tool scripts reconfigure the system and always run as root
+    pub fn tool(label: &str, script: &str) -> Self {
+        HardnAction {
+            label: label.to_string(),
+            kind: ActionKind::ToolScript(script.to_string()),
+            args: Vec::new(),
+            needs_root: true,
+            expected_duration: Duration::from_secs(60),
+            destructive: false,
+        }
+    }
+
+    pub fn hardn(label: &str, subcommand: &str) -> Self {
+        HardnAction {
+            label: label.to_string(),
+            kind: ActionKind::Hardn {
+                options: Vec::new(),
+                subcommand: subcommand.to_string(),
+            },
+            args: Vec::new(),
+            needs_root: false,
+            expected_duration: Duration::from_secs(10),
+            destructive: false,
+        }
+    }
+
+    // Helper operations are root-only by definition
+    pub fn privileged(label: &str, request: HelperRequest) -> Self {
+        HardnAction {
+            label: label.to_string(),
+            kind: ActionKind::Privileged(request),
+            args: Vec::new(),
+            needs_root: true,
+            expected_duration: Duration::from_secs(10),
+            destructive: false,
+        }
+    }
+
+    pub fn option(mut self, option: &str) -> Self {
+        if let ActionKind::Hardn { options, .. } = &mut self.kind {
+            options.push(option.to_string());
+        }
+        self
+    }
+
+    pub fn arg(mut self, arg: &str) -> Self {
+        self.args.push(arg.to_string());
+        self
+    }
+
+    pub fn root(mut self) -> Self {
+        self.needs_root = true;
+        self
+    }
+
+    // Run a helper command directly as the user, e.g. a scan of their own files
+    pub fn unprivileged(mut self) -> Self {
+        self.needs_root = false;
+        self
+    }
+
+    pub fn expected_secs(mut self, secs: u64) -> Self {
+        self.expected_duration = Duration::from_secs(secs);
+        self
+    }
+
+    pub fn destructive(mut self) -> Self {
+        self.destructive = true;
+        self
+    }
+
+    pub fn subcommand(&self) -> Option<&str> {
+        match &self.kind {
+            ActionKind::Hardn { subcommand, .. } => Some(subcommand),
+            ActionKind::ToolScript(_) | ActionKind::Privileged(_) => None,
+        }
+    }
+
+    // Reads of the helper, e.g. the audit log; tool scripts and hardn subcommands may change the system
+    pub fn is_read_only(&self) -> bool {
+        matches!(&self.kind, ActionKind::Privileged(request) if request.is_read_only())
+    }
+
+    // Program followed by its arguments, exactly as executed
+    pub fn argv(&self) -> Vec<String> {
+        let mut argv = match &self.kind {
+            ActionKind::ToolScript(script) => vec!["/bin/bash".to_string(), format!("{}/{}", TOOLS_DIR, script)],
+            ActionKind::Hardn { options, subcommand } => {
+                let mut argv = vec!["hardn".to_string()];
+                argv.extend(options.iter().cloned());
+                argv.push(subcommand.clone());
+                argv
+            }
+            ActionKind::Privileged(request) => request.argv(),
+        };
+        argv.extend(self.args.iter().cloned());
+        argv
+    }
+
+    // Shell-quoted command line for display and logging
+    pub fn command_line(&self) -> String {
+        self.argv()
+            .iter()
+            .map(|arg| shell_quote(arg))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn to_command(&self) -> Command {
+        let argv = self.argv();
+        let mut cmd = Command::new(&argv[0]);
+        cmd.args(&argv[1..]);
+        cmd
+    }
+
+    pub fn helper_request(&self) -> Result<HelperRequest, ValidationError> {
+        let request = match &self.kind {
+            ActionKind::ToolScript(script) => {
+                if !self.args.is_empty() {
+                    return Err(ValidationError(format!("tool scripts take no arguments: {}", script)));
+                }
+                HelperRequest::RunTool { script: script.clone() }
+            }
+            ActionKind::Hardn { options, subcommand } => HelperRequest::Hardn {
+                options: options.clone(),
+                subcommand: subcommand.clone(),
+                args: self.args.clone(),
+            },
+            ActionKind::Privileged(request) => {
+                if !self.args.is_empty() {
+                    return Err(ValidationError(format!("{} takes no extra arguments", request.describe())));
+                }
+                request.clone()
+            }
+        };
+        request.validate()?;
+        Ok(request)
+    }
+
+    pub fn expected_duration_text(&self) -> String {
+        let secs = self.expected_duration.as_secs();
+        if secs >= 120 {
+            format!("about {} minutes", secs / 60)
+        } else if secs >= 60 {
+            "about a minute".to_string()
+        } else {
+            "a few seconds".to_string()
+        }
+    }
+}
+
+pub fn shell_quote(arg: &str) -> String {
+    let safe = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c));
+    if safe {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
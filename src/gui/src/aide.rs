@@ -0,0 +1,545 @@
+// AIDE check output, as saved by check_system_integrity in modules/audit.sh to
+// aide-check-<timestamp>.log. `aide --check` lists the added, removed and changed
+// entries, then the changed attributes of each entry:
+//
+//   Changed entries:
+//   f   ...    .C... : /etc/passwd
+//
+//   Detailed information about changes:
+//   File: /etc/passwd
+//     Size      : 1234                             | 1250
+//     SHA256    : 3Xa1z5Hq...                      | Ks8dW0eF...
+//
+// AIDE 0.16 and older separate old and new values with "," instead of "|" and
+// prefix the entries with "added:", "removed:" or "changed:". Long values wrap
+// onto continuation lines, indented up to the value column. When AIDE cannot check
+// at all, e.g. without a database, the log only has its error message.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+
+const LOG_PREFIX: &str = "aide-check-";
+const LOG_EXTENSION: &str = "log";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl ChangeKind {
+    pub fn badge(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "➕ Added",
+            ChangeKind::Removed => "➖ Removed",
+            ChangeKind::Changed => "✏️ Changed",
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "success",
+            ChangeKind::Removed => "error",
+            ChangeKind::Changed => "warning",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AttributeGroup {
+    Hash,
+    Permissions,
+    Owner,
+    Time,
+    Inode,
+    Size,
+    Other,
+}
+
+impl AttributeGroup {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AttributeGroup::Hash => "hash",
+            AttributeGroup::Permissions => "permissions",
+            AttributeGroup::Owner => "owner",
+            AttributeGroup::Time => "time",
+            AttributeGroup::Inode => "inode",
+            AttributeGroup::Size => "size",
+            AttributeGroup::Other => "other",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeChange {
+    pub name: String,
+    pub old: String,
+    pub new: String,
+}
+
+impl AttributeChange {
+    pub fn group(&self) -> AttributeGroup {
+        match self.name.to_ascii_lowercase().as_str() {
+            "md5" | "sha1" | "sha256" | "sha512" | "rmd160" | "tiger" | "crc32" | "haval" | "gost" | "whirlpool"
+            | "stribog256" | "stribog512" | "sha512_256" | "sha3_256" | "sha3_512" => AttributeGroup::Hash,
+            "perm" | "acl" | "xattrs" | "selinux" | "e2fsattrs" | "caps" | "capabilities" => AttributeGroup::Permissions,
+            "uid" | "gid" => AttributeGroup::Owner,
+            "mtime" | "ctime" | "atime" => AttributeGroup::Time,
+            "inode" | "linkcount" => AttributeGroup::Inode,
+            "size" | "bcount" => AttributeGroup::Size,
+            _ => AttributeGroup::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub kind: ChangeKind,
+    pub path: String,
+    // "File", "Directory", "Link", ... as AIDE names it, when known
+    pub entry_type: Option<String>,
+    // AIDE's one-line summary of the changed attributes, e.g. "f   ...    .C... "
+    pub flags: Option<String>,
+    pub attributes: Vec<AttributeChange>,
+}
+
+impl FileChange {
+    fn new(kind: ChangeKind, path: &str) -> Self {
+        FileChange {
+            kind,
+            path: path.to_string(),
+            entry_type: None,
+            flags: None,
+            attributes: Vec::new(),
+        }
+    }
+
+    pub fn directory(&self) -> &str {
+        match self.path.rfind('/') {
+            Some(0) | None => "/",
+            Some(end) => &self.path[..end],
+        }
+    }
+
+    // Kinds of attributes that changed, each once
+    pub fn groups(&self) -> Vec<AttributeGroup> {
+        let mut groups: Vec<AttributeGroup> = self.attributes.iter().map(AttributeChange::group).collect();
+        groups.sort();
+        groups.dedup();
+        groups
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Preamble,
+    Entries(ChangeKind),
+    Details,
+    Trailer,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AideReport {
+    pub path: Option<PathBuf>,
+    pub started: Option<String>,
+    pub version: Option<String>,
+    pub differences_found: bool,
+    pub total_entries: Option<u64>,
+    pub changes: Vec<FileChange>,
+    // What AIDE printed instead of a result
+    pub error: Option<String>,
+}
+
+impl AideReport {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let mut report = Self::parse(&content);
+        report.path = Some(path.to_path_buf());
+        Ok(report)
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let mut report = AideReport::default();
+        let mut section = Section::Preamble;
+        // Index of the entry the detail lines belong to, and the indentation of its attribute names
+        let mut current: Option<usize> = None;
+        let mut attribute_indent = 0;
+        let mut checked = false;
+
+        for line in content.lines() {
+            let line = line.trim_end_matches('\r');
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.chars().all(|c| c == '-') {
+                continue;
+            }
+
+            if let Some(header) = section_header(line) {
+                section = header;
+                current = None;
+                continue;
+            }
+            if let Some(rest) = trimmed.strip_prefix("Start timestamp:") {
+                report.parse_start(rest);
+                continue;
+            }
+            if trimmed.contains("found differences between database and filesystem") {
+                report.differences_found = true;
+                checked = true;
+            }
+            if trimmed.contains("found NO differences between database and filesystem") || trimmed.contains("All files match AIDE database") {
+                checked = true;
+            }
+            let count = ["Total number of entries:", "Total number of files:", "Number of entries:"]
+                .iter()
+                .find_map(|prefix| trimmed.strip_prefix(prefix));
+            if let Some(count) = count {
+                report.total_entries = count.trim().parse().ok();
+                continue;
+            }
+
+            match section {
+                Section::Preamble | Section::Trailer => {}
+                Section::Entries(kind) => {
+                    if let Some((flags, path)) = parse_entry(line) {
+                        let index = report.entry(kind, path);
+                        let change = &mut report.changes[index];
+                        if let Some(flags) = flags {
+                            change.entry_type = entry_type_from_flag(flags).map(str::to_string);
+                            change.flags = Some(flags.to_string());
+                        }
+                    }
+                }
+                Section::Details => {
+                    let indented = line.starts_with(char::is_whitespace);
+                    if !indented {
+                        // "<Type>: <path>" starts the details of an entry
+                        if let Some((entry_type, path)) = line.split_once(": ").filter(|(_, path)| path.starts_with('/')) {
+                            let index = report.entry(ChangeKind::Changed, path.trim());
+                            report.changes[index].entry_type = Some(entry_type.trim().to_string());
+                            current = Some(index);
+                            attribute_indent = 0;
+                        }
+                        continue;
+                    }
+                    let Some(index) = current else {
+                        continue;
+                    };
+                    let attributes = &mut report.changes[index].attributes;
+                    let indent = line.len() - line.trim_start().len();
+                    // Wrapped values such as "A: group::r--" of an ACL look like attributes
+                    let continued = !attributes.is_empty() && indent > attribute_indent;
+                    match split_attribute(trimmed).filter(|_| !continued) {
+                        Some((name, values)) => {
+                            attribute_indent = indent;
+                            let (old, new) = split_values(values);
+                            attributes.push(AttributeChange {
+                                name: name.to_string(),
+                                old: old.to_string(),
+                                new: new.to_string(),
+                            });
+                        }
+                        None => {
+                            // Continuation of a wrapped value, e.g. a long hash, or the next entry of a list
+                            if let Some(last) = attributes.last_mut() {
+                                let separator = if matches!(last.name.as_str(), "ACL" | "XAttrs") { " " } else { "" };
+                                let (old, new) = split_values(trimmed);
+                                for (value, more) in [(&mut last.old, old), (&mut last.new, new)] {
+                                    if !value.is_empty() && !more.is_empty() {
+                                        value.push_str(separator);
+                                    }
+                                    value.push_str(more);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if !report.changes.is_empty() {
+            report.differences_found = true;
+        } else if !checked {
+            let message = content.lines().map(str::trim).find(|line| !line.is_empty() && !line.starts_with("Start timestamp:"));
+            report.error = Some(message.unwrap_or("the log is empty").to_string());
+        }
+        report
+    }
+
+    // "2024-05-01 10:00:00 +0000 (AIDE 0.18.6)" or just the time for older versions
+    fn parse_start(&mut self, rest: &str) {
+        let rest = rest.trim();
+        match rest.split_once("(AIDE ") {
+            Some((time, version)) => {
+                self.started = Some(time.trim().to_string());
+                self.version = Some(version.trim_end_matches(')').trim().to_string());
+            }
+            None => self.started = Some(rest.to_string()),
+        }
+    }
+
+    // Index of the entry for `path`, added if the path was not listed yet. Details for an
+    // entry listed as added or removed stay with that entry.
+    fn entry(&mut self, kind: ChangeKind, path: &str) -> usize {
+        if let Some(index) = self.changes.iter().position(|change| change.path == path) {
+            return index;
+        }
+        self.changes.push(FileChange::new(kind, path));
+        self.changes.len() - 1
+    }
+
+    pub fn count(&self, kind: ChangeKind) -> usize {
+        self.changes.iter().filter(|change| change.kind == kind).count()
+    }
+
+    // Changes grouped by parent directory, directories in path order
+    pub fn by_directory(&self) -> BTreeMap<&str, Vec<&FileChange>> {
+        let mut groups: BTreeMap<&str, Vec<&FileChange>> = BTreeMap::new();
+        for change in &self.changes {
+            groups.entry(change.directory()).or_default().push(change);
+        }
+        groups
+    }
+
+    // Time of the check as AIDE reports it, or the timestamp in the log file name
+    pub fn label(&self) -> String {
+        if let Some(started) = &self.started {
+            return started.clone();
+        }
+        self.path
+            .as_deref()
+            .and_then(Path::file_stem)
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.strip_prefix(LOG_PREFIX))
+            .unwrap_or("unknown")
+            .to_string()
+    }
+
+    pub fn summary(&self) -> String {
+        if let Some(error) = &self.error {
+            return format!("❌ AIDE did not check the filesystem: {}", error);
+        }
+        if !self.differences_found {
+            return "✅ No differences between the AIDE database and the filesystem".to_string();
+        }
+        let mut parts = vec![
+            format!("{} added", self.count(ChangeKind::Added)),
+            format!("{} removed", self.count(ChangeKind::Removed)),
+            format!("{} changed", self.count(ChangeKind::Changed)),
+        ];
+        if let Some(total) = self.total_entries {
+            parts.push(format!("{} entries checked", total));
+        }
+        if let Some(version) = &self.version {
+            parts.push(format!("AIDE {}", version));
+        }
+        parts.join(" · ")
+    }
+}
+
+fn section_header(line: &str) -> Option<Section> {
+    // Headers start in the first column; the summary's "  Added entries:  2" does not
+    if line.starts_with(char::is_whitespace) {
+        return None;
+    }
+    match line.trim() {
+        // AIDE 0.15 says "files" instead of "entries"
+        "Added entries:" | "Added files:" => Some(Section::Entries(ChangeKind::Added)),
+        "Removed entries:" | "Removed files:" => Some(Section::Entries(ChangeKind::Removed)),
+        "Changed entries:" | "Changed files:" => Some(Section::Entries(ChangeKind::Changed)),
+        "Detailed information about changes:" => Some(Section::Details),
+        line if line.starts_with("The attributes of the") || line.starts_with("End timestamp:") => Some(Section::Trailer),
+        _ => None,
+    }
+}
+
+// "f++++++++++++++++: /etc/newfile", "f   ...    .C... : /etc/passwd" or "added: /etc/newfile"
+fn parse_entry(line: &str) -> Option<(Option<&str>, &str)> {
+    let (flags, path) = line.split_once(": ")?;
+    let path = path.trim();
+    if !path.starts_with('/') {
+        return None;
+    }
+    match flags.trim() {
+        "added" | "removed" | "changed" => Some((None, path)),
+        _ => Some((Some(flags), path)),
+    }
+}
+
+fn entry_type_from_flag(flags: &str) -> Option<&'static str> {
+    match flags.chars().next()? {
+        'f' => Some("File"),
+        'd' => Some("Directory"),
+        'l' => Some("Link"),
+        'c' => Some("Character device"),
+        'b' => Some("Block device"),
+        'p' => Some("FIFO"),
+        's' => Some("Socket"),
+        _ => None,
+    }
+}
+
+// "Size      : 1234 | 1250" into the attribute name and its values
+fn split_attribute(line: &str) -> Option<(&str, &str)> {
+    let (name, values) = line.split_once(':')?;
+    let name = name.trim();
+    let is_name = !name.is_empty()
+        && name.len() <= 20
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ' ');
+    is_name.then_some((name, values.trim()))
+}
+
+// Old and new value; a value without separator is the old one
+fn split_values(values: &str) -> (&str, &str) {
+    values
+        .split_once(" | ")
+        .or_else(|| values.split_once(" , "))
+        .map(|(old, new)| (old.trim(), new.trim()))
+        .unwrap_or((values.trim(), ""))
+}
+
+// All check logs in `dir`, oldest first, plus one message per unreadable file
+pub fn load_reports(dir: &Path) -> (Vec<AideReport>, Vec<String>) {
+    let files = match config::timestamped_files(dir, LOG_PREFIX, LOG_EXTENSION) {
+        Ok(files) => files,
+        Err(e) => return (Vec::new(), vec![e]),
+    };
+    let mut reports = Vec::new();
+    let mut errors = Vec::new();
+    for path in files {
+        match AideReport::load(&path) {
+            Ok(report) => reports.push(report),
+            Err(e) => errors.push(e),
+        }
+    }
+    (reports, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `aide --check` logs as check_system_integrity saves them: AIDE 0.16 with one
+    // change, AIDE 0.18 with differences, without any and without a database
+    fn dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/aide")
+    }
+
+    fn load(name: &str) -> AideReport {
+        AideReport::load(&dir().join(name)).unwrap()
+    }
+
+    fn attribute(name: &str, old: &str, new: &str) -> AttributeChange {
+        AttributeChange { name: name.to_string(), old: old.to_string(), new: new.to_string() }
+    }
+
+    #[test]
+    fn differences() {
+        let report = load("aide-check-20251012_033501.log");
+        assert!(report.differences_found);
+        assert_eq!(report.error, None);
+        assert_eq!(report.started.as_deref(), Some("2025-10-12 03:35:01 +0000"));
+        assert_eq!(report.version.as_deref(), Some("0.18.6"));
+        assert_eq!(report.total_entries, Some(48213));
+        assert_eq!(report.summary(), "2 added · 1 removed · 3 changed · 48213 entries checked · AIDE 0.18.6");
+
+        let entries: Vec<(ChangeKind, &str, Option<&str>)> =
+            report.changes.iter().map(|change| (change.kind, change.path.as_str(), change.entry_type.as_deref())).collect();
+        assert_eq!(
+            entries,
+            [
+                (ChangeKind::Added, "/etc/cron.d/hardn-clamscan", Some("File")),
+                (ChangeKind::Added, "/etc/hardn/backup", Some("Directory")),
+                (ChangeKind::Removed, "/usr/local/bin/old-tool", Some("File")),
+                (ChangeKind::Changed, "/etc/passwd", Some("File")),
+                (ChangeKind::Changed, "/etc/ssh/sshd_config", Some("File")),
+                (ChangeKind::Changed, "/etc/ssh/sshd_config.d", Some("Directory")),
+            ]
+        );
+        // The database's own attributes in the trailer belong to no entry
+        assert!(report.changes[..3].iter().all(|change| change.attributes.is_empty()));
+        assert_eq!(report.changes[3].flags.as_deref(), Some("f   ...    .C... "));
+    }
+
+    #[test]
+    fn changed_attributes() {
+        let report = load("aide-check-20251012_033501.log");
+        let passwd = &report.changes[3];
+        // The wrapped hash is joined
+        assert_eq!(
+            passwd.attributes,
+            [
+                attribute("Size", "1234", "1250"),
+                attribute("SHA256", "3Xa1z5HqbT2mVv0pLw9bC7yN1kR4sE6uoG2dQ=", "Ks8dW0eFj2Qa9Zc4Lm7Vb1Nx3Ty5Hp8RuI6sA="),
+            ]
+        );
+        assert_eq!(passwd.groups(), [AttributeGroup::Hash, AttributeGroup::Size]);
+
+        // ACL entries continue the value even though they contain a colon
+        let sshd = &report.changes[4];
+        assert_eq!(
+            sshd.attributes,
+            [
+                attribute("Perm", "-rw-r--r--", "-rw-------"),
+                attribute("ACL", "A: user::rw- A: group::r-- A: other::r--", "A: user::rw- A: group::--- A: other::---"),
+            ]
+        );
+        assert_eq!(sshd.groups(), [AttributeGroup::Permissions]);
+        assert_eq!(report.changes[5].groups(), [AttributeGroup::Time]);
+
+        let directories: Vec<(&str, usize)> = report.by_directory().into_iter().map(|(dir, changes)| (dir, changes.len())).collect();
+        assert_eq!(directories, [("/etc", 1), ("/etc/cron.d", 1), ("/etc/hardn", 1), ("/etc/ssh", 2), ("/usr/local/bin", 1)]);
+    }
+
+    #[test]
+    fn no_differences() {
+        let report = load("aide-check-20251015_033501.log");
+        assert!(!report.differences_found);
+        assert_eq!(report.error, None);
+        assert!(report.changes.is_empty());
+        assert_eq!(report.total_entries, Some(48213));
+        assert_eq!(report.summary(), "✅ No differences between the AIDE database and the filesystem");
+
+        let report = AideReport::parse("AIDE, version 0.16\n\n### All files match AIDE database. Looks okay!\n");
+        assert_eq!(report.error, None);
+        assert!(!report.differences_found);
+    }
+
+    #[test]
+    fn checks_that_did_not_run() {
+        let report = load("aide-check-20251018_033501.log");
+        assert!(!report.differences_found);
+        assert_eq!(report.error.as_deref(), Some("Couldn't open file /var/lib/aide/aide.db for reading"));
+        assert_eq!(report.summary(), "❌ AIDE did not check the filesystem: Couldn't open file /var/lib/aide/aide.db for reading");
+        // Named after the log file, as AIDE gave no start time
+        assert_eq!(report.label(), "20251018_033501");
+        assert_eq!(AideReport::parse("").error.as_deref(), Some("the log is empty"));
+    }
+
+    #[test]
+    fn older_aide_logs() {
+        let report = load("aide-check-20250901_033501.log");
+        assert_eq!(report.started.as_deref(), Some("2025-09-01 03:35:01"));
+        assert_eq!(report.version, None);
+        assert_eq!(report.total_entries, Some(40117));
+        assert_eq!(report.changes.len(), 1);
+        let shadow = &report.changes[0];
+        assert_eq!((shadow.kind, shadow.flags.as_deref(), shadow.entry_type.as_deref()), (ChangeKind::Changed, None, Some("File")));
+        assert_eq!(
+            shadow.attributes,
+            [attribute("Mtime", "2025-08-20 09:12:44", "2025-08-31 22:05:10"), attribute("Inode", "131090", "131102")]
+        );
+        assert_eq!(report.summary(), "0 added · 0 removed · 1 changed · 40117 entries checked");
+    }
+
+    #[test]
+    fn logs_in_a_directory() {
+        let (reports, errors) = load_reports(&dir());
+        assert!(errors.is_empty());
+        let labels: Vec<String> = reports.iter().map(AideReport::label).collect();
+        assert_eq!(labels, ["2025-09-01 03:35:01", "2025-10-12 03:35:01 +0000", "2025-10-15 03:35:01 +0000", "20251018_033501"]);
+    }
+}
@@ -0,0 +1,467 @@
+// AppArmor state: the loaded profiles from securityfs, one "name (mode)" per line, and
+// the DENIED records the kernel logs for them:
+//
+//   type=AVC msg=audit(1700000000.123:456): apparmor="DENIED" operation="open"
+//     profile="/usr/sbin/cupsd" name="/etc/shadow" pid=812 comm="cupsd"
+//     requested_mask="r" denied_mask="r" fsuid=0 ouid=0
+//
+// audit.log has them when auditd runs, kern.log otherwise (with a syslog prefix).
+// Both files and usually the profile list are only readable by root; the helper then
+// prints them as a transcript with "### PROFILES" and "### DENIED" sections.
+//
+// This file is compiled into both binaries and each only uses its own side of it.
+#![allow(dead_code)]
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Relative to the root, which HARDN_APPARMOR_ROOT replaces for tests, e.g.
+// HARDN_APPARMOR_ROOT=$PWD/src/gui/fixtures/apparmor-root hardn-gui
+const PROFILES: &str = "sys/kernel/security/apparmor/profiles";
+const AUDIT_LOG: &str = "var/log/audit/audit.log";
+const KERN_LOG: &str = "var/log/kern.log";
+const ROOT_ENV: &str = "HARDN_APPARMOR_ROOT";
+
+pub const PROFILE_DIR: &str = "/etc/apparmor.d";
+pub const AA_ENFORCE: &str = "/usr/sbin/aa-enforce";
+pub const AA_COMPLAIN: &str = "/usr/sbin/aa-complain";
+// Most recent DENIED records kept
+pub const MAX_DENIALS: usize = 200;
+
+const PROFILES_SECTION: &str = "### PROFILES";
+const DENIED_SECTION: &str = "### DENIED";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    Enforce,
+    Complain,
+    Kill,
+    Unconfined,
+    Other(String),
+}
+
+impl Mode {
+    pub fn parse(text: &str) -> Self {
+        match text {
+            "enforce" => Mode::Enforce,
+            "complain" => Mode::Complain,
+            "kill" => Mode::Kill,
+            "unconfined" => Mode::Unconfined,
+            other => Mode::Other(other.to_string()),
+        }
+    }
+
+    pub fn keyword(&self) -> &str {
+        match self {
+            Mode::Enforce => "enforce",
+            Mode::Complain => "complain",
+            Mode::Kill => "kill",
+            Mode::Unconfined => "unconfined",
+            Mode::Other(other) => other,
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Mode::Enforce | Mode::Kill => "success",
+            Mode::Complain => "warning",
+            Mode::Unconfined | Mode::Other(_) => "dim-label",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    pub name: String,
+    pub mode: Mode,
+}
+
+impl Profile {
+    // Hats and child profiles ("parent//child") follow their parent's mode
+    pub fn is_toggleable(&self) -> bool {
+        matches!(self.mode, Mode::Enforce | Mode::Complain) && !self.name.contains("//")
+    }
+}
+
+pub fn parse_profiles(content: &str) -> Vec<Profile> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (name, mode) = line.trim().strip_suffix(')')?.rsplit_once(" (")?;
+            Some(Profile { name: name.to_string(), mode: Mode::parse(mode) })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Denial {
+    // Seconds since the epoch, from the audit(<time>:<serial>) stamp
+    pub time: Option<i64>,
+    pub profile: String,
+    pub operation: String,
+    // The object: a path, a capability or a network family
+    pub target: String,
+    pub denied: String,
+    pub comm: String,
+    pub pid: Option<u32>,
+}
+
+impl Denial {
+    // The loaded profile the record belongs to, without any hat or child part
+    pub fn top_profile(&self) -> &str {
+        self.profile.split("//").next().unwrap_or(&self.profile)
+    }
+
+    pub fn describe(&self) -> String {
+        let mut text = format!("{} {}", self.operation, self.target);
+        if !self.denied.is_empty() {
+            text.push_str(&format!(" ({})", self.denied));
+        }
+        if !self.comm.is_empty() {
+            text.push_str(&format!(" by {}", self.comm));
+            if let Some(pid) = self.pid {
+                text.push_str(&format!("[{}]", pid));
+            }
+        }
+        text
+    }
+}
+
+// key=value and key="quoted value" fields of an audit record
+fn audit_fields(line: &str) -> HashMap<&str, &str> {
+    let mut fields = HashMap::new();
+    let mut rest = line;
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].rsplit(' ').next().unwrap_or("");
+        let after = &rest[eq + 1..];
+        let (value, next) = match after.strip_prefix('"') {
+            Some(quoted) => match quoted.find('"') {
+                Some(end) => (&quoted[..end], &quoted[end + 1..]),
+                None => (quoted, ""),
+            },
+            None => match after.find(' ') {
+                Some(end) => (&after[..end], &after[end..]),
+                None => (after, ""),
+            },
+        };
+        fields.entry(key).or_insert(value);
+        rest = next;
+    }
+    fields
+}
+
+// auditd writes paths with spaces or quotes unquoted in hex: name=2F746D702F61206220
+fn decode_hex(value: &str) -> Option<String> {
+    if !value.starts_with("2F") || !value.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let bytes: Option<Vec<u8>> = value
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| std::str::from_utf8(pair).ok().filter(|pair| pair.len() == 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()))
+        .collect();
+    Some(String::from_utf8_lossy(&bytes?).to_string())
+}
+
+pub fn parse_denial(line: &str) -> Option<Denial> {
+    if !line.contains("apparmor=\"DENIED\"") {
+        return None;
+    }
+    let fields = audit_fields(line);
+    let time = line
+        .split_once("audit(")
+        .and_then(|(_, stamp)| stamp.split(['.', ':']).next())
+        .and_then(|seconds| seconds.parse().ok());
+    let target = ["name", "capname", "family", "signal", "peer"]
+        .iter()
+        .find_map(|key| fields.get(key))
+        .map(|value| decode_hex(value).unwrap_or_else(|| value.to_string()))
+        .unwrap_or_default();
+    Some(Denial {
+        time,
+        profile: fields.get("profile")?.to_string(),
+        operation: fields.get("operation").map(|value| value.to_string()).unwrap_or_default(),
+        target,
+        denied: fields.get("denied_mask").or_else(|| fields.get("denied")).map(|value| value.to_string()).unwrap_or_default(),
+        comm: fields.get("comm").map(|value| value.to_string()).unwrap_or_default(),
+        pid: fields.get("pid").and_then(|pid| pid.parse().ok()),
+    })
+}
+
+// The last MAX_DENIALS records, oldest first
+pub fn parse_denials(content: &str) -> Vec<Denial> {
+    let mut denials: Vec<Denial> = content.lines().filter_map(parse_denial).collect();
+    denials.drain(..denials.len().saturating_sub(MAX_DENIALS));
+    denials
+}
+
+// Profile and mode a process runs under: "unconfined" or e.g. "/usr/sbin/cupsd (enforce)"
+pub fn process_label(proc_dir: &Path, pid: u32) -> Option<String> {
+    let process_dir = proc_dir.join(pid.to_string());
+    // Newer kernels keep the LSM specific file apart from the shared attr/current
+    fs::read_to_string(process_dir.join("attr/apparmor/current"))
+        .or_else(|_| fs::read_to_string(process_dir.join("attr/current")))
+        .ok()
+        .map(|label| label.trim_end_matches(['\n', '\0']).to_string())
+        .filter(|label| !label.is_empty())
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Status {
+    pub profiles: Vec<Profile>,
+    pub denials: Vec<Denial>,
+    // Where the denials were read from
+    pub log: Option<String>,
+    pub errors: Vec<String>,
+}
+
+// Helper side: the profile list and the DENIED lines of `log`
+pub fn print_transcript(profiles: &str, log: &str, denials: &str) {
+    println!("{}", PROFILES_SECTION);
+    print!("{}", profiles);
+    println!("{} {}", DENIED_SECTION, log);
+    print!("{}", denials);
+}
+
+impl Status {
+    pub fn parse_transcript(output: &str) -> Self {
+        let mut profiles = String::new();
+        let mut denials = String::new();
+        let mut log = None;
+        let mut in_denials = None;
+        for line in output.lines() {
+            if line == PROFILES_SECTION {
+                in_denials = Some(false);
+            } else if let Some(path) = line.strip_prefix(DENIED_SECTION) {
+                in_denials = Some(true);
+                log = Some(path.trim().to_string()).filter(|path| !path.is_empty());
+            } else if let Some(in_denials) = in_denials {
+                let text = if in_denials { &mut denials } else { &mut profiles };
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+        Status { profiles: parse_profiles(&profiles), denials: parse_denials(&denials), log, errors: Vec::new() }
+    }
+
+    pub fn denials_for(&self, profile: &str) -> Vec<&Denial> {
+        self.denials.iter().filter(|denial| denial.top_profile() == profile).collect()
+    }
+
+    pub fn count(&self, mode: &Mode) -> usize {
+        self.profiles.iter().filter(|profile| &profile.mode == mode).count()
+    }
+}
+
+pub struct AppArmor {
+    root: PathBuf,
+}
+
+impl AppArmor {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        AppArmor { root: root.into() }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(std::env::var(ROOT_ENV).unwrap_or_else(|_| "/".to_string()))
+    }
+
+    pub fn proc_dir(&self) -> PathBuf {
+        self.root.join("proc")
+    }
+
+    pub fn read_profiles(&self) -> io::Result<String> {
+        fs::read_to_string(self.root.join(PROFILES))
+    }
+
+    // DENIED lines of audit.log, or kern.log when auditd does not run, and which file
+    pub fn read_denials(&self) -> io::Result<(String, String)> {
+        let mut last_error = None;
+        for log in [AUDIT_LOG, KERN_LOG] {
+            match fs::read_to_string(self.root.join(log)) {
+                Ok(content) => {
+                    let lines: Vec<&str> = content.lines().filter(|line| line.contains("apparmor=\"DENIED\"")).collect();
+                    let recent = &lines[lines.len().saturating_sub(MAX_DENIALS)..];
+                    return Ok((format!("/{}", log), recent.iter().map(|line| format!("{}\n", line)).collect()));
+                }
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => last_error = Some(io::Error::new(e.kind(), format!("/{}: {}", log, e))),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| io::Error::new(io::ErrorKind::NotFound, "neither audit.log nor kern.log exists")))
+    }
+
+    // Fails when the profile list cannot be read; an unreadable log is only noted
+    pub fn status(&self) -> io::Result<Status> {
+        let profiles = self.read_profiles()?;
+        let mut status = Status { profiles: parse_profiles(&profiles), ..Status::default() };
+        match self.read_denials() {
+            Ok((log, denials)) => {
+                status.denials = parse_denials(&denials);
+                status.log = Some(log);
+            }
+            Err(e) => status.errors.push(e.to_string()),
+        }
+        Ok(status)
+    }
+}
+
+// Switch a profile between enforce and complain mode with apparmor-utils
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModeChange {
+    pub profile: String,
+    pub mode: Mode,
+}
+
+impl ModeChange {
+    pub fn validate(&self) -> Result<(), String> {
+        if !matches!(self.mode, Mode::Enforce | Mode::Complain) {
+            return Err(format!("profiles can only be put into enforce or complain mode, not {}", self.mode.keyword()));
+        }
+        let name = &self.profile;
+        let valid = !name.is_empty()
+            && name.len() <= 256
+            && !name.starts_with('-')
+            && !name.contains("//")
+            && !name.contains("..")
+            && !name.chars().any(|c| c.is_whitespace() || c.is_control());
+        // Named profiles live in a file of the same name
+        if !valid || (!name.starts_with('/') && name.contains('/')) {
+            return Err(format!("invalid profile name: {}", name));
+        }
+        Ok(())
+    }
+
+    // aa-enforce and aa-complain take a program path or a profile file
+    pub fn target(&self) -> String {
+        if self.profile.starts_with('/') {
+            self.profile.clone()
+        } else {
+            format!("{}/{}", PROFILE_DIR, self.profile)
+        }
+    }
+
+    pub fn program(&self) -> &'static str {
+        match self.mode {
+            Mode::Complain => AA_COMPLAIN,
+            _ => AA_ENFORCE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // securityfs profile list, audit.log and /proc of a host with CUPS confined and a
+    // few unconfined daemons listening
+    fn root() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/apparmor-root")
+    }
+
+    #[test]
+    fn profiles_and_modes() {
+        let status = AppArmor::new(root()).status().unwrap();
+        assert_eq!(status.profiles.len(), 14);
+        assert_eq!((status.count(&Mode::Enforce), status.count(&Mode::Complain), status.count(&Mode::Unconfined)), (12, 1, 1));
+        let profile = |name: &str| status.profiles.iter().find(|profile| profile.name == name).unwrap();
+        assert_eq!(profile("/usr/sbin/named").mode, Mode::Complain);
+        assert!(profile("/usr/sbin/named").is_toggleable());
+        // Hats follow their parent, unconfined profiles cannot be switched
+        assert!(!profile("/usr/sbin/cupsd//third_party").is_toggleable());
+        assert!(!profile("firefox").is_toggleable());
+
+        assert_eq!(parse_profiles("a b (prompt)\nbroken line\n"), [Profile { name: "a b".to_string(), mode: Mode::Other("prompt".to_string()) }]);
+    }
+
+    #[test]
+    fn denials_belong_to_their_top_profile() {
+        let status = AppArmor::new(root()).status().unwrap();
+        assert_eq!(status.log.as_deref(), Some("/var/log/audit/audit.log"));
+        assert!(status.errors.is_empty());
+        // ALLOWED records of complain mode are not denials
+        assert_eq!(status.denials.len(), 5);
+
+        let cupsd: Vec<String> = status.denials_for("/usr/sbin/cupsd").iter().map(|denial| denial.describe()).collect();
+        assert_eq!(
+            cupsd,
+            [
+                "open /etc/shadow (r) by cupsd[812]",
+                "capable net_admin by cupsd[812]",
+                // Hex encoded because of the space
+                "exec /tmp/bad script  (x) by foomatic-rip[1430]",
+            ]
+        );
+        assert_eq!(status.denials[2].profile, "/usr/sbin/cupsd//third_party");
+        assert_eq!(status.denials[0].time, Some(1760780112));
+        assert_eq!(status.denials_for("tcpdump")[0].target, "inet6");
+        assert!(status.denials_for("/usr/sbin/named").is_empty());
+        // Records of profiles that are no longer loaded are kept
+        assert_eq!(status.denials.last().unwrap().profile, "old-profile");
+    }
+
+    #[test]
+    fn kern_log_is_used_without_auditd() {
+        let dir = std::env::temp_dir().join(format!("hardn-apparmor-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join("sys/kernel/security/apparmor")).unwrap();
+        fs::create_dir_all(dir.join("var/log")).unwrap();
+        fs::write(dir.join(PROFILES), "/usr/sbin/cupsd (enforce)\n").unwrap();
+        let without_logs = AppArmor::new(&dir).status().unwrap();
+        fs::write(
+            dir.join(KERN_LOG),
+            "2025-10-18T10:15:12.345678+00:00 host kernel: audit: type=1400 audit(1760782512.345:77): apparmor=\"DENIED\" \
+             operation=\"open\" class=\"file\" profile=\"/usr/sbin/cupsd\" name=\"/etc/ssl/private/key.pem\" pid=812 \
+             comm=\"cupsd\" requested_mask=\"r\" denied_mask=\"r\" fsuid=7 ouid=0\n\
+             2025-10-18T10:15:13.000000+00:00 host kernel: usb 1-1: new device\n",
+        )
+        .unwrap();
+        let status = AppArmor::new(&dir).status().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(without_logs.errors, ["neither audit.log nor kern.log exists"]);
+        assert_eq!(status.log.as_deref(), Some("/var/log/kern.log"));
+        assert_eq!(status.denials.len(), 1);
+        assert_eq!(status.denials[0].time, Some(1760782512));
+        assert_eq!(status.denials[0].target, "/etc/ssl/private/key.pem");
+    }
+
+    #[test]
+    fn transcripts_read_back_into_a_status() {
+        let profiles = AppArmor::new(root()).read_profiles().unwrap();
+        let (log, denials) = AppArmor::new(root()).read_denials().unwrap();
+        let transcript = format!("{}\n{}{} {}\n{}", PROFILES_SECTION, profiles, DENIED_SECTION, log, denials);
+        let status = Status::parse_transcript(&transcript);
+        assert_eq!(status.profiles, parse_profiles(&profiles));
+        assert_eq!(status.denials, AppArmor::new(root()).status().unwrap().denials);
+        assert_eq!(status.log.as_deref(), Some("/var/log/audit/audit.log"));
+    }
+
+    #[test]
+    fn process_labels() {
+        let proc_dir = AppArmor::new(root()).proc_dir();
+        assert_eq!(process_label(&proc_dir, 812).as_deref(), Some("/usr/sbin/cupsd (enforce)"));
+        assert_eq!(process_label(&proc_dir, 2500).as_deref(), Some("unconfined"));
+        // Older kernels only have the shared attr/current
+        assert_eq!(process_label(&proc_dir, 2600).as_deref(), Some("unconfined"));
+        assert_eq!(process_label(&proc_dir, 3100).as_deref(), Some("firefox (unconfined)"));
+        assert_eq!(process_label(&proc_dir, 4000), None);
+    }
+
+    #[test]
+    fn mode_changes_are_validated() {
+        let change = |profile: &str, mode: Mode| ModeChange { profile: profile.to_string(), mode };
+        assert_eq!(change("/usr/sbin/named", Mode::Enforce).target(), "/usr/sbin/named");
+        assert_eq!(change("tcpdump", Mode::Complain).target(), "/etc/apparmor.d/tcpdump");
+        assert_eq!(change("tcpdump", Mode::Complain).program(), AA_COMPLAIN);
+        assert!(change("tcpdump", Mode::Enforce).validate().is_ok());
+        for bad in ["", "-tcpdump", "nvidia_modprobe//kmod", "/usr/../etc/shadow", "local/tcpdump", "tcp dump"] {
+            assert!(change(bad, Mode::Enforce).validate().is_err(), "{}", bad);
+        }
+        assert!(change("tcpdump", Mode::Kill).validate().is_err());
+        assert!(change("tcpdump", Mode::Unconfined).validate().is_err());
+    }
+}
@@ -0,0 +1,755 @@
+// Linux audit: the rules auditd loads and the events it logs.
+//
+// Rules are auditctl arguments, one rule per line, in /etc/audit/rules.d/*.rules
+// (augenrules concatenates them in name order into /etc/audit/audit.rules):
+//
+//   -w /etc/passwd -p wa -k identity
+//   -a always,exit -F arch=b64 -S execve -F euid=0 -k privileged
+//
+// audit.log has one record per line; the records of one event share the
+// audit(<seconds>.<millis>:<serial>) stamp and may interleave with other events':
+//
+//   type=SYSCALL msg=audit(1760781000.123:812): arch=c000003e syscall=59 success=yes ...
+//     auid=1000 uid=0 euid=0 comm="sudo" exe="/usr/bin/sudo" key="privileged"
+//   type=EXECVE msg=audit(1760781000.123:812): argc=3 a0="ls" a1="-l" a2=2F746D702F6120622063
+//
+// Strings that contain spaces, quotes or control characters are written unquoted in
+// hex. With log_format=ENRICHED a record ends in a 0x1d separator followed by the
+// names of numeric ids, e.g. AUID="alice" SYSCALL=execve.
+//
+// Both the rules and the logs are only readable by root, so the helper prints them;
+// log events are filtered by time there and only the newest MAX_EVENTS are sent.
+//
+// This file is compiled into both binaries and each only uses its own side of it.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+pub const RULES_DIR: &str = "/etc/audit/rules.d";
+// Written by configure_audit_system in modules/hardening.sh
+pub const HARDN_RULES: &str = "/etc/audit/rules.d/hardn-audit.rules";
+const AUDIT_RULES: &str = "/etc/audit/audit.rules";
+pub const LOG_DIR: &str = "/var/log/audit";
+const LOG_FILE: &str = "audit.log";
+pub const AUDITCTL: &str = "/sbin/auditctl";
+pub const MAX_EVENTS: usize = 1000;
+// Choices for how far back to read events
+pub const TIME_RANGES: &[(&str, Option<i64>)] = &[
+    ("Last hour", Some(3600)),
+    ("Last 24 hours", Some(86400)),
+    ("Last 7 days", Some(7 * 86400)),
+    ("Last 30 days", Some(30 * 86400)),
+    ("All", None),
+];
+// Directory standing in for / when the GUI reads rules and logs itself
+pub const ROOT_ENV: &str = "HARDN_AUDIT_ROOT";
+
+const FILE_MARKER: &str = "### FILE ";
+const LOADED_MARKER: &str = "### LOADED";
+// auid of processes that were never part of a login session
+const UNSET_ID: &str = "4294967295";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleKind {
+    // -w path -p perms
+    Watch { path: String, perms: String },
+    // -a action,list -S syscalls -F fields
+    Syscall { action: String, list: String, syscalls: Vec<String>, fields: Vec<String> },
+    // -D, -b 8192, -e 2, ...
+    Control { option: String, value: Option<String> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub file: String,
+    pub line: usize,
+    pub raw: String,
+    pub kind: RuleKind,
+    pub keys: Vec<String>,
+}
+
+impl Rule {
+    pub fn describe(&self) -> String {
+        match &self.kind {
+            RuleKind::Watch { path, perms } => {
+                let perms: Vec<&str> = perms
+                    .chars()
+                    .filter_map(|perm| match perm {
+                        'r' => Some("read"),
+                        'w' => Some("write"),
+                        'x' => Some("execute"),
+                        'a' => Some("attribute change"),
+                        _ => None,
+                    })
+                    .collect();
+                let perms = if perms.is_empty() { "any access".to_string() } else { perms.join(", ") };
+                format!("Watch {} for {}", path, perms)
+            }
+            RuleKind::Syscall { action, list, syscalls, fields } => {
+                let verb = if action == "never" { "Never log" } else { "Log" };
+                let syscalls = if syscalls.is_empty() || syscalls.iter().any(|syscall| syscall == "all") {
+                    "all syscalls".to_string()
+                } else {
+                    syscalls.join(", ")
+                };
+                let mut text = format!("{} {} on {}", verb, syscalls, list);
+                if !fields.is_empty() {
+                    text.push_str(&format!(" where {}", fields.join(" ")));
+                }
+                text
+            }
+            RuleKind::Control { option, value } => {
+                let value = value.as_deref().unwrap_or("");
+                match option.as_str() {
+                    "-D" => "Delete all previously loaded rules".to_string(),
+                    "-b" => format!("Kernel backlog of {} buffers", value),
+                    "-f" => format!(
+                        "On failure: {}",
+                        match value {
+                            "0" => "stay silent",
+                            "1" => "log to the kernel log",
+                            "2" => "panic",
+                            other => other,
+                        }
+                    ),
+                    "-e" => match value {
+                        "0" => "Disable auditing".to_string(),
+                        "1" => "Enable auditing".to_string(),
+                        "2" => "Lock the rules until reboot (immutable)".to_string(),
+                        other => format!("Set enabled flag to {}", other),
+                    },
+                    "-r" => format!("Limit to {} messages per second", value),
+                    "--backlog_wait_time" => format!("Wait up to {} ticks for backlog space", value),
+                    "--loginuid-immutable" => "Login uids cannot be changed once set".to_string(),
+                    _ => self.raw.trim().to_string(),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    // In the order augenrules loads them
+    pub files: Vec<String>,
+    pub rules: Vec<Rule>,
+    // `auditctl -l`, when read through the helper
+    pub loaded: Option<Vec<String>>,
+    pub errors: Vec<String>,
+}
+
+impl RuleSet {
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.rules.iter().flat_map(|rule| rule.keys.iter().cloned()).collect();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+
+    // Helper side: the files augenrules would load, then the rules in the kernel
+    pub fn print_transcript() -> io::Result<usize> {
+        let files = rules_files(Path::new(RULES_DIR))?;
+        for file in &files {
+            println!("{}{}", FILE_MARKER, file.display());
+            let content = fs::read_to_string(file).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", file.display(), e)))?;
+            // Numbered, since blank lines do not reach the GUI
+            for (index, line) in content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+                println!("{}:{}", index + 1, line);
+            }
+        }
+        let output = Command::new(AUDITCTL)
+            .arg("-l")
+            .env_clear()
+            .env("LANG", "C.UTF-8")
+            .stdin(Stdio::null())
+            .output();
+        match output {
+            Ok(output) if output.status.success() => {
+                println!("{}", LOADED_MARKER);
+                print!("{}", String::from_utf8_lossy(&output.stdout));
+            }
+            Ok(output) => eprintln!("[WARN] hardn-gui-helper: auditctl -l failed: {}", String::from_utf8_lossy(&output.stderr).trim()),
+            Err(e) => eprintln!("[WARN] hardn-gui-helper: {}: {}", AUDITCTL, e),
+        }
+        Ok(files.len())
+    }
+
+    pub fn parse_transcript(output: &str) -> Self {
+        let mut set = RuleSet::default();
+        let mut file: Option<String> = None;
+        for line in output.lines() {
+            if let Some(path) = line.strip_prefix(FILE_MARKER) {
+                file = Some(path.to_string());
+                set.files.push(path.to_string());
+            } else if line == LOADED_MARKER {
+                file = None;
+                set.loaded = Some(Vec::new());
+            } else if let Some(loaded) = set.loaded.as_mut() {
+                // "No rules" is what auditctl prints for an empty list
+                if line != "No rules" && !line.starts_with('[') {
+                    loaded.push(line.to_string());
+                }
+            } else if let (Some(file), Some((number, text))) = (&file, line.split_once(':')) {
+                set.parse_line(file, number.parse().unwrap_or(0), text);
+            }
+        }
+        set
+    }
+
+    // Read the files directly, for a root GUI or a readable rules directory
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let mut set = RuleSet::default();
+        for file in rules_files(dir)? {
+            let content = fs::read_to_string(&file)?;
+            let path = file.display().to_string();
+            for (index, line) in content.lines().enumerate() {
+                set.parse_line(&path, index + 1, line);
+            }
+            set.files.push(path);
+        }
+        Ok(set)
+    }
+
+    fn parse_line(&mut self, file: &str, line: usize, raw: &str) {
+        let text = raw.trim();
+        if text.is_empty() || text.starts_with('#') {
+            return;
+        }
+        match parse_rule(text) {
+            Ok((kind, keys)) => self.rules.push(Rule { file: file.to_string(), line, raw: raw.to_string(), kind, keys }),
+            Err(e) => self.errors.push(format!("{}:{}: {}", file, line, e)),
+        }
+    }
+}
+
+// The rules and log directories under $HARDN_AUDIT_ROOT, or the system ones
+pub fn local_dirs() -> (PathBuf, PathBuf) {
+    let root = std::env::var_os(ROOT_ENV).map(PathBuf::from).unwrap_or_else(|| PathBuf::from("/"));
+    (root.join(RULES_DIR.trim_start_matches('/')), root.join(LOG_DIR.trim_start_matches('/')))
+}
+
+// *.rules in name order, or audit.rules when rules.d has none
+fn rules_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "rules"))
+        .collect();
+    files.sort();
+    let compiled = dir.parent().unwrap_or(dir).join(Path::new(AUDIT_RULES).file_name().unwrap_or_default());
+    if files.is_empty() && compiled.exists() {
+        files.push(compiled);
+    }
+    Ok(files)
+}
+
+pub fn parse_rule(text: &str) -> Result<(RuleKind, Vec<String>), String> {
+    let args: Vec<&str> = text.split_whitespace().collect();
+    let mut keys = Vec::new();
+    let mut watch = None;
+    let mut perms = String::new();
+    let mut syscall_rule: Option<(String, String)> = None;
+    let mut syscalls = Vec::new();
+    let mut fields = Vec::new();
+    let mut control = None;
+
+    let mut index = 0;
+    while index < args.len() {
+        let option = args[index];
+        let mut value = || {
+            index += 1;
+            args.get(index).map(|value| value.trim_matches('"').to_string()).ok_or_else(|| format!("{} needs a value", option))
+        };
+        match option {
+            "-w" | "-W" => watch = Some(value()?),
+            "-p" => perms = value()?,
+            "-k" => keys.push(value()?),
+            "-a" | "-A" => {
+                let spec = value()?;
+                let (first, second) = spec.split_once(',').ok_or_else(|| format!("expected action,list: {}", spec))?;
+                // Either order is accepted
+                let (action, list) = if ["always", "never"].contains(&first) { (first, second) } else { (second, first) };
+                syscall_rule = Some((action.to_string(), list.to_string()));
+            }
+            "-S" => syscalls.extend(value()?.split(',').map(str::to_string)),
+            "-F" | "-C" => {
+                let field = value()?;
+                match field.strip_prefix("key=") {
+                    Some(key) => keys.push(key.to_string()),
+                    None => fields.push(field),
+                }
+            }
+            "-D" | "--loginuid-immutable" | "--reset-lost" | "-c" | "-i" => control = Some((option.to_string(), None)),
+            "-b" | "-f" | "-e" | "-r" | "--backlog_wait_time" => {
+                let value = value()?;
+                control = Some((option.to_string(), Some(value)));
+            }
+            other => return Err(format!("unsupported option {}", other)),
+        }
+        index += 1;
+    }
+
+    let kind = match (watch, syscall_rule, control) {
+        (Some(path), None, _) => RuleKind::Watch { path, perms },
+        (None, Some((action, list)), _) => RuleKind::Syscall { action, list, syscalls, fields },
+        (None, None, Some((option, value))) => RuleKind::Control { option, value },
+        (Some(_), Some(_), _) => return Err("a rule is either a watch (-w) or a syscall rule (-a)".to_string()),
+        (None, None, None) => return Err("no rule".to_string()),
+    };
+    Ok((kind, keys))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub kind: String,
+    // Decoded values in log order; the names of an enriched record are upper case
+    pub fields: Vec<(String, String)>,
+    pub raw: String,
+}
+
+impl Record {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.iter().find(|(name, _)| name == key).map(|(_, value)| value.as_str())
+    }
+}
+
+// Fields that auditd hex-encodes when they contain unsafe characters
+const ENCODED_FIELDS: &[&str] = &["name", "proctitle", "cwd", "comm", "exe", "acct", "cmd", "data", "key", "path", "old", "new"];
+
+fn decode_hex(value: &str) -> Option<String> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let bytes: Option<Vec<u8>> = value
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| std::str::from_utf8(pair).ok().filter(|pair| pair.len() == 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()))
+        .collect();
+    Some(String::from_utf8_lossy(&bytes?).to_string())
+}
+
+// key=value pairs with "double", 'single' or unquoted values; msg='...' of user space
+// records holds fields of its own, which are added in its place
+fn split_fields(text: &str, kind: &str, fields: &mut Vec<(String, String)>) {
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+        let Some(eq) = rest.find('=') else {
+            break;
+        };
+        let key = rest[..eq].rsplit(' ').next().unwrap_or("").to_string();
+        let after = &rest[eq + 1..];
+        let (value, quoted, next) = match after.chars().next() {
+            Some(quote @ ('"' | '\'')) => match after[1..].find(quote) {
+                Some(end) => (&after[1..end + 1], true, &after[end + 2..]),
+                None => (&after[1..], true, ""),
+            },
+            _ => match after.find(' ') {
+                Some(end) => (&after[..end], false, &after[end..]),
+                None => (after, false, ""),
+            },
+        };
+        rest = next.trim_start();
+        if key == "msg" && quoted {
+            split_fields(value, kind, fields);
+            continue;
+        }
+        let encoded = ENCODED_FIELDS.contains(&key.as_str()) || (kind == "EXECVE" && key.starts_with('a') && key != "argc");
+        let value = match decode_hex(value).filter(|_| encoded && !quoted) {
+            Some(decoded) if key == "proctitle" => decoded.replace('\0', " ").trim().to_string(),
+            Some(decoded) => decoded,
+            None => value.to_string(),
+        };
+        fields.push((key, value));
+    }
+}
+
+// Record type, event id ("1760781000.123:812"), seconds and fields of one log line
+pub fn parse_record(line: &str) -> Option<(String, Record, i64)> {
+    let rest = line.strip_prefix("type=")?;
+    let (kind, rest) = rest.split_once(' ')?;
+    let rest = rest.strip_prefix("msg=audit(")?;
+    let (id, body) = rest.split_once("):")?;
+    let seconds = id.split(['.', ':']).next()?.parse().ok()?;
+    let (body, enriched) = body.split_once('\u{1d}').unwrap_or((body, ""));
+    let mut fields = Vec::new();
+    split_fields(body, kind, &mut fields);
+    split_fields(enriched, kind, &mut fields);
+    Some((id.to_string(), Record { kind: kind.to_string(), fields, raw: line.to_string() }, seconds))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub id: String,
+    pub time: i64,
+    pub records: Vec<Record>,
+}
+
+// Syscall names for the x86_64 numbers the HARDN and common STIG rules use
+const X86_64_SYSCALLS: &[(u32, &str)] = &[
+    (2, "open"),
+    (76, "truncate"),
+    (77, "ftruncate"),
+    (82, "rename"),
+    (85, "creat"),
+    (87, "unlink"),
+    (90, "chmod"),
+    (91, "fchmod"),
+    (92, "chown"),
+    (93, "fchown"),
+    (94, "lchown"),
+    (159, "adjtimex"),
+    (164, "settimeofday"),
+    (165, "mount"),
+    (166, "umount2"),
+    (170, "sethostname"),
+    (171, "setdomainname"),
+    (175, "init_module"),
+    (176, "delete_module"),
+    (188, "setxattr"),
+    (227, "clock_settime"),
+    (257, "openat"),
+    (260, "fchownat"),
+    (263, "unlinkat"),
+    (264, "renameat"),
+    (268, "fchmodat"),
+    (313, "finit_module"),
+    (322, "execveat"),
+    (59, "execve"),
+];
+const ARCH_X86_64: &str = "c000003e";
+
+impl Event {
+    pub fn record(&self, kind: &str) -> Option<&Record> {
+        self.records.iter().find(|record| record.kind == kind)
+    }
+
+    // The first record carries the event's main type, e.g. SYSCALL or USER_AUTH
+    pub fn kind(&self) -> &str {
+        self.records.first().map(|record| record.kind.as_str()).unwrap_or("")
+    }
+
+    // Rule keys; several keys of one rule are joined with 0x01
+    pub fn keys(&self) -> Vec<String> {
+        self.records
+            .iter()
+            .filter_map(|record| record.get("key"))
+            .filter(|key| *key != "(null)")
+            .flat_map(|key| key.split('\u{1}').map(str::to_string))
+            .collect()
+    }
+
+    fn field(&self, key: &str) -> Option<&str> {
+        self.records.iter().find_map(|record| record.get(key))
+    }
+
+    // The login user, which sudo and su do not change; uid when there was no login
+    pub fn user(&self, names: &HashMap<u32, String>) -> String {
+        let lookup = |enriched: &str, numeric: &str| {
+            self.field(enriched).map(str::to_string).or_else(|| {
+                let id = self.field(numeric)?;
+                Some(id.parse().ok().and_then(|uid: u32| names.get(&uid).cloned()).unwrap_or_else(|| id.to_string()))
+            })
+        };
+        match self.field("auid") {
+            Some(UNSET_ID) | None => lookup("UID", "uid").unwrap_or_else(|| "unset".to_string()),
+            Some(_) => lookup("AUID", "auid").unwrap_or_else(|| "unset".to_string()),
+        }
+    }
+
+    pub fn syscall(&self) -> Option<String> {
+        let record = self.record("SYSCALL")?;
+        if let Some(name) = record.get("SYSCALL") {
+            return Some(name.to_string());
+        }
+        let number = record.get("syscall")?;
+        let name = (record.get("arch") == Some(ARCH_X86_64))
+            .then(|| number.parse::<u32>().ok())
+            .flatten()
+            .and_then(|number| X86_64_SYSCALLS.iter().find(|(n, _)| *n == number))
+            .map(|(_, name)| name.to_string());
+        Some(name.unwrap_or_else(|| format!("syscall {}", number)))
+    }
+
+    // argv from EXECVE, else the process title
+    pub fn command(&self) -> Option<String> {
+        if let Some(execve) = self.record("EXECVE") {
+            let argc: usize = execve.get("argc")?.parse().ok()?;
+            let args: Vec<String> = (0..argc)
+                .map(|i| {
+                    // Long arguments are split into a<i>[0], a<i>[1], ...
+                    execve.get(&format!("a{}", i)).map(str::to_string).unwrap_or_else(|| {
+                        (0..)
+                            .map_while(|part| execve.get(&format!("a{}[{}]", i, part)))
+                            .collect()
+                    })
+                })
+                .collect();
+            return Some(args.join(" "));
+        }
+        self.field("proctitle").map(str::to_string)
+    }
+
+    pub fn paths(&self) -> Vec<String> {
+        self.records
+            .iter()
+            .filter(|record| record.kind == "PATH")
+            .filter_map(|record| record.get("name"))
+            .filter(|name| *name != "(null)")
+            .map(str::to_string)
+            .collect()
+    }
+
+    pub fn success(&self) -> Option<bool> {
+        match self.field("success").or_else(|| self.field("res"))? {
+            "yes" | "success" | "1" => Some(true),
+            _ => Some(false),
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(syscall) = self.syscall() {
+            parts.push(syscall);
+        } else if let Some(op) = self.field("op").or_else(|| self.field("unit")) {
+            parts.push(op.to_string());
+        }
+        if let Some(acct) = self.field("acct") {
+            parts.push(format!("account {}", acct));
+        }
+        match self.command() {
+            Some(command) => parts.push(command),
+            None => parts.extend(self.field("exe").map(str::to_string)),
+        }
+        let paths = self.paths();
+        if !paths.is_empty() && self.record("EXECVE").is_none() {
+            parts.push(paths.join(", "));
+        }
+        if let Some(addr) = self.field("addr").filter(|addr| !["?", ""].contains(addr)) {
+            parts.push(format!("from {}", addr));
+        }
+        if self.success() == Some(false) {
+            parts.push("(failed)".to_string());
+        }
+        parts.join(" · ")
+    }
+}
+
+// Events in log order, each with its records in log order
+pub fn parse_log(content: &str) -> Vec<Event> {
+    let mut events: Vec<Event> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for line in content.lines() {
+        let Some((id, record, time)) = parse_record(line) else {
+            continue;
+        };
+        // EOE only marks the end of a multi-record event
+        if record.kind == "EOE" {
+            continue;
+        }
+        match index.get(&id) {
+            Some(&position) => events[position].records.push(record),
+            None => {
+                index.insert(id.clone(), events.len());
+                events.push(Event { id, time, records: vec![record] });
+            }
+        }
+    }
+    events
+}
+
+// audit.log and its rotations, oldest first: audit.log.3, audit.log.2, audit.log.1, audit.log
+pub fn log_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut rotated: Vec<(u32, PathBuf)> = fs::read_dir(dir)?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let number = name.to_str()?.strip_prefix(LOG_FILE)?.strip_prefix('.')?.parse().ok()?;
+            Some((number, entry.path()))
+        })
+        .collect();
+    rotated.sort_by_key(|(number, _)| std::cmp::Reverse(*number));
+    let mut files: Vec<PathBuf> = rotated.into_iter().map(|(_, path)| path).collect();
+    let current = dir.join(LOG_FILE);
+    if current.exists() {
+        files.push(current);
+    }
+    Ok(files)
+}
+
+// The newest MAX_EVENTS events between `since` and `until` (seconds, inclusive)
+pub fn read_events(dir: &Path, since: Option<i64>, until: Option<i64>) -> io::Result<Vec<Event>> {
+    read_newest(dir, since, until, MAX_EVENTS)
+}
+
+// Oldest first, but the files are read newest first and one at a time, until `limit`
+// events were found or a file starts before `since`
+fn read_newest(dir: &Path, since: Option<i64>, until: Option<i64>, limit: usize) -> io::Result<Vec<Event>> {
+    let mut newest_first: Vec<Vec<Event>> = Vec::new();
+    let mut found = 0;
+    for file in log_files(dir)?.iter().rev() {
+        let content = fs::read(file).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", file.display(), e)))?;
+        let events = parse_log(&String::from_utf8_lossy(&content));
+        let older_than_range = events.first().is_some_and(|event| event.time < since.unwrap_or(i64::MIN));
+        let mut matching: Vec<Event> = events
+            .into_iter()
+            .filter(|event| since.unwrap_or(i64::MIN) <= event.time && event.time <= until.unwrap_or(i64::MAX))
+            .collect();
+        matching.drain(..matching.len().saturating_sub(limit - found));
+        found += matching.len();
+        newest_first.push(matching);
+        if found >= limit || older_than_range {
+            break;
+        }
+    }
+    Ok(newest_first.into_iter().rev().flatten().collect())
+}
+
+// Helper side: the raw records, which the GUI parses with parse_log
+pub fn print_events(events: &[Event]) {
+    for event in events {
+        for record in &event.records {
+            println!("{}", record.raw);
+        }
+    }
+}
+
+pub fn validate_range(since: Option<i64>, until: Option<i64>) -> Result<(), String> {
+    if since.is_some_and(|since| since < 0) || until.is_some_and(|until| until < 0) {
+        return Err("times must not be negative".to_string());
+    }
+    if let (Some(since), Some(until)) = (since, until) {
+        if since > until {
+            return Err("the time range ends before it starts".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Records in the log_format = ENRICHED layout; audit.log.1 is the rotation before
+    fn log_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/audit-root/var/log/audit")
+    }
+
+    fn events() -> Vec<Event> {
+        parse_log(&fs::read_to_string(log_dir().join(LOG_FILE)).unwrap())
+    }
+
+    fn event(id: &str) -> Event {
+        events().into_iter().find(|event| event.id.ends_with(id)).unwrap_or_else(|| panic!("no event {}", id))
+    }
+
+    #[test]
+    fn records_are_grouped_into_events_by_serial() {
+        let events = events();
+        let ids: Vec<&str> = events.iter().map(|event| event.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "1760781000.123:812",
+                "1760781042.551:820",
+                "1760781100.904:833",
+                "1760781101.010:834",
+                "1760781220.300:851",
+                "1760781300.052:860",
+                "1760781360.000:871",
+            ]
+        );
+        // USER_LOGIN 834 is logged between the records of 833; EOE is dropped
+        let kinds: Vec<&str> = events[2].records.iter().map(|record| record.kind.as_str()).collect();
+        assert_eq!(kinds, ["SYSCALL", "CWD", "PATH", "PATH", "PROCTITLE"]);
+        assert_eq!(events[3].kind(), "USER_LOGIN");
+        assert_eq!(events[2].time, 1760781100);
+    }
+
+    #[test]
+    fn hex_encoded_keys_are_split() {
+        // key=6964656E7469747901706173737764 is "identity" 0x01 "passwd"
+        assert_eq!(event(":833").keys(), ["identity", "passwd"]);
+        assert_eq!(event(":812").keys(), ["privileged"]);
+        assert_eq!(event(":860").keys(), ["modules"]);
+        assert!(event(":820").keys().is_empty());
+    }
+
+    #[test]
+    fn execve_arguments_are_reassembled() {
+        // a2 is hex encoded because it contains spaces
+        assert_eq!(event(":812").command().as_deref(), Some("ls -l /tmp/a b c"));
+        // a2_len=40 a2[0]=... a2[1]=...
+        assert_eq!(event(":851").command().as_deref(), Some("bash -c echo hardn > /etc/motd.d/99-hardn"));
+    }
+
+    #[test]
+    fn proctitle_is_decoded_without_nul_bytes() {
+        assert_eq!(event(":833").command().as_deref(), Some("useradd -m carf"));
+        assert_eq!(event(":860").command().as_deref(), Some("modprobe vfat"));
+        assert_eq!(event(":812").record("PROCTITLE").and_then(|record| record.get("proctitle")), Some("ls -l /tmp/a b c"));
+    }
+
+    #[test]
+    fn event_details() {
+        let names = HashMap::from([(0, "root".to_string())]);
+        let ls = event(":812");
+        assert_eq!(ls.user(&names), "alice");
+        assert_eq!(ls.syscall().as_deref(), Some("execve"));
+        assert_eq!(ls.paths(), ["/usr/bin/ls", "/lib64/ld-linux-x86-64.so.2"]);
+        assert_eq!(event(":851").paths(), ["/usr/bin/bash"]);
+
+        // Not enriched: the syscall name comes from the table
+        let module = event(":860");
+        assert_eq!(module.syscall().as_deref(), Some("finit_module"));
+        assert_eq!(module.success(), Some(false));
+
+        let login = event(":834");
+        assert_eq!(login.user(&names), "root");
+        assert_eq!(login.summary(), "login · account bad user · /usr/sbin/sshd · from 203.0.113.7 · (failed)");
+        assert_eq!(event(":820").user(&names), "bob");
+    }
+
+    #[test]
+    fn events_come_from_rotated_logs_in_time_order() {
+        let events = read_events(&log_dir(), None, None).unwrap();
+        assert_eq!(events.len(), 10);
+        assert_eq!(events[0].kind(), "DAEMON_START");
+        assert!(events.windows(2).all(|pair| pair[0].time <= pair[1].time));
+
+        let recent = read_events(&log_dir(), Some(1760781100), Some(1760781300)).unwrap();
+        let ids: Vec<&str> = recent.iter().map(|event| event.id.rsplit(':').next().unwrap()).collect();
+        assert_eq!(ids, ["833", "834", "851", "860"]);
+
+        let newest = read_newest(&log_dir(), None, None, 2).unwrap();
+        assert_eq!(newest.iter().map(Event::kind).collect::<Vec<_>>(), ["SYSCALL", "SERVICE_STOP"]);
+    }
+
+    #[test]
+    fn rotated_logs_are_not_read_once_enough_events_were_found() {
+        let dir = std::env::temp_dir().join(format!("hardn-auditd-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join("audit.log.1")).unwrap();
+        fs::copy(log_dir().join(LOG_FILE), dir.join(LOG_FILE)).unwrap();
+
+        // audit.log.1 is a directory, so reading it fails
+        let newest = read_newest(&dir, None, None, 3);
+        let too_many = read_newest(&dir, None, None, 100);
+        let in_range = read_newest(&dir, Some(1760781001), None, 100);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(newest.unwrap().len(), 3);
+        assert!(too_many.is_err());
+        // audit.log starts before `since`, so the rotation holds nothing newer
+        assert_eq!(in_range.unwrap().len(), 6);
+    }
+
+    #[test]
+    fn time_ranges_are_validated() {
+        assert!(validate_range(Some(10), Some(20)).is_ok());
+        assert!(validate_range(Some(20), Some(10)).is_err());
+        assert!(validate_range(Some(-1), None).is_err());
+    }
+}
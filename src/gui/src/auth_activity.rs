@@ -0,0 +1,403 @@
+// What the authentication events add up to: failures per source address and per
+// user, brute-force bursts, logins that succeeded after failures and whether
+// Fail2Ban banned the addresses involved.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::IpAddr;
+
+use crate::authlog::{Activity, AuthEvent, Ban, EventKind};
+use crate::report::Severity;
+
+// A burst is this many failures with no pause longer than BURST_GAP between them;
+// the defaults of fail2ban's sshd jail are 5 failures within 10 minutes
+pub const BURST_FAILURES: u32 = 10;
+pub const BURST_GAP: i64 = 600;
+// Failures within this time before a success make the success suspicious
+pub const SUSPICIOUS_FAILURES: u32 = 3;
+pub const SUSPICIOUS_WINDOW: i64 = 3600;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BanState {
+    // Banned and not unbanned since
+    Banned { jail: String, since: i64 },
+    Unbanned { jail: String, at: i64 },
+    Never,
+}
+
+impl BanState {
+    pub fn label(&self) -> String {
+        match self {
+            BanState::Banned { jail, .. } => format!("🚫 banned [{}]", jail),
+            BanState::Unbanned { jail, .. } => format!("was banned [{}]", jail),
+            BanState::Never => "not banned".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SourceStats {
+    pub ip: IpAddr,
+    pub failures: u32,
+    pub successes: u32,
+    pub users: BTreeSet<String>,
+    pub last: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct UserStats {
+    pub user: String,
+    pub failures: u32,
+    pub successes: u32,
+    pub sources: BTreeSet<IpAddr>,
+    pub last: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Alert {
+    pub time: i64,
+    pub severity: Severity,
+    pub title: String,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Analysis {
+    pub activity: Activity,
+    // Most failures first
+    pub sources: Vec<SourceStats>,
+    pub users: Vec<UserStats>,
+    // Most severe first, then newest first
+    pub alerts: Vec<Alert>,
+}
+
+impl Analysis {
+    pub fn new(activity: Activity) -> Self {
+        let mut sources: BTreeMap<IpAddr, SourceStats> = BTreeMap::new();
+        let mut users: BTreeMap<String, UserStats> = BTreeMap::new();
+        for event in &activity.events {
+            let (failures, successes) = if event.success { (0, event.count) } else { (event.count, 0) };
+            if let Some(ip) = event.ip {
+                let stats = sources.entry(ip).or_insert_with(|| SourceStats {
+                    ip,
+                    failures: 0,
+                    successes: 0,
+                    users: BTreeSet::new(),
+                    last: event.time,
+                });
+                stats.failures += failures;
+                stats.successes += successes;
+                stats.users.insert(event.user.clone());
+                stats.last = stats.last.max(event.time);
+            }
+            let stats = users.entry(event.user.clone()).or_insert_with(|| UserStats {
+                user: event.user.clone(),
+                failures: 0,
+                successes: 0,
+                sources: BTreeSet::new(),
+                last: event.time,
+            });
+            stats.failures += failures;
+            stats.successes += successes;
+            stats.sources.extend(event.ip);
+            stats.last = stats.last.max(event.time);
+        }
+
+        let mut analysis = Analysis {
+            sources: sources.into_values().filter(|stats| stats.failures > 0).collect(),
+            users: users.into_values().filter(|stats| stats.failures > 0).collect(),
+            alerts: Vec::new(),
+            activity,
+        };
+        analysis.sources.sort_by(|a, b| b.failures.cmp(&a.failures).then(b.last.cmp(&a.last)));
+        analysis.users.sort_by(|a, b| b.failures.cmp(&a.failures).then(b.last.cmp(&a.last)));
+        analysis.alerts = analysis.bursts();
+        analysis.alerts.extend(analysis.successes_after_failures());
+        analysis.alerts.sort_by(|a, b| a.severity.cmp(&b.severity).then(b.time.cmp(&a.time)));
+        analysis
+    }
+
+    pub fn failures(&self) -> u32 {
+        self.activity.events.iter().filter(|event| !event.success).map(|event| event.count).sum()
+    }
+
+    pub fn successes(&self, kind: EventKind) -> u32 {
+        self.activity.events.iter().filter(|event| event.success && event.kind == kind).map(|event| event.count).sum()
+    }
+
+    // The last ban or unban of `ip` up to `time`, in any jail
+    pub fn ban_state(&self, ip: IpAddr, time: i64) -> BanState {
+        let last: Option<&Ban> = self.activity.bans.iter().filter(|ban| ban.ip == ip && ban.time <= time).max_by_key(|ban| ban.time);
+        match last {
+            Some(ban) if ban.banned => BanState::Banned { jail: ban.jail.clone(), since: ban.time },
+            Some(ban) => BanState::Unbanned { jail: ban.jail.clone(), at: ban.time },
+            None => BanState::Never,
+        }
+    }
+
+    // The first ban of `ip` at or after `time`
+    fn banned_after(&self, ip: IpAddr, time: i64) -> Option<&Ban> {
+        self.activity.bans.iter().filter(|ban| ban.ip == ip && ban.banned && ban.time >= time).min_by_key(|ban| ban.time)
+    }
+
+    // Runs of failures from one address, or against one account from none or several
+    fn bursts(&self) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        let failures: Vec<&AuthEvent> = self.activity.events.iter().filter(|event| !event.success).collect();
+
+        for source in &self.sources {
+            let times: Vec<(i64, u32)> = failures.iter().filter(|event| event.ip == Some(source.ip)).map(|event| (event.time, event.count)).collect();
+            for (start, end, count) in runs(&times) {
+                let succeeded = self.activity.events.iter().any(|event| event.success && event.ip == Some(source.ip) && event.time >= start);
+                let ban = match self.banned_after(source.ip, start) {
+                    Some(ban) => format!("banned by [{}] after {}", ban.jail, duration(ban.time - start)),
+                    None => "⚠️ never banned by Fail2Ban".to_string(),
+                };
+                let mut detail = format!("{} failures in {} · users {} · {}", count, duration(end - start), join(&source.users), ban);
+                if succeeded {
+                    detail.push_str(" · a login from this address succeeded afterwards");
+                }
+                alerts.push(Alert {
+                    time: start,
+                    severity: if succeeded { Severity::High } else { Severity::Medium },
+                    title: format!("Brute force from {}", source.ip),
+                    detail,
+                });
+            }
+        }
+
+        // One address is already reported above
+        for user in self.users.iter().filter(|user| user.sources.len() != 1) {
+            let times: Vec<(i64, u32)> = failures.iter().filter(|event| event.user == user.user).map(|event| (event.time, event.count)).collect();
+            for (start, end, count) in runs(&times) {
+                let origin = match user.sources.len() {
+                    0 => "locally".to_string(),
+                    n => format!("from {} addresses", n),
+                };
+                alerts.push(Alert {
+                    time: start,
+                    severity: Severity::Medium,
+                    title: format!("Password guessing against {}", user.user),
+                    detail: format!("{} failures {} in {}", count, origin, duration(end - start)),
+                });
+            }
+        }
+        alerts
+    }
+
+    // Successes preceded by failures for the same account or from the same address
+    fn successes_after_failures(&self) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        for success in self.activity.events.iter().filter(|event| event.success) {
+            let earlier: Vec<&AuthEvent> = self
+                .activity
+                .events
+                .iter()
+                .filter(|event| !event.success && event.kind == success.kind)
+                .filter(|event| event.time <= success.time && success.time - event.time <= SUSPICIOUS_WINDOW)
+                .filter(|event| event.user == success.user || (success.ip.is_some() && event.ip == success.ip))
+                .collect();
+            let count: u32 = earlier.iter().map(|event| event.count).sum();
+            if count < SUSPICIOUS_FAILURES {
+                continue;
+            }
+            let same_source = success.ip.is_some() && earlier.iter().any(|event| event.ip == success.ip);
+            let severity = match success.kind {
+                EventKind::Login if same_source => Severity::High,
+                EventKind::Login => Severity::Medium,
+                EventKind::Privilege => Severity::Low,
+            };
+            let what = match success.kind {
+                EventKind::Login => format!(
+                    "{} logged in through {} ({}){}",
+                    success.user,
+                    success.service,
+                    success.detail,
+                    success.ip.map(|ip| format!(" from {}", ip)).unwrap_or_default()
+                ),
+                EventKind::Privilege => format!("{} used {} {}", success.user, success.service, success.detail),
+            };
+            alerts.push(Alert {
+                time: success.time,
+                severity,
+                title: format!("{} after {} failures", what, count),
+                detail: format!(
+                    "failures within the hour before: {}",
+                    join(&earlier.iter().map(|event| event.ip.map(|ip| ip.to_string()).unwrap_or_else(|| event.user.clone())).collect())
+                ),
+            });
+        }
+        alerts
+    }
+
+    // Failures and successes per bucket of `width` seconds from `start`
+    pub fn histogram(&self, start: i64, end: i64, width: i64) -> Vec<(u32, u32)> {
+        let buckets = ((end - start) / width + 1).max(1) as usize;
+        let mut histogram = vec![(0, 0); buckets];
+        for event in self.activity.events.iter().filter(|event| event.time >= start && event.time <= end) {
+            let bucket = &mut histogram[((event.time - start) / width) as usize];
+            if event.success {
+                bucket.1 += event.count;
+            } else {
+                bucket.0 += event.count;
+            }
+        }
+        histogram
+    }
+}
+
+// (start, end, failures) of the runs in time ordered (time, count) pairs that reach
+// BURST_FAILURES without a gap longer than BURST_GAP
+fn runs(times: &[(i64, u32)]) -> Vec<(i64, i64, u32)> {
+    let mut runs = Vec::new();
+    let mut current: Option<(i64, i64, u32)> = None;
+    for &(time, count) in times {
+        current = match current {
+            Some((start, end, total)) if time - end <= BURST_GAP => Some((start, time, total + count)),
+            previous => {
+                runs.extend(previous.filter(|(_, _, total)| *total >= BURST_FAILURES));
+                Some((time, time, count))
+            }
+        };
+    }
+    runs.extend(current.filter(|(_, _, total)| *total >= BURST_FAILURES));
+    runs
+}
+
+fn duration(seconds: i64) -> String {
+    match seconds {
+        s if s < 120 => format!("{} s", s),
+        s if s < 7200 => format!("{} min", s / 60),
+        s => format!("{} h", s / 3600),
+    }
+}
+
+fn join<T: ToString>(items: &BTreeSet<T>) -> String {
+    let mut names: Vec<String> = items.iter().take(5).map(T::to_string).collect();
+    if items.len() > 5 {
+        names.push(format!("and {} more", items.len() - 5));
+    }
+    names.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authlog::{AuthLog, Source};
+    use std::path::Path;
+
+    // The fixture's auth.log has a brute force from 203.0.113.7 that Fail2Ban banned, one
+    // from 198.51.100.23 it missed and that ended in a login, slow password guessing
+    // against oracle from several addresses, and sudo and su failures
+    const NOW: i64 = 1792324800; // 2026-10-18T12:00:00Z
+    const SINCE: i64 = NOW - 86400;
+
+    fn analysis() -> Analysis {
+        // fail2ban.log and auth.log.1 stamps are local time
+        std::env::set_var("TZ", "UTC");
+        let log = AuthLog::new(Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/authlog-root"));
+        Analysis::new(log.activity(Source::AuthLog, SINCE, NOW))
+    }
+
+    fn ip(text: &str) -> IpAddr {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn counts_per_source_and_user() {
+        let analysis = analysis();
+        assert_eq!((analysis.failures(), analysis.successes(EventKind::Login), analysis.successes(EventKind::Privilege)), (41, 4, 2));
+        // Sources and users without failures are left out
+        assert_eq!(
+            analysis.sources.iter().map(|stats| (stats.ip.to_string(), stats.failures, stats.successes)).collect::<Vec<_>>(),
+            [
+                ("198.51.100.23".to_string(), 11, 1),
+                ("203.0.113.7".to_string(), 11, 0),
+                ("2001:db8::42".to_string(), 3, 0),
+                ("198.51.100.90".to_string(), 3, 0),
+                ("192.0.2.81".to_string(), 3, 0),
+                ("192.0.2.77".to_string(), 3, 0),
+                ("192.0.2.10".to_string(), 1, 2),
+            ]
+        );
+        assert_eq!(join(&analysis.sources[1].users), "admin, oracle, root, test, ubuntu");
+        let user = |name: &str| analysis.users.iter().find(|stats| stats.user == name).unwrap();
+        assert_eq!(analysis.users[0].user, "oracle");
+        assert_eq!((user("oracle").failures, user("oracle").sources.len()), (13, 5));
+        // "message repeated 3 times" counts three failures
+        assert_eq!(user("root").failures, 6);
+        assert!(user("bob").sources.is_empty());
+        assert_eq!((user("alice").failures, user("alice").successes), (2, 4));
+    }
+
+    #[test]
+    fn bursts_and_successes_after_failures() {
+        let analysis = analysis();
+        let alerts: Vec<_> = analysis.alerts.iter().map(|alert| (alert.severity, alert.time, alert.title.as_str(), alert.detail.as_str())).collect();
+        assert_eq!(
+            alerts,
+            [
+                (
+                    Severity::High,
+                    1792317915,
+                    "deploy logged in through sshd (password) from 198.51.100.23 after 11 failures",
+                    "failures within the hour before: 198.51.100.23"
+                ),
+                (
+                    Severity::High,
+                    1792317600,
+                    "Brute force from 198.51.100.23",
+                    "11 failures in 4 min · users deploy · ⚠️ never banned by Fail2Ban · a login from this address succeeded afterwards"
+                ),
+                (Severity::Medium, 1792321200, "Password guessing against oracle", "12 failures from 5 addresses in 16 min"),
+                (
+                    Severity::Medium,
+                    1792314601,
+                    "Brute force from 203.0.113.7",
+                    "11 failures in 87 s · users admin, oracle, root, test, ubuntu · banned by [sshd] after 45 s"
+                ),
+            ]
+        );
+        // Three sudo failures before su succeeded are not enough: they were bob's, not alice's
+        assert!(analysis.alerts.iter().all(|alert| alert.severity != Severity::Low));
+    }
+
+    #[test]
+    fn runs_need_enough_failures_without_long_pauses() {
+        assert_eq!(runs(&[]), []);
+        assert_eq!(runs(&[(0, 9)]), []);
+        assert_eq!(runs(&[(0, 5), (BURST_GAP, 5)]), [(0, BURST_GAP, 10)]);
+        assert_eq!(runs(&[(0, 5), (BURST_GAP + 1, 5)]), []);
+        assert_eq!(runs(&[(0, 10), (BURST_GAP + 1, 1), (BURST_GAP + 2, 12)]), [(0, 0, 10), (BURST_GAP + 1, BURST_GAP + 2, 13)]);
+        assert_eq!([duration(119), duration(120), duration(7199), duration(7200)], ["119 s", "2 min", "119 min", "2 h"]);
+    }
+
+    #[test]
+    fn histogram_buckets() {
+        let analysis = analysis();
+        let histogram = analysis.histogram(SINCE, NOW, 3600);
+        // The end is inclusive, so a day of hours has 25 buckets
+        assert_eq!(histogram.len(), 25);
+        assert_eq!((histogram[10], histogram[11]), ((1, 1), (1, 1)));
+        // 09:00-10:00 has alice's login, her sudo and the banned burst, 11:00-12:00 the sudo and su attempts
+        assert_eq!(&histogram[21..], [(11, 2), (11, 1), (17, 1), (0, 0)]);
+        assert_eq!(histogram.iter().map(|bucket| bucket.0 + bucket.1).sum::<u32>(), 41 + 6);
+
+        // Events outside the range are not counted
+        assert_eq!(analysis.histogram(1792317600, 1792317915, 60), [(3, 0), (2, 0), (3, 0), (2, 0), (1, 0), (0, 1)]);
+        assert_eq!(analysis.histogram(NOW, NOW, 3600), [(0, 0)]);
+    }
+
+    #[test]
+    fn bans_are_correlated_by_time() {
+        let analysis = analysis();
+        let attacker = ip("203.0.113.7");
+        assert_eq!(analysis.ban_state(attacker, 1792314645), BanState::Never);
+        assert_eq!(analysis.ban_state(attacker, 1792314646), BanState::Banned { jail: "sshd".to_string(), since: 1792314646 });
+        assert_eq!(analysis.ban_state(attacker, NOW), BanState::Unbanned { jail: "sshd".to_string(), at: 1792315246 });
+        assert_eq!(analysis.ban_state(ip("192.0.2.81"), NOW).label(), "🚫 banned [sshd]");
+        assert_eq!(analysis.ban_state(ip("198.51.100.23"), NOW), BanState::Never);
+
+        assert_eq!(analysis.banned_after(attacker, 1792314601).map(|ban| ban.time), Some(1792314646));
+        assert!(analysis.banned_after(attacker, 1792314647).is_none());
+    }
+}
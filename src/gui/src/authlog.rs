@@ -0,0 +1,735 @@
+// Authentication events from auth.log or the systemd journal, and Fail2Ban's bans.
+//
+// auth.log has rsyslog's auth and authpriv facilities, with a traditional or an
+// RFC 3339 timestamp depending on the rsyslog template:
+//
+//   Oct 18 13:15:42 web sshd[2210]: Failed password for root from 203.0.113.7 port 52114 ssh2
+//   2025-10-18T13:15:42.118302+02:00 web sudo: alice : TTY=pts/0 ; PWD=/home/alice ; USER=root ; COMMAND=/usr/bin/ls
+//
+// Without rsyslog the same messages are only in the journal, read with
+// `journalctl -o export SYSLOG_FACILITY=4 SYSLOG_FACILITY=10`: entries of KEY=value
+// lines separated by a blank line, where fields with binary data are written as the
+// key, a little endian 64 bit length and the raw bytes.
+//
+// Both are only readable by root and the adm or systemd-journal groups. The helper
+// prints the lines that classify() recognizes, newest MAX_LINES, journal entries in
+// `journalctl -o short-unix` form, followed by "### FAIL2BAN" and the Ban and Unban
+// lines of fail2ban.log.
+//
+// This file is compiled into both binaries and each only uses its own side of it.
+#![allow(dead_code)]
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+// Relative to the root, which HARDN_AUTHLOG_ROOT replaces for tests, e.g.
+// HARDN_AUTHLOG_ROOT=$PWD/src/gui/fixtures/authlog-root hardn-gui
+const AUTH_LOG: &str = "var/log/auth.log";
+const FAIL2BAN_LOG: &str = "var/log/fail2ban.log";
+const ROOT_ENV: &str = "HARDN_AUTHLOG_ROOT";
+// A `journalctl -o export` recording the GUI reads instead of the journal. Only
+// from_env() looks at it; the helper always runs journalctl.
+const JOURNAL_ENV: &str = "HARDN_JOURNAL_EXPORT";
+
+pub const JOURNALCTL: &str = "/usr/bin/journalctl";
+// Most recent authentication lines kept
+pub const MAX_LINES: usize = 2000;
+
+const FAIL2BAN_SECTION: &str = "### FAIL2BAN";
+
+// How far back to look and the width of one histogram bar, in seconds
+pub const WINDOWS: &[(&str, i64, i64)] = &[
+    ("Last 24 hours", 86400, 3600),
+    ("Last 7 days", 7 * 86400, 6 * 3600),
+    ("Last 30 days", 30 * 86400, 86400),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    AuthLog,
+    Journal,
+}
+
+impl Source {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Source::AuthLog => "auth.log",
+            Source::Journal => "systemd journal",
+        }
+    }
+}
+
+// One syslog message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    pub time: i64,
+    pub host: String,
+    pub program: String,
+    pub pid: Option<u32>,
+    pub message: String,
+}
+
+impl LogLine {
+    // `journalctl -o short-unix`, which parse_syslog_line reads back
+    pub fn short_unix(&self) -> String {
+        let tag = match self.pid {
+            Some(pid) => format!("{}[{}]", self.program, pid),
+            None => self.program.clone(),
+        };
+        format!("{} {} {}: {}", self.time, self.host, tag, self.message.replace('\n', " "))
+    }
+}
+
+// Days since 1970-01-01 of a proleptic Gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+// Seconds since the epoch of a wall clock time in the local time zone
+pub fn local_time(year: i32, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> Option<i64> {
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    tm.tm_year = year - 1900;
+    tm.tm_mon = month as i32 - 1;
+    tm.tm_mday = day as i32;
+    tm.tm_hour = hour as i32;
+    tm.tm_min = minute as i32;
+    tm.tm_sec = second as i32;
+    tm.tm_isdst = -1;
+    match unsafe { libc::mktime(&mut tm) } {
+        -1 => None,
+        time => Some(time),
+    }
+}
+
+fn local_year(time: i64) -> i32 {
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    let time = time as libc::time_t;
+    unsafe { libc::localtime_r(&time, &mut tm) };
+    tm.tm_year + 1900
+}
+
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+fn parse_clock(text: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = text.splitn(3, ':').map(|part| part.parse::<u32>().ok());
+    Some((parts.next()??, parts.next()??, parts.next()??))
+}
+
+// "2025-10-18T13:15:42.118302+02:00"
+fn parse_rfc3339(text: &str) -> Option<i64> {
+    let (date, clock) = text.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    let zone_start = clock.find(['Z', '+', '-'])?;
+    let (hour, minute, second) = parse_clock(clock[..zone_start].split('.').next()?)?;
+    let zone = &clock[zone_start..];
+    let offset = match zone {
+        "Z" => 0,
+        _ => {
+            let (hours, minutes) = zone[1..].split_once(':')?;
+            let offset = hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().ok()? * 60;
+            if zone.starts_with('-') {
+                -offset
+            } else {
+                offset
+            }
+        }
+    };
+    Some(days_from_civil(year, month, day) * 86400 + i64::from(hour * 3600 + minute * 60 + second) - offset)
+}
+
+// Timestamp of a syslog line and the rest of it. Traditional timestamps have no year:
+// it is the one that puts the line closest before `now`.
+fn parse_timestamp(line: &str, now: i64) -> Option<(i64, &str)> {
+    let first = line.chars().next()?;
+    if first.is_ascii_digit() {
+        let (stamp, rest) = line.split_once(' ')?;
+        let time = if stamp.contains('T') {
+            parse_rfc3339(stamp)?
+        } else {
+            stamp.split('.').next()?.parse().ok()?
+        };
+        return Some((time, rest));
+    }
+    // "Oct  8 13:15:42 "
+    let stamp = line.get(..15)?;
+    let month = MONTHS.iter().position(|month| stamp.starts_with(month))? as u32 + 1;
+    let day = stamp.get(4..6)?.trim().parse().ok()?;
+    let (hour, minute, second) = parse_clock(stamp.get(7..15)?)?;
+    let year = local_year(now);
+    let time = local_time(year, month, day, hour, minute, second)?;
+    let time = if time > now + 86400 { local_time(year - 1, month, day, hour, minute, second)? } else { time };
+    Some((time, line.get(16..)?))
+}
+
+pub fn parse_syslog_line(line: &str, now: i64) -> Option<LogLine> {
+    let (time, rest) = parse_timestamp(line, now)?;
+    let (host, rest) = rest.trim_start().split_once(' ')?;
+    let (tag, message) = rest.split_once(": ").or_else(|| rest.strip_suffix(':').map(|tag| (tag, "")))?;
+    let (program, pid) = match tag.split_once('[') {
+        Some((program, pid)) => (program, pid.strip_suffix(']').and_then(|pid| pid.parse().ok())),
+        None => (tag, None),
+    };
+    Some(LogLine { time, host: host.to_string(), program: program.to_string(), pid, message: message.to_string() })
+}
+
+// Entries of `journalctl -o export`
+pub fn parse_journal_export(data: &[u8]) -> Vec<LogLine> {
+    let mut lines = Vec::new();
+    let mut fields: Vec<(String, String)> = Vec::new();
+    let mut rest = data;
+    loop {
+        let end = rest.iter().position(|&b| b == b'\n').unwrap_or(rest.len());
+        let line = &rest[..end];
+        rest = rest.get(end + 1..).unwrap_or_default();
+        if line.is_empty() {
+            if !fields.is_empty() {
+                lines.extend(journal_entry(&fields));
+                fields.clear();
+            }
+            if rest.is_empty() {
+                break;
+            }
+            continue;
+        }
+        match line.iter().position(|&b| b == b'=') {
+            Some(eq) => fields.push((
+                String::from_utf8_lossy(&line[..eq]).to_string(),
+                String::from_utf8_lossy(&line[eq + 1..]).to_string(),
+            )),
+            // Binary field: the size, the data and a newline follow the key
+            None => {
+                let Some(size) = rest.get(..8).and_then(|size| size.try_into().ok()).map(u64::from_le_bytes) else {
+                    break;
+                };
+                let Some(value) = rest.get(8..8 + size as usize) else {
+                    break;
+                };
+                fields.push((String::from_utf8_lossy(line).to_string(), String::from_utf8_lossy(value).to_string()));
+                rest = rest.get(8 + size as usize + 1..).unwrap_or_default();
+            }
+        }
+    }
+    lines
+}
+
+fn journal_entry(fields: &[(String, String)]) -> Option<LogLine> {
+    let get = |key: &str| fields.iter().find(|(name, _)| name == key).map(|(_, value)| value.as_str());
+    let usec: i64 = get("__REALTIME_TIMESTAMP")?.parse().ok()?;
+    Some(LogLine {
+        time: usec / 1_000_000,
+        host: get("_HOSTNAME").unwrap_or("localhost").to_string(),
+        program: get("SYSLOG_IDENTIFIER").or_else(|| get("_COMM"))?.to_string(),
+        pid: get("SYSLOG_PID").or_else(|| get("_PID")).and_then(|pid| pid.parse().ok()),
+        message: get("MESSAGE")?.to_string(),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    // sshd, login and display managers
+    Login,
+    // sudo and su
+    Privilege,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthEvent {
+    pub time: i64,
+    pub service: String,
+    pub kind: EventKind,
+    pub success: bool,
+    // The account logged into, or the one that ran sudo or su
+    pub user: String,
+    pub ip: Option<IpAddr>,
+    pub detail: String,
+    // rsyslog folds identical lines into "message repeated N times: [ ... ]"
+    pub count: u32,
+}
+
+fn word_after<'a>(text: &'a str, marker: &str) -> Option<&'a str> {
+    text.split_once(marker)?.1.split_whitespace().next()
+}
+
+// "key=value" of PAM messages, where empty values are followed by two spaces
+fn pam_field<'a>(message: &'a str, key: &str) -> Option<&'a str> {
+    message
+        .split_whitespace()
+        .find_map(|field| field.strip_prefix(key)?.strip_prefix('='))
+        .filter(|value| !value.is_empty())
+}
+
+// PAM services logged as logins when a session opens
+const LOGIN_SERVICES: &[&str] = &["login", "gdm-password", "lightdm", "sddm"];
+
+pub fn classify(line: &LogLine) -> Option<AuthEvent> {
+    let (count, message) = match line
+        .message
+        .strip_prefix("message repeated ")
+        .and_then(|rest| rest.split_once(" times: [ "))
+    {
+        Some((count, message)) => (count.parse().ok()?, message.trim_end_matches(" ]").trim_end()),
+        None => (1, line.message.as_str()),
+    };
+    let event = |kind, success, user: &str, ip: Option<IpAddr>, detail: String| AuthEvent {
+        time: line.time,
+        service: line.program.clone(),
+        kind,
+        success,
+        user: user.to_string(),
+        ip,
+        detail,
+        count,
+    };
+    let ip_after_from = |text: &str| word_after(text, " from ").and_then(|ip| ip.parse().ok());
+
+    match line.program.as_str() {
+        "sshd" | "sshd-session" => {
+            // "Failed password for invalid user admin from 203.0.113.7 port 52114 ssh2"
+            if let Some(rest) = message.strip_prefix("Failed ") {
+                let (method, rest) = rest.split_once(" for ")?;
+                let (invalid, rest) = match rest.strip_prefix("invalid user ") {
+                    Some(rest) => (true, rest),
+                    None => (false, rest),
+                };
+                let user = rest.split(" from ").next()?;
+                let detail = if invalid { format!("{} for unknown user", method) } else { method.to_string() };
+                return Some(event(EventKind::Login, false, user, ip_after_from(rest), detail));
+            }
+            // "Accepted publickey for alice from 198.51.100.4 port 40022 ssh2: ED25519 SHA256:..."
+            if let Some(rest) = message.strip_prefix("Accepted ") {
+                let (method, rest) = rest.split_once(" for ")?;
+                let user = rest.split(" from ").next()?;
+                return Some(event(EventKind::Login, true, user, ip_after_from(rest), method.to_string()));
+            }
+            None
+        }
+        "sudo" => {
+            // "alice : TTY=pts/0 ; PWD=/home/alice ; USER=root ; COMMAND=/usr/bin/ls"
+            let Some((user, rest)) = message.split_once(" : ") else {
+                return pam_event(line, message, count);
+            };
+            let target = rest.split(" ; ").find_map(|field| field.strip_prefix("USER=")).unwrap_or("root");
+            let command = rest.split(" ; ").find_map(|field| field.strip_prefix("COMMAND=")).unwrap_or("");
+            if rest.starts_with("TTY=") || rest.starts_with("PWD=") {
+                return Some(event(EventKind::Privilege, true, user.trim(), None, format!("as {}: {}", target, command)));
+            }
+            // "bob : user NOT in sudoers ; TTY=pts/1 ; ..."; wrong passwords are counted from PAM
+            if rest.contains("NOT in sudoers") || rest.contains("NOT allowed") {
+                return Some(event(EventKind::Privilege, false, user.trim(), None, format!("not allowed to run {}", command)));
+            }
+            None
+        }
+        // "(to root) alice on pts/0"
+        "su" => match message.strip_prefix("(to ") {
+            Some(rest) => {
+                let (target, rest) = rest.split_once(") ")?;
+                let user = rest.split(" on ").next()?;
+                Some(event(EventKind::Privilege, true, user, None, format!("to {}", target)))
+            }
+            None => pam_event(line, message, count),
+        },
+        _ => pam_event(line, message, count),
+    }
+}
+
+// pam_unix auth failures of everything but sshd, which logs its own "Failed" lines,
+// and sessions of console and graphical logins:
+//
+//   pam_unix(sudo:auth): authentication failure; logname=bob uid=1001 euid=0 tty=/dev/pts/1 ruser=bob rhost=  user=bob
+//   pam_unix(login:session): session opened for user alice(uid=1000) by LOGIN(uid=0)
+fn pam_event(line: &LogLine, message: &str, count: u32) -> Option<AuthEvent> {
+    let (module, rest) = message.split_once("): ")?;
+    let (_, service) = module.split_once('(')?;
+    let (service, facility) = service.split_once(':')?;
+    if service == "sshd" {
+        return None;
+    }
+    let kind = if LOGIN_SERVICES.contains(&service) { EventKind::Login } else { EventKind::Privilege };
+    let event = |success, user: &str, ip, detail| AuthEvent {
+        time: line.time,
+        service: service.to_string(),
+        kind,
+        success,
+        user: user.to_string(),
+        ip,
+        detail,
+        count,
+    };
+    if facility == "auth" && rest.starts_with("authentication failure") {
+        let target = pam_field(rest, "user").unwrap_or("?");
+        let ip = pam_field(rest, "rhost").and_then(|rhost| rhost.parse().ok());
+        return Some(match kind {
+            EventKind::Login => event(false, target, ip, "wrong password".to_string()),
+            // Who tried to become `target`
+            EventKind::Privilege => {
+                let user = pam_field(rest, "ruser").or_else(|| pam_field(rest, "logname")).unwrap_or(target);
+                event(false, user, ip, format!("wrong password for {}", target))
+            }
+        });
+    }
+    if facility == "session" && kind == EventKind::Login {
+        let user = word_after(rest, "session opened for user ")?;
+        return Some(event(true, user.split('(').next()?, None, "session opened".to_string()));
+    }
+    None
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ban {
+    pub time: i64,
+    pub jail: String,
+    pub ip: IpAddr,
+    pub banned: bool,
+}
+
+// "2025-10-18 13:20:01,234 fail2ban.actions        [812]: NOTICE  [sshd] Ban 203.0.113.7",
+// in local time; "Restore Ban" after a restart counts as a ban
+pub fn parse_ban(line: &str) -> Option<Ban> {
+    let (date, rest) = line.split_once(' ')?;
+    let (clock, rest) = rest.split_once(' ')?;
+    let mut date = date.splitn(3, '-').map(|part| part.parse::<u32>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    let (hour, minute, second) = parse_clock(clock.split(',').next()?)?;
+    let (_, rest) = rest.split_once("]: ")?.1.split_once('[')?;
+    let (jail, action) = rest.split_once("] ")?;
+    let mut words = action.split_whitespace();
+    let banned = match words.next()? {
+        "Ban" => true,
+        "Unban" => false,
+        "Restore" if words.next() == Some("Ban") => true,
+        _ => return None,
+    };
+    Some(Ban {
+        time: local_time(year as i32, month, day, hour, minute, second)?,
+        jail: jail.to_string(),
+        ip: words.next()?.parse().ok()?,
+        banned,
+    })
+}
+
+// Authentication events and bans, as read locally or from the helper's transcript
+#[derive(Debug, Clone, Default)]
+pub struct Activity {
+    pub events: Vec<AuthEvent>,
+    pub bans: Vec<Ban>,
+    // The file or command the events came from
+    pub source: String,
+    pub errors: Vec<String>,
+}
+
+impl Activity {
+    pub fn parse_transcript(output: &str, since: i64, now: i64) -> Self {
+        let mut activity = Activity::default();
+        let mut in_bans = false;
+        for line in output.lines() {
+            if line == FAIL2BAN_SECTION {
+                in_bans = true;
+            } else if in_bans {
+                activity.bans.extend(parse_ban(line));
+            } else if let Some(event) = parse_syslog_line(line, now).as_ref().and_then(classify) {
+                if event.time >= since {
+                    activity.events.push(event);
+                }
+            }
+        }
+        activity
+    }
+}
+
+pub struct AuthLog {
+    root: PathBuf,
+    // Recording read instead of running journalctl
+    journal_export: Option<PathBuf>,
+}
+
+impl AuthLog {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        AuthLog { root: root.into(), journal_export: None }
+    }
+
+    pub fn with_journal_export(mut self, path: impl Into<PathBuf>) -> Self {
+        self.journal_export = Some(path.into());
+        self
+    }
+
+    // GUI side only: the helper reads the real logs whatever its environment says
+    pub fn from_env() -> Self {
+        let log = Self::new(std::env::var(ROOT_ENV).unwrap_or_else(|_| "/".to_string()));
+        match std::env::var_os(JOURNAL_ENV) {
+            Some(path) => log.with_journal_export(path),
+            None => log,
+        }
+    }
+
+    // Whether rsyslog writes auth.log; otherwise the journal is the only source
+    pub fn default_source(&self) -> Source {
+        if self.root.join(AUTH_LOG).exists() {
+            Source::AuthLog
+        } else {
+            Source::Journal
+        }
+    }
+
+    // The recognized lines since `since`, newest MAX_LINES, and where they came from
+    pub fn read_lines(&self, source: Source, since: i64, now: i64) -> io::Result<(Vec<String>, String)> {
+        let keep = |line: &LogLine| line.time >= since && classify(line).is_some();
+        let (mut lines, origin) = match source {
+            Source::AuthLog => {
+                let mut lines = Vec::new();
+                let path = self.root.join(AUTH_LOG);
+                // The first rotation is uncompressed; older ones are gzipped and skipped
+                let rotated = path.with_extension("log.1");
+                for file in [&rotated, &path] {
+                    let content = match fs::read(file) {
+                        Ok(content) => content,
+                        Err(e) if e.kind() == io::ErrorKind::NotFound && file == &rotated => continue,
+                        Err(e) => return Err(io::Error::new(e.kind(), format!("{}: {}", file.display(), e))),
+                    };
+                    lines.extend(
+                        String::from_utf8_lossy(&content)
+                            .lines()
+                            .filter(|line| parse_syslog_line(line, now).is_some_and(|parsed| keep(&parsed)))
+                            .map(str::to_string),
+                    );
+                }
+                (lines, path.display().to_string())
+            }
+            Source::Journal => {
+                let (export, origin) = self.journal_export(since)?;
+                let lines = parse_journal_export(&export).iter().filter(|line| keep(line)).map(LogLine::short_unix).collect();
+                (lines, origin)
+            }
+        };
+        lines.drain(..lines.len().saturating_sub(MAX_LINES));
+        Ok((lines, origin))
+    }
+
+    fn journal_export(&self, since: i64) -> io::Result<(Vec<u8>, String)> {
+        if let Some(path) = &self.journal_export {
+            let export = fs::read(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+            return Ok((export, path.display().to_string()));
+        }
+        let output = Command::new(JOURNALCTL)
+            .args(["--output=export", "--no-pager", &format!("--since=@{}", since), "SYSLOG_FACILITY=4", "SYSLOG_FACILITY=10"])
+            .env_clear()
+            .env("LANG", "C.UTF-8")
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", JOURNALCTL, e)))?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!("journalctl failed: {}", String::from_utf8_lossy(&output.stderr).trim())));
+        }
+        Ok((output.stdout, "journalctl".to_string()))
+    }
+
+    // Ban and Unban lines of fail2ban.log and its first rotation
+    pub fn read_bans(&self) -> io::Result<Vec<String>> {
+        let path = self.root.join(FAIL2BAN_LOG);
+        let mut lines = Vec::new();
+        for file in [path.with_extension("log.1"), path.clone()] {
+            match fs::read(&file) {
+                Ok(content) => lines.extend(
+                    String::from_utf8_lossy(&content).lines().filter(|line| parse_ban(line).is_some()).map(str::to_string),
+                ),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(io::Error::new(e.kind(), format!("{}: {}", file.display(), e))),
+            }
+        }
+        Ok(lines)
+    }
+
+    // Without the helper: whatever the user's groups allow
+    pub fn activity(&self, source: Source, since: i64, now: i64) -> Activity {
+        let mut activity = Activity::default();
+        match self.read_lines(source, since, now) {
+            Ok((lines, origin)) => {
+                activity = Activity::parse_transcript(&lines.join("\n"), since, now);
+                activity.source = origin;
+            }
+            Err(e) => activity.errors.push(denied_hint(e)),
+        }
+        match self.read_bans() {
+            Ok(lines) => activity.bans = lines.iter().filter_map(|line| parse_ban(line)).collect(),
+            Err(e) => activity.errors.push(denied_hint(e)),
+        }
+        activity
+    }
+}
+
+fn denied_hint(e: io::Error) -> String {
+    match e.kind() {
+        io::ErrorKind::PermissionDenied => format!("{}; load as administrator", e),
+        _ => e.to_string(),
+    }
+}
+
+pub fn print_transcript(lines: &[String], bans: &[String]) {
+    for line in lines {
+        println!("{}", line);
+    }
+    println!("{}", FAIL2BAN_SECTION);
+    for line in bans {
+        println!("{}", line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    // auth.log with RFC 3339 stamps and its rotation with traditional ones, fail2ban.log
+    // banning one of the brute forcing addresses, and a journal export of another host
+    // with a multi-line sudo message stored as binary data
+    const NOW: i64 = 1792324800; // 2026-10-18T12:00:00Z
+    const SINCE: i64 = NOW - 86400;
+
+    fn root() -> PathBuf {
+        // Traditional and fail2ban.log stamps are local time
+        std::env::set_var("TZ", "UTC");
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/authlog-root")
+    }
+
+    fn journal() -> Vec<u8> {
+        fs::read(Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/authlog-journal.export")).unwrap()
+    }
+
+    #[test]
+    fn syslog_timestamps() {
+        root();
+        let line = parse_syslog_line("2026-10-18T11:15:42.118302+02:00 web sudo: alice : TTY=pts/0", NOW).unwrap();
+        assert_eq!((line.time, line.host.as_str(), line.program.as_str(), line.pid), (1792322142 - 7200, "web", "sudo", None));
+        assert_eq!(line.message, "alice : TTY=pts/0");
+        assert_eq!(parse_syslog_line("2026-10-18T09:15:42Z web sshd[7]: x", NOW).unwrap().time, 1792314942);
+        assert_eq!(parse_syslog_line("2026-10-18T09:15:42-01:30 web sshd[7]: x", NOW).unwrap().time, 1792314942 + 5400);
+
+        // Traditional stamps take the year that puts them before now
+        let line = parse_syslog_line("Oct  8 09:15:42 web sshd[2210]: Failed password", NOW).unwrap();
+        assert_eq!((line.time, line.pid), (1792314942 - 10 * 86400, Some(2210)));
+        assert_eq!(parse_syslog_line("Oct 19 09:15:42 web cron[1]: x", NOW).unwrap().time, 1792314942 + 86400);
+        assert_eq!(parse_syslog_line("Dec 31 23:00:00 web cron[1]: x", NOW).unwrap().time, 1767222000);
+
+        // short-unix output of the journal, which is what the helper prints
+        let line = parse_syslog_line("1792314100 web sshd[3001]: Failed password for root", NOW).unwrap();
+        assert_eq!(parse_syslog_line(&line.short_unix(), NOW), Some(line));
+        assert_eq!(parse_syslog_line("1792314100.512 web kernel:", NOW).unwrap().message, "");
+        assert_eq!(parse_syslog_line("not a log line", NOW), None);
+    }
+
+    #[test]
+    fn auth_log_and_rotation() {
+        let log = AuthLog::new(root());
+        assert_eq!(log.default_source(), Source::AuthLog);
+        let (lines, origin) = log.read_lines(Source::AuthLog, SINCE, NOW).unwrap();
+        assert!(origin.ends_with("authlog-root/var/log/auth.log"));
+        // The rotation comes first; unrecognized lines are dropped
+        assert!(lines[0].starts_with("Oct 17 22:14:03 web login[702]: pam_unix(login:auth)"));
+        assert!(lines.iter().all(|line| !line.contains("Server listening") && !line.contains("FAILED LOGIN")));
+
+        let activity = log.activity(Source::AuthLog, SINCE, NOW);
+        assert!(activity.errors.is_empty());
+        assert_eq!(activity.events.len(), 45);
+        assert_eq!(activity.events.iter().map(|event| event.count).sum::<u32>(), 47);
+        let event = |time| activity.events.iter().find(|event| event.time == time).unwrap();
+        assert_eq!(
+            *event(1792275243),
+            AuthEvent {
+                time: 1792275243,
+                service: "login".to_string(),
+                kind: EventKind::Login,
+                success: false,
+                user: "carol".to_string(),
+                ip: None,
+                detail: "wrong password".to_string(),
+                count: 1,
+            }
+        );
+        // "message repeated 3 times: [ Failed password for root from 203.0.113.7 ... ]"
+        let repeated = event(1792314688);
+        assert_eq!((repeated.count, repeated.user.as_str(), repeated.ip), (3, "root", "203.0.113.7".parse().ok()));
+        assert_eq!(event(1792314623).detail, "password for unknown user");
+        assert_eq!(event(1792314030).detail, "as root: /usr/bin/systemctl restart nginx");
+        assert_eq!(event(1792323100).detail, "not allowed to run /usr/bin/cat /etc/shadow");
+        assert_eq!((event(1792323300).detail.as_str(), event(1792323320).detail.as_str()), ("wrong password for root", "to root"));
+
+        // The window cuts off the rotation
+        assert_eq!(log.activity(Source::AuthLog, 1792314000, NOW).events.len(), 41);
+        let missing = AuthLog::new(root().join("var")).activity(Source::AuthLog, SINCE, NOW);
+        assert!(missing.errors[0].ends_with("var/var/log/auth.log: No such file or directory (os error 2)"));
+    }
+
+    #[test]
+    fn journal_export() {
+        let lines = parse_journal_export(&journal());
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0].short_unix(), "1792314100 web sshd[3001]: Failed password for root from 203.0.113.99 port 60000 ssh2");
+        // The binary MESSAGE keeps its newline, which short-unix folds
+        let sudo = &lines[3];
+        assert_eq!(sudo.message, "carol : TTY=pts/2 ; PWD=/home/carol ; USER=root ;\nCOMMAND=/usr/bin/apt update");
+        assert_eq!(
+            sudo.short_unix(),
+            "1792314200 web sudo[3100]: carol : TTY=pts/2 ; PWD=/home/carol ; USER=root ; COMMAND=/usr/bin/apt update"
+        );
+        let folded = parse_syslog_line(&sudo.short_unix(), NOW).unwrap();
+        assert_eq!(classify(&folded).unwrap().detail, "as root: /usr/bin/apt update");
+
+        // Entries without a timestamp or a message are skipped, truncated binary data ends the export
+        assert_eq!(parse_journal_export(b"MESSAGE=x\nSYSLOG_IDENTIFIER=sshd\n\n__REALTIME_TIMESTAMP=5\nMESSAGE=y\n"), []);
+        let data = journal();
+        let binary = data.windows(8).position(|window| window == b"MESSAGE\n").unwrap();
+        assert_eq!(parse_journal_export(&data[..binary + 12]).len(), 3);
+
+        let log = AuthLog::new(root()).with_journal_export(Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/authlog-journal.export"));
+        let (lines, origin) = log.read_lines(Source::Journal, SINCE, NOW).unwrap();
+        assert!(origin.ends_with("fixtures/authlog-journal.export"));
+        // "Server listening" is not an authentication event
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[3], sudo.short_unix());
+    }
+
+    #[test]
+    fn fail2ban_bans() {
+        root();
+        let ban = parse_ban("2026-10-18 09:10:46,646 fail2ban.actions        [812]: NOTICE  [sshd] Ban 203.0.113.7").unwrap();
+        assert_eq!(ban, Ban { time: 1792314646, jail: "sshd".to_string(), ip: "203.0.113.7".parse().unwrap(), banned: true });
+        let restored = parse_ban("2026-10-18 09:10:46,646 fail2ban.actions [812]: NOTICE  [recidive] Restore Ban 2001:db8::42").unwrap();
+        assert_eq!((restored.jail.as_str(), restored.ip.to_string(), restored.banned), ("recidive", "2001:db8::42".to_string(), true));
+        assert_eq!(parse_ban("2026-10-18 09:10:12,612 fail2ban.filter [812]: INFO    [sshd] Found 203.0.113.7 - 2026-10-18 09:10:12"), None);
+        assert_eq!(parse_ban("2026-10-18 09:10:46,646 fail2ban.actions [812]: NOTICE  [sshd] Ban not-an-address"), None);
+
+        let log = AuthLog::new(root());
+        let bans: Vec<_> = log.read_bans().unwrap().iter().filter_map(|line| parse_ban(line)).collect();
+        assert_eq!(
+            bans.iter().map(|ban| (ban.time, ban.ip.to_string(), ban.banned)).collect::<Vec<_>>(),
+            [
+                (1792314646, "203.0.113.7".to_string(), true),
+                (1792315246, "203.0.113.7".to_string(), false),
+                (1792321300, "192.0.2.81".to_string(), true),
+            ]
+        );
+        assert_eq!(log.activity(Source::AuthLog, SINCE, NOW).bans, bans);
+    }
+
+    #[test]
+    fn transcripts_read_back_into_activity() {
+        let log = AuthLog::new(root());
+        let (lines, _) = log.read_lines(Source::AuthLog, SINCE, NOW).unwrap();
+        let bans = log.read_bans().unwrap();
+        let transcript = format!("{}\n{}\n{}\n", lines.join("\n"), FAIL2BAN_SECTION, bans.join("\n"));
+        let activity = Activity::parse_transcript(&transcript, SINCE, NOW);
+        let local = log.activity(Source::AuthLog, SINCE, NOW);
+        assert_eq!((activity.events, activity.bans), (local.events, local.bans));
+        assert!(activity.source.is_empty());
+    }
+}
@@ -0,0 +1,208 @@
+// Tool catalog: every script shipped under /usr/share/hardn/tools, described by
+// tools/catalog.json. Scripts missing from the manifest are still discovered and
+// listed under "Other", using their "# Purpose:" header line as description.
+
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::action::HardnAction;
+use crate::protocol::TOOLS_DIR;
+
+pub const MANIFEST_FILE: &str = "catalog.json";
+const OTHER_CATEGORY: &str = "Other";
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_expected_secs() -> u64 {
+    60
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ToolEntry {
+    pub script: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub icon: String,
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_true")]
+    pub requires_root: bool,
+    #[serde(default)]
+    pub stig: Option<String>,
+    #[serde(default = "default_expected_secs")]
+    pub expected_secs: u64,
+    #[serde(default)]
+    pub destructive: bool,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    tools: Vec<ToolEntry>,
+}
+
+impl ToolEntry {
+    fn discovered(script: &str) -> Self {
+        ToolEntry {
+            script: script.to_string(),
+            name: String::new(),
+            icon: String::new(),
+            category: String::new(),
+            description: String::new(),
+            requires_root: true,
+            stig: None,
+            expected_secs: default_expected_secs(),
+            destructive: false,
+            hidden: false,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        if self.icon.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} {}", self.icon, self.name)
+        }
+    }
+
+    pub fn action(&self) -> HardnAction {
+        let mut action = HardnAction::tool(&self.label(), &self.script).expected_secs(self.expected_secs);
+        action.needs_root = self.requires_root;
+        if self.destructive {
+            action = action.destructive();
+        }
+        action
+    }
+
+    // Case-insensitive match against everything shown for the tool
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return true;
+        }
+        [
+            self.name.as_str(),
+            self.script.as_str(),
+            self.category.as_str(),
+            self.description.as_str(),
+            self.stig.as_deref().unwrap_or(""),
+        ]
+        .iter()
+        .any(|field| field.to_lowercase().contains(&query))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ToolCatalog {
+    pub tools: Vec<ToolEntry>,
+}
+
+impl ToolCatalog {
+    pub fn load_default() -> Self {
+        Self::load(Path::new(TOOLS_DIR))
+    }
+
+    pub fn load(tools_dir: &Path) -> Self {
+        let mut entries: Vec<ToolEntry> = fs::read_to_string(tools_dir.join(MANIFEST_FILE))
+            .ok()
+            .and_then(|content| match serde_json::from_str::<Manifest>(&content) {
+                Ok(manifest) => Some(manifest.tools),
+                Err(e) => {
+                    eprintln!("Invalid tool manifest {}: {}", tools_dir.join(MANIFEST_FILE).display(), e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        let scripts = discover_scripts(tools_dir);
+        for script in &scripts {
+            if !entries.iter().any(|entry| &entry.script == script) {
+                entries.push(ToolEntry::discovered(script));
+            }
+        }
+
+        let tools = entries
+            .into_iter()
+            .filter(|entry| !entry.hidden && scripts.contains(&entry.script))
+            .map(|mut entry| {
+                if entry.name.is_empty() {
+                    entry.name = name_from_script(&entry.script);
+                }
+                if entry.category.is_empty() {
+                    entry.category = OTHER_CATEGORY.to_string();
+                }
+                if entry.description.is_empty() {
+                    entry.description = read_purpose(&tools_dir.join(&entry.script)).unwrap_or_default();
+                }
+                entry
+            })
+            .collect();
+
+        ToolCatalog { tools }
+    }
+
+    // Tools grouped by category, in manifest order with "Other" last
+    pub fn by_category(&self) -> Vec<(String, Vec<&ToolEntry>)> {
+        let mut groups: Vec<(String, Vec<&ToolEntry>)> = Vec::new();
+        for tool in &self.tools {
+            match groups.iter_mut().find(|(category, _)| *category == tool.category) {
+                Some((_, tools)) => tools.push(tool),
+                None => groups.push((tool.category.clone(), vec![tool])),
+            }
+        }
+        groups.sort_by_key(|(category, _)| category == OTHER_CATEGORY);
+        groups
+    }
+}
+
+// Script paths relative to the tools directory, including the stig/ subdirectory
+fn discover_scripts(tools_dir: &Path) -> Vec<String> {
+    let mut scripts = Vec::new();
+    for (dir, prefix) in [(tools_dir.to_path_buf(), ""), (tools_dir.join("stig"), "stig/")] {
+        let Ok(read_dir) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in read_dir.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if file_name.ends_with(".sh") && entry.path().is_file() {
+                scripts.push(format!("{}{}", prefix, file_name));
+            }
+        }
+    }
+    scripts.sort();
+    scripts
+}
+
+// "stig/core_dumps.sh" -> "Core Dumps"
+fn name_from_script(script: &str) -> String {
+    let stem = script.rsplit('/').next().unwrap_or(script).trim_end_matches(".sh");
+    stem.split(['_', '-'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// The "# Purpose: ..." line of the standard HARDN script header
+fn read_purpose(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    content
+        .lines()
+        .take(15)
+        .find_map(|line| line.trim().strip_prefix("# Purpose:"))
+        .map(|purpose| purpose.trim().to_string())
+}
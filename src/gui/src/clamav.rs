@@ -0,0 +1,383 @@
+// ClamAV scan results and a client for clamd's local socket.
+//
+// clamscan prints one line per file followed by a summary:
+//
+//   /home/user/eicar.com: Win.Test.EICAR_HDB-1 FOUND
+//   /etc/passwd: OK
+//   /root/secret: Access denied. ERROR
+//
+// clamd answers INSTREAM in the same form with "stream" in place of the path.
+// ClamdClient scans file by file over INSTREAM, which also works when clamd runs as
+// a user that cannot read the files itself.
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::config;
+use crate::malware::SCAN_LOG_PREFIX;
+
+const SCAN_LOG_EXTENSION: &str = "log";
+
+// Socket of Debian's clamav-daemon, overridable for other layouts and testing
+const CLAMD_SOCKET_ENV: &str = "HARDN_CLAMD_SOCKET";
+const CLAMD_SOCKET: &str = "/var/run/clamav/clamd.ctl";
+const CLAMD_TIMEOUT: Duration = Duration::from_secs(120);
+// Well below clamd's default StreamMaxLength of 25M per chunk
+const CHUNK_SIZE: usize = 64 * 1024;
+
+// What check_malware in modules/audit.sh scans
+pub const DEFAULT_SCAN_TARGETS: &[&str] = &["/bin", "/sbin", "/usr/bin", "/usr/sbin", "/etc", "/home"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanEngine {
+    // clamscan as the GUI user: only what the user can read
+    Clamscan,
+    // clamscan through the helper
+    ClamscanAsRoot,
+    // The running clamd over its socket; signatures are already loaded, so it starts fast
+    Clamd,
+}
+
+impl ScanEngine {
+    pub const ALL: [ScanEngine; 3] = [ScanEngine::Clamscan, ScanEngine::ClamscanAsRoot, ScanEngine::Clamd];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScanEngine::Clamscan => "clamscan",
+            ScanEngine::ClamscanAsRoot => "clamscan as administrator",
+            ScanEngine::Clamd => "clamd daemon",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Detection {
+    pub path: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanLine {
+    // Scanned or deliberately skipped (empty files, symlinks, exclusions)
+    Clean(String),
+    Found(Detection),
+    Error { path: String, message: String },
+}
+
+impl ScanLine {
+    pub fn path(&self) -> &str {
+        match self {
+            ScanLine::Clean(path) | ScanLine::Error { path, .. } => path,
+            ScanLine::Found(detection) => &detection.path,
+        }
+    }
+
+    // Same form clamscan prints
+    pub fn to_line(&self) -> String {
+        match self {
+            ScanLine::Clean(path) => format!("{}: OK", path),
+            ScanLine::Found(Detection { path, signature }) => format!("{}: {} FOUND", path, signature),
+            ScanLine::Error { path, message } => format!("{}: {} ERROR", path, message),
+        }
+    }
+}
+
+// One result line; the summary, banners and anything else give None
+pub fn parse_line(line: &str) -> Option<ScanLine> {
+    let line = line.trim_end_matches(['\0', '\r', '\n']).trim_start();
+    if let Some(rest) = line.strip_suffix(" FOUND") {
+        let (path, signature) = rest.rsplit_once(": ")?;
+        return Some(ScanLine::Found(Detection { path: path.to_string(), signature: signature.to_string() }));
+    }
+    if let Some(rest) = line.strip_suffix(" ERROR") {
+        let (path, message) = rest.rsplit_once(": ")?;
+        return Some(ScanLine::Error { path: path.to_string(), message: message.trim_end_matches('.').to_string() });
+    }
+    let (path, status) = line.rsplit_once(": ")?;
+    match status {
+        "OK" | "Empty file" | "Symbolic link" | "Excluded" => Some(ScanLine::Clean(path.to_string())),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScanTally {
+    pub scanned: usize,
+    pub detections: Vec<Detection>,
+    pub errors: Vec<(String, String)>,
+}
+
+impl ScanTally {
+    pub fn record(&mut self, line: &ScanLine) {
+        self.scanned += 1;
+        match line {
+            ScanLine::Clean(_) => {}
+            ScanLine::Found(detection) => self.detections.push(detection.clone()),
+            ScanLine::Error { path, message } => self.errors.push((path.clone(), message.clone())),
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} files scanned · {} detections · {} errors",
+            self.scanned,
+            self.detections.len(),
+            self.errors.len()
+        )
+    }
+}
+
+// Detections in the newest clamav-scan-<timestamp>.log (audit or scheduled scan)
+pub fn load_latest_log(dir: &Path) -> Result<Option<(PathBuf, Vec<Detection>)>, String> {
+    let files = config::timestamped_files(dir, SCAN_LOG_PREFIX, SCAN_LOG_EXTENSION)?;
+    let Some(path) = files.into_iter().next_back() else {
+        return Ok(None);
+    };
+    let content = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let detections = content
+        .lines()
+        .filter_map(parse_line)
+        .filter_map(|line| match line {
+            ScanLine::Found(detection) => Some(detection),
+            _ => None,
+        })
+        .collect();
+    Ok(Some((path, detections)))
+}
+
+pub struct ClamdClient {
+    socket: PathBuf,
+}
+
+impl ClamdClient {
+    pub fn new(socket: &Path) -> Self {
+        ClamdClient { socket: socket.to_path_buf() }
+    }
+
+    pub fn from_env() -> Self {
+        let socket = std::env::var(CLAMD_SOCKET_ENV).unwrap_or_else(|_| CLAMD_SOCKET.to_string());
+        Self::new(Path::new(&socket))
+    }
+
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+
+    pub fn ping(&self) -> io::Result<()> {
+        match self.command("PING")?.as_str() {
+            "PONG" => Ok(()),
+            reply => Err(io::Error::new(io::ErrorKind::InvalidData, format!("unexpected reply to PING: {}", reply))),
+        }
+    }
+
+    // e.g. "ClamAV 1.0.3/27245/Tue Apr  2 08:24:21 2024"
+    pub fn version(&self) -> io::Result<String> {
+        self.command("VERSION")
+    }
+
+    pub fn scan_file(&self, path: &Path) -> ScanLine {
+        let display = path.to_string_lossy().to_string();
+        let result = fs::File::open(path).and_then(|file| self.scan_stream(file));
+        match result {
+            Ok(ScanLine::Clean(_)) => ScanLine::Clean(display),
+            Ok(ScanLine::Found(Detection { signature, .. })) => ScanLine::Found(Detection { path: display, signature }),
+            Ok(ScanLine::Error { message, .. }) => ScanLine::Error { path: display, message },
+            Err(e) => ScanLine::Error { path: display, message: e.to_string() },
+        }
+    }
+
+    // INSTREAM: chunks prefixed with their length as 4 bytes big endian, then a
+    // zero length. The result's path is "stream".
+    pub fn scan_stream(&self, mut reader: impl Read) -> io::Result<ScanLine> {
+        let mut stream = self.connect()?;
+        stream.write_all(b"zINSTREAM\0")?;
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+        let sent = loop {
+            let n = reader.read(&mut buffer)?;
+            if n == 0 {
+                break stream.write_all(&0u32.to_be_bytes());
+            }
+            let chunk = stream.write_all(&(n as u32).to_be_bytes()).and_then(|_| stream.write_all(&buffer[..n]));
+            if chunk.is_err() {
+                // clamd closes the connection when the stream exceeds StreamMaxLength,
+                // after saying so; prefer its reply over the broken pipe
+                break chunk;
+            }
+        };
+        let reply = read_reply(&mut stream);
+        match (reply, sent) {
+            (Ok(reply), _) if !reply.is_empty() => parse_line(&reply)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("unexpected reply: {}", reply))),
+            (_, Err(e)) | (Err(e), _) => Err(e),
+            (Ok(_), Ok(())) => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "clamd closed the connection")),
+        }
+    }
+
+    fn command(&self, command: &str) -> io::Result<String> {
+        let mut stream = self.connect()?;
+        stream.write_all(format!("z{}\0", command).as_bytes())?;
+        read_reply(&mut stream)
+    }
+
+    fn connect(&self) -> io::Result<UnixStream> {
+        let stream = UnixStream::connect(&self.socket)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", self.socket.display(), e)))?;
+        stream.set_read_timeout(Some(CLAMD_TIMEOUT))?;
+        stream.set_write_timeout(Some(CLAMD_TIMEOUT))?;
+        Ok(stream)
+    }
+}
+
+// z-commands are answered with one NUL terminated reply
+fn read_reply(stream: &mut UnixStream) -> io::Result<String> {
+    let mut reply = Vec::new();
+    let mut byte = [0u8; 1];
+    while stream.read(&mut byte)? == 1 && byte[0] != 0 {
+        reply.push(byte[0]);
+    }
+    Ok(String::from_utf8_lossy(&reply).trim().to_string())
+}
+
+// Scan every regular file under `paths` through clamd, reporting each result as it
+// comes in. Symlinks are not followed, like clamscan's default. Stops early once
+// `cancel` is set; returns whether the walk finished.
+pub fn scan_with_clamd(client: &ClamdClient, paths: &[String], cancel: &AtomicBool, mut report: impl FnMut(ScanLine)) -> bool {
+    let mut pending: Vec<PathBuf> = paths.iter().rev().map(PathBuf::from).collect();
+    while let Some(path) = pending.pop() {
+        if cancel.load(Ordering::Relaxed) {
+            return false;
+        }
+        let file_type = match fs::symlink_metadata(&path) {
+            Ok(meta) => meta.file_type(),
+            Err(e) => {
+                report(ScanLine::Error { path: path.to_string_lossy().to_string(), message: e.to_string() });
+                continue;
+            }
+        };
+        if file_type.is_dir() {
+            match fs::read_dir(&path) {
+                Ok(read_dir) => {
+                    let mut entries: Vec<PathBuf> = read_dir.flatten().map(|entry| entry.path()).collect();
+                    entries.sort_by(|a, b| b.cmp(a));
+                    pending.extend(entries);
+                }
+                Err(e) => report(ScanLine::Error { path: path.to_string_lossy().to_string(), message: e.to_string() }),
+            }
+        } else if file_type.is_file() {
+            report(client.scan_file(&path));
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixListener;
+    use std::sync::{Arc, Mutex};
+
+    // Answers PING, VERSION and INSTREAM like clamd, finding the EICAR string, and
+    // keeps the size of every chunk it was sent
+    fn stub_clamd(socket: &Path) -> Arc<Mutex<Vec<u32>>> {
+        let listener = UnixListener::bind(socket).unwrap();
+        let chunks = Arc::new(Mutex::new(Vec::new()));
+        let seen = chunks.clone();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().map_while(Result::ok) {
+                let mut command = Vec::new();
+                let mut byte = [0u8; 1];
+                while stream.read(&mut byte).unwrap() == 1 && byte[0] != 0 {
+                    command.push(byte[0]);
+                }
+                let reply = match command.as_slice() {
+                    b"zPING" => "PONG".to_string(),
+                    b"zVERSION" => "ClamAV 1.0.3/27245/Tue Apr  2 08:24:21 2024".to_string(),
+                    b"zINSTREAM" => {
+                        let mut content = Vec::new();
+                        loop {
+                            let mut size = [0u8; 4];
+                            stream.read_exact(&mut size).unwrap();
+                            let size = u32::from_be_bytes(size);
+                            if size == 0 {
+                                break;
+                            }
+                            seen.lock().unwrap().push(size);
+                            let mut chunk = vec![0u8; size as usize];
+                            stream.read_exact(&mut chunk).unwrap();
+                            content.extend(chunk);
+                        }
+                        match String::from_utf8_lossy(&content).contains("EICAR-STANDARD-ANTIVIRUS-TEST-FILE") {
+                            true => "stream: Win.Test.EICAR_HDB-1 FOUND".to_string(),
+                            false => "stream: OK".to_string(),
+                        }
+                    }
+                    _ => "UNKNOWN COMMAND".to_string(),
+                };
+                stream.write_all(format!("{}\0", reply).as_bytes()).unwrap();
+            }
+        });
+        chunks
+    }
+
+    #[test]
+    fn scans_through_a_stub_clamd() {
+        let dir = std::env::temp_dir().join(format!("hardn-clamav-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("tree/sub")).unwrap();
+        fs::write(dir.join("tree/eicar.com"), "X5O!P%@AP[4\\PZX54(P^)7CC)7}$EICAR-STANDARD-ANTIVIRUS-TEST-FILE!$H+H*").unwrap();
+        fs::write(dir.join("tree/sub/large.bin"), vec![b'a'; CHUNK_SIZE + 10]).unwrap();
+        std::os::unix::fs::symlink("/etc/passwd", dir.join("tree/passwd")).unwrap();
+        let chunks = stub_clamd(&dir.join("clamd.ctl"));
+        let client = ClamdClient::new(&dir.join("clamd.ctl"));
+
+        let ping = client.ping();
+        let version = client.version().unwrap();
+        let tree = dir.join("tree").to_string_lossy().to_string();
+        let missing = dir.join("missing").to_string_lossy().to_string();
+        let mut lines = Vec::new();
+        let finished = scan_with_clamd(&client, &[tree.clone(), missing.clone()], &AtomicBool::new(false), |line| lines.push(line));
+        let cancelled = scan_with_clamd(&client, std::slice::from_ref(&tree), &AtomicBool::new(true), |_| panic!("scanned after cancel"));
+        let unreachable = ClamdClient::new(&dir.join("no-such.ctl")).ping().unwrap_err();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(ping.is_ok());
+        assert_eq!(version, "ClamAV 1.0.3/27245/Tue Apr  2 08:24:21 2024");
+        assert!(finished && !cancelled);
+        // Sorted walk, symlinks skipped, and the stream's name replaced by the file's path
+        assert_eq!(
+            lines[..2],
+            [
+                ScanLine::Found(Detection { path: format!("{}/eicar.com", tree), signature: "Win.Test.EICAR_HDB-1".to_string() }),
+                ScanLine::Clean(format!("{}/sub/large.bin", tree)),
+            ]
+        );
+        assert!(matches!(&lines[2], ScanLine::Error { path, message } if *path == missing && message.contains("No such file")));
+        assert_eq!(lines.len(), 3);
+        assert_eq!(*chunks.lock().unwrap(), [68, CHUNK_SIZE as u32, 10]);
+        assert!(unreachable.to_string().ends_with("no-such.ctl: No such file or directory (os error 2)"));
+    }
+
+    #[test]
+    fn scan_lines_and_tallies() {
+        assert_eq!(
+            parse_line("/home/user/eicar.com: Win.Test.EICAR_HDB-1 FOUND"),
+            Some(ScanLine::Found(Detection { path: "/home/user/eicar.com".to_string(), signature: "Win.Test.EICAR_HDB-1".to_string() }))
+        );
+        assert_eq!(parse_line("/etc/passwd: OK"), Some(ScanLine::Clean("/etc/passwd".to_string())));
+        assert_eq!(
+            parse_line("/root/secret: Access denied. ERROR"),
+            Some(ScanLine::Error { path: "/root/secret".to_string(), message: "Access denied".to_string() })
+        );
+        assert_eq!(parse_line("stream: OK\0"), Some(ScanLine::Clean("stream".to_string())));
+        assert_eq!(parse_line("----------- SCAN SUMMARY -----------"), None);
+        assert_eq!(parse_line("Infected files: 1"), None);
+
+        let mut tally = ScanTally::default();
+        ["/a: OK", "/b: Eicar FOUND", "/c: Access denied. ERROR"].iter().filter_map(|line| parse_line(line)).for_each(|line| tally.record(&line));
+        assert_eq!(tally.summary(), "3 files scanned · 1 detections · 1 errors");
+    }
+}
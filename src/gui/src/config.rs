@@ -0,0 +1,579 @@
+// hardn.conf, the shell-style KEY="value" file that usr/bin/hardn sources from
+// $HARDN_CONFIG_DIR (default /etc/hardn). Only plain assignments are understood;
+// anything else is ignored the same way the GUI ignores unknown keys.
+//
+// The Settings tab edits the keys listed in SCHEMA. ConfigFile keeps every line as
+// written and only replaces the value of an assignment, so comments, ordering and
+// quoting survive. The package installs the file as root:hardn 0640; the helper then
+// prints it as "### FILE <lines> <path>" followed by "<number>:<text>" for each
+// non-blank line, and carries out the changes itself after checking them again.
+//
+// This file is compiled into both binaries and each only uses its own side of it.
+#![allow(dead_code)]
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::net::IpAddr;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+use crate::malware::write_atomic;
+
+// Same variables and defaults as usr/bin/hardn
+const CONFIG_DIR_ENV: &str = "HARDN_CONFIG_DIR";
+const CONFIG_DIR: &str = "/etc/hardn";
+const LOG_DIR_ENV: &str = "HARDN_LOG_DIR";
+const LOG_DIR: &str = "/var/log/hardn";
+pub const CONFIG_FILE_NAME: &str = "hardn.conf";
+// What debian/postinst copies to /etc/hardn when there is no configuration yet
+pub const TEMPLATE_FILE: &str = "/usr/share/hardn/templates/hardn.conf";
+const FILE_MARKER: &str = "### FILE ";
+// A few more than SCHEMA has keys
+pub const MAX_CHANGES: usize = 64;
+
+// templates/hardn.conf ships with this value
+pub const DEFAULT_MINIMUM_HARDENING_SCORE: u32 = 70;
+
+#[derive(Debug, Clone, Default)]
+pub struct HardnConfig {
+    // The assignment that wins for each key, as ConfigFile reads them
+    settings: Vec<Setting>,
+}
+
+impl HardnConfig {
+    pub fn path() -> PathBuf {
+        let dir = std::env::var(CONFIG_DIR_ENV).unwrap_or_else(|_| CONFIG_DIR.to_string());
+        Path::new(&dir).join(CONFIG_FILE_NAME)
+    }
+
+    pub fn load() -> io::Result<Self> {
+        Self::load_from(&Self::path())
+    }
+
+    pub fn load_from(path: &Path) -> io::Result<Self> {
+        Ok(Self::parse(&fs::read_to_string(path)?))
+    }
+
+    pub fn parse(content: &str) -> Self {
+        HardnConfig { settings: ConfigFile::parse(content).settings() }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.settings.iter().find(|setting| setting.key == key).map(|setting| setting.value.as_str())
+    }
+
+    pub fn minimum_hardening_score(&self) -> u32 {
+        self.get("MINIMUM_HARDENING_SCORE")
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_MINIMUM_HARDENING_SCORE)
+    }
+}
+
+// `KEY="value"  # comment`, `KEY='value'` or `KEY=value`, optionally after `export`
+pub fn parse_assignment(line: &str) -> Option<(String, String)> {
+    let line = line.trim();
+    let line = line.strip_prefix("export ").unwrap_or(line).trim_start();
+    if line.starts_with('#') {
+        return None;
+    }
+    let (key, raw) = line.split_once('=')?;
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+
+    let value = match raw.chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+            let rest = &raw[1..];
+            rest[..rest.find(quote)?].to_string()
+        }
+        _ => raw.split(|c: char| c.is_whitespace() || c == '#').next().unwrap_or("").to_string(),
+    };
+    Some((key.to_string(), value))
+}
+
+// The file the helper edits; HARDN_CONFIG_DIR is not passed through pkexec
+pub fn system_path() -> PathBuf {
+    Path::new(CONFIG_DIR).join(CONFIG_FILE_NAME)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    // "true" or "false"
+    Bool,
+    // Whole number from min to max, in `unit`
+    Integer { min: u64, max: u64, unit: &'static str },
+    // Number with an optional K, M or G suffix, e.g. "100M"
+    Size,
+    IpAddress,
+    Choice(&'static [&'static str]),
+    // Written by the package; shown but never changed
+    ReadOnly,
+}
+
+impl Kind {
+    pub fn check(&self, value: &str) -> Result<(), String> {
+        let is_number = |text: &str| !text.is_empty() && text.chars().all(|c| c.is_ascii_digit());
+        match self {
+            Kind::Bool if value == "true" || value == "false" => Ok(()),
+            Kind::Bool => Err("must be true or false".to_string()),
+            Kind::Integer { min, max, unit } => match value.parse::<u64>() {
+                Ok(number) if is_number(value) && (*min..=*max).contains(&number) => Ok(()),
+                _ => Err(format!("must be a whole number of {} from {} to {}", unit, min, max)),
+            },
+            Kind::Size => {
+                let digits = value.strip_suffix(['K', 'M', 'G']).unwrap_or(value);
+                if is_number(digits) && digits.parse::<u64>().is_ok_and(|number| number > 0) {
+                    Ok(())
+                } else {
+                    Err("must be a size such as 500K, 100M or 1G".to_string())
+                }
+            }
+            Kind::IpAddress => value.parse::<IpAddr>().map(|_| ()).map_err(|_| "must be an IPv4 or IPv6 address".to_string()),
+            Kind::Choice(options) if options.contains(&value) => Ok(()),
+            Kind::Choice(options) => Err(format!("must be one of {}", options.join(", "))),
+            Kind::ReadOnly => Err("is set by the package".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Spec {
+    pub key: &'static str,
+    // The comment heading the key in templates/hardn.conf
+    pub section: &'static str,
+    pub kind: Kind,
+    pub description: &'static str,
+}
+
+const LOG_LEVELS: &[&str] = &["debug", "info", "warn", "error"];
+const INTERVALS: &[&str] = &["daily", "weekly"];
+// As ufw accepts them, see firewall::POLICIES
+const POLICIES: &[&str] = &["allow", "deny", "reject"];
+
+const fn spec(key: &'static str, section: &'static str, kind: Kind, description: &'static str) -> Spec {
+    Spec { key, section, kind, description }
+}
+
+// Every key of templates/hardn.conf, in its order
+pub const SCHEMA: &[Spec] = &[
+    spec("HARDN_VERSION", "General", Kind::ReadOnly, "HARDN release the file was written for"),
+    spec("HARDN_CONFIG_VERSION", "General", Kind::ReadOnly, "Format version of this file"),
+    spec("LOG_LEVEL", "Logging", Kind::Choice(LOG_LEVELS), "Least severe message the hardn scripts log"),
+    spec("LOG_RETENTION_DAYS", "Logging", Kind::Integer { min: 1, max: 3650, unit: "days" }, "How long logs are kept"),
+    spec("MAX_LOG_SIZE", "Logging", Kind::Size, "Size at which a log is rotated"),
+    spec("BACKUP_RETENTION_DAYS", "Backup", Kind::Integer { min: 1, max: 3650, unit: "days" }, "How long backups are kept"),
+    spec("AUTO_BACKUP_ENABLED", "Backup", Kind::Bool, "Take backups on a schedule"),
+    spec("BACKUP_BEFORE_CHANGES", "Backup", Kind::Bool, "Back up configuration files before hardening changes them"),
+    spec("ENABLE_KERNEL_HARDENING", "Security", Kind::Bool, "Apply the sysctl kernel hardening"),
+    spec("ENABLE_NETWORK_HARDENING", "Security", Kind::Bool, "Apply the network stack hardening"),
+    spec("ENABLE_AUDIT_SYSTEM", "Security", Kind::Bool, "Install and configure auditd"),
+    spec("ENABLE_INTRUSION_DETECTION", "Security", Kind::Bool, "Set up intrusion detection"),
+    spec("ENABLE_MALWARE_PROTECTION", "Security", Kind::Bool, "Set up malware scanning"),
+    spec("ENABLE_INTEGRITY_MONITORING", "Security", Kind::Bool, "Set up file integrity monitoring"),
+    spec("UFW_DEFAULT_INCOMING", "Firewall", Kind::Choice(POLICIES), "Default UFW policy for incoming traffic"),
+    spec("UFW_DEFAULT_OUTGOING", "Firewall", Kind::Choice(POLICIES), "Default UFW policy for outgoing traffic"),
+    spec("UFW_ENABLE_LOGGING", "Firewall", Kind::Bool, "Turn on UFW logging"),
+    spec("USE_SECURE_DNS", "DNS", Kind::Bool, "Point the resolver at the servers below"),
+    spec("PRIMARY_DNS", "DNS", Kind::IpAddress, "First DNS server"),
+    spec("SECONDARY_DNS", "DNS", Kind::IpAddress, "Second DNS server"),
+    spec("BACKUP_DNS", "DNS", Kind::IpAddress, "DNS server used when the others fail"),
+    spec("ENABLE_MONITORING", "Monitoring", Kind::Bool, "Run the hardn-monitor service"),
+    spec("MONITORING_INTERVAL", "Monitoring", Kind::Integer { min: 10, max: 86400, unit: "seconds" }, "Time between monitoring checks"),
+    spec("ALERT_ON_SECURITY_EVENTS", "Monitoring", Kind::Bool, "Raise alerts for security events"),
+    spec("AUTO_UPDATE_SIGNATURES", "Update", Kind::Bool, "Update malware and rootkit signatures automatically"),
+    spec("AUTO_UPDATE_SECURITY_PACKAGES", "Update", Kind::Bool, "Install security updates automatically"),
+    spec("UPDATE_CHECK_INTERVAL", "Update", Kind::Choice(INTERVALS), "How often to look for updates"),
+    spec("ENFORCE_STIG_COMPLIANCE", "Compliance", Kind::Bool, "Apply the STIG checks during setup"),
+    spec("LYNIS_SCAN_INTERVAL", "Compliance", Kind::Choice(INTERVALS), "How often Lynis audits the system"),
+    spec("MINIMUM_HARDENING_SCORE", "Compliance", Kind::Integer { min: 0, max: 100, unit: "points" }, "Lynis hardening index the Audit tab expects"),
+    spec("ENABLE_APPARMOR", "Advanced", Kind::Bool, "Set up AppArmor"),
+    spec("ENABLE_FAIL2BAN", "Advanced", Kind::Bool, "Set up Fail2Ban"),
+    spec("ENABLE_AIDE", "Advanced", Kind::Bool, "Set up AIDE"),
+    spec("ENABLE_RKHUNTER", "Advanced", Kind::Bool, "Set up rkhunter"),
+    spec("ENABLE_CLAMAV", "Advanced", Kind::Bool, "Set up ClamAV"),
+    spec("NON_INTERACTIVE_MODE", "Non-interactive", Kind::Bool, "Never prompt, as with --non-interactive"),
+    spec("FORCE_MODE", "Non-interactive", Kind::Bool, "Skip confirmations, as with --force"),
+    spec("DRY_RUN_MODE", "Non-interactive", Kind::Bool, "Only report what would change"),
+];
+
+pub fn find_spec(key: &str) -> Option<&'static Spec> {
+    SCHEMA.iter().find(|spec| spec.key == key)
+}
+
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    let spec = find_spec(key).ok_or_else(|| format!("{} is not a setting the GUI edits", key))?;
+    spec.kind.check(value).map_err(|e| format!("{} {}", key, e))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Setting {
+    pub key: String,
+    pub value: String,
+    // Of the assignment that wins
+    pub line: usize,
+}
+
+// hardn.conf line by line, as written
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigFile {
+    lines: Vec<String>,
+    trailing_newline: bool,
+}
+
+impl ConfigFile {
+    pub fn parse(content: &str) -> Self {
+        ConfigFile {
+            lines: content.lines().map(str::to_string).collect(),
+            trailing_newline: content.ends_with('\n'),
+        }
+    }
+
+    pub fn content(&self) -> String {
+        let mut content = self.lines.join("\n");
+        if self.trailing_newline {
+            content.push('\n');
+        }
+        content
+    }
+
+    // One per key, in the order the keys first appear
+    pub fn settings(&self) -> Vec<Setting> {
+        let mut settings: Vec<Setting> = Vec::new();
+        for (index, line) in self.lines.iter().enumerate() {
+            let Some((key, value)) = parse_assignment(line) else {
+                continue;
+            };
+            let setting = Setting { key, value, line: index + 1 };
+            match settings.iter_mut().find(|seen| seen.key == setting.key) {
+                Some(seen) => *seen = setting,
+                None => settings.push(setting),
+            }
+        }
+        settings
+    }
+
+    pub fn get(&self, key: &str) -> Option<Setting> {
+        self.settings().into_iter().find(|setting| setting.key == key)
+    }
+
+    // Replace the value of the assignment that wins, keeping its quotes and comment. A
+    // key that is not set goes after the last key of its section, or at the end.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        validate_setting(key, value)?;
+        if let Some(setting) = self.get(key) {
+            let line = &mut self.lines[setting.line - 1];
+            *line = replace_value(line, value).ok_or_else(|| format!("line {} is not an assignment", setting.line))?;
+            return Ok(());
+        }
+        let section = find_spec(key).map(|spec| spec.section);
+        let after = self
+            .settings()
+            .iter()
+            .filter(|setting| find_spec(&setting.key).map(|spec| spec.section) == section)
+            .map(|setting| setting.line)
+            .max()
+            .unwrap_or(self.lines.len());
+        self.lines.insert(after, format!("{}=\"{}\"", key, value));
+        Ok(())
+    }
+
+    // Changes that were computed against another copy of the file fail when a value
+    // they replace is no longer there
+    pub fn apply(&mut self, changes: &[SettingChange]) -> Result<(), String> {
+        for change in changes {
+            let current = self.get(&change.key).map(|setting| setting.value);
+            if current != change.old {
+                return Err(format!(
+                    "{} changed since it was read (now {})",
+                    change.key,
+                    current.as_deref().unwrap_or("not set")
+                ));
+            }
+            self.set(&change.key, &change.value)?;
+        }
+        Ok(())
+    }
+
+    pub fn print_transcript(&self, path: &Path) {
+        println!("{}{} {}", FILE_MARKER, self.lines.len(), path.display());
+        for (index, line) in self.lines.iter().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            println!("{}:{}", index + 1, line);
+        }
+    }
+
+    // The file the helper printed and its path
+    pub fn parse_transcript(output: &str) -> Option<(PathBuf, Self)> {
+        let mut lines = output.lines().skip_while(|line| !line.starts_with(FILE_MARKER));
+        let (count, path) = lines.next()?.strip_prefix(FILE_MARKER)?.split_once(' ')?;
+        let mut file = ConfigFile { lines: vec![String::new(); count.parse().ok()?], trailing_newline: true };
+        for line in lines {
+            let numbered = line.split_once(':').and_then(|(number, text)| Some((number.parse::<usize>().ok()?, text)));
+            if let Some((number, text)) = numbered.filter(|(number, _)| (1..=file.lines.len()).contains(number)) {
+                file.lines[number - 1] = text.to_string();
+            }
+        }
+        Some((PathBuf::from(path), file))
+    }
+}
+
+// `line` with the value after `=` replaced
+fn replace_value(line: &str, value: &str) -> Option<String> {
+    let (head, raw) = line.split_at(line.find('=')? + 1);
+    let (quote, rest) = match raw.chars().next() {
+        Some(quote @ ('"' | '\'')) => (quote.to_string(), &raw[raw[1..].find(quote)? + 2..]),
+        _ => (String::new(), &raw[raw.find(|c: char| c.is_whitespace() || c == '#').unwrap_or(raw.len())..]),
+    };
+    Some(format!("{}{}{}{}{}", head, quote, value, quote, rest))
+}
+
+// One value to write. `old` is the value the GUI read, None when the key was not set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettingChange {
+    pub key: String,
+    pub old: Option<String>,
+    pub value: String,
+}
+
+impl SettingChange {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.old.as_ref().is_some_and(|old| old.contains(char::is_control)) {
+            return Err(format!("the old value of {} must be a single line", self.key));
+        }
+        validate_setting(&self.key, &self.value)
+    }
+
+    pub fn describe(&self) -> String {
+        match &self.old {
+            Some(old) => format!("{}: {} -> {}", self.key, old, self.value),
+            None => format!("{}: set to {}", self.key, self.value),
+        }
+    }
+}
+
+// Write the changes to system_path(), keeping its owner and mode. Without a file yet,
+// start from the template the way debian/postinst does.
+pub fn install(changes: &[SettingChange]) -> io::Result<String> {
+    let path = system_path();
+    let (content, mode, owner) = match fs::symlink_metadata(&path) {
+        Ok(meta) if !meta.file_type().is_file() => {
+            return Err(io::Error::other(format!("{} is not a regular file", path.display())));
+        }
+        Ok(meta) => {
+            (fs::read_to_string(&path)?, meta.permissions().mode() & 0o7777, (meta.uid(), meta.gid()))
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let template = fs::read_to_string(TEMPLATE_FILE).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", TEMPLATE_FILE, e)))?;
+            // root:hardn like the directory
+            let dir = fs::metadata(CONFIG_DIR).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", CONFIG_DIR, e)))?;
+            (template, 0o640, (0, dir.gid()))
+        }
+        Err(e) => return Err(io::Error::new(e.kind(), format!("{}: {}", path.display(), e))),
+    };
+
+    let mut file = ConfigFile::parse(&content);
+    file.apply(changes).map_err(io::Error::other)?;
+    for change in changes {
+        println!("{}", change.describe());
+    }
+    write_atomic(&path, file.content().as_bytes(), mode)?;
+    std::os::unix::fs::chown(&path, Some(owner.0), Some(owner.1))?;
+    Ok(format!("Updated {} settings in {}", changes.len(), path.display()))
+}
+
+// Where the hardn scripts keep their logs and reports
+pub fn log_dir() -> PathBuf {
+    PathBuf::from(std::env::var(LOG_DIR_ENV).unwrap_or_else(|_| LOG_DIR.to_string()))
+}
+
+// "<prefix><timestamp>.<extension>" files in `dir`, oldest first (the names embed the timestamp)
+pub fn timestamped_files(dir: &Path, prefix: &str, extension: &str) -> Result<Vec<PathBuf>, String> {
+    let read_dir = fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let mut files: Vec<PathBuf> = read_dir
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == extension)
+                && path.file_name().is_some_and(|name| name.to_string_lossy().starts_with(prefix))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+// Per-user GUI state: $XDG_DATA_HOME/hardn, by default ~/.local/share/hardn
+pub fn user_data_dir() -> PathBuf {
+    std::env::var("XDG_DATA_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(&std::env::var("HOME").unwrap_or_default()).join(".local/share"))
+        .join("hardn")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // templates/hardn.conf as a user might have edited it: other quoting, comments
+    // after values, a key assigned twice and lines the GUI does not understand
+    fn fixture() -> String {
+        fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/hardn.conf")).unwrap()
+    }
+
+    fn change(key: &str, old: Option<&str>, value: &str) -> SettingChange {
+        SettingChange { key: key.to_string(), old: old.map(str::to_string), value: value.to_string() }
+    }
+
+    #[test]
+    fn files_round_trip_byte_for_byte() {
+        let content = fixture();
+        assert_eq!(ConfigFile::parse(&content).content(), content);
+        // The template has no newline after its last line
+        for content in ["", "\n", "A=1", "A=1\n\n", "\n\n# only comments\n", "A=\"x\"\r\nB=2\r\n"] {
+            assert_eq!(ConfigFile::parse(content).content(), content.replace('\r', ""), "{:?}", content);
+        }
+    }
+
+    #[test]
+    fn assignments() {
+        let file = ConfigFile::parse(&fixture());
+        let value = |key: &str| file.get(key).map(|setting| (setting.value, setting.line));
+        assert_eq!(value("LOG_LEVEL"), Some(("info".to_string(), 9)));
+        assert_eq!(value("LOG_RETENTION_DAYS"), Some(("30".to_string(), 10)));
+        assert_eq!(value("MAX_LOG_SIZE"), Some(("100M".to_string(), 11)));
+        assert_eq!(value("UFW_DEFAULT_INCOMING"), Some(("reject".to_string(), 22)));
+        assert_eq!(value("CUSTOM_HOOK"), Some(("/usr/local/bin/after-hardening".to_string(), 29)));
+        assert_eq!(value("BACKUP_BEFORE_CHANGES"), None);
+        let keys: Vec<String> = file.settings().into_iter().map(|setting| setting.key).collect();
+        assert_eq!(keys.len(), 12);
+        assert_eq!(keys[..3], ["HARDN_VERSION", "HARDN_CONFIG_VERSION", "LOG_LEVEL"]);
+
+        assert_eq!(parse_assignment("  export  KEY=\"a b\" # c"), Some(("KEY".to_string(), "a b".to_string())));
+        assert_eq!(parse_assignment("KEY="), Some(("KEY".to_string(), String::new())));
+        assert_eq!(parse_assignment("KEY=\"unterminated"), None);
+        assert_eq!(parse_assignment("# KEY=1"), None);
+        assert_eq!(parse_assignment("BAD-KEY=1"), None);
+        assert_eq!(parse_assignment("=1"), None);
+
+        assert_eq!(HardnConfig::parse(&fixture()).minimum_hardening_score(), DEFAULT_MINIMUM_HARDENING_SCORE);
+        assert_eq!(HardnConfig::parse("MINIMUM_HARDENING_SCORE=\"85\"\n").minimum_hardening_score(), 85);
+        assert_eq!(HardnConfig::parse("MINIMUM_HARDENING_SCORE=high\n").minimum_hardening_score(), DEFAULT_MINIMUM_HARDENING_SCORE);
+    }
+
+    #[test]
+    fn values_are_replaced_in_place() {
+        assert_eq!(replace_value("LOG_LEVEL=\"info\"  # debug, info", "warn").as_deref(), Some("LOG_LEVEL=\"warn\"  # debug, info"));
+        assert_eq!(replace_value("export MAX_LOG_SIZE='100M'", "1G").as_deref(), Some("export MAX_LOG_SIZE='1G'"));
+        assert_eq!(replace_value("LOG_RETENTION_DAYS=30\t# days", "7").as_deref(), Some("LOG_RETENTION_DAYS=7\t# days"));
+        assert_eq!(replace_value("LOG_RETENTION_DAYS=30#days", "7").as_deref(), Some("LOG_RETENTION_DAYS=7#days"));
+        assert_eq!(replace_value("PRIMARY_DNS=", "1.1.1.1").as_deref(), Some("PRIMARY_DNS=1.1.1.1"));
+        assert_eq!(replace_value("LOG_LEVEL=\"info", "warn"), None);
+        assert_eq!(replace_value("LOG_LEVEL", "warn"), None);
+
+        let content = fixture();
+        let mut file = ConfigFile::parse(&content);
+        file.set("LOG_LEVEL", "warn").unwrap();
+        file.set("LOG_RETENTION_DAYS", "7").unwrap();
+        file.set("MAX_LOG_SIZE", "1G").unwrap();
+        // The assignment that wins changes, the earlier one stays
+        file.set("UFW_DEFAULT_INCOMING", "deny").unwrap();
+        let expected = content
+            .replace("LOG_LEVEL=\"info\"  #", "LOG_LEVEL=\"warn\"  #")
+            .replace("LOG_RETENTION_DAYS=30\t#", "LOG_RETENTION_DAYS=7\t#")
+            .replace("MAX_LOG_SIZE='100M'", "MAX_LOG_SIZE='1G'")
+            .replace("UFW_DEFAULT_INCOMING=\"reject\"   #", "UFW_DEFAULT_INCOMING=\"deny\"   #");
+        assert_eq!(file.content(), expected);
+        assert_eq!(file.get("UFW_DEFAULT_INCOMING").unwrap().value, "deny");
+    }
+
+    #[test]
+    fn new_keys_go_after_their_section() {
+        let mut file = ConfigFile::parse(&fixture());
+        file.set("BACKUP_BEFORE_CHANGES", "true").unwrap();
+        file.set("USE_SECURE_DNS", "false").unwrap();
+        // Nothing of the Monitoring section is set
+        file.set("ENABLE_MONITORING", "false").unwrap();
+        let content = file.content();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines[14..17], ["AUTO_BACKUP_ENABLED=\"true\"", "BACKUP_BEFORE_CHANGES=\"true\"", "#BACKUP_BEFORE_CHANGES=\"false\""]);
+        assert_eq!(lines[25..28], ["PRIMARY_DNS=\"9.9.9.9\"", "SECONDARY_DNS=\"149.112.112.112\"", "USE_SECURE_DNS=\"false\""]);
+        assert_eq!(lines.last(), Some(&"ENABLE_MONITORING=\"false\""));
+        assert_eq!(lines.len(), 33);
+        assert!(content.ends_with('\n'));
+    }
+
+    #[test]
+    fn changes_apply_only_to_the_values_they_were_made_against() {
+        let content = fixture();
+        let mut file = ConfigFile::parse(&content);
+        file.apply(&[change("LOG_LEVEL", Some("info"), "debug"), change("BACKUP_BEFORE_CHANGES", None, "false")]).unwrap();
+        assert_eq!(file.get("LOG_LEVEL").unwrap().value, "debug");
+        assert_eq!(file.get("BACKUP_BEFORE_CHANGES").unwrap().value, "false");
+
+        let mut file = ConfigFile::parse(&content);
+        assert_eq!(
+            file.apply(&[change("UFW_DEFAULT_INCOMING", Some("deny"), "allow")]),
+            Err("UFW_DEFAULT_INCOMING changed since it was read (now reject)".to_string())
+        );
+        assert_eq!(
+            file.apply(&[change("BACKUP_BEFORE_CHANGES", Some("false"), "true")]),
+            Err("BACKUP_BEFORE_CHANGES changed since it was read (now not set)".to_string())
+        );
+        assert_eq!(
+            file.apply(&[change("LOG_LEVEL", None, "warn")]),
+            Err("LOG_LEVEL changed since it was read (now info)".to_string())
+        );
+        assert!(file.apply(&[change("LOG_LEVEL", Some("info"), "verbose")]).is_err());
+        assert!(file.apply(&[change("CUSTOM_HOOK", Some("/usr/local/bin/after-hardening"), "/tmp/x")]).is_err());
+        assert_eq!(file.content(), content);
+    }
+
+    #[test]
+    fn kinds_reject_bad_values() {
+        let days = Kind::Integer { min: 1, max: 3650, unit: "days" };
+        let cases: &[(Kind, &[&str], &[&str])] = &[
+            (Kind::Bool, &["true", "false"], &["", "yes", "1", "True", " true"]),
+            (days, &["1", "30", "3650"], &["0", "3651", "", "-1", "+5", "1.5", "30 ", "99999999999999999999"]),
+            (Kind::Size, &["500K", "100M", "1G", "4096"], &["", "M", "0M", "100m", "100MB", "1T", "-1G", "1.5G"]),
+            (Kind::IpAddress, &["9.9.9.9", "2620:fe::fe"], &["", "9.9.9", "dns.quad9.net", "9.9.9.9/32", "9.9.9.9:53"]),
+            (Kind::Choice(LOG_LEVELS), &["debug", "error"], &["", "DEBUG", "trace", "info "]),
+            (Kind::ReadOnly, &[], &["", "2.0.0"]),
+        ];
+        for (kind, good, bad) in cases {
+            for value in *good {
+                assert_eq!(kind.check(value), Ok(()), "{:?} {:?}", kind, value);
+            }
+            for value in *bad {
+                assert!(kind.check(value).is_err(), "{:?} {:?}", kind, value);
+            }
+        }
+        assert_eq!(validate_setting("LOG_RETENTION_DAYS", "0"), Err("LOG_RETENTION_DAYS must be a whole number of days from 1 to 3650".to_string()));
+        assert_eq!(validate_setting("EDITOR", "vi"), Err("EDITOR is not a setting the GUI edits".to_string()));
+    }
+
+    #[test]
+    fn transcripts_read_back_into_the_file() {
+        let content = fixture();
+        let file = ConfigFile::parse(&content);
+        let mut transcript = String::from("[INFO] hardn-gui-helper: running read /etc/hardn/hardn.conf on behalf of uid 1000\n");
+        transcript.push_str(&format!("{}{} /etc/hardn/hardn.conf\n", FILE_MARKER, file.lines.len()));
+        for (index, line) in file.lines.iter().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            transcript.push_str(&format!("{}:{}\n", index + 1, line));
+        }
+        // Out of range and malformed lines are ignored
+        transcript.push_str("0:ZERO=1\n31:PAST_THE_END=1\nnot numbered\n");
+
+        let (path, read) = ConfigFile::parse_transcript(&transcript).unwrap();
+        assert_eq!(path, Path::new("/etc/hardn/hardn.conf"));
+        assert_eq!(read.content(), content);
+        assert_eq!(read.settings(), file.settings());
+
+        // A value with a colon keeps it
+        let (_, read) = ConfigFile::parse_transcript("### FILE 2 /x\n2:HOOK=\"a:b\"\n").unwrap();
+        assert_eq!(read.content(), "\nHOOK=\"a:b\"\n");
+        assert_eq!(ConfigFile::parse_transcript("no file\n"), None);
+        assert_eq!(ConfigFile::parse_transcript("### FILE many /x\n"), None);
+        assert_eq!(ConfigFile::parse_transcript("### FILE 3\n"), None);
+    }
+}
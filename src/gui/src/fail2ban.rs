@@ -0,0 +1,483 @@
+// Fail2Ban jails as `fail2ban-client` reports them, and the HARDN jail file.
+//
+// The server socket is only accessible to root, so the helper runs the client and
+// prints a transcript: each command as "$ fail2ban-client ARGS" followed by its
+// output, e.g.
+//
+//   $ fail2ban-client status
+//   Status
+//   |- Number of jail:	1
+//   `- Jail list:	sshd
+//   $ fail2ban-client status sshd
+//   Status for the jail: sshd
+//   |- Filter
+//   |  |- Currently failed:	1
+//   |  |- Total failed:	12
+//   |  `- Journal matches:	_SYSTEMD_UNIT=sshd.service + _COMM=sshd
+//   `- Actions
+//      |- Currently banned:	1
+//      |- Total banned:	3
+//      `- Banned IP list:	192.0.2.10
+//   $ fail2ban-client get sshd bantime
+//   3600
+//
+// The GUI parses the transcript back into JailStatus. With HARDN_FAIL2BAN_CLIENT set,
+// the GUI runs that program itself instead of going through the helper, which is how
+// src/gui/fixtures/fake-fail2ban-client is used to try the panel without a server.
+//
+// This file is compiled into both binaries and each only uses its own side of it.
+#![allow(dead_code)]
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::malware::write_atomic;
+
+pub const FAIL2BAN_CLIENT: &str = "/usr/bin/fail2ban-client";
+// Written by configure_fail2ban in modules/hardening.sh
+pub const HARDN_JAIL_FILE: &str = "/etc/fail2ban/jail.d/hardn-jail.conf";
+pub const DEFAULT_SECTION: &str = "DEFAULT";
+const CLIENT_ENV: &str = "HARDN_FAIL2BAN_CLIENT";
+const COMMAND_PREFIX: &str = "$ fail2ban-client ";
+
+// Settings the GUI edits, as effective values are read with `get <jail> <key>`
+pub const SETTINGS: [&str; 3] = ["bantime", "findtime", "maxretry"];
+
+pub struct Client {
+    program: PathBuf,
+}
+
+impl Client {
+    pub fn system() -> Self {
+        Client { program: PathBuf::from(FAIL2BAN_CLIENT) }
+    }
+
+    // The program named by HARDN_FAIL2BAN_CLIENT, if set
+    pub fn from_env() -> Option<Self> {
+        std::env::var_os(CLIENT_ENV).map(|program| Client { program: PathBuf::from(program) })
+    }
+
+    // stdout on success, otherwise what the client said on stderr
+    pub fn run(&self, args: &[String]) -> Result<String, String> {
+        let output = Command::new(&self.program)
+            .args(args)
+            .env_clear()
+            .env("PATH", "/usr/sbin:/usr/bin:/sbin:/bin")
+            .env("LANG", "C.UTF-8")
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| format!("failed to start {}: {}", self.program.display(), e))?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            Err(if stderr.is_empty() { format!("fail2ban-client {} failed ({})", args.join(" "), output.status) } else { stderr })
+        }
+    }
+
+    // Overview, then status and settings of every jail; each line goes to `out`.
+    // Stops at the first failing command and returns its error.
+    pub fn transcript(&self, out: &mut dyn FnMut(&str)) -> Result<usize, String> {
+        let mut run = |args: Vec<String>| {
+            out(&format!("{}{}", COMMAND_PREFIX, args.join(" ")));
+            let output = self.run(&args)?;
+            output.lines().for_each(&mut *out);
+            Ok::<String, String>(output)
+        };
+        let jails = parse_jail_list(&run(vec!["status".to_string()])?);
+        for jail in &jails {
+            run(vec!["status".to_string(), jail.clone()])?;
+            for key in SETTINGS {
+                run(vec!["get".to_string(), jail.clone(), key.to_string()])?;
+            }
+        }
+        Ok(jails.len())
+    }
+}
+
+// `|- Key:\tvalue` lines of a status tree, without the tree drawing
+fn status_fields(output: &str) -> BTreeMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_start_matches(|c: char| c == '|' || c == '`' || c == '-' || c.is_whitespace());
+            let (key, value) = line.split_once(':')?;
+            Some((key.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+// Jail names from `fail2ban-client status`
+pub fn parse_jail_list(output: &str) -> Vec<String> {
+    status_fields(output)
+        .get("Jail list")
+        .map(|list| list.split(',').map(str::trim).filter(|jail| !jail.is_empty()).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JailStatus {
+    pub name: String,
+    // "File list" or "Journal matches", whichever the backend reports
+    pub filter_source: String,
+    pub currently_failed: u64,
+    pub total_failed: u64,
+    pub currently_banned: u64,
+    pub total_banned: u64,
+    pub banned: Vec<String>,
+    // Effective bantime, findtime and maxretry, as far as they were read
+    pub settings: BTreeMap<String, String>,
+}
+
+// `fail2ban-client status <jail>`
+pub fn parse_jail_status(name: &str, output: &str) -> JailStatus {
+    let fields = status_fields(output);
+    let count = |key: &str| fields.get(key).and_then(|value| value.parse().ok()).unwrap_or(0);
+    let filter_source = ["File list", "Journal matches"]
+        .iter()
+        .find_map(|key| fields.get(*key).map(|value| format!("{}: {}", key, value)))
+        .unwrap_or_default();
+    JailStatus {
+        name: name.to_string(),
+        filter_source,
+        currently_failed: count("Currently failed"),
+        total_failed: count("Total failed"),
+        currently_banned: count("Currently banned"),
+        total_banned: count("Total banned"),
+        banned: fields.get("Banned IP list").map(|list| list.split_whitespace().map(str::to_string).collect()).unwrap_or_default(),
+        settings: BTreeMap::new(),
+    }
+}
+
+// Jails from a transcript as Client::transcript prints it, in the client's order
+pub fn parse_transcript(transcript: &str) -> Vec<JailStatus> {
+    let mut commands: Vec<(Vec<&str>, String)> = Vec::new();
+    for line in transcript.lines() {
+        if let Some(args) = line.strip_prefix(COMMAND_PREFIX) {
+            commands.push((args.split_whitespace().collect(), String::new()));
+        } else if let Some((_, output)) = commands.last_mut() {
+            output.push_str(line);
+            output.push('\n');
+        }
+    }
+
+    let mut jails: Vec<JailStatus> = Vec::new();
+    for (args, output) in &commands {
+        match args.as_slice() {
+            ["status"] => jails = parse_jail_list(output).iter().map(|name| JailStatus { name: name.clone(), ..Default::default() }).collect(),
+            ["status", name] => {
+                if let Some(jail) = jails.iter_mut().find(|jail| jail.name == *name) {
+                    *jail = JailStatus { settings: std::mem::take(&mut jail.settings), ..parse_jail_status(name, output) };
+                }
+            }
+            ["get", name, key] => {
+                if let Some(jail) = jails.iter_mut().find(|jail| jail.name == *name) {
+                    // The value is one line; the helper's own report may follow it
+                    jail.settings.insert(key.to_string(), output.lines().next().unwrap_or_default().trim().to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    jails
+}
+
+// Section names as fail2ban allows them for jails
+pub fn valid_jail_name(name: &str) -> bool {
+    !name.is_empty() && name.len() <= 64 && !name.starts_with('-') && name.chars().all(|c| c.is_ascii_alphanumeric() || "_.-".contains(c))
+}
+
+// Seconds, or fail2ban's time abbreviations such as "10m" or "1h30m"; "-1" bans forever
+pub fn valid_time(value: &str) -> bool {
+    if value == "-1" {
+        return true;
+    }
+    let mut rest = value;
+    while !rest.is_empty() {
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits == 0 || digits > 9 {
+            return false;
+        }
+        rest = &rest[digits..];
+        rest = rest.strip_prefix(['s', 'm', 'h', 'd', 'w']).unwrap_or(rest);
+    }
+    !value.is_empty()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum JailCommand {
+    Ban { jail: String, ip: String },
+    Unban { jail: String, ip: String },
+}
+
+impl JailCommand {
+    pub fn validate(&self) -> Result<(), String> {
+        let (JailCommand::Ban { jail, ip } | JailCommand::Unban { jail, ip }) = self;
+        if !valid_jail_name(jail) {
+            return Err(format!("invalid jail name: {}", jail));
+        }
+        ip.parse::<IpAddr>().map(|_| ()).map_err(|_| format!("not an IP address: {}", ip))
+    }
+
+    // Arguments after `fail2ban-client`
+    pub fn args(&self) -> Vec<String> {
+        let (jail, verb, ip) = match self {
+            JailCommand::Ban { jail, ip } => (jail, "banip", ip),
+            JailCommand::Unban { jail, ip } => (jail, "unbanip", ip),
+        };
+        vec!["set".to_string(), jail.clone(), verb.to_string(), ip.clone()]
+    }
+}
+
+// bantime, findtime and maxretry of one section of the HARDN jail file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JailSettings {
+    // A jail name or DEFAULT_SECTION
+    pub section: String,
+    pub bantime: String,
+    pub findtime: String,
+    pub maxretry: u32,
+}
+
+impl JailSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !valid_jail_name(&self.section) {
+            return Err(format!("invalid jail name: {}", self.section));
+        }
+        if !valid_time(&self.bantime) {
+            return Err(format!("invalid bantime: {}", self.bantime));
+        }
+        if self.findtime == "-1" || !valid_time(&self.findtime) {
+            return Err(format!("invalid findtime: {}", self.findtime));
+        }
+        if self.maxretry == 0 || self.maxretry > 1000 {
+            return Err(format!("maxretry must be between 1 and 1000, not {}", self.maxretry));
+        }
+        Ok(())
+    }
+
+    fn values(&self) -> [(&'static str, String); 3] {
+        [("bantime", self.bantime.clone()), ("findtime", self.findtime.clone()), ("maxretry", self.maxretry.to_string())]
+    }
+
+    // `content` with the three keys set in our section, everything else as it was.
+    // Missing keys go after the section's last setting, a missing section at the end.
+    pub fn apply_to(&self, content: &str) -> String {
+        let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+        let header = format!("[{}]", self.section);
+        let start = lines.iter().position(|line| line.trim() == header);
+        let Some(start) = start else {
+            if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                lines.push(String::new());
+            }
+            lines.push(header);
+            lines.extend(self.values().iter().map(|(key, value)| format!("{} = {}", key, value)));
+            return lines.join("\n") + "\n";
+        };
+        let end = lines[start + 1..]
+            .iter()
+            .position(|line| line.trim_start().starts_with('['))
+            .map(|offset| start + 1 + offset)
+            .unwrap_or(lines.len());
+
+        let mut insert_at = lines[start + 1..end]
+            .iter()
+            .rposition(|line| !line.trim().is_empty())
+            .map(|offset| start + 2 + offset)
+            .unwrap_or(start + 1);
+        for (key, value) in self.values() {
+            let existing = (start + 1..end).find(|&index| {
+                let line = lines[index].trim_start();
+                !line.starts_with(['#', ';']) && line.split_once('=').map(|(name, _)| name.trim() == key).unwrap_or(false)
+            });
+            match existing {
+                Some(index) => lines[index] = format!("{} = {}", key, value),
+                None => {
+                    lines.insert(insert_at, format!("{} = {}", key, value));
+                    insert_at += 1;
+                }
+            }
+        }
+        lines.join("\n") + "\n"
+    }
+
+    // Rewrite the jail file and have the server pick it up
+    pub fn install(&self, path: &Path, client: &Client) -> io::Result<()> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        write_atomic(path, self.apply_to(&content).as_bytes(), 0o644)?;
+        client
+            .run(&["reload".to_string()])
+            .map(|_| ())
+            .map_err(|e| io::Error::other(format!("{} was saved, but reloading fail2ban failed: {}", path.display(), e)))
+    }
+}
+
+// Values set per section in the jail file; DEFAULT_SECTION holds the defaults
+pub fn read_jail_file(path: &Path) -> io::Result<BTreeMap<String, BTreeMap<String, String>>> {
+    let mut sections: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+    let mut section = String::new();
+    for line in fs::read_to_string(path)?.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            section = name.to_string();
+        } else if let Some((key, value)) = line.split_once('=').filter(|_| !line.starts_with(['#', ';'])) {
+            sections.entry(section.clone()).or_default().insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+    Ok(sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(name)
+    }
+
+    fn fake_client() -> Client {
+        Client { program: fixture("fake-fail2ban-client") }
+    }
+
+    fn args(args: &str) -> Vec<String> {
+        args.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn status_output_is_parsed() {
+        let client = fake_client();
+        assert_eq!(parse_jail_list(&client.run(&args("status")).unwrap()), ["sshd", "apache-auth"]);
+
+        let sshd = parse_jail_status("sshd", &client.run(&args("status sshd")).unwrap());
+        assert_eq!(sshd.filter_source, "Journal matches: _SYSTEMD_UNIT=sshd.service + _COMM=sshd");
+        assert_eq!((sshd.currently_failed, sshd.total_failed), (1, 17));
+        // The fake counts earlier bans on top of the current ones
+        assert_eq!(sshd.currently_banned as usize, sshd.banned.len());
+        assert_eq!(sshd.total_banned, sshd.currently_banned + 4);
+        assert!(sshd.banned.iter().all(|ip| ip.parse::<IpAddr>().is_ok()), "{:?}", sshd.banned);
+
+        let apache = parse_jail_status("apache-auth", &client.run(&args("status apache-auth")).unwrap());
+        assert_eq!(apache.filter_source, "File list: /var/log/apache2/error.log");
+
+        assert_eq!(client.run(&args("status nope")).unwrap_err(), "Sorry but the jail 'nope' does not exist");
+    }
+
+    #[test]
+    fn transcripts_read_back_into_jails() {
+        let mut lines = Vec::new();
+        assert_eq!(fake_client().transcript(&mut |line| lines.push(line.to_string())), Ok(2));
+        assert_eq!(lines[0], "$ fail2ban-client status");
+        let jails = parse_transcript(&lines.join("\n"));
+        assert_eq!(jails.iter().map(|jail| jail.name.as_str()).collect::<Vec<_>>(), ["sshd", "apache-auth"]);
+        for jail in &jails {
+            assert_eq!(jail.total_failed, 17);
+            let settings: Vec<(&str, &str)> = jail.settings.iter().map(|(key, value)| (key.as_str(), value.as_str())).collect();
+            assert_eq!(settings, [("bantime", "3600"), ("findtime", "600"), ("maxretry", "3")]);
+        }
+
+        // Commands for jails the overview did not list are ignored
+        let extra = "$ fail2ban-client status\nStatus\n`- Jail list:\tsshd\n$ fail2ban-client get other bantime\n60\n";
+        let jails = parse_transcript(extra);
+        assert_eq!(jails.len(), 1);
+        assert!(jails[0].settings.is_empty());
+    }
+
+    #[test]
+    fn ban_and_unban_through_the_client() {
+        let client = fake_client();
+        let ban = JailCommand::Ban { jail: "apache-auth".to_string(), ip: "203.0.113.99".to_string() };
+        let unban = JailCommand::Unban { jail: "apache-auth".to_string(), ip: "203.0.113.99".to_string() };
+        assert_eq!(ban.args(), args("set apache-auth banip 203.0.113.99"));
+
+        client.run(&ban.args()).unwrap();
+        let banned = parse_jail_status("apache-auth", &client.run(&args("status apache-auth")).unwrap()).banned;
+        client.run(&unban.args()).unwrap();
+        assert!(banned.contains(&"203.0.113.99".to_string()));
+        assert_eq!(client.run(&unban.args()).unwrap_err(), "203.0.113.99 is not banned");
+    }
+
+    #[test]
+    fn commands_and_settings_are_validated() {
+        assert!(JailCommand::Ban { jail: "sshd".to_string(), ip: "2001:db8::1".to_string() }.validate().is_ok());
+        assert!(JailCommand::Ban { jail: "-sshd".to_string(), ip: "192.0.2.1".to_string() }.validate().is_err());
+        assert!(JailCommand::Unban { jail: "sshd".to_string(), ip: "192.0.2.0/24".to_string() }.validate().is_err());
+        assert!(JailCommand::Unban { jail: "ssh d".to_string(), ip: "192.0.2.1".to_string() }.validate().is_err());
+
+        for time in ["600", "10m", "1h30m", "1w", "-1"] {
+            assert!(valid_time(time), "{}", time);
+        }
+        for time in ["", "m", "10x", "-10", "1.5h", "1234567890"] {
+            assert!(!valid_time(time), "{}", time);
+        }
+
+        let settings = JailSettings { section: "sshd".to_string(), bantime: "1h".to_string(), findtime: "10m".to_string(), maxretry: 5 };
+        assert!(settings.validate().is_ok());
+        assert!(JailSettings { section: DEFAULT_SECTION.to_string(), bantime: "-1".to_string(), ..settings.clone() }.validate().is_ok());
+        let bad = [
+            JailSettings { section: "[sshd]".to_string(), ..settings.clone() },
+            JailSettings { bantime: "1 h".to_string(), ..settings.clone() },
+            JailSettings { findtime: "-1".to_string(), ..settings.clone() },
+            JailSettings { maxretry: 0, ..settings.clone() },
+            JailSettings { maxretry: 1001, ..settings.clone() },
+        ];
+        for settings in bad {
+            assert!(settings.validate().is_err(), "{:?}", settings);
+        }
+    }
+
+    // hardn-jail.conf is the file configure_fail2ban in modules/hardening.sh writes
+    #[test]
+    fn jail_file_sections_are_rewritten_in_place() {
+        let original = fs::read_to_string(fixture("hardn-jail.conf")).unwrap();
+        let sshd = JailSettings { section: "sshd".to_string(), bantime: "1h".to_string(), findtime: "10m".to_string(), maxretry: 5 };
+        let updated = sshd.apply_to(&original);
+        let lines: Vec<&str> = updated.lines().collect();
+        let start = lines.iter().position(|line| *line == "[sshd]").unwrap();
+        assert_eq!(
+            lines[start..start + 8],
+            ["[sshd]", "enabled = true", "port = ssh", "logpath = %(sshd_log)s", "maxretry = 5", "bantime = 1h", "findtime = 10m", ""]
+        );
+        assert_eq!(updated.lines().count(), original.lines().count() + 2);
+        // The other sections keep their values
+        assert!(updated.contains("[DEFAULT]\nbantime = 3600\nfindtime = 600\nmaxretry = 3\nbackend = systemd\n"));
+        assert!(updated.ends_with("[apache-overflows]\nenabled = true\nmaxretry = 2\n"));
+
+        let defaults = JailSettings { section: DEFAULT_SECTION.to_string(), ..sshd.clone() };
+        assert!(defaults.apply_to(&original).contains("[DEFAULT]\nbantime = 1h\nfindtime = 10m\nmaxretry = 5\nbackend = systemd\n"));
+
+        let recidive = JailSettings { section: "recidive".to_string(), ..sshd };
+        assert!(recidive.apply_to(&original).ends_with("maxretry = 2\n\n[recidive]\nbantime = 1h\nfindtime = 10m\nmaxretry = 5\n"));
+        assert_eq!(recidive.apply_to(""), "[recidive]\nbantime = 1h\nfindtime = 10m\nmaxretry = 5\n");
+    }
+
+    #[test]
+    fn jail_files_are_read_and_installed() {
+        let sections = read_jail_file(&fixture("hardn-jail.conf")).unwrap();
+        assert_eq!(sections.keys().collect::<Vec<_>>(), ["DEFAULT", "apache-auth", "apache-noscript", "apache-overflows", "sshd"]);
+        assert_eq!(sections["DEFAULT"]["backend"], "systemd");
+        assert_eq!(sections["sshd"]["logpath"], "%(sshd_log)s");
+
+        let dir = std::env::temp_dir().join(format!("hardn-fail2ban-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("hardn-jail.conf");
+        let settings = JailSettings { section: "sshd".to_string(), bantime: "1h".to_string(), findtime: "10m".to_string(), maxretry: 5 };
+        let installed = settings.install(&path, &fake_client());
+        let written = fs::read_to_string(&path);
+        let unreloaded = settings.install(&path, &Client { program: PathBuf::from("/bin/false") });
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(installed.is_ok());
+        assert_eq!(written.unwrap(), "[sshd]\nbantime = 1h\nfindtime = 10m\nmaxretry = 5\n");
+        assert!(unreloaded.unwrap_err().to_string().contains("was saved, but reloading fail2ban failed"));
+    }
+}
@@ -0,0 +1,348 @@
+// UFW rules and settings as the GUI edits them, and the `ufw` commands that apply them.
+//
+// The GUI builds a list of UfwCommand, shows their command lines for review and sends
+// them to the helper in one request; the helper validates every command again and
+// runs them in order, stopping at the first failure.
+//
+// This file is compiled into both binaries and each only uses its own side of it.
+#![allow(dead_code)]
+
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+use crate::protocol::UFW_BIN;
+
+// Only root can read the rules files
+pub const USER_RULES: &str = "/etc/ufw/user.rules";
+pub const USER6_RULES: &str = "/etc/ufw/user6.rules";
+// World readable: ENABLED, LOGLEVEL and the default policies
+pub const UFW_CONF: &str = "/etc/ufw/ufw.conf";
+pub const UFW_DEFAULTS: &str = "/etc/default/ufw";
+
+// Upper bound for commands in one request
+pub const MAX_COMMANDS: usize = 50;
+// iptables multiport accepts up to 15 ports, a range counting as two
+const MAX_MULTIPORT: usize = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Allow,
+    Deny,
+    Reject,
+    Limit,
+}
+
+impl Action {
+    pub const ALL: [Action; 4] = [Action::Allow, Action::Deny, Action::Reject, Action::Limit];
+
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            Action::Allow => "allow",
+            Action::Deny => "deny",
+            Action::Reject => "reject",
+            Action::Limit => "limit",
+        }
+    }
+
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.keyword() == keyword)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    In,
+    Out,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 2] = [Direction::In, Direction::Out];
+
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            Direction::In => "in",
+            Direction::Out => "out",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleProtocol {
+    Any,
+    Tcp,
+    Udp,
+}
+
+impl RuleProtocol {
+    pub const ALL: [RuleProtocol; 3] = [RuleProtocol::Any, RuleProtocol::Tcp, RuleProtocol::Udp];
+
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            RuleProtocol::Any => "any",
+            RuleProtocol::Tcp => "tcp",
+            RuleProtocol::Udp => "udp",
+        }
+    }
+}
+
+// One rule in the terms of the `ufw` command line:
+// ACTION [in|out [on IFACE]] [log|log-all] [proto P] from A [port P|app X] to B [port P|app X] [comment C]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleSpec {
+    pub action: Action,
+    pub direction: Direction,
+    pub interface: Option<String>,
+    // "log" or "log-all"
+    pub log: Option<String>,
+    pub protocol: RuleProtocol,
+    // "any", an address or a CIDR
+    pub from: String,
+    pub from_port: Option<String>,
+    pub from_app: Option<String>,
+    pub to: String,
+    pub to_port: Option<String>,
+    pub to_app: Option<String>,
+    pub comment: Option<String>,
+}
+
+impl RuleSpec {
+    pub fn new(action: Action, direction: Direction) -> Self {
+        RuleSpec {
+            action,
+            direction,
+            interface: None,
+            log: None,
+            protocol: RuleProtocol::Any,
+            from: "any".to_string(),
+            from_port: None,
+            from_app: None,
+            to: "any".to_string(),
+            to_port: None,
+            to_app: None,
+            comment: None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(interface) = &self.interface {
+            if !valid_interface(interface) {
+                return Err(format!("invalid interface name: {}", interface));
+            }
+        }
+        if let Some(log) = &self.log {
+            if log != "log" && log != "log-all" {
+                return Err(format!("invalid log option: {}", log));
+            }
+        }
+
+        let from_family = address_family(&self.from).ok_or_else(|| format!("invalid source address: {}", self.from))?;
+        let to_family = address_family(&self.to).ok_or_else(|| format!("invalid destination address: {}", self.to))?;
+        if let (Some(from), Some(to)) = (from_family, to_family) {
+            if from != to {
+                return Err("source and destination must both be IPv4 or both be IPv6".to_string());
+            }
+        }
+
+        for (side, port, app) in [("source", &self.from_port, &self.from_app), ("destination", &self.to_port, &self.to_app)] {
+            if port.is_some() && app.is_some() {
+                return Err(format!("the {} takes either a port or an application profile, not both", side));
+            }
+            if let Some(port) = port {
+                let ports = parse_ports(port).ok_or_else(|| format!("invalid {} port: {}", side, port))?;
+                let multiport = ports.len() > 1 || ports.iter().any(|(low, high)| low != high);
+                if multiport && self.protocol == RuleProtocol::Any {
+                    return Err(format!("port lists and ranges need protocol tcp or udp: {}", port));
+                }
+            }
+            if let Some(app) = app {
+                if !valid_app_name(app) {
+                    return Err(format!("invalid application profile name: {}", app));
+                }
+                if self.protocol != RuleProtocol::Any {
+                    return Err("application profiles bring their own protocol; use protocol any".to_string());
+                }
+            }
+        }
+
+        if let Some(comment) = &self.comment {
+            if comment.len() > 128 || comment.starts_with('-') || comment.chars().any(char::is_control) {
+                return Err("comments are limited to 128 printable characters and may not start with -".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    // Arguments after `ufw [insert N]`
+    pub fn args(&self) -> Vec<String> {
+        let mut args = vec![self.action.keyword().to_string(), self.direction.keyword().to_string()];
+        if let Some(interface) = &self.interface {
+            args.extend(["on".to_string(), interface.clone()]);
+        }
+        args.extend(self.log.iter().cloned());
+        if self.protocol != RuleProtocol::Any {
+            args.extend(["proto".to_string(), self.protocol.keyword().to_string()]);
+        }
+        args.extend(["from".to_string(), self.from.clone()]);
+        push_port_or_app(&mut args, &self.from_port, &self.from_app);
+        args.extend(["to".to_string(), self.to.clone()]);
+        push_port_or_app(&mut args, &self.to_port, &self.to_app);
+        if let Some(comment) = &self.comment {
+            args.extend(["comment".to_string(), comment.clone()]);
+        }
+        args
+    }
+
+    // IPv6 when either address is, None when both are "any" (ufw adds both families)
+    pub fn is_v6(&self) -> Option<bool> {
+        match (address_family(&self.from).flatten(), address_family(&self.to).flatten()) {
+            (Some(v6), _) | (None, Some(v6)) => Some(v6),
+            (None, None) => None,
+        }
+    }
+}
+
+fn push_port_or_app(args: &mut Vec<String>, port: &Option<String>, app: &Option<String>) {
+    if let Some(port) = port {
+        args.extend(["port".to_string(), port.clone()]);
+    } else if let Some(app) = app {
+        args.extend(["app".to_string(), app.clone()]);
+    }
+}
+
+// "22", "80,443" or "6000:6007,8080": (low, high) pairs, None when malformed
+pub fn parse_ports(spec: &str) -> Option<Vec<(u16, u16)>> {
+    let ranges = spec
+        .split(',')
+        .map(|part| {
+            let (low, high) = match part.split_once(':') {
+                Some((low, high)) => (low.parse::<u16>().ok()?, high.parse::<u16>().ok()?),
+                None => {
+                    let port = part.parse::<u16>().ok()?;
+                    (port, port)
+                }
+            };
+            (low > 0 && low <= high).then_some((low, high))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let slots: usize = ranges.iter().map(|(low, high)| if low == high { 1 } else { 2 }).sum();
+    (slots <= MAX_MULTIPORT).then_some(ranges)
+}
+
+// Some(None) for "any", Some(Some(is_v6)) for an address or CIDR, None when invalid
+fn address_family(address: &str) -> Option<Option<bool>> {
+    if address == "any" {
+        return Some(None);
+    }
+    let (ip, prefix) = match address.split_once('/') {
+        Some((ip, prefix)) => (ip, Some(prefix.parse::<u8>().ok()?)),
+        None => (address, None),
+    };
+    let ip: IpAddr = ip.parse().ok()?;
+    let max_prefix = if ip.is_ipv6() { 128 } else { 32 };
+    if prefix.is_some_and(|prefix| prefix > max_prefix) {
+        return None;
+    }
+    Some(Some(ip.is_ipv6()))
+}
+
+fn valid_interface(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 15
+        && !name.starts_with('-')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || "._-@".contains(c))
+}
+
+// Profile names as ufw prints them, e.g. "OpenSSH" or "Apache Full"
+pub fn valid_app_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && !name.starts_with([' ', '-'])
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || " ._+-".contains(c))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyChain {
+    Incoming,
+    Outgoing,
+    Routed,
+}
+
+impl PolicyChain {
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            PolicyChain::Incoming => "incoming",
+            PolicyChain::Outgoing => "outgoing",
+            PolicyChain::Routed => "routed",
+        }
+    }
+}
+
+pub const POLICIES: [&str; 3] = ["allow", "deny", "reject"];
+pub const LOG_LEVELS: [&str; 5] = ["off", "low", "medium", "high", "full"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum UfwCommand {
+    // Append (per address family), or insert before rule `position`
+    Add { rule: Box<RuleSpec>, position: Option<u32> },
+    // By number as in `ufw status numbered`
+    Delete { number: u32 },
+    Default { chain: PolicyChain, policy: String },
+    Logging { level: String },
+}
+
+impl UfwCommand {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            UfwCommand::Add { rule, position } => {
+                if *position == Some(0) {
+                    return Err("rule positions start at 1".to_string());
+                }
+                rule.validate()
+            }
+            UfwCommand::Delete { number } => {
+                if *number == 0 {
+                    return Err("rule numbers start at 1".to_string());
+                }
+                Ok(())
+            }
+            UfwCommand::Default { policy, .. } => {
+                if POLICIES.contains(&policy.as_str()) {
+                    Ok(())
+                } else {
+                    Err(format!("invalid default policy: {}", policy))
+                }
+            }
+            UfwCommand::Logging { level } => {
+                if LOG_LEVELS.contains(&level.as_str()) {
+                    Ok(())
+                } else {
+                    Err(format!("invalid logging level: {}", level))
+                }
+            }
+        }
+    }
+
+    pub fn argv(&self) -> Vec<String> {
+        let mut argv = vec![UFW_BIN.to_string()];
+        match self {
+            UfwCommand::Add { rule, position } => {
+                if let Some(position) = position {
+                    argv.extend(["insert".to_string(), position.to_string()]);
+                }
+                argv.extend(rule.args());
+            }
+            // --force skips the confirmation prompt
+            UfwCommand::Delete { number } => argv.extend(["--force".to_string(), "delete".to_string(), number.to_string()]),
+            UfwCommand::Default { chain, policy } => {
+                argv.extend(["default".to_string(), policy.clone(), chain.keyword().to_string()])
+            }
+            UfwCommand::Logging { level } => argv.extend(["logging".to_string(), level.clone()]),
+        }
+        argv
+    }
+}
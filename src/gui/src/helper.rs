@@ -0,0 +1,348 @@
+// hardn-gui-helper: privileged side of the HARDN GUI.
+//
+// Started by the GUI through `pkexec`, which authorizes the caller against the
+// org.hardn.gui.run-helper polkit action. Reads one HelperRequest from stdin,
+// refuses anything outside the allow-list and runs the matching command as root.
+// The command gets its own process group, which is terminated when the GUI cancels
+// the job or closes stdin. The helper carries out the builtin requests itself:
+// quarantine and scan scheduling, reading the UFW rules files and applying a batch of
+// ufw commands, reading the Fail2Ban status and editing the HARDN jail file, reading
+// sshd_config and applying line edits to it, the AppArmor status, reading the audit
+// rules and audit.log, reading auth.log or the journal for logins, and reading and
+// changing hardn.conf.
+
+mod apparmor;
+mod auditd;
+mod authlog;
+mod config;
+mod fail2ban;
+mod firewall;
+mod malware;
+mod protocol;
+mod sshd_config;
+
+use protocol::{HelperRequest, CANCEL_REQUEST, EXIT_BAD_REQUEST, EXIT_REJECTED, PROGRESS_ENV, PROGRESS_MODE, TOOLS_DIR};
+use std::io::{self, BufRead};
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::path::Path;
+use std::process::{exit, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+const SAFE_PATH: &str = "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin";
+// Time a cancelled command gets to exit after SIGTERM before it is killed
+const KILL_GRACE: Duration = Duration::from_secs(5);
+
+fn main() {
+    let mut input = io::stdin().lock();
+    let mut line = String::new();
+    if input.read_line(&mut line).is_err() || line.trim().is_empty() {
+        eprintln!("[ERROR] hardn-gui-helper: no request received on stdin");
+        exit(EXIT_BAD_REQUEST);
+    }
+
+    let request = match HelperRequest::decode(&line) {
+        Ok(request) => request,
+        Err(e) => {
+            eprintln!("[ERROR] hardn-gui-helper: {}", e);
+            exit(EXIT_BAD_REQUEST);
+        }
+    };
+
+    if let Err(e) = request.validate().and_then(|_| check_target(&request)) {
+        eprintln!("[ERROR] hardn-gui-helper: request rejected: {}", e);
+        exit(EXIT_REJECTED);
+    }
+
+    let caller = std::env::var("PKEXEC_UID").unwrap_or_else(|_| "unknown".to_string());
+    eprintln!("[INFO] hardn-gui-helper: running {} on behalf of uid {}", request.describe(), caller);
+
+    if request.is_builtin() {
+        exit(run_builtin(&request, &caller));
+    }
+
+    let child = request
+        .to_command()
+        .env_clear()
+        .env("PATH", SAFE_PATH)
+        .env("HOME", "/root")
+        .env("LANG", "C.UTF-8")
+        .env("DEBIAN_FRONTEND", "noninteractive")
+        .env("NEEDRESTART_MODE", "a")
+        .env(PROGRESS_ENV, PROGRESS_MODE)
+        .stdin(Stdio::null())
+        .process_group(0)
+        .spawn();
+
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("[ERROR] hardn-gui-helper: failed to start {}: {}", request.describe(), e);
+            exit(1);
+        }
+    };
+
+    // The exit status decides the helper's exit code, cancelled or not
+    let pgid = child.id() as i32;
+    let description = request.describe();
+    let waiter = thread::spawn(move || match child.wait() {
+        // Like a shell, report death by signal as 128 + signal number
+        Ok(status) => exit(status.code().or_else(|| status.signal().map(|signal| 128 + signal)).unwrap_or(1)),
+        Err(e) => {
+            eprintln!("[ERROR] hardn-gui-helper: failed to wait for {}: {}", description, e);
+            exit(1);
+        }
+    });
+
+    watch_for_cancel(input, pgid);
+    let _ = waiter.join();
+    exit(1);
+}
+
+// Terminate the command's process group once the GUI cancels the job or goes away
+fn watch_for_cancel(mut input: impl BufRead, pgid: i32) {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        match input.read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) if line.trim() == CANCEL_REQUEST => break,
+            Ok(_) => {}
+        }
+    }
+
+    eprintln!("[WARN] hardn-gui-helper: job cancelled, terminating process group {}", pgid);
+    // SAFETY: kill(2) has no memory-safety requirements; a negative pid addresses the group
+    unsafe {
+        libc::kill(-pgid, libc::SIGTERM);
+    }
+    thread::sleep(KILL_GRACE);
+    unsafe {
+        libc::kill(-pgid, libc::SIGKILL);
+    }
+}
+
+// Requests without a command; prints the outcome the way the tool scripts do
+fn run_builtin(request: &HelperRequest, caller: &str) -> i32 {
+    let store = malware::QuarantineStore::system();
+    let result = match request {
+        HelperRequest::Quarantine { path, signature } => store
+            .add(Path::new(path), signature, caller)
+            .map(|item| format!("Quarantined {} ({}) as {}", item.original_path, item.signature, item.id)),
+        HelperRequest::RestoreQuarantined { id } => store
+            .restore(id)
+            .map(|item| format!("Restored {} from quarantine", item.original_path)),
+        HelperRequest::DeleteQuarantined { id } => store
+            .delete(id)
+            .map(|item| format!("Deleted quarantined copy of {}", item.original_path)),
+        HelperRequest::ScheduleScan { schedule } => schedule
+            .install(Path::new(malware::SCHEDULE_FILE))
+            .map(|_| format!("Scheduled scan updated: {}", schedule.describe())),
+        HelperRequest::ReadUfwRules => read_ufw_rules(),
+        HelperRequest::UfwApply { commands } => apply_ufw_commands(commands),
+        HelperRequest::Fail2banStatus => fail2ban::Client::system()
+            .transcript(&mut |line| println!("{}", line))
+            .map(|jails| format!("Read the status of {} Fail2Ban jails", jails))
+            .map_err(io::Error::other),
+        HelperRequest::Fail2banSettings { settings } => settings
+            .install(Path::new(fail2ban::HARDN_JAIL_FILE), &fail2ban::Client::system())
+            .map(|_| format!("Updated [{}] in {} and reloaded fail2ban", settings.section, fail2ban::HARDN_JAIL_FILE)),
+        HelperRequest::ReadSshdConfig => read_sshd_config(),
+        HelperRequest::SshdApplyPatch { edits } => sshd_config::apply(edits),
+        HelperRequest::AppArmorStatus => read_apparmor_status(),
+        HelperRequest::ReadAuditRules => auditd::RuleSet::print_transcript()
+            .map(|files| format!("Read {} audit rules files", files))
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", auditd::RULES_DIR, e))),
+        HelperRequest::ReadAuditLog { since, until } => read_audit_log(*since, *until),
+        HelperRequest::ReadAuthLog { source, since } => read_auth_log(*source, *since),
+        HelperRequest::ReadHardnConfig => read_hardn_config(),
+        HelperRequest::HardnConfigUpdate { changes } => config::install(changes),
+        _ => {
+            eprintln!("[ERROR] hardn-gui-helper: {} is not a builtin operation", request.describe());
+            return EXIT_BAD_REQUEST;
+        }
+    };
+    match result {
+        Ok(message) => {
+            println!("[PASS] {}", message);
+            0
+        }
+        Err(e) => {
+            eprintln!("[ERROR] hardn-gui-helper: {}: {}", request.describe(), e);
+            1
+        }
+    }
+}
+
+fn read_ufw_rules() -> io::Result<String> {
+    let mut read = 0;
+    for path in [firewall::USER_RULES, firewall::USER6_RULES] {
+        match std::fs::read_to_string(path) {
+            Ok(content) => {
+                print!("{}", content);
+                read += 1;
+            }
+            // user6.rules only exists with IPV6=yes
+            Err(e) if e.kind() == io::ErrorKind::NotFound && path == firewall::USER6_RULES => {}
+            Err(e) => return Err(io::Error::new(e.kind(), format!("{}: {}", path, e))),
+        }
+    }
+    Ok(format!("Read {} UFW rules files", read))
+}
+
+fn read_sshd_config() -> io::Result<String> {
+    let config = sshd_config::SshdConfig::load(Path::new(sshd_config::SSHD_CONFIG), &sshd_config::SystemFs)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", sshd_config::SSHD_CONFIG, e)))?;
+    sshd_config::Transcript::print(&config);
+    for error in &config.errors {
+        eprintln!("[WARN] hardn-gui-helper: {}", error);
+    }
+    Ok(format!("Read {} sshd configuration files", config.files.len()))
+}
+
+fn read_apparmor_status() -> io::Result<String> {
+    let apparmor = apparmor::AppArmor::new("/");
+    let profiles = apparmor
+        .read_profiles()
+        .map_err(|e| io::Error::new(e.kind(), format!("AppArmor profiles: {} (is AppArmor enabled?)", e)))?;
+    let (log, denials) = apparmor.read_denials().unwrap_or_else(|e| {
+        eprintln!("[WARN] hardn-gui-helper: {}", e);
+        (String::new(), String::new())
+    });
+    apparmor::print_transcript(&profiles, &log, &denials);
+    Ok(format!("Read {} AppArmor profiles and {} DENIED records", profiles.lines().count(), denials.lines().count()))
+}
+
+fn read_audit_log(since: Option<i64>, until: Option<i64>) -> io::Result<String> {
+    let events = auditd::read_events(Path::new(auditd::LOG_DIR), since, until)?;
+    auditd::print_events(&events);
+    Ok(format!("Read {} audit events", events.len()))
+}
+
+fn read_auth_log(source: authlog::Source, since: i64) -> io::Result<String> {
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0);
+    let log = authlog::AuthLog::new("/");
+    let (lines, origin) = log.read_lines(source, since, now)?;
+    let bans = log.read_bans().unwrap_or_else(|e| {
+        eprintln!("[WARN] hardn-gui-helper: {}", e);
+        Vec::new()
+    });
+    authlog::print_transcript(&lines, &bans);
+    Ok(format!("Read {} authentication lines from {} and {} Fail2Ban bans", lines.len(), origin, bans.len()))
+}
+
+// Before hardn setup ran there is only the template the file will be copied from
+fn read_hardn_config() -> io::Result<String> {
+    let path = config::system_path();
+    let (path, content) = match std::fs::read_to_string(&path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let template = Path::new(config::TEMPLATE_FILE).to_path_buf();
+            let content = std::fs::read_to_string(&template).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", template.display(), e)))?;
+            (template, content)
+        }
+        result => (path.clone(), result.map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?),
+    };
+    let file = config::ConfigFile::parse(&content);
+    file.print_transcript(&path);
+    Ok(format!("Read {} settings from {}", file.settings().len(), path.display()))
+}
+
+// Each command sees the rules as the previous ones left them, so a failure stops the batch
+fn apply_ufw_commands(commands: &[firewall::UfwCommand]) -> io::Result<String> {
+    for (done, command) in commands.iter().enumerate() {
+        let argv = command.argv();
+        println!("$ {}", argv.join(" "));
+        let status = Command::new(&argv[0])
+            .args(&argv[1..])
+            .env_clear()
+            .env("PATH", SAFE_PATH)
+            .env("HOME", "/root")
+            .env("LANG", "C.UTF-8")
+            .stdin(Stdio::null())
+            .status()
+            .map_err(|e| io::Error::new(e.kind(), format!("failed to start {}: {}", argv[0], e)))?;
+        if !status.success() {
+            return Err(io::Error::other(format!(
+                "`{}` failed ({}); {} of {} commands were applied",
+                argv.join(" "),
+                status,
+                done,
+                commands.len()
+            )));
+        }
+    }
+    Ok(format!("Applied {} UFW commands", commands.len()))
+}
+
+// Only run tool scripts that are root-owned regular files nobody else can modify
+fn check_target(request: &HelperRequest) -> Result<(), protocol::ValidationError> {
+    match request {
+        HelperRequest::RunTool { script } => check_script(&Path::new(TOOLS_DIR).join(script)),
+        _ => Ok(()),
+    }
+}
+
+fn check_script(path: &Path) -> Result<(), protocol::ValidationError> {
+    let reject = |reason: &str| protocol::ValidationError(format!("{}: {}", path.display(), reason));
+
+    let meta = std::fs::symlink_metadata(path).map_err(|e| reject(&e.to_string()))?;
+    if !meta.file_type().is_file() {
+        return Err(reject("not a regular file"));
+    }
+    if meta.uid() != 0 {
+        return Err(reject("not owned by root"));
+    }
+    if meta.permissions().mode() & 0o022 != 0 {
+        return Err(reject("writable by group or others"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn tool_scripts_must_be_root_owned_and_unwritable() {
+        let dir = std::env::temp_dir().join(format!("hardn-helper-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let script = |name: &str, mode: u32, uid: u32| {
+            let path = dir.join(name);
+            fs::write(&path, "#!/bin/bash\n").unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+            std::os::unix::fs::lchown(&path, Some(uid), Some(0)).unwrap();
+            path
+        };
+        let good = script("good.sh", 0o755, 0);
+        let group_writable = script("group.sh", 0o775, 0);
+        let other_writable = script("other.sh", 0o757, 0);
+        let user_owned = script("user.sh", 0o755, 1000);
+        let link = dir.join("link.sh");
+        std::os::unix::fs::symlink(&good, &link).unwrap();
+
+        let results: Vec<Result<(), String>> = [&good, &group_writable, &other_writable, &user_owned, &link, &dir.join("missing.sh"), &dir]
+            .into_iter()
+            .map(|path| check_script(path).map_err(|e| e.0.rsplit(": ").next().unwrap().to_string()))
+            .collect();
+        // Other requests have no script to check
+        let other = check_target(&HelperRequest::ReloadSysctl);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            results,
+            [
+                Ok(()),
+                Err("writable by group or others".to_string()),
+                Err("writable by group or others".to_string()),
+                Err("not owned by root".to_string()),
+                Err("not a regular file".to_string()),
+                Err("No such file or directory (os error 2)".to_string()),
+                Err("not a regular file".to_string()),
+            ]
+        );
+        assert_eq!(other, Ok(()));
+    }
+}
@@ -0,0 +1,357 @@
+// Job history: every job run from the GUI with its captured output, one JSON file per
+// run under $XDG_DATA_HOME/hardn/history. It is the audit trail of what was run from
+// the GUI, by whom and with which result, and lets a run be replayed, compared with
+// another run of the same action or started again. Plain reads of logs and
+// configuration (HardnAction::is_read_only) are not recorded.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::HardnAction;
+use crate::config;
+use crate::jobs::JobState;
+use crate::output::Record;
+
+// Use another history directory, e.g. a shared /var/lib/hardn/history
+const HISTORY_ENV: &str = "HARDN_HISTORY_DIR";
+// Oldest runs beyond this are removed when a new one is saved
+const MAX_ENTRIES: usize = 200;
+// Above this many line pairs the diff falls back to replacing the differing block
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    // "<YYYYMMDD_HHMMSS>-<pid>", also the file name
+    pub id: String,
+    pub action: HardnAction,
+    pub command: String,
+    pub user: String,
+    pub started_at: u64,
+    pub ended_at: u64,
+    pub state: JobState,
+    #[serde(default)]
+    pub output: Vec<Record>,
+}
+
+impl HistoryEntry {
+    pub fn duration_secs(&self) -> u64 {
+        self.ended_at.saturating_sub(self.started_at)
+    }
+
+    pub fn lines(&self) -> Vec<&str> {
+        self.output.iter().map(|record| record.text.as_str()).collect()
+    }
+}
+
+pub struct HistoryStore {
+    dir: PathBuf,
+}
+
+impl HistoryStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        HistoryStore { dir: dir.into() }
+    }
+
+    pub fn from_env() -> Self {
+        if let Ok(dir) = std::env::var(HISTORY_ENV) {
+            return Self::new(dir);
+        }
+        Self::new(config::user_data_dir().join("history"))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    // Write through a temporary file so a crash never leaves a truncated entry
+    pub fn save(&self, entry: &HistoryEntry) -> io::Result<PathBuf> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!("{}.json", entry.id));
+        let tmp = self.dir.join(format!(".{}.json.tmp", entry.id));
+        let json = serde_json::to_string(entry).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, &path)?;
+        self.prune()?;
+        Ok(path)
+    }
+
+    // All readable entries, newest first, plus one message per unreadable file
+    pub fn load_all(&self) -> (Vec<HistoryEntry>, Vec<String>) {
+        let mut entries = Vec::new();
+        let mut errors = Vec::new();
+        for path in self.entry_files() {
+            let entry = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| serde_json::from_str::<HistoryEntry>(&content).map_err(|e| e.to_string()));
+            match entry {
+                Ok(entry) => entries.push(entry),
+                Err(e) => errors.push(format!("{}: {}", path.display(), e)),
+            }
+        }
+        entries.sort_by(|a, b| b.started_at.cmp(&a.started_at).then_with(|| b.id.cmp(&a.id)));
+        (entries, errors)
+    }
+
+    fn entry_files(&self) -> Vec<PathBuf> {
+        let Ok(read_dir) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut files: Vec<PathBuf> = read_dir
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension().is_some_and(|extension| extension == "json")
+                    && !path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.'))
+            })
+            .collect();
+        // Ids start with the timestamp, so name order is age order
+        files.sort();
+        files
+    }
+
+    fn prune(&self) -> io::Result<()> {
+        let files = self.entry_files();
+        let excess = files.len().saturating_sub(MAX_ENTRIES);
+        for path in &files[..excess] {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffOp {
+    Same,
+    Removed,
+    Added,
+}
+
+impl DiffOp {
+    pub fn marker(&self) -> &'static str {
+        match self {
+            DiffOp::Same => "  ",
+            DiffOp::Removed => "- ",
+            DiffOp::Added => "+ ",
+        }
+    }
+}
+
+// Line diff from `old` to `new` based on their longest common subsequence
+pub fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(DiffOp, &'a str)> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut diff: Vec<(DiffOp, &str)> = old[..prefix].iter().map(|line| (DiffOp::Same, *line)).collect();
+
+    if old_middle.len() * new_middle.len() > MAX_DIFF_CELLS {
+        diff.extend(old_middle.iter().map(|line| (DiffOp::Removed, *line)));
+        diff.extend(new_middle.iter().map(|line| (DiffOp::Added, *line)));
+    } else {
+        // lcs[i][j]: length of the LCS of old_middle[i..] and new_middle[j..]
+        let (n, m) = (old_middle.len(), new_middle.len());
+        let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if old_middle[i] == new_middle[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < n && j < m {
+            if old_middle[i] == new_middle[j] {
+                diff.push((DiffOp::Same, old_middle[i]));
+                i += 1;
+                j += 1;
+            } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+                diff.push((DiffOp::Removed, old_middle[i]));
+                i += 1;
+            } else {
+                diff.push((DiffOp::Added, new_middle[j]));
+                j += 1;
+            }
+        }
+        diff.extend(old_middle[i..].iter().map(|line| (DiffOp::Removed, *line)));
+        diff.extend(new_middle[j..].iter().map(|line| (DiffOp::Added, *line)));
+    }
+
+    diff.extend(old[old.len() - suffix..].iter().map(|line| (DiffOp::Same, *line)));
+    diff
+}
+
+// diff_lines of two versions of `path` as a unified diff without context lines, the
+// way the Settings tab previews a change to hardn.conf
+pub fn unified_diff(path: &Path, old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let mut out = format!("--- {0}\n+++ {0}\n", path.display());
+    // Line indexes in `old` and `new` of the next operation
+    let (mut i, mut j) = (0, 0);
+    let mut diff = diff_lines(&old, &new).into_iter().peekable();
+    while let Some((op, _)) = diff.peek() {
+        if *op == DiffOp::Same {
+            diff.next();
+            i += 1;
+            j += 1;
+            continue;
+        }
+        let (mut removed, mut added) = (Vec::new(), Vec::new());
+        while let Some((op, line)) = diff.next_if(|(op, _)| *op != DiffOp::Same) {
+            match op {
+                DiffOp::Removed => removed.push(line),
+                _ => added.push(line),
+            }
+        }
+        let range = |start: usize, count: usize| match count {
+            1 => format!("{}", start + 1),
+            // An empty range names the line it follows
+            0 => format!("{},0", start),
+            count => format!("{},{}", start + 1, count),
+        };
+        out.push_str(&format!("@@ -{} +{} @@\n", range(i, removed.len()), range(j, added.len())));
+        removed.iter().for_each(|line| out.push_str(&format!("-{}\n", line)));
+        added.iter().for_each(|line| out.push_str(&format!("+{}\n", line)));
+        i += removed.len();
+        j += added.len();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unified_diff_hunks() {
+        let old = "# hardn.conf\nLOG_LEVEL=\"info\"\nUFW_DEFAULT_INCOMING=\"deny\"\nAUTO_UPDATE=\"true\"\n";
+        let new = "# hardn.conf\nLOG_LEVEL=\"debug\"\nUFW_DEFAULT_INCOMING=\"deny\"\nAUTO_UPDATE=\"true\"\nUPDATE_INTERVAL=\"weekly\"\n";
+        assert_eq!(
+            unified_diff(Path::new("/etc/hardn/hardn.conf"), old, new),
+            "--- /etc/hardn/hardn.conf\n+++ /etc/hardn/hardn.conf\n\
+             @@ -2 +2 @@\n-LOG_LEVEL=\"info\"\n+LOG_LEVEL=\"debug\"\n\
+             @@ -4,0 +5 @@\n+UPDATE_INTERVAL=\"weekly\"\n"
+        );
+        assert_eq!(unified_diff(Path::new("a"), "x\ny\nz\n", "z\n"), "--- a\n+++ a\n@@ -1,2 +0,0 @@\n-x\n-y\n");
+        assert_eq!(unified_diff(Path::new("a"), "x\n", "x\n"), "--- a\n+++ a\n");
+    }
+
+    fn entry(id: &str, started_at: u64, lines: &[&str]) -> HistoryEntry {
+        HistoryEntry {
+            id: id.to_string(),
+            action: HardnAction::tool("Configure UFW", "ufw.sh"),
+            command: format!("/bin/bash {}/ufw.sh", crate::protocol::TOOLS_DIR),
+            user: "admin".to_string(),
+            started_at,
+            ended_at: started_at + 3,
+            state: JobState::Failed(Some(2)),
+            output: lines
+                .iter()
+                .map(|line| Record::new(&crate::output::Line::plain(crate::output::Level::Info, line), None))
+                .collect(),
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hardn-history-{}-test-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn entries_are_saved_and_loaded_newest_first() {
+        let dir = temp_dir("load");
+        let store = HistoryStore::new(&dir);
+        let saved = store.save(&entry("20261001_080000-41", 1_790_150_400, &["Rules updated", "Firewall reloaded"]));
+        store.save(&entry("20261002_080000-42", 1_790_236_800, &[])).unwrap();
+        store.save(&entry("20261002_080000-43", 1_790_236_800, &[])).unwrap();
+        fs::write(dir.join("20261003_080000-44.json"), "{\"id\":").unwrap();
+        fs::write(dir.join(".20261004_080000-45.json.tmp"), "{}").unwrap();
+        fs::write(dir.join("notes.txt"), "not an entry").unwrap();
+        let (entries, errors) = store.load_all();
+        let leftovers: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| name.ends_with(".tmp"))
+            .collect();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(saved.unwrap(), dir.join("20261001_080000-41.json"));
+        let ids: Vec<&str> = entries.iter().map(|entry| entry.id.as_str()).collect();
+        assert_eq!(ids, ["20261002_080000-43", "20261002_080000-42", "20261001_080000-41"]);
+
+        let oldest = &entries[2];
+        assert_eq!(oldest.action, HardnAction::tool("Configure UFW", "ufw.sh"));
+        assert_eq!(oldest.state, JobState::Failed(Some(2)));
+        assert_eq!(oldest.duration_secs(), 3);
+        assert_eq!(oldest.lines(), ["Rules updated", "Firewall reloaded"]);
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("20261003_080000-44.json"), "{}", errors[0]);
+        // Only the temporary file the test planted; save() renames its own
+        assert_eq!(leftovers, [".20261004_080000-45.json.tmp"]);
+    }
+
+    #[test]
+    fn oldest_entries_are_pruned() {
+        let dir = temp_dir("prune");
+        let store = HistoryStore::new(&dir);
+        let id = |n: usize| format!("20260101_{:06}-1", n);
+        let results: Vec<io::Result<PathBuf>> = (0..MAX_ENTRIES + 2)
+            .map(|n| store.save(&entry(&id(n), n as u64, &[])))
+            .collect();
+        let (entries, errors) = store.load_all();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(results.iter().all(|result| result.is_ok()));
+        assert!(errors.is_empty());
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries.first().unwrap().id, id(MAX_ENTRIES + 1));
+        assert_eq!(entries.last().unwrap().id, id(2));
+    }
+
+    #[test]
+    fn missing_directory_has_no_entries() {
+        let (entries, errors) = HistoryStore::new(temp_dir("missing")).load_all();
+        assert!(entries.is_empty());
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn line_diffs() {
+        use DiffOp::{Added, Removed, Same};
+
+        assert_eq!(diff_lines(&["a", "b"], &["a", "b"]), [(Same, "a"), (Same, "b")]);
+        assert_eq!(diff_lines(&[], &["a"]), [(Added, "a")]);
+        assert_eq!(diff_lines(&["a"], &[]), [(Removed, "a")]);
+        assert!(diff_lines(&[], &[]).is_empty());
+
+        assert_eq!(
+            diff_lines(&["start", "22/tcp ALLOW", "80/tcp ALLOW", "done"], &["start", "80/tcp ALLOW", "443/tcp ALLOW", "done"]),
+            [(Same, "start"), (Removed, "22/tcp ALLOW"), (Same, "80/tcp ALLOW"), (Added, "443/tcp ALLOW"), (Same, "done")]
+        );
+        // Changed lines come out as the old line followed by the new one
+        assert_eq!(diff_lines(&["a", "x", "c"], &["a", "y", "c"]), [(Same, "a"), (Removed, "x"), (Added, "y"), (Same, "c")]);
+        // Repeated lines keep the common prefix and suffix intact
+        assert_eq!(diff_lines(&["ok", "ok"], &["ok", "ok", "ok"]), [(Same, "ok"), (Same, "ok"), (Added, "ok")]);
+
+        let marked: String = diff_lines(&["a", "b"], &["b", "c"])
+            .iter()
+            .map(|(op, line)| format!("{}{}\n", op.marker(), line))
+            .collect();
+        assert_eq!(marked, "- a\n  b\n+ c\n");
+    }
+}
@@ -0,0 +1,390 @@
+// Jobs started from the GUI. Direct commands run in their own process group so a job
+// is cancelled as a whole (SIGTERM, then SIGKILL after a grace period). Elevated jobs
+// run as root below hardn-gui-helper, which the GUI may not signal; they are cancelled
+// by asking the helper, which escalates the same way (see helper.rs).
+
+use std::io::Write;
+use std::process::{Child, ChildStdin, ExitStatus};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::protocol::CANCEL_REQUEST;
+
+pub type JobId = u64;
+
+// Time a cancelled job gets to exit after SIGTERM before it is killed
+pub const KILL_GRACE: Duration = Duration::from_secs(5);
+// Finished jobs kept for the jobs list
+const FINISHED_JOBS_KEPT: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Running,
+    Finished,
+    // Exit code, or None if the job was killed by a signal
+    Failed(Option<i32>),
+    Cancelled,
+}
+
+impl JobState {
+    pub fn label(&self) -> String {
+        match self {
+            JobState::Running => "⏳ Running".to_string(),
+            JobState::Finished => "✅ Finished".to_string(),
+            JobState::Failed(Some(code)) => format!("❌ Failed (exit code {})", code),
+            JobState::Failed(None) => "❌ Failed (killed by a signal)".to_string(),
+            JobState::Cancelled => "⏹️ Cancelled".to_string(),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        *self == JobState::Running
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct JobInfo {
+    pub id: JobId,
+    pub label: String,
+    pub pid: u32,
+    pub elevated: bool,
+    pub state: JobState,
+    pub started: Instant,
+    pub ended: Option<Instant>,
+    // Wall-clock start, for the job history
+    pub started_at: SystemTime,
+}
+
+impl JobInfo {
+    pub fn elapsed(&self) -> Duration {
+        self.ended.unwrap_or_else(Instant::now) - self.started
+    }
+}
+
+// How a running job is stopped
+enum Control {
+    // Direct children lead a process group with the same id as their pid
+    ProcessGroup(i32),
+    // The helper's stdin; it terminates its command when asked or on EOF
+    Helper(ChildStdin),
+}
+
+struct Job {
+    info: JobInfo,
+    control: Option<Control>,
+    cancel_requested: bool,
+}
+
+#[derive(Default)]
+struct Registry {
+    next_id: JobId,
+    jobs: Vec<Job>,
+}
+
+#[derive(Clone, Default)]
+pub struct JobManager {
+    registry: Arc<Mutex<Registry>>,
+}
+
+impl JobManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Track a spawned child. Direct children must have been spawned with
+    // `process_group(0)`; for elevated ones the helper's stdin is taken over.
+    pub fn register(&self, label: &str, child: &mut Child, elevated: bool) -> JobId {
+        let control = if elevated {
+            child.stdin.take().map(Control::Helper)
+        } else {
+            Some(Control::ProcessGroup(child.id() as i32))
+        };
+
+        let mut registry = self.lock();
+        registry.next_id += 1;
+        let id = registry.next_id;
+        registry.jobs.push(Job {
+            info: JobInfo {
+                id,
+                label: label.to_string(),
+                pid: child.id(),
+                elevated,
+                state: JobState::Running,
+                started: Instant::now(),
+                ended: None,
+                started_at: SystemTime::now(),
+            },
+            control,
+            cancel_requested: false,
+        });
+        id
+    }
+
+    // Record how a job ended; `None` if waiting for it failed
+    pub fn finish(&self, id: JobId, status: Option<ExitStatus>) -> JobState {
+        let mut registry = self.lock();
+        let Some(job) = registry.jobs.iter_mut().find(|job| job.info.id == id) else {
+            return JobState::Failed(None);
+        };
+
+        job.info.state = match status {
+            _ if job.cancel_requested => JobState::Cancelled,
+            Some(status) if status.success() => JobState::Finished,
+            Some(status) => JobState::Failed(status.code()),
+            None => JobState::Failed(None),
+        };
+        job.info.ended = Some(Instant::now());
+        job.control = None;
+        let state = job.info.state;
+
+        let finished = registry.jobs.iter().filter(|job| !job.info.state.is_running()).count();
+        let mut excess = finished.saturating_sub(FINISHED_JOBS_KEPT);
+        registry.jobs.retain(|job| {
+            let drop = excess > 0 && !job.info.state.is_running();
+            if drop {
+                excess -= 1;
+            }
+            !drop
+        });
+
+        state
+    }
+
+    // Ask a running job to stop; returns false if it is not running
+    pub fn cancel(&self, id: JobId) -> bool {
+        let mut registry = self.lock();
+        let Some(job) = registry.jobs.iter_mut().find(|job| job.info.id == id && job.info.state.is_running()) else {
+            return false;
+        };
+        job.cancel_requested = true;
+
+        match &mut job.control {
+            Some(Control::ProcessGroup(pgid)) => {
+                let pgid = *pgid;
+                signal_group(pgid, libc::SIGTERM);
+                let manager = self.clone();
+                thread::spawn(move || {
+                    thread::sleep(KILL_GRACE);
+                    if manager.state(id).is_some_and(|state| state.is_running()) {
+                        signal_group(pgid, libc::SIGKILL);
+                    }
+                });
+            }
+            Some(Control::Helper(stdin)) => {
+                let _ = writeln!(stdin, "{}", CANCEL_REQUEST);
+            }
+            None => {}
+        }
+        true
+    }
+
+    pub fn state(&self, id: JobId) -> Option<JobState> {
+        self.info(id).map(|info| info.state)
+    }
+
+    pub fn info(&self, id: JobId) -> Option<JobInfo> {
+        self.lock().jobs.iter().find(|job| job.info.id == id).map(|job| job.info.clone())
+    }
+
+    // Snapshot of running and recently finished jobs, oldest first
+    pub fn jobs(&self) -> Vec<JobInfo> {
+        self.lock().jobs.iter().map(|job| job.info.clone()).collect()
+    }
+
+    pub fn running_count(&self) -> usize {
+        self.lock().jobs.iter().filter(|job| job.info.state.is_running()).count()
+    }
+
+    // Cancel every running job and wait up to `grace` for them to exit; direct
+    // children still running after that are killed. Helpers see EOF on their stdin
+    // when the GUI exits and clean up by themselves. Blocks, so keep it off the UI thread.
+    pub fn shutdown(&self, grace: Duration) {
+        let running: Vec<JobId> = self
+            .jobs()
+            .iter()
+            .filter(|job| job.state.is_running())
+            .map(|job| job.id)
+            .collect();
+        for id in &running {
+            self.cancel(*id);
+        }
+
+        let deadline = Instant::now() + grace;
+        while self.running_count() > 0 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        for job in self.lock().jobs.iter().filter(|job| job.info.state.is_running()) {
+            if let Some(Control::ProcessGroup(pgid)) = job.control {
+                signal_group(pgid, libc::SIGKILL);
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Registry> {
+        self.registry.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// Signal every process in a process group
+pub fn signal_group(pgid: i32, signal: i32) -> bool {
+    if pgid <= 1 {
+        return false;
+    }
+    // SAFETY: kill(2) has no memory-safety requirements; a negative pid addresses the group
+    unsafe { libc::kill(-pgid, signal) == 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::os::unix::process::CommandExt;
+    use std::process::{Command, Stdio};
+
+    // Direct jobs, spawned the way execute_command_streaming does
+    fn spawn(script: &str) -> Child {
+        Command::new("/bin/sh")
+            .args(["-c", script])
+            .process_group(0)
+            .stdin(Stdio::null())
+            .spawn()
+            .unwrap()
+    }
+
+    fn run(manager: &JobManager, script: &str) -> (JobId, ExitStatus) {
+        let mut child = spawn(script);
+        let id = manager.register(script, &mut child, false);
+        (id, child.wait().unwrap())
+    }
+
+    #[test]
+    fn jobs_finish_or_fail_with_their_exit_status() {
+        let manager = JobManager::new();
+        let (ok, status) = run(&manager, "exit 0");
+        assert_eq!(manager.state(ok), Some(JobState::Running));
+        assert_eq!(manager.running_count(), 1);
+        assert_eq!(manager.finish(ok, Some(status)), JobState::Finished);
+
+        let (failed, status) = run(&manager, "exit 3");
+        assert_eq!(manager.finish(failed, Some(status)), JobState::Failed(Some(3)));
+        let (killed, status) = run(&manager, "kill -KILL $$");
+        assert_eq!(manager.finish(killed, Some(status)), JobState::Failed(None));
+        let (lost, _) = run(&manager, "exit 0");
+        assert_eq!(manager.finish(lost, None), JobState::Failed(None));
+
+        assert_eq!(manager.running_count(), 0);
+        let info = manager.info(ok).unwrap();
+        assert!(info.ended.is_some());
+        assert!(!info.elevated);
+        assert_eq!(manager.jobs().iter().map(|job| job.id).collect::<Vec<_>>(), [ok, failed, killed, lost]);
+    }
+
+    #[test]
+    fn cancelled_jobs_are_terminated() {
+        let manager = JobManager::new();
+        let mut child = spawn("sleep 30");
+        let id = manager.register("sleep", &mut child, false);
+
+        assert!(manager.cancel(id));
+        let status = child.wait().unwrap();
+        assert_eq!(manager.state(id), Some(JobState::Running));
+        // A second request while the job is still exiting is accepted too
+        assert!(manager.cancel(id));
+
+        assert_eq!(std::os::unix::process::ExitStatusExt::signal(&status), Some(libc::SIGTERM));
+        assert_eq!(manager.finish(id, Some(status)), JobState::Cancelled);
+        assert_eq!(manager.running_count(), 0);
+    }
+
+    #[test]
+    fn cancel_after_exit() {
+        let manager = JobManager::new();
+
+        // Exited but not yet reaped by its stream thread: the cancel request wins
+        let (racing, status) = run(&manager, "exit 0");
+        assert!(manager.cancel(racing));
+        assert_eq!(manager.finish(racing, Some(status)), JobState::Cancelled);
+
+        // Once finish() has recorded the exit, the state is final
+        let (done, status) = run(&manager, "exit 1");
+        assert_eq!(manager.finish(done, Some(status)), JobState::Failed(Some(1)));
+        assert!(!manager.cancel(done));
+        assert_eq!(manager.state(done), Some(JobState::Failed(Some(1))));
+
+        assert!(!manager.cancel(99));
+        assert_eq!(manager.finish(99, None), JobState::Failed(None));
+        assert_eq!(manager.state(99), None);
+    }
+
+    #[test]
+    fn elevated_jobs_are_cancelled_through_the_helper() {
+        let manager = JobManager::new();
+        // Stands in for the helper: reads one request from stdin and exits
+        let mut child = Command::new("/bin/sh")
+            .args(["-c", "head -n 1"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let id = manager.register("helper", &mut child, true);
+        assert!(child.stdin.is_none());
+
+        assert!(manager.cancel(id));
+        let mut received = String::new();
+        child.stdout.take().unwrap().read_to_string(&mut received).unwrap();
+        let status = child.wait().unwrap();
+
+        assert_eq!(received, format!("{}\n", CANCEL_REQUEST));
+        assert_eq!(manager.finish(id, Some(status)), JobState::Cancelled);
+        assert!(manager.info(id).unwrap().elevated);
+    }
+
+    #[test]
+    fn only_recent_finished_jobs_are_kept() {
+        let manager = JobManager::new();
+        let mut running = spawn("sleep 30");
+        let running_id = manager.register("sleep", &mut running, false);
+        let finished: Vec<JobId> = (0..FINISHED_JOBS_KEPT + 3)
+            .map(|_| {
+                let (id, status) = run(&manager, "exit 0");
+                manager.finish(id, Some(status));
+                id
+            })
+            .collect();
+        let ids: Vec<JobId> = manager.jobs().iter().map(|job| job.id).collect();
+        manager.cancel(running_id);
+        let status = running.wait().unwrap();
+        manager.finish(running_id, Some(status));
+
+        assert_eq!(ids.len(), FINISHED_JOBS_KEPT + 1);
+        assert_eq!(ids[0], running_id);
+        assert_eq!(ids[1..], finished[3..]);
+    }
+
+    #[test]
+    fn shutdown_cancels_running_jobs() {
+        let manager = JobManager::new();
+        let mut child = spawn("sleep 30");
+        let id = manager.register("sleep", &mut child, false);
+        // The stream thread that reaps the job in the GUI
+        let reaper = {
+            let manager = manager.clone();
+            thread::spawn(move || {
+                let status = child.wait().ok();
+                manager.finish(id, status)
+            })
+        };
+
+        let started = Instant::now();
+        manager.shutdown(Duration::from_secs(10));
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(reaper.join().unwrap(), JobState::Cancelled);
+        assert_eq!(manager.running_count(), 0);
+    }
+}
@@ -0,0 +1,343 @@
+// Lynis report files (lynis-report-<timestamp>.dat, written by run_lynis_audit in
+// modules/audit.sh). The format is one `key=value` per line; keys ending in `[]` are
+// repeated to build a list. Warnings and suggestions are pipe separated:
+//
+//   warning[]=AUTH-9286|Configure maximum password age in /etc/login.defs|-|-|
+//   suggestion[]=SSH-7408|Consider hardening SSH configuration|AllowTcpForwarding (set YES to NO)|-|
+//
+// i.e. test ID, message, details and solution, with "-" for a missing field.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+
+const REPORT_PREFIX: &str = "lynis-report-";
+const REPORT_EXTENSION: &str = "dat";
+
+// Per-test documentation on the Lynis website
+const CONTROL_URL: &str = "https://cisofy.com/lynis/controls/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FindingKind {
+    Warning,
+    Suggestion,
+}
+
+impl FindingKind {
+    pub fn badge(&self) -> &'static str {
+        match self {
+            FindingKind::Warning => "⚠️ Warning",
+            FindingKind::Suggestion => "💡 Suggestion",
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            FindingKind::Warning => "warning",
+            FindingKind::Suggestion => "dim-label",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LynisFinding {
+    pub kind: FindingKind,
+    pub test_id: String,
+    pub message: String,
+    pub details: Option<String>,
+    pub solution: Option<String>,
+}
+
+impl LynisFinding {
+    fn parse(kind: FindingKind, value: &str) -> Option<Self> {
+        let mut fields = value.split('|').map(str::trim);
+        let test_id = fields.next().filter(|id| !id.is_empty())?.to_string();
+        let message = fields.next().unwrap_or_default().to_string();
+        // Some Lynis versions tag free text as "text:..."
+        let mut optional = || {
+            fields
+                .next()
+                .map(|field| field.strip_prefix("text:").unwrap_or(field).trim())
+                .filter(|field| !field.is_empty() && *field != "-")
+                .map(str::to_string)
+        };
+        let details = optional();
+        let solution = optional();
+        Some(LynisFinding { kind, test_id, message, details, solution })
+    }
+
+    // The solution when it is a link, otherwise the test's documentation page
+    pub fn link(&self) -> String {
+        match self.solution.as_deref() {
+            Some(solution) if solution.starts_with("http://") || solution.starts_with("https://") => solution.to_string(),
+            _ => format!("{}{}/", CONTROL_URL, self.test_id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LynisReport {
+    pub path: Option<PathBuf>,
+    pub hardening_index: Option<u32>,
+    pub warnings: Vec<LynisFinding>,
+    pub suggestions: Vec<LynisFinding>,
+    pub packages: Vec<Package>,
+    values: BTreeMap<String, String>,
+    lists: BTreeMap<String, Vec<String>>,
+}
+
+impl LynisReport {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let mut report = Self::parse(&content);
+        report.path = Some(path.to_path_buf());
+        Ok(report)
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let mut report = LynisReport::default();
+        for line in content.lines() {
+            let line = line.trim_end_matches('\r');
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            match key.strip_suffix("[]") {
+                Some(list) => report.lists.entry(list.to_string()).or_default().push(value.to_string()),
+                None => {
+                    report.values.insert(key.to_string(), value.to_string());
+                }
+            }
+        }
+
+        report.hardening_index = report.get("hardening_index").and_then(|value| value.trim().parse().ok());
+        report.warnings = report.findings_of(FindingKind::Warning, "warning");
+        report.suggestions = report.findings_of(FindingKind::Suggestion, "suggestion");
+        report.packages = report.parse_packages();
+        report
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str).filter(|value| !value.is_empty())
+    }
+
+    pub fn list(&self, key: &str) -> &[String] {
+        self.lists.get(key).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    // Warnings first, each group in report order
+    pub fn findings(&self) -> impl Iterator<Item = &LynisFinding> {
+        self.warnings.iter().chain(&self.suggestions)
+    }
+
+    // Start of the audit as written by Lynis, e.g. "2024-05-01 10:00:00", or the
+    // timestamp in the file name for reports without it
+    pub fn started(&self) -> String {
+        if let Some(started) = self.get("report_datetime_start") {
+            return started.to_string();
+        }
+        self.path
+            .as_deref()
+            .and_then(Path::file_stem)
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.strip_prefix(REPORT_PREFIX))
+            .unwrap_or("unknown")
+            .to_string()
+    }
+
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(version) = self.get("lynis_version") {
+            parts.push(format!("Lynis {}", version));
+        }
+        if let Some(hostname) = self.get("hostname") {
+            parts.push(hostname.to_string());
+        }
+        if let (Some(os), Some(version)) = (self.get("os_name"), self.get("os_version")) {
+            parts.push(format!("{} {}", os, version));
+        }
+        parts.push(format!("{} warnings", self.warnings.len()));
+        parts.push(format!("{} suggestions", self.suggestions.len()));
+        parts.push(format!("{} packages", self.packages.len()));
+        parts.join(" · ")
+    }
+
+    fn findings_of(&self, kind: FindingKind, key: &str) -> Vec<LynisFinding> {
+        self.list(key).iter().filter_map(|value| LynisFinding::parse(kind, value)).collect()
+    }
+
+    // "installed_packages_array=|name,version,|name,version,|" in Lynis 3, or one
+    // "installed_package[]=name,version" per package in older versions
+    fn parse_packages(&self) -> Vec<Package> {
+        let entries = self
+            .get("installed_packages_array")
+            .map(|array| array.split('|').map(str::to_string).collect())
+            .unwrap_or_else(|| self.list("installed_package").to_vec());
+        entries
+            .iter()
+            .filter_map(|entry| {
+                let mut fields = entry.split(',').map(str::trim);
+                let name = fields.next().filter(|name| !name.is_empty())?;
+                Some(Package {
+                    name: name.to_string(),
+                    version: fields.next().unwrap_or_default().to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+// All reports in `dir`, oldest first, plus one message per unreadable file
+pub fn load_reports(dir: &Path) -> (Vec<LynisReport>, Vec<String>) {
+    let files = match config::timestamped_files(dir, REPORT_PREFIX, REPORT_EXTENSION) {
+        Ok(files) => files,
+        Err(e) => return (Vec::new(), vec![e]),
+    };
+    let mut reports = Vec::new();
+    let mut errors = Vec::new();
+    for path in files {
+        match LynisReport::load(&path) {
+            Ok(report) => reports.push(report),
+            Err(e) => errors.push(e),
+        }
+    }
+    (reports, errors)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrendPoint {
+    pub label: String,
+    pub hardening_index: u32,
+}
+
+// Hardening index over time; reports without one (e.g. aborted runs) are skipped
+pub fn trend(reports: &[LynisReport]) -> Vec<TrendPoint> {
+    reports
+        .iter()
+        .filter_map(|report| {
+            Some(TrendPoint {
+                label: report.started(),
+                hardening_index: report.hardening_index?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reports as run_lynis_audit leaves them: a Lynis 3 run, an aborted one without a
+    // hardening index and a Lynis 2 report with CRLF line ends; the .log is no report
+    fn dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/lynis")
+    }
+
+    fn load(name: &str) -> LynisReport {
+        LynisReport::load(&dir().join(name)).unwrap()
+    }
+
+    #[test]
+    fn warnings_and_suggestions() {
+        let report = load("lynis-report-20251012_033001.dat");
+        let ids = |findings: &[LynisFinding]| findings.iter().map(|finding| finding.test_id.clone()).collect::<Vec<_>>();
+        // The entry without a test ID is dropped
+        assert_eq!(ids(&report.warnings), ["AUTH-9286", "FIRE-4512"]);
+        assert_eq!(ids(&report.suggestions), ["SSH-7408", "AUTH-9286", "PKGS-7370", "KRNL-6000"]);
+        assert_eq!(
+            report.warnings[0],
+            LynisFinding {
+                kind: FindingKind::Warning,
+                test_id: "AUTH-9286".to_string(),
+                message: "Configure maximum password age in /etc/login.defs".to_string(),
+                details: None,
+                solution: None,
+            }
+        );
+        let sysctl = &report.suggestions[3];
+        assert_eq!(sysctl.kind, FindingKind::Suggestion);
+        assert_eq!(sysctl.details.as_deref(), Some("fs.protected_fifos (exp: 2)"));
+        assert_eq!(sysctl.solution.as_deref(), Some("Change sysctl value or disable test (skip-test=KRNL-6000:fs.protected_fifos)"));
+        assert_eq!(sysctl.link(), "https://cisofy.com/lynis/controls/KRNL-6000/");
+        assert_eq!(report.suggestions[0].details.as_deref(), Some("AllowTcpForwarding (set YES to NO)"));
+
+        let kinds: Vec<FindingKind> = report.findings().map(|finding| finding.kind).collect();
+        assert_eq!(kinds, [[FindingKind::Warning; 2].as_slice(), &[FindingKind::Suggestion; 4]].concat());
+        assert_eq!(report.list("vulnerable_package"), ["openssl"]);
+        assert!(report.list("deleted_file").is_empty());
+    }
+
+    #[test]
+    fn report_values() {
+        let report = load("lynis-report-20251012_033001.dat");
+        assert_eq!(report.hardening_index, Some(64));
+        assert_eq!(report.started(), "2025-10-12 03:30:01");
+        assert_eq!(report.get("lynis_tests_done"), Some("262"));
+        // Empty values count as missing
+        assert_eq!(report.get("license_key"), None);
+        assert_eq!(report.summary(), "Lynis 3.0.9 · web01 · Debian 12 · 2 warnings · 4 suggestions · 4 packages");
+        assert_eq!(
+            report.packages[2],
+            Package { name: "openssh-server".to_string(), version: "1:9.2p1-2+deb12u3".to_string() }
+        );
+
+        // Without report_datetime_start the file name tells when it ran
+        let aborted = load("lynis-report-20251015_033001.dat");
+        assert_eq!(aborted.hardening_index, None);
+        assert_eq!(aborted.started(), "20251015_033001");
+        assert_eq!(aborted.summary(), "Lynis 3.0.9 · web01 · 0 warnings · 0 suggestions · 0 packages");
+        assert_eq!(LynisReport::parse("").started(), "unknown");
+        assert_eq!(LynisReport::parse("hardening_index=n/a\n").hardening_index, None);
+    }
+
+    #[test]
+    fn older_lynis_reports() {
+        let report = load("lynis-report-20251018_033002.dat");
+        assert_eq!(report.hardening_index, Some(71));
+        assert_eq!(report.started(), "2025-10-18 03:30:02");
+        assert_eq!(
+            report.packages,
+            [
+                Package { name: "adduser".to_string(), version: "3.134".to_string() },
+                Package { name: "ufw".to_string(), version: String::new() },
+            ]
+        );
+        let mail = &report.warnings[0];
+        assert_eq!(mail.message, "Found some information disclosure in SMTP banner (OS or software name)");
+        assert_eq!(mail.details.as_deref(), Some("smtpd_banner"));
+        assert_eq!(mail.link(), "https://cisofy.com/lynis/controls/MAIL-8818/");
+        // "text:-" is as empty as "-"
+        assert_eq!(report.suggestions[0].details, None);
+        assert_eq!(report.suggestions[0].solution, None);
+    }
+
+    #[test]
+    fn reports_in_a_directory() {
+        let (reports, errors) = load_reports(&dir());
+        assert!(errors.is_empty());
+        let started: Vec<String> = reports.iter().map(LynisReport::started).collect();
+        assert_eq!(started, ["2025-10-12 03:30:01", "20251015_033001", "2025-10-18 03:30:02"]);
+        // The aborted run has no point
+        assert_eq!(
+            trend(&reports),
+            [
+                TrendPoint { label: "2025-10-12 03:30:01".to_string(), hardening_index: 64 },
+                TrendPoint { label: "2025-10-18 03:30:02".to_string(), hardening_index: 71 },
+            ]
+        );
+
+        let (reports, errors) = load_reports(&dir().join("missing"));
+        assert!(reports.is_empty());
+        assert_eq!(errors.len(), 1);
+    }
+}
@@ -1,18 +1,79 @@
 use gtk4::prelude::*;
 use gtk4::{
     glib, Application, ApplicationWindow, Box, Button, HeaderBar, Label, ScrolledWindow, TextView,
-    Notebook, Frame, Grid, Separator, ProgressBar, Dialog, Entry, ResponseType
+    Notebook, Frame, Grid, Separator, ProgressBar, Dialog, ResponseType, SearchEntry, Expander, CheckButton, MenuButton, Popover, FileChooserNative,
+    Stack, StackSwitcher, DropDown, StringList, DrawingArea, LinkButton
 };
 use std::io::{BufRead, BufReader};
-use std::process::{Command, Stdio};
-use std::sync::{mpsc, Arc, Mutex};
+use std::process::{Child, Command, Stdio};
+use std::os::unix::process::CommandExt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
 use std::thread;
-use std::collections::HashSet;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+mod action;
+mod aide;
+mod apparmor;
+mod auditd;
+mod auth_activity;
+mod authlog;
+mod catalog;
+mod clamav;
+mod config;
+mod fail2ban;
+mod firewall;
+mod history;
+mod jobs;
+mod lynis;
+mod malware;
+mod network;
+mod output;
+mod privilege;
+mod progress;
+mod protocol;
+mod report;
+mod rootkit;
+mod ssh_audit;
+mod sshd_config;
+mod status;
+mod stig;
+mod sysctl;
+mod systemd;
+mod ufw;
+
+use action::HardnAction;
+use jobs::JobManager;
+use systemd::UnitOperation;
 
 const APP_ID: &str = "org.hardn.GUI";
+const STATUS_REFRESH_SECS: u32 = 30;
+const MONITOR_UNIT: &str = "hardn-monitor.service";
+
+// How long closing the window waits for cancelled jobs before killing them
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(3);
+
+// Unformatted output of a job, for tabs that post-process it (e.g. the audit report).
+// Progress lines are parsed instead of being passed on as output.
+// The channel disconnects once the job has exited and all output was forwarded.
+enum JobEvent {
+    Line(String),
+    Progress(progress::Progress),
+    Exited(Option<i32>),
+}
+type JobObserver = mpsc::Sender<JobEvent>;
 
-// Global process tracker - just track PIDs
-type ProcessTracker = Arc<Mutex<HashSet<u32>>>;
+// What workers send to an output pane; Done ends the stream
+enum OutputMessage {
+    Line(output::Stream, String),
+    // A message from the GUI itself, e.g. a job's final status
+    Status(output::Level, String),
+    // The job has exited; sent before Done
+    Ended(jobs::JobInfo),
+    Done,
+}
 
 fn main() -> glib::ExitCode {
     let app = Application::builder().application_id(APP_ID).build();
@@ -28,31 +89,42 @@ fn build_ui(app: &Application) {
         .default_height(800)
         .build();
 
-    // Create process tracker for cleanup
-    let process_tracker: ProcessTracker = Arc::new(Mutex::new(HashSet::new()));
+    // Jobs started from any tab
+    let job_manager = JobManager::new();
     
-    // Handle window close event
-    let process_tracker_clone = process_tracker.clone();
-    window.connect_close_request(move |_| {
-        println!("🛡️  GUI closing - terminating all background processes...");
-        
-        // Kill all tracked processes
-        if let Ok(pids) = process_tracker_clone.lock() {
-            for &pid in pids.iter() {
-                println!("🔪 Terminating process {}", pid);
-                let _ = Command::new("kill")
-                    .args(["-TERM", &pid.to_string()])
-                    .output();
-            }
+    // Stop our own jobs when the window closes. The window is hidden while a worker
+    // thread waits for them and destroyed once they are gone.
+    let job_manager_clone = job_manager.clone();
+    let shutting_down = Rc::new(Cell::new(false));
+    window.connect_close_request(move |window| {
+        if shutting_down.get() {
+            return glib::Propagation::Stop;
         }
-        
-        // Also kill any lingering hardn processes
-        let _ = Command::new("pkill")
-            .args(["-f", "hardn"])
-            .output();
-            
-        println!("✅ All processes terminated. GUI closed safely.");
-        glib::Propagation::Proceed
+        let running = job_manager_clone.running_count();
+        if running == 0 {
+            println!("✅ GUI closed safely.");
+            return glib::Propagation::Proceed;
+        }
+
+        println!("🛡️  GUI closing - cancelling {} running job(s)...", running);
+        shutting_down.set(true);
+        window.set_visible(false);
+        let (tx, rx) = mpsc::channel();
+        let job_manager = job_manager_clone.clone();
+        thread::spawn(move || {
+            job_manager.shutdown(SHUTDOWN_GRACE);
+            let _ = tx.send(());
+        });
+        let window = window.clone();
+        glib::timeout_add_local(Duration::from_millis(100), move || match rx.try_recv() {
+            Err(mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+            _ => {
+                println!("✅ GUI closed safely.");
+                window.destroy();
+                glib::ControlFlow::Break
+            }
+        });
+        glib::Propagation::Stop
     });
 
     let header_bar = HeaderBar::new();
@@ -71,30 +143,162 @@ fn build_ui(app: &Application) {
     let refresh_btn = Button::with_label("🔄 Refresh Status");
     refresh_btn.add_css_class("suggested-action");
     header_bar.pack_end(&refresh_btn);
+    header_bar.pack_end(&create_jobs_menu(&job_manager));
     
     window.set_titlebar(Some(&header_bar));
 
     let notebook = Notebook::new();
     notebook.set_scrollable(true);
     
-    create_dashboard_tab(&notebook, &process_tracker, &window);
-    create_hardening_tab(&notebook, &process_tracker, &window);
-    create_monitoring_tab(&notebook, &process_tracker, &window);
-    create_audit_tab(&notebook, &process_tracker, &window);
-    create_backup_tab(&notebook, &process_tracker, &window);
-    create_tools_tab(&notebook, &process_tracker, &window);
+    let (status_rows, rootkit_row) = create_dashboard_tab(&notebook, &job_manager, &window);
+    create_hardening_tab(&notebook, &job_manager, &window);
+    let monitor_status = create_monitoring_tab(&notebook, &job_manager, &window);
+    create_audit_tab(&notebook, &job_manager, &window);
+    create_stig_tab(&notebook, &job_manager, &window);
+    create_sysctl_tab(&notebook, &job_manager, &window);
+    create_network_tab(&notebook, &job_manager, &window);
+    create_firewall_tab(&notebook, &job_manager);
+    create_fail2ban_tab(&notebook, &job_manager, &window);
+    create_ssh_tab(&notebook, &job_manager);
+    create_apparmor_tab(&notebook, &job_manager);
+    create_auditd_tab(&notebook, &job_manager);
+    create_malware_tab(&notebook, &job_manager, &window);
+    create_backup_tab(&notebook, &job_manager, &window);
+    create_tools_tab(&notebook, &job_manager, &window);
+    create_history_tab(&notebook, &job_manager, &window);
+    create_settings_tab(&notebook, &job_manager);
+
+    // Probe service status on startup, on a timer and whenever Refresh is clicked
+    refresh_service_status(&status_rows);
+    refresh_rootkit_status(&rootkit_row);
+    
+    let status_rows_clone = status_rows.clone();
+    let rootkit_row_clone = rootkit_row.clone();
+    refresh_btn.connect_clicked(move |_| {
+        refresh_service_status(&status_rows_clone);
+        refresh_rootkit_status(&rootkit_row_clone);
+    });
+    
+    let status_rows_clone2 = status_rows.clone();
+    glib::timeout_add_seconds_local(STATUS_REFRESH_SECS, move || {
+        refresh_service_status(&status_rows_clone2);
+        refresh_rootkit_status(&rootkit_row);
+        glib::ControlFlow::Continue
+    });
+    
+    watch_service_status(&status_rows, &monitor_status);
 
     window.set_child(Some(&notebook));
     window.present();
 }
 
-fn create_dashboard_tab(notebook: &Notebook, process_tracker: &ProcessTracker, window: &ApplicationWindow) {
+// Header bar menu listing running and recent jobs, each running one with a Cancel button
+fn create_jobs_menu(job_manager: &JobManager) -> MenuButton {
+    let menu_btn = MenuButton::new();
+    menu_btn.set_label("⏳ Jobs");
+    menu_btn.set_tooltip_text(Some("Running and recent jobs"));
+    
+    let jobs_box = Box::new(gtk4::Orientation::Vertical, 5);
+    jobs_box.set_margin_start(10);
+    jobs_box.set_margin_end(10);
+    jobs_box.set_margin_top(10);
+    jobs_box.set_margin_bottom(10);
+    jobs_box.set_width_request(380);
+    
+    let popover = Popover::new();
+    popover.set_child(Some(&jobs_box));
+    menu_btn.set_popover(Some(&popover));
+    
+    let job_manager_clone = job_manager.clone();
+    let jobs_box_clone = jobs_box.clone();
+    popover.connect_show(move |_| {
+        show_jobs(&job_manager_clone, &jobs_box_clone);
+    });
+    
+    let job_manager = job_manager.clone();
+    let menu_btn_weak = menu_btn.downgrade();
+    glib::timeout_add_seconds_local(1, move || {
+        let Some(menu_btn) = menu_btn_weak.upgrade() else {
+            return glib::ControlFlow::Break;
+        };
+        match job_manager.running_count() {
+            0 => menu_btn.set_label("⏳ Jobs"),
+            running => menu_btn.set_label(&format!("⏳ Jobs ({})", running)),
+        }
+        if popover.is_visible() {
+            show_jobs(&job_manager, &jobs_box);
+        }
+        glib::ControlFlow::Continue
+    });
+    
+    menu_btn
+}
+
+fn show_jobs(job_manager: &JobManager, jobs_box: &Box) {
+    while let Some(child) = jobs_box.first_child() {
+        jobs_box.remove(&child);
+    }
+    
+    let jobs = job_manager.jobs();
+    if jobs.is_empty() {
+        let empty_label = Label::new(Some("No jobs started yet"));
+        empty_label.add_css_class("dim-label");
+        jobs_box.append(&empty_label);
+        return;
+    }
+    
+    // Newest first
+    for job in jobs.iter().rev() {
+        let row = Box::new(gtk4::Orientation::Horizontal, 10);
+        
+        let text_box = Box::new(gtk4::Orientation::Vertical, 2);
+        text_box.set_hexpand(true);
+        let title = Label::new(Some(&job.label));
+        title.set_xalign(0.0);
+        title.add_css_class("heading");
+        let privileges = if job.elevated { ", as administrator" } else { "" };
+        let details = Label::new(Some(&format!(
+            "{} · {}s · pid {}{}",
+            job.state.label(),
+            job.elapsed().as_secs(),
+            job.pid,
+            privileges
+        )));
+        details.set_xalign(0.0);
+        details.add_css_class("dim-label");
+        text_box.append(&title);
+        text_box.append(&details);
+        row.append(&text_box);
+        
+        if job.state.is_running() {
+            let cancel_btn = Button::with_label("⏹ Cancel");
+            cancel_btn.add_css_class("destructive-action");
+            cancel_btn.set_valign(gtk4::Align::Center);
+            let job_manager = job_manager.clone();
+            let id = job.id;
+            cancel_btn.connect_clicked(move |btn| {
+                if job_manager.cancel(id) {
+                    println!("🛑 Cancelling job {}", id);
+                }
+                btn.set_sensitive(false);
+                btn.set_label("Cancelling...");
+            });
+            row.append(&cancel_btn);
+        }
+        
+        jobs_box.append(&row);
+    }
+}
+
+fn create_dashboard_tab(notebook: &Notebook, job_manager: &JobManager, window: &ApplicationWindow) -> (Vec<(Label, Label)>, (Label, Label)) {
     let main_box = Box::new(gtk4::Orientation::Vertical, 10);
     main_box.set_margin_start(20);
     main_box.set_margin_end(20);
     main_box.set_margin_top(20);
     main_box.set_margin_bottom(20);
     
+    let info_output = create_output_area();
+    
     let status_frame = Frame::new(Some("System Status Overview"));
     let status_grid = Grid::new();
     status_grid.set_row_spacing(10);
@@ -104,29 +308,64 @@ fn create_dashboard_tab(notebook: &Notebook, process_tracker: &ProcessTracker, w
     status_grid.set_margin_top(15);
     status_grid.set_margin_bottom(15);
     
-    let services = vec![
-        ("UFW Firewall", "🔥"),
-        ("Fail2Ban IPS", "🚫"),
-        ("Audit System", "📊"),
-        ("AppArmor MAC", "🛡️"),
-        ("ClamAV Antivirus", "🦠"),
-        ("SSH Hardening", "🔐"),
-    ];
+    let mut status_rows = Vec::new();
     
-    for (i, (service, icon)) in services.iter().enumerate() {
+    for (i, (service, icon, unit)) in status::MONITORED_SERVICES.iter().enumerate() {
         let icon_label = Label::new(Some(icon));
         let service_label = Label::new(Some(service));
+        service_label.set_xalign(0.0);
         let status_label = Label::new(Some("Checking..."));
+        status_label.set_xalign(0.0);
         status_label.add_css_class("dim-label");
+        let detail_label = Label::new(None);
+        detail_label.set_xalign(0.0);
+        detail_label.add_css_class("dim-label");
         
         status_grid.attach(&icon_label, 0, i as i32, 1, 1);
         status_grid.attach(&service_label, 1, i as i32, 1, 1);
         status_grid.attach(&status_label, 2, i as i32, 1, 1);
+        status_grid.attach(&detail_label, 3, i as i32, 1, 1);
+        
+        if systemd::MANAGED_UNITS.contains(unit) {
+            let controls = Box::new(gtk4::Orientation::Horizontal, 5);
+            for (icon, operation) in [("▶️", UnitOperation::Start), ("⏹️", UnitOperation::Stop), ("🔄", UnitOperation::Restart)] {
+                let btn = Button::with_label(icon);
+                btn.add_css_class("flat");
+                btn.set_tooltip_text(Some(&format!("{} {}", operation.label(), unit)));
+                
+                let info_clone = info_output.clone();
+                let unit = unit.to_string();
+                btn.connect_clicked(move |btn| {
+                    run_unit_operations(vec![(unit.clone(), operation)], &info_clone, btn);
+                });
+                controls.append(&btn);
+            }
+            status_grid.attach(&controls, 4, i as i32, 1, 1);
+        }
+        
+        status_rows.push((status_label, detail_label));
     }
     
+    // Latest rootkit scans, less the whitelisted false positives
+    let rootkit_row = status::MONITORED_SERVICES.len() as i32;
+    let rootkit_status = Label::new(Some("Checking..."));
+    rootkit_status.set_xalign(0.0);
+    rootkit_status.add_css_class("dim-label");
+    let rootkit_detail = Label::new(None);
+    rootkit_detail.set_xalign(0.0);
+    rootkit_detail.add_css_class("dim-label");
+    let rootkit_label = Label::new(Some("Rootkit Scan"));
+    rootkit_label.set_xalign(0.0);
+    status_grid.attach(&Label::new(Some("🕵️")), 0, rootkit_row, 1, 1);
+    status_grid.attach(&rootkit_label, 1, rootkit_row, 1, 1);
+    status_grid.attach(&rootkit_status, 2, rootkit_row, 1, 1);
+    status_grid.attach(&rootkit_detail, 3, rootkit_row, 1, 1);
+    
     status_frame.set_child(Some(&status_grid));
     main_box.append(&status_frame);
     
+    create_auth_activity_frame(&main_box, &info_output, job_manager, window);
+    
     let actions_frame = Frame::new(Some("Quick Actions"));
     let actions_box = Box::new(gtk4::Orientation::Horizontal, 10);
     actions_box.set_margin_start(15);
@@ -150,41 +389,519 @@ fn create_dashboard_tab(notebook: &Notebook, process_tracker: &ProcessTracker, w
     main_box.append(&actions_frame);
     
     let info_frame = Frame::new(Some("System Information"));
-    let info_output = create_output_area();
-    info_frame.set_child(Some(&info_output));
+    info_frame.set_child(Some(&info_output.container));
     main_box.append(&info_frame);
     
-    let info_clone = info_output.clone();
-    let tracker_clone = process_tracker.clone();
-    let window_weak = window.downgrade();
-    status_btn.connect_clicked(move |btn| {
-        if let Some(win) = window_weak.upgrade() {
-            setup_command_handlers("hardn status", &info_clone, btn, &tracker_clone, &win);
+    connect_action(&status_btn, HardnAction::hardn("System Status", "status"), &info_output, job_manager, window);
+    connect_action(
+        &monitor_btn,
+        HardnAction::hardn("Start Monitoring", "monitor").arg("start").root().expected_secs(30),
+        &info_output,
+        job_manager,
+        window,
+    );
+    connect_action(
+        &audit_btn,
+        HardnAction::hardn("Security Audit", "audit").root().expected_secs(600),
+        &info_output,
+        job_manager,
+        window,
+    );
+    
+    notebook.append_page(&main_box, Some(&Label::new(Some("�� Dashboard"))));
+    
+    (status_rows, (rootkit_status, rootkit_detail))
+}
+
+// Failed and successful logins from auth.log or the journal, below the service status
+fn create_auth_activity_frame(main_box: &Box, output_area: &OutputPane, job_manager: &JobManager, window: &ApplicationWindow) {
+    let frame = Frame::new(Some("Authentication Activity"));
+    let frame_box = Box::new(gtk4::Orientation::Vertical, 8);
+    frame_box.set_margin_start(15);
+    frame_box.set_margin_end(15);
+    frame_box.set_margin_top(15);
+    frame_box.set_margin_bottom(15);
+    
+    let auth_log = authlog::AuthLog::from_env();
+    let toolbar = Box::new(gtk4::Orientation::Horizontal, 5);
+    let window_labels: Vec<&str> = authlog::WINDOWS.iter().map(|(label, _, _)| *label).collect();
+    let window_selector = DropDown::from_strings(&window_labels);
+    let source_selector = DropDown::from_strings(&[authlog::Source::AuthLog.label(), authlog::Source::Journal.label()]);
+    source_selector.set_selected(if auth_log.default_source() == authlog::Source::AuthLog { 0 } else { 1 });
+    let refresh_btn = Button::with_label("🔄 Refresh");
+    refresh_btn.set_tooltip_text(Some("Read the log as the current user, which works for members of adm or systemd-journal"));
+    let load_btn = Button::with_label("🔑 Load as Administrator");
+    let load_action = HardnAction::privileged(
+        "Read Authentication Log",
+        protocol::HelperRequest::ReadAuthLog { source: authlog::Source::AuthLog, since: 0 },
+    );
+    set_action_tooltip(&load_btn, &load_action);
+    toolbar.append(&window_selector);
+    toolbar.append(&source_selector);
+    toolbar.append(&refresh_btn);
+    toolbar.append(&load_btn);
+    frame_box.append(&toolbar);
+    
+    let summary_label = Label::new(Some("Reading..."));
+    summary_label.set_xalign(0.0);
+    summary_label.set_wrap(true);
+    frame_box.append(&summary_label);
+    
+    let histogram_area = DrawingArea::new();
+    histogram_area.set_content_height(90);
+    histogram_area.set_hexpand(true);
+    frame_box.append(&histogram_area);
+    
+    let tables = Box::new(gtk4::Orientation::Horizontal, 15);
+    let alerts_list = Box::new(gtk4::Orientation::Vertical, 4);
+    let sources_grid = Grid::new();
+    let users_grid = Grid::new();
+    for (title, child, expand) in [
+        ("Alerts", alerts_list.clone().upcast::<gtk4::Widget>(), true),
+        ("Failed logins by address", sources_grid.clone().upcast(), false),
+        ("Failures by user", users_grid.clone().upcast(), false),
+    ] {
+        if let Some(grid) = child.downcast_ref::<Grid>() {
+            grid.set_row_spacing(4);
+            grid.set_column_spacing(12);
+        }
+        let pane = Box::new(gtk4::Orientation::Vertical, 4);
+        let heading = Label::new(Some(title));
+        heading.set_xalign(0.0);
+        heading.add_css_class("heading");
+        pane.append(&heading);
+        pane.append(
+            &ScrolledWindow::builder()
+                .hscrollbar_policy(gtk4::PolicyType::Automatic)
+                .vscrollbar_policy(gtk4::PolicyType::Automatic)
+                .min_content_height(150)
+                .child(&child)
+                .build(),
+        );
+        pane.set_hexpand(expand);
+        tables.append(&pane);
+    }
+    frame_box.append(&tables);
+    
+    frame.set_child(Some(&frame_box));
+    main_box.append(&frame);
+    
+    let view = AuthActivityView {
+        window_selector,
+        source_selector,
+        summary_label,
+        histogram_area,
+        alerts_list,
+        sources_grid,
+        users_grid,
+        analysis: Rc::new(RefCell::new(None)),
+        range: Rc::new(Cell::new((0, 0, 1))),
+        output_area: output_area.clone(),
+        job_manager: job_manager.clone(),
+        window: window.downgrade(),
+    };
+    
+    let view_clone = view.clone();
+    view.histogram_area.set_draw_func(move |_, cr, width, height| {
+        let analysis = view_clone.analysis.borrow();
+        let (start, end, bucket) = view_clone.range.get();
+        let buckets = analysis.as_ref().map(|analysis| analysis.histogram(start, end, bucket)).unwrap_or_default();
+        draw_auth_histogram(cr, f64::from(width), f64::from(height), &buckets, start, bucket);
+    });
+    view.refresh();
+    
+    let view_clone = view.clone();
+    refresh_btn.connect_clicked(move |_| view_clone.refresh());
+    
+    let view_clone = view.clone();
+    load_btn.connect_clicked(move |btn| view_clone.load(btn));
+    
+    for selector in [&view.window_selector, &view.source_selector] {
+        let view_clone = view.clone();
+        selector.connect_selected_notify(move |_| view_clone.refresh());
+    }
+}
+
+// State of the Dashboard's authentication activity frame
+#[derive(Clone)]
+struct AuthActivityView {
+    window_selector: DropDown,
+    source_selector: DropDown,
+    summary_label: Label,
+    histogram_area: DrawingArea,
+    alerts_list: Box,
+    sources_grid: Grid,
+    users_grid: Grid,
+    // None until a log could be read
+    analysis: Rc<RefCell<Option<auth_activity::Analysis>>>,
+    // Start, end and bucket width of the histogram, in seconds
+    range: Rc<Cell<(i64, i64, i64)>>,
+    output_area: OutputPane,
+    job_manager: JobManager,
+    window: glib::WeakRef<ApplicationWindow>,
+}
+
+// Rows per table; the rest is in the counts of the summary
+const MAX_AUTH_ROWS: usize = 15;
+
+impl AuthActivityView {
+    fn source(&self) -> authlog::Source {
+        if self.source_selector.selected() == 0 {
+            authlog::Source::AuthLog
+        } else {
+            authlog::Source::Journal
+        }
+    }
+    
+    // Sets the range for the selected window ending now and returns (since, now)
+    fn update_range(&self) -> (i64, i64) {
+        let now = glib::DateTime::now_utc().map(|now| now.to_unix()).unwrap_or(0);
+        let (_, length, bucket) = authlog::WINDOWS[(self.window_selector.selected() as usize).min(authlog::WINDOWS.len() - 1)];
+        // Whole buckets, so bars line up with the hour or day
+        let start = (now - length) / bucket * bucket;
+        self.range.set((start, now, bucket));
+        (start, now)
+    }
+    
+    fn refresh(&self) {
+        let (since, now) = self.update_range();
+        let source = self.source();
+        self.summary_label.set_text(&format!("Reading the {}...", source.label()));
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(authlog::AuthLog::from_env().activity(source, since, now));
+        });
+        
+        let view = self.clone();
+        glib::timeout_add_local(Duration::from_millis(100), move || match rx.try_recv() {
+            Ok(activity) => {
+                view.show(activity);
+                glib::ControlFlow::Break
+            }
+            Err(mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+            Err(mpsc::TryRecvError::Disconnected) => glib::ControlFlow::Break,
+        });
+    }
+    
+    fn load(&self, btn: &Button) {
+        let Some(window) = self.window.upgrade() else {
+            return;
+        };
+        let (since, now) = self.update_range();
+        let source = self.source();
+        let action = HardnAction::privileged("Read Authentication Log", protocol::HelperRequest::ReadAuthLog { source, since });
+        let (observer, events) = mpsc::channel();
+        dispatch_action(&action, &self.output_area, btn, &self.job_manager, &window, Some(observer));
+        
+        let view = self.clone();
+        drain_observer(events, Vec::new(), |lines, line| lines.push(line), move |lines, exit_code| {
+            if exit_code == Some(0) {
+                let mut activity = authlog::Activity::parse_transcript(&lines.join("\n"), since, now);
+                activity.source = format!("{} (as administrator)", source.label());
+                view.show(activity);
+            } else {
+                view.summary_label.set_text("❌ Could not read the authentication log; see the output");
+            }
+        });
+    }
+    
+    fn show(&self, activity: authlog::Activity) {
+        let analysis = auth_activity::Analysis::new(activity);
+        let mut summary = vec![
+            format!("{} failed logins from {} addresses", analysis.failures(), analysis.sources.len()),
+            format!("{} successful logins", analysis.successes(authlog::EventKind::Login)),
+            format!("{} sudo/su", analysis.successes(authlog::EventKind::Privilege)),
+        ];
+        let high = analysis.alerts.iter().filter(|alert| alert.severity == report::Severity::High).count();
+        if high > 0 {
+            summary.push(format!("🔴 {} successful logins after repeated failures", high));
+        }
+        if !analysis.activity.source.is_empty() {
+            summary.push(format!("from {}", analysis.activity.source));
+        }
+        summary.extend(analysis.activity.errors.iter().map(|error| format!("❌ {}", error)));
+        self.summary_label.set_text(&summary.join(" · "));
+        
+        while let Some(child) = self.alerts_list.first_child() {
+            self.alerts_list.remove(&child);
+        }
+        for alert in analysis.alerts.iter().take(MAX_AUTH_ROWS) {
+            let row = Box::new(gtk4::Orientation::Horizontal, 8);
+            let severity = Label::new(Some(alert.severity.badge()));
+            severity.set_valign(gtk4::Align::Start);
+            severity.add_css_class(alert.severity.css_class());
+            row.append(&severity);
+            let text = Label::new(Some(&format!("{}  {}\n{}", format_unix_time(alert.time), alert.title, alert.detail)));
+            text.set_xalign(0.0);
+            text.set_wrap(true);
+            text.set_selectable(true);
+            row.append(&text);
+            self.alerts_list.append(&row);
+        }
+        if analysis.alerts.is_empty() {
+            let label = Label::new(Some("No brute force and no login after repeated failures"));
+            label.set_xalign(0.0);
+            label.add_css_class("dim-label");
+            self.alerts_list.append(&label);
+        }
+        
+        let (_, now, _) = self.range.get();
+        let sources: Vec<Vec<String>> = analysis
+            .sources
+            .iter()
+            .take(MAX_AUTH_ROWS)
+            .map(|source| {
+                vec![
+                    source.ip.to_string(),
+                    source.failures.to_string(),
+                    source.users.iter().cloned().collect::<Vec<_>>().join(", "),
+                    analysis.ban_state(source.ip, now).label(),
+                ]
+            })
+            .collect();
+        fill_auth_grid(&self.sources_grid, &["Address", "Failures", "Users", "Fail2Ban"], &sources);
+        let users: Vec<Vec<String>> = analysis
+            .users
+            .iter()
+            .take(MAX_AUTH_ROWS)
+            .map(|user| vec![user.user.clone(), user.failures.to_string(), user.sources.len().to_string(), user.successes.to_string()])
+            .collect();
+        fill_auth_grid(&self.users_grid, &["User", "Failures", "Addresses", "Successes"], &users);
+        
+        self.analysis.replace(Some(analysis));
+        self.histogram_area.queue_draw();
+    }
+}
+
+fn fill_auth_grid(grid: &Grid, headers: &[&str], rows: &[Vec<String>]) {
+    while let Some(child) = grid.first_child() {
+        grid.remove(&child);
+    }
+    for (column, title) in headers.iter().enumerate() {
+        let header = Label::new(Some(title));
+        header.set_xalign(0.0);
+        header.add_css_class("dim-label");
+        grid.attach(&header, column as i32, 0, 1, 1);
+    }
+    for (row, cells) in rows.iter().enumerate() {
+        for (column, cell) in cells.iter().enumerate() {
+            let label = Label::new(Some(cell));
+            label.set_xalign(0.0);
+            label.set_selectable(column == 0);
+            grid.attach(&label, column as i32, row as i32 + 1, 1, 1);
+        }
+    }
+    if rows.is_empty() {
+        let label = Label::new(Some("No failed logins"));
+        label.set_xalign(0.0);
+        label.add_css_class("dim-label");
+        grid.attach(&label, 0, 1, headers.len() as i32, 1);
+    }
+}
+
+fn format_unix_time(seconds: i64) -> String {
+    glib::DateTime::from_unix_local(seconds)
+        .ok()
+        .and_then(|dt| dt.format("%Y-%m-%d %H:%M").ok())
+        .map(|time| time.to_string())
+        .unwrap_or_default()
+}
+
+// Failures stacked on successes per bucket, red over green
+fn draw_auth_histogram(cr: &gtk4::cairo::Context, width: f64, height: f64, buckets: &[(u32, u32)], start: i64, bucket: i64) {
+    let (left, right, top, bottom) = (32.0, 12.0, 8.0, 16.0);
+    let plot_width = (width - left - right).max(1.0);
+    let plot_height = (height - top - bottom).max(1.0);
+    let max = buckets.iter().map(|(failures, successes)| failures + successes).max().unwrap_or(0).max(1);
+    
+    cr.set_font_size(10.0);
+    cr.set_line_width(1.0);
+    cr.set_source_rgba(0.5, 0.5, 0.5, 0.3);
+    cr.move_to(left, top + plot_height);
+    cr.line_to(width - right, top + plot_height);
+    let _ = cr.stroke();
+    cr.set_source_rgb(0.5, 0.5, 0.5);
+    cr.move_to(4.0, top + 8.0);
+    let _ = cr.show_text(&max.to_string());
+    cr.move_to(4.0, top + plot_height);
+    let _ = cr.show_text("0");
+    
+    if buckets.is_empty() {
+        return;
+    }
+    let bar_width = plot_width / buckets.len() as f64;
+    for (i, (failures, successes)) in buckets.iter().enumerate() {
+        let x = left + bar_width * i as f64;
+        let success_height = plot_height * f64::from(*successes) / f64::from(max);
+        let failure_height = plot_height * f64::from(*failures) / f64::from(max);
+        cr.set_source_rgb(0.18, 0.76, 0.49);
+        cr.rectangle(x + 1.0, top + plot_height - success_height, (bar_width - 2.0).max(1.0), success_height);
+        let _ = cr.fill();
+        cr.set_source_rgb(0.88, 0.11, 0.14);
+        cr.rectangle(x + 1.0, top + plot_height - success_height - failure_height, (bar_width - 2.0).max(1.0), failure_height);
+        let _ = cr.fill();
+    }
+    
+    // Start and end of the window below the axis
+    cr.set_source_rgb(0.5, 0.5, 0.5);
+    cr.move_to(left, height - 3.0);
+    let _ = cr.show_text(&format_unix_time(start));
+    let last = format_unix_time(start + bucket * buckets.len() as i64);
+    let extents_width = cr.text_extents(&last).map(|extents| extents.width()).unwrap_or(0.0);
+    cr.move_to(width - right - extents_width, height - 3.0);
+    let _ = cr.show_text(&last);
+}
+fn refresh_service_status(status_rows: &[(Label, Label)]) {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(status::probe_all());
+    });
+    
+    let status_rows = status_rows.to_vec();
+    glib::timeout_add_local(Duration::from_millis(100), move || {
+        match rx.try_recv() {
+            Ok(statuses) => {
+                for ((status_label, detail_label), service) in status_rows.iter().zip(statuses.iter()) {
+                    apply_service_status(status_label, detail_label, service);
+                }
+                glib::ControlFlow::Break
+            }
+            Err(mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+            Err(mpsc::TryRecvError::Disconnected) => glib::ControlFlow::Break,
+        }
+    });
+}
+
+fn refresh_rootkit_status((status_label, detail_label): &(Label, Label)) {
+    let results = rootkit::load_latest(&config::log_dir());
+    let whitelist = rootkit::Whitelist::load();
+    let active = match &whitelist {
+        Ok(whitelist) => whitelist.active(&results.findings),
+        // Never hide findings behind a whitelist that cannot be read
+        Err(_) => results.findings.iter().collect(),
+    };
+    
+    let (state, class) = if results.sources.is_empty() {
+        ("⚪ No scan yet".to_string(), "dim-label")
+    } else if active.iter().any(|finding| finding.severity == report::Severity::High) {
+        (format!("🔴 {} findings", active.len()), "error")
+    } else if !active.is_empty() {
+        (format!("🟠 {} findings", active.len()), "warning")
+    } else {
+        ("🟢 Nothing found".to_string(), "success")
+    };
+    for class in ["success", "warning", "error", "dim-label"] {
+        status_label.remove_css_class(class);
+    }
+    status_label.add_css_class(class);
+    status_label.set_text(&state);
+    
+    let mut detail = Vec::new();
+    match &whitelist {
+        Ok(_) if results.findings.len() > active.len() => detail.push(format!("{} whitelisted", results.findings.len() - active.len())),
+        Ok(_) => {}
+        Err(e) => detail.push(format!("whitelist unreadable: {}", e)),
+    }
+    for (tool, path) in &results.sources {
+        let scanned = path.file_stem().and_then(|stem| stem.to_str()).and_then(|stem| stem.split_once('-')).map_or("", |(_, timestamp)| timestamp);
+        detail.push(format!("{} {}", tool.label(), scanned));
+    }
+    detail.extend(results.errors.first().cloned());
+    detail_label.set_text(&detail.join(" · "));
+}
+
+fn apply_service_status(status_label: &Label, detail_label: &Label, service: &status::ServiceStatus) {
+    for class in ["success", "warning", "error", "dim-label"] {
+        status_label.remove_css_class(class);
+    }
+    status_label.add_css_class(service.state.css_class());
+    status_label.set_text(&service.summary());
+    status_label.set_tooltip_text(Some(&service.details()));
+    
+    let mut detail = Vec::new();
+    let since = service
+        .since_usec
+        .and_then(|usec| glib::DateTime::from_unix_local((usec / 1_000_000) as i64).ok())
+        .and_then(|dt| dt.format("%Y-%m-%d %H:%M:%S").ok());
+    if let Some(since) = since {
+        detail.push(format!("since {}", since));
+    }
+    if let Some(pid) = service.main_pid {
+        detail.push(format!("PID {}", pid));
+    }
+    if service.unit == sshd_config::SSH_UNIT {
+        detail.extend(ssh_audit::dashboard_summary());
+    }
+    detail_label.set_text(&detail.join(" · "));
+}
+
+fn watch_service_status(status_rows: &[(Label, Label)], monitor_status: &Label) {
+    let (tx, rx) = mpsc::channel::<status::ServiceStatus>();
+    thread::spawn(move || {
+        let mut units: Vec<&str> = status::MONITORED_SERVICES.iter().map(|(_, _, unit)| *unit).collect();
+        units.push(MONITOR_UNIT);
+        
+        match systemd::SystemdClient::connect() {
+            Ok(client) => {
+                if let Err(e) = client.watch_units(&units, tx) {
+                    eprintln!("Failed to subscribe to systemd unit changes: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to connect to systemd over D-Bus: {}", e),
         }
     });
     
-    let info_clone2 = info_output.clone();
-    let tracker_clone2 = process_tracker.clone();
-    let window_weak2 = window.downgrade();
-    monitor_btn.connect_clicked(move |btn| {
-        if let Some(win) = window_weak2.upgrade() {
-            setup_command_handlers("hardn monitor start", &info_clone2, btn, &tracker_clone2, &win);
+    let status_rows = status_rows.to_vec();
+    let monitor_status = monitor_status.clone();
+    glib::timeout_add_local(Duration::from_millis(500), move || {
+        loop {
+            match rx.try_recv() {
+                Ok(service) if service.unit == MONITOR_UNIT => {
+                    monitor_status.set_text(&format!("Status: {}", service.summary()));
+                }
+                Ok(service) => {
+                    let row = status::MONITORED_SERVICES.iter().position(|(_, _, unit)| *unit == service.unit);
+                    if let Some((status_label, detail_label)) = row.and_then(|i| status_rows.get(i)) {
+                        apply_service_status(status_label, detail_label, &service);
+                    }
+                }
+                Err(mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(mpsc::TryRecvError::Disconnected) => return glib::ControlFlow::Break,
+            }
         }
     });
+}
+
+fn run_unit_operations(steps: Vec<(String, UnitOperation)>, output_area: &OutputPane, button: &Button) {
+    let summary: Vec<String> = steps.iter().map(|(unit, op)| format!("{} {}", op.label(), unit)).collect();
+    output_area.clear(&format!("Running: systemctl {}\n", summary.join(", ")));
+    let buffer = output_area.buffer();
+    buffer.insert_at_cursor("=".repeat(60).as_str());
+    buffer.insert_at_cursor("\n");
     
-    let info_clone3 = info_output.clone();
-    let tracker_clone3 = process_tracker.clone();
-    let window_weak3 = window.downgrade();
-    audit_btn.connect_clicked(move |btn| {
-        if let Some(win) = window_weak3.upgrade() {
-            setup_command_handlers("hardn audit", &info_clone3, btn, &tracker_clone3, &win);
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        match systemd::SystemdClient::connect() {
+            Ok(client) => {
+                for (unit, operation) in steps {
+                    let message = match client.run(&unit, operation) {
+                        Ok(detail) => OutputMessage::Status(output::Level::Pass, format!("✅ {} {}: {}", operation.label(), unit, detail)),
+                        Err(e) => OutputMessage::Status(output::Level::Error, format!("❌ {} {} failed: {}", operation.label(), unit, e)),
+                    };
+                    let _ = tx.send(message);
+                }
+            }
+            Err(e) => {
+                let _ = tx.send(OutputMessage::Status(output::Level::Error, format!("❌ Cannot connect to systemd over D-Bus: {}", e)));
+            }
         }
+        let _ = tx.send(OutputMessage::Done);
     });
     
-    notebook.append_page(&main_box, Some(&Label::new(Some("�� Dashboard"))));
+    stream_to_output(rx, output_area, button, None);
 }
 
-fn create_hardening_tab(notebook: &Notebook, process_tracker: &ProcessTracker, window: &ApplicationWindow) {
+fn create_hardening_tab(notebook: &Notebook, job_manager: &JobManager, window: &ApplicationWindow) {
     let main_box = Box::new(gtk4::Orientation::Horizontal, 10);
     main_box.set_margin_start(20);
     main_box.set_margin_end(20);
@@ -210,66 +927,114 @@ fn create_hardening_tab(notebook: &Notebook, process_tracker: &ProcessTracker, w
     
     let separator = Separator::new(gtk4::Orientation::Horizontal);
     
+    let setup_progress = ProgressPanel::new("Setup not started");
+    
     hardening_box.append(&setup_btn);
     hardening_box.append(&setup_ni_btn);
+    hardening_box.append(&setup_progress.container);
     hardening_box.append(&separator);
     
-    let tools = vec![
-        ("🔥 UFW Firewall", "sudo /usr/share/hardn/tools/ufw.sh"),
-        ("🚫 Fail2Ban Setup", "sudo /usr/share/hardn/tools/fail2ban.sh"),
-        ("🔐 SSH Hardening", "sudo /usr/share/hardn/tools/openssh.sh"),
-        ("🛡️ AppArmor Setup", "sudo /usr/share/hardn/tools/apparmor.sh"),
-        ("📦 System Updates", "sudo /usr/share/hardn/tools/update_system_packages.sh"),
-        ("🧹 System Cleanup", "sudo /usr/share/hardn/tools/cleanup.sh"),
-    ];
-    
     let output_area = create_output_area();
     
-    for (name, cmd) in tools {
-        let btn = Button::with_label(name);
-        btn.set_height_request(35);
+    let search_entry = SearchEntry::new();
+    search_entry.set_placeholder_text(Some("Search tools, categories, STIG..."));
+    hardening_box.append(&search_entry);
+    
+    let catalog_box = Box::new(gtk4::Orientation::Vertical, 5);
+    let catalog = catalog::ToolCatalog::load_default();
+    let mut catalog_rows = Vec::new();
+    
+    for (i, (category, tools)) in catalog.by_category().into_iter().enumerate() {
+        let expander = Expander::new(Some(&format!("{} ({})", category, tools.len())));
+        expander.set_expanded(i == 0);
+        let category_box = Box::new(gtk4::Orientation::Vertical, 5);
+        category_box.set_margin_top(5);
         
-        let output_clone = output_area.clone();
-        let cmd_clone = cmd.to_string();
-        let tracker_clone = process_tracker.clone();
-        let window_weak = window.downgrade();
-        btn.connect_clicked(move |btn| {
-            if let Some(win) = window_weak.upgrade() {
-                setup_command_handlers(&cmd_clone, &output_clone, btn, &tracker_clone, &win);
+        let mut buttons = Vec::new();
+        for tool in tools {
+            let btn = Button::with_label(&tool.label());
+            btn.set_height_request(35);
+            connect_action(&btn, tool.action(), &output_area, job_manager, window);
+            
+            let mut tooltip = tool.description.clone();
+            if let Some(stig) = &tool.stig {
+                tooltip.push_str(&format!("\nSTIG: {}", stig));
             }
-        });
+            if let Some(command) = btn.tooltip_text() {
+                tooltip.push_str(&format!("\n\n{}", command));
+            }
+            btn.set_tooltip_text(Some(tooltip.trim()));
+            
+            category_box.append(&btn);
+            buttons.push((btn, tool.clone()));
+        }
         
-        hardening_box.append(&btn);
+        expander.set_child(Some(&category_box));
+        catalog_box.append(&expander);
+        catalog_rows.push((expander, buttons));
     }
     
-    hardening_frame.set_child(Some(&hardening_box));
-    left_panel.append(&hardening_frame);
+    if catalog.tools.is_empty() {
+        let empty_label = Label::new(Some(&format!("No tools found in {}", protocol::TOOLS_DIR)));
+        empty_label.add_css_class("dim-label");
+        catalog_box.append(&empty_label);
+    }
     
-    let output_clone = output_area.clone();
-    let tracker_clone = process_tracker.clone();
-    let window_weak = window.downgrade();
-    setup_btn.connect_clicked(move |btn| {
-        if let Some(win) = window_weak.upgrade() {
-            setup_command_handlers("sudo hardn setup", &output_clone, btn, &tracker_clone, &win);
+    search_entry.connect_search_changed(move |entry| {
+        let query = entry.text();
+        for (expander, buttons) in &catalog_rows {
+            let mut any_visible = false;
+            for (btn, tool) in buttons {
+                let visible = tool.matches(&query);
+                btn.set_visible(visible);
+                any_visible |= visible;
+            }
+            expander.set_visible(any_visible);
+            if !query.is_empty() {
+                expander.set_expanded(any_visible);
+            }
         }
     });
     
-    let output_clone2 = output_area.clone();
-    let tracker_clone2 = process_tracker.clone();
-    let window_weak2 = window.downgrade();
-    setup_ni_btn.connect_clicked(move |btn| {
-        if let Some(win) = window_weak2.upgrade() {
-            setup_command_handlers("sudo hardn setup --non-interactive", &output_clone2, btn, &tracker_clone2, &win);
-        }
-    });
+    let catalog_scroll = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .vscrollbar_policy(gtk4::PolicyType::Automatic)
+        .vexpand(true)
+        .child(&catalog_box)
+        .build();
+    hardening_box.append(&catalog_scroll);
+    
+    hardening_frame.set_child(Some(&hardening_box));
+    hardening_frame.set_vexpand(true);
+    left_panel.append(&hardening_frame);
+    
+    connect_action_with_progress(
+        &setup_btn,
+        HardnAction::hardn("Complete Setup", "setup").root().expected_secs(1200),
+        &output_area,
+        job_manager,
+        window,
+        &setup_progress,
+    );
+    connect_action_with_progress(
+        &setup_ni_btn,
+        HardnAction::hardn("Non-Interactive Setup", "setup")
+            .option("--non-interactive")
+            .root()
+            .expected_secs(1200),
+        &output_area,
+        job_manager,
+        window,
+        &setup_progress,
+    );
     
     main_box.append(&left_panel);
-    main_box.append(&output_area);
+    main_box.append(&output_area.container);
     
     notebook.append_page(&main_box, Some(&Label::new(Some("⚙️ Hardening"))));
 }
 
-fn create_monitoring_tab(notebook: &Notebook, process_tracker: &ProcessTracker, window: &ApplicationWindow) {
+fn create_monitoring_tab(notebook: &Notebook, job_manager: &JobManager, window: &ApplicationWindow) -> Label {
     let main_box = Box::new(gtk4::Orientation::Vertical, 10);
     main_box.set_margin_start(20);
     main_box.set_margin_end(20);
@@ -301,32 +1066,37 @@ fn create_monitoring_tab(notebook: &Notebook, process_tracker: &ProcessTracker,
     
     let output_frame = Frame::new(Some("Real-time Monitoring"));
     let output_area = create_output_area();
-    output_frame.set_child(Some(&output_area));
+    output_frame.set_child(Some(&output_area.container));
     main_box.append(&output_frame);
     
     let output_clone = output_area.clone();
     let status_clone = status_indicator.clone();
-    let tracker_clone = process_tracker.clone();
-    let window_weak = window.downgrade();
     start_btn.connect_clicked(move |btn| {
         status_clone.set_text("Status: Starting...");
-        if let Some(win) = window_weak.upgrade() {
-            setup_command_handlers("sudo hardn monitor start", &output_clone, btn, &tracker_clone, &win);
-        }
+        let steps = systemd::MONITORING_START_UNITS
+            .iter()
+            .flat_map(|unit| [(unit.to_string(), UnitOperation::Enable), (unit.to_string(), UnitOperation::Start)])
+            .collect();
+        run_unit_operations(steps, &output_clone, btn);
     });
     
     let output_clone2 = output_area.clone();
     let status_clone2 = status_indicator.clone();
-    let tracker_clone2 = process_tracker.clone();
     stop_btn.connect_clicked(move |btn| {
         status_clone2.set_text("Status: Stopping...");
-        run_command_in_output("hardn monitor stop", &output_clone2, btn, &tracker_clone2);
+        let steps = systemd::MONITORING_STOP_UNITS
+            .iter()
+            .flat_map(|unit| [(unit.to_string(), UnitOperation::Stop), (unit.to_string(), UnitOperation::Disable)])
+            .collect();
+        run_unit_operations(steps, &output_clone2, btn);
     });
     
     notebook.append_page(&main_box, Some(&Label::new(Some("📊 Monitoring"))));
+    
+    status_indicator
 }
 
-fn create_audit_tab(notebook: &Notebook, process_tracker: &ProcessTracker, window: &ApplicationWindow) {
+fn create_audit_tab(notebook: &Notebook, job_manager: &JobManager, window: &ApplicationWindow) {
     let main_box = Box::new(gtk4::Orientation::Vertical, 10);
     main_box.set_margin_start(20);
     main_box.set_margin_end(20);
@@ -344,327 +1114,5595 @@ fn create_audit_tab(notebook: &Notebook, process_tracker: &ProcessTracker, windo
     audit_btn.add_css_class("suggested-action");
     audit_btn.set_height_request(50);
     
-    let progress = ProgressBar::new();
-    progress.set_show_text(true);
-    progress.set_text(Some("Ready to run audit"));
+    let audit_progress = ProgressPanel::new("Ready to run audit");
+    audit_progress.container.set_hexpand(true);
+    audit_progress.container.set_valign(gtk4::Align::Center);
     
     control_box.append(&audit_btn);
-    control_box.append(&progress);
+    control_box.append(&audit_progress.container);
     
     control_frame.set_child(Some(&control_box));
     main_box.append(&control_frame);
     
-    let output_frame = Frame::new(Some("Audit Results"));
-    let output_area = create_output_area();
-    output_frame.set_child(Some(&output_area));
-    main_box.append(&output_frame);
+    let findings_frame = Frame::new(Some("Findings"));
+    let findings_box = Box::new(gtk4::Orientation::Vertical, 10);
+    findings_box.set_margin_start(15);
+    findings_box.set_margin_end(15);
+    findings_box.set_margin_top(15);
+    findings_box.set_margin_bottom(15);
     
-    let output_clone = output_area.clone();
-    let tracker_clone = process_tracker.clone();
-    audit_btn.connect_clicked(move |btn| {
-        run_command_in_output("hardn audit", &output_clone, btn, &tracker_clone);
-    });
+    let toolbar = Box::new(gtk4::Orientation::Horizontal, 5);
+    toolbar.append(&Label::new(Some("Sort by:")));
+    let sort_buttons: Vec<(Button, report::SortKey)> = [
+        ("Severity", report::SortKey::Severity),
+        ("Section", report::SortKey::Section),
+        ("Title", report::SortKey::Title),
+    ]
+    .into_iter()
+    .map(|(label, key)| {
+        let btn = Button::with_label(label);
+        btn.add_css_class("flat");
+        toolbar.append(&btn);
+        (btn, key)
+    })
+    .collect();
     
-    notebook.append_page(&main_box, Some(&Label::new(Some("🔍 Audit"))));
-}
-
-fn create_backup_tab(notebook: &Notebook, process_tracker: &ProcessTracker, window: &ApplicationWindow) {
-    let main_box = Box::new(gtk4::Orientation::Vertical, 10);
-    main_box.set_margin_start(20);
-    main_box.set_margin_end(20);
-    main_box.set_margin_top(20);
-    main_box.set_margin_bottom(20);
+    let export_json_btn = Button::with_label("💾 Export JSON");
+    let export_html_btn = Button::with_label("🌐 Export HTML");
+    export_json_btn.set_sensitive(false);
+    export_html_btn.set_sensitive(false);
+    let export_label = Label::new(None);
+    export_label.set_xalign(0.0);
+    export_label.set_hexpand(true);
+    export_label.set_selectable(true);
+    export_label.add_css_class("dim-label");
+    toolbar.append(&Separator::new(gtk4::Orientation::Vertical));
+    toolbar.append(&export_json_btn);
+    toolbar.append(&export_html_btn);
+    toolbar.append(&export_label);
+    findings_box.append(&toolbar);
     
-    let control_frame = Frame::new(Some("Backup & Restore"));
-    let control_grid = Grid::new();
-    control_grid.set_row_spacing(10);
-    control_grid.set_column_spacing(10);
-    control_grid.set_margin_start(15);
-    control_grid.set_margin_end(15);
-    control_grid.set_margin_top(15);
-    control_grid.set_margin_bottom(15);
+    let findings_grid = Grid::new();
+    findings_grid.set_row_spacing(8);
+    findings_grid.set_column_spacing(15);
+    let findings_scroll = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .vscrollbar_policy(gtk4::PolicyType::Automatic)
+        .vexpand(true)
+        .child(&findings_grid)
+        .build();
+    findings_box.append(&findings_scroll);
     
-    let backup_btn = Button::with_label("💾 Create Backup");
-    backup_btn.add_css_class("suggested-action");
-    backup_btn.set_height_request(50);
+    findings_frame.set_child(Some(&findings_box));
     
-    let restore_btn = Button::with_label("♻️ Restore from Backup");
-    restore_btn.set_height_request(50);
+    let lynis_view = LynisView::new();
+    let lynis_view_clone = lynis_view.clone();
+    lynis_view.container.connect_map(move |_| lynis_view_clone.refresh());
     
-    control_grid.attach(&backup_btn, 0, 0, 1, 1);
-    control_grid.attach(&restore_btn, 1, 0, 1, 1);
+    let output_frame = Frame::new(Some("Audit Output"));
+    let output_area = create_output_area();
+    output_area.container.set_height_request(150);
+    output_frame.set_child(Some(&output_area.container));
     
-    control_frame.set_child(Some(&control_grid));
-    main_box.append(&control_frame);
+    let aide_view = AideView::new(&output_area, job_manager, window);
+    let aide_view_clone = aide_view.clone();
+    aide_view.container.connect_map(move |_| aide_view_clone.refresh());
     
-    let output_frame = Frame::new(Some("Backup Operations"));
-    let output_area = create_output_area();
-    output_frame.set_child(Some(&output_area));
+    let rootkit_view = RootkitView::new();
+    let rootkit_view_clone = rootkit_view.clone();
+    rootkit_view.container.connect_map(move |_| rootkit_view_clone.refresh());
+    
+    let views = Stack::new();
+    views.set_vexpand(true);
+    views.add_titled(&findings_frame, Some("findings"), "Audit Findings");
+    views.add_titled(&lynis_view.container, Some("lynis"), "Lynis Report");
+    views.add_titled(&aide_view.container, Some("aide"), "File Integrity");
+    views.add_titled(&rootkit_view.container, Some("rootkits"), "Rootkits");
+    let views_switcher = StackSwitcher::new();
+    views_switcher.set_stack(Some(&views));
+    views_switcher.set_halign(gtk4::Align::Center);
+    main_box.append(&views_switcher);
+    main_box.append(&views);
     main_box.append(&output_frame);
     
-    let output_clone = output_area.clone();
-    let tracker_clone = process_tracker.clone();
-    backup_btn.connect_clicked(move |btn| {
-        run_command_in_output("hardn backup", &output_clone, btn, &tracker_clone);
-    });
+    let current_report: Rc<RefCell<Option<report::AuditReport>>> = Rc::new(RefCell::new(None));
+    let sort_key = Rc::new(Cell::new(report::SortKey::Severity));
     
-    let output_clone2 = output_area.clone();
-    let tracker_clone2 = process_tracker.clone();
-    restore_btn.connect_clicked(move |btn| {
-        run_command_in_output("hardn restore", &output_clone2, btn, &tracker_clone2);
+    for (btn, key) in sort_buttons {
+        let current_report = current_report.clone();
+        let sort_key = sort_key.clone();
+        let findings_grid = findings_grid.clone();
+        btn.connect_clicked(move |_| {
+            sort_key.set(key);
+            if let Some(report) = current_report.borrow().as_ref() {
+                show_audit_findings(report, key, &findings_grid);
+            }
+        });
+    }
+    
+    for (btn, format) in [(&export_json_btn, report::ExportFormat::Json), (&export_html_btn, report::ExportFormat::Html)] {
+        let current_report = current_report.clone();
+        let export_label = export_label.clone();
+        btn.connect_clicked(move |_| {
+            let Some(report) = current_report.borrow().clone() else {
+                return;
+            };
+            let dir = glib::user_data_dir().join("hardn").join("reports");
+            match report::export(&report, &dir, format) {
+                Ok(path) => export_label.set_text(&format!("Saved {}", path.display())),
+                Err(e) => export_label.set_text(&format!("❌ Export failed: {}", e)),
+            }
+        });
+    }
+    
+    let action = HardnAction::hardn("Security Audit", "audit").root().expected_secs(600);
+    audit_btn.set_tooltip_text(Some(&format!("{}\nruns as administrator, takes {}", action.command_line(), action.expected_duration_text())));
+    
+    let job_manager = job_manager.clone();
+    let window_weak = window.downgrade();
+    audit_btn.connect_clicked(move |btn| {
+        let Some(window) = window_weak.upgrade() else {
+            return;
+        };
+        
+        current_report.replace(None);
+        export_json_btn.set_sensitive(false);
+        export_html_btn.set_sensitive(false);
+        export_label.set_text("");
+        while let Some(child) = findings_grid.first_child() {
+            findings_grid.remove(&child);
+        }
+        audit_progress.reset("Starting audit...");
+        
+        let (observer, events) = mpsc::channel();
+        dispatch_action(&action, &output_area, btn, &job_manager, &window, Some(observer));
+        
+        let mut builder = Some(report::ReportBuilder::new(&action.command_line()));
+        let mut steps = progress::StepTracker::default();
+        let mut exit_code = None;
+        let current_report = current_report.clone();
+        let sort_key = sort_key.clone();
+        let findings_grid = findings_grid.clone();
+        let audit_progress = audit_progress.clone();
+        let export_json_btn = export_json_btn.clone();
+        let export_html_btn = export_html_btn.clone();
+        let lynis_view = lynis_view.clone();
+        let aide_view = aide_view.clone();
+        let rootkit_view = rootkit_view.clone();
+        glib::timeout_add_local(Duration::from_millis(200), move || {
+            loop {
+                match events.try_recv() {
+                    Ok(JobEvent::Line(line)) => {
+                        if let Some(builder) = builder.as_mut() {
+                            builder.push_line(&line);
+                            // Older hardn versions do not report progress; show the section at least
+                            if let Some(section) = builder.current_section().filter(|_| steps.is_empty()) {
+                                audit_progress.bar.pulse();
+                                audit_progress.bar.set_text(Some(section));
+                            }
+                        }
+                    }
+                    Ok(JobEvent::Progress(progress)) => {
+                        steps.update(progress);
+                        audit_progress.show(&steps);
+                    }
+                    Ok(JobEvent::Exited(code)) => exit_code = code,
+                    Err(mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                    Err(mpsc::TryRecvError::Disconnected) => {
+                        let report = builder.take().map(|builder| builder.finish(exit_code));
+                        match report.filter(|report| !report.sections.is_empty()) {
+                            Some(report) => {
+                                audit_progress.bar.set_fraction(1.0);
+                                audit_progress.bar.set_text(Some(&report.summary()));
+                                show_audit_findings(&report, sort_key.get(), &findings_grid);
+                                current_report.replace(Some(report));
+                                export_json_btn.set_sensitive(true);
+                                export_html_btn.set_sensitive(true);
+                            }
+                            None => audit_progress.bar.set_text(Some("The audit did not produce any results")),
+                        }
+                        // The audit writes a new Lynis report, AIDE check and rootkit scan logs
+                        lynis_view.refresh();
+                        aide_view.refresh();
+                        rootkit_view.refresh();
+                        return glib::ControlFlow::Break;
+                    }
+                }
+            }
+        });
+    });
+    
+    notebook.append_page(&main_box, Some(&Label::new(Some("🔍 Audit"))));
+}
+
+fn show_audit_findings(report: &report::AuditReport, sort_key: report::SortKey, findings_grid: &Grid) {
+    while let Some(child) = findings_grid.first_child() {
+        findings_grid.remove(&child);
+    }
+    
+    for (i, finding) in report.sorted_findings(sort_key).into_iter().enumerate() {
+        let row = i as i32;
+        
+        let severity_label = Label::new(Some(finding.severity.badge()));
+        severity_label.set_xalign(0.0);
+        severity_label.set_valign(gtk4::Align::Start);
+        severity_label.add_css_class(finding.severity.css_class());
+        
+        let id_label = Label::new(Some(&finding.id));
+        id_label.set_valign(gtk4::Align::Start);
+        id_label.add_css_class("dim-label");
+        
+        let section_label = Label::new(Some(&finding.section));
+        section_label.set_xalign(0.0);
+        section_label.set_valign(gtk4::Align::Start);
+        
+        let expander = Expander::new(Some(&finding.title));
+        expander.set_hexpand(true);
+        let details = Box::new(gtk4::Orientation::Vertical, 5);
+        details.set_margin_start(20);
+        for evidence in &finding.evidence {
+            let label = Label::new(Some(evidence));
+            label.set_xalign(0.0);
+            label.set_wrap(true);
+            label.set_selectable(true);
+            label.add_css_class("monospace");
+            details.append(&label);
+        }
+        if let Some(remediation) = &finding.remediation {
+            let label = Label::new(Some(&format!("Remediation: {}", remediation)));
+            label.set_xalign(0.0);
+            label.set_wrap(true);
+            details.append(&label);
+        }
+        if finding.evidence.is_empty() && finding.remediation.is_none() {
+            let label = Label::new(Some("No further details"));
+            label.set_xalign(0.0);
+            label.add_css_class("dim-label");
+            details.append(&label);
+        }
+        expander.set_child(Some(&details));
+        
+        findings_grid.attach(&severity_label, 0, row, 1, 1);
+        findings_grid.attach(&id_label, 1, row, 1, 1);
+        findings_grid.attach(&section_label, 2, row, 1, 1);
+        findings_grid.attach(&expander, 3, row, 1, 1);
+    }
+}
+
+// Browser for the Lynis reports stored by run_lynis_audit, with the hardening index
+// trend across all of them
+#[derive(Clone)]
+struct LynisView {
+    container: Frame,
+    report_selector: DropDown,
+    summary_label: Label,
+    trend_area: DrawingArea,
+    trend_label: Label,
+    findings_grid: Grid,
+    // Newest first, as listed in the selector
+    reports: Rc<RefCell<Vec<lynis::LynisReport>>>,
+    trend: Rc<RefCell<Vec<lynis::TrendPoint>>>,
+    minimum_score: Rc<Cell<u32>>,
+}
+
+impl LynisView {
+    fn new() -> Self {
+        let container = Frame::new(Some("Lynis Report"));
+        let content = Box::new(gtk4::Orientation::Vertical, 10);
+        content.set_margin_start(15);
+        content.set_margin_end(15);
+        content.set_margin_top(15);
+        content.set_margin_bottom(15);
+        
+        let toolbar = Box::new(gtk4::Orientation::Horizontal, 5);
+        toolbar.append(&Label::new(Some("Report:")));
+        let report_selector = DropDown::from_strings(&[]);
+        toolbar.append(&report_selector);
+        let reload_btn = Button::with_label("🔄 Reload");
+        toolbar.append(&reload_btn);
+        let summary_label = Label::new(None);
+        summary_label.set_xalign(0.0);
+        summary_label.set_hexpand(true);
+        summary_label.set_wrap(true);
+        toolbar.append(&summary_label);
+        content.append(&toolbar);
+        
+        let trend_box = Box::new(gtk4::Orientation::Vertical, 5);
+        let trend_label = Label::new(None);
+        trend_label.set_xalign(0.0);
+        trend_label.add_css_class("dim-label");
+        let trend_area = DrawingArea::new();
+        trend_area.set_content_height(140);
+        trend_box.append(&trend_label);
+        trend_box.append(&trend_area);
+        content.append(&trend_box);
+        
+        let findings_grid = Grid::new();
+        findings_grid.set_row_spacing(8);
+        findings_grid.set_column_spacing(15);
+        let findings_scroll = ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vexpand(true)
+            .child(&findings_grid)
+            .build();
+        content.append(&findings_scroll);
+        container.set_child(Some(&content));
+        
+        let view = LynisView {
+            container,
+            report_selector,
+            summary_label,
+            trend_area,
+            trend_label,
+            findings_grid,
+            reports: Rc::new(RefCell::new(Vec::new())),
+            trend: Rc::new(RefCell::new(Vec::new())),
+            minimum_score: Rc::new(Cell::new(config::DEFAULT_MINIMUM_HARDENING_SCORE)),
+        };
+        
+        let trend = view.trend.clone();
+        let minimum_score = view.minimum_score.clone();
+        view.trend_area.set_draw_func(move |_, cr, width, height| {
+            draw_hardening_trend(cr, f64::from(width), f64::from(height), &trend.borrow(), minimum_score.get());
+        });
+        
+        let view_clone = view.clone();
+        view.report_selector.connect_selected_notify(move |selector| view_clone.show_report(selector.selected()));
+        
+        let view_clone = view.clone();
+        reload_btn.connect_clicked(move |_| view_clone.refresh());
+        
+        view
+    }
+    
+    fn refresh(&self) {
+        let minimum_score = config::HardnConfig::load()
+            .map(|config| config.minimum_hardening_score())
+            .unwrap_or(config::DEFAULT_MINIMUM_HARDENING_SCORE);
+        self.minimum_score.set(minimum_score);
+        
+        let dir = config::log_dir();
+        let (mut reports, errors) = lynis::load_reports(&dir);
+        for error in &errors {
+            eprintln!("Skipping Lynis report {}", error);
+        }
+        
+        let trend = lynis::trend(&reports);
+        self.trend_label.set_text(&match (trend.last(), trend.iter().rev().nth(1)) {
+            (Some(latest), Some(previous)) => format!(
+                "Hardening index over {} audits: latest {} ({:+} since the previous audit), minimum {} from {}",
+                trend.len(),
+                latest.hardening_index,
+                i64::from(latest.hardening_index) - i64::from(previous.hardening_index),
+                minimum_score,
+                config::CONFIG_FILE_NAME
+            ),
+            (Some(latest), None) => format!("Hardening index: {}, minimum {} from {}", latest.hardening_index, minimum_score, config::CONFIG_FILE_NAME),
+            _ => format!("No hardening index recorded yet, minimum {} from {}", minimum_score, config::CONFIG_FILE_NAME),
+        });
+        self.trend.replace(trend);
+        self.trend_area.queue_draw();
+        
+        reports.reverse();
+        let names: Vec<String> = reports.iter().map(|report| report.started()).collect();
+        self.reports.replace(reports);
+        
+        // The new model selects the newest report; the selection may not change, so show it explicitly
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        self.report_selector.set_model(Some(&StringList::new(&names)));
+        self.show_report(self.report_selector.selected());
+        if names.is_empty() {
+            let message = match errors.first() {
+                Some(error) => format!("❌ Cannot read the Lynis reports: {}", error),
+                None => format!("No Lynis reports in {} yet; run a security audit first", dir.display()),
+            };
+            self.summary_label.set_text(&message);
+        }
+    }
+    
+    fn show_report(&self, position: u32) {
+        while let Some(child) = self.findings_grid.first_child() {
+            self.findings_grid.remove(&child);
+        }
+        for class in ["success", "error"] {
+            self.summary_label.remove_css_class(class);
+        }
+        
+        let reports = self.reports.borrow();
+        let Some(report) = reports.get(position as usize) else {
+            self.summary_label.set_text("");
+            return;
+        };
+        
+        let minimum_score = self.minimum_score.get();
+        let summary = match report.hardening_index {
+            Some(index) => {
+                self.summary_label.add_css_class(if index >= minimum_score { "success" } else { "error" });
+                format!("Hardening index {} (minimum {}) · {}", index, minimum_score, report.summary())
+            }
+            None => format!("No hardening index · {}", report.summary()),
+        };
+        self.summary_label.set_text(&summary);
+        
+        for (i, finding) in report.findings().enumerate() {
+            let row = i as i32;
+            
+            let kind_label = Label::new(Some(finding.kind.badge()));
+            kind_label.set_xalign(0.0);
+            kind_label.set_valign(gtk4::Align::Start);
+            kind_label.add_css_class(finding.kind.css_class());
+            
+            let id_label = Label::new(Some(&finding.test_id));
+            id_label.set_valign(gtk4::Align::Start);
+            id_label.set_selectable(true);
+            id_label.add_css_class("monospace");
+            
+            let expander = Expander::new(Some(&finding.message));
+            expander.set_hexpand(true);
+            let details = Box::new(gtk4::Orientation::Vertical, 5);
+            details.set_margin_start(20);
+            if let Some(text) = &finding.details {
+                let label = Label::new(Some(&format!("Details: {}", text)));
+                label.set_xalign(0.0);
+                label.set_wrap(true);
+                label.set_selectable(true);
+                details.append(&label);
+            }
+            if let Some(text) = &finding.solution {
+                let label = Label::new(Some(&format!("Solution: {}", text)));
+                label.set_xalign(0.0);
+                label.set_wrap(true);
+                label.set_selectable(true);
+                details.append(&label);
+            }
+            let link = LinkButton::with_label(&finding.link(), &format!("Documentation for {}", finding.test_id));
+            link.set_halign(gtk4::Align::Start);
+            details.append(&link);
+            expander.set_child(Some(&details));
+            
+            self.findings_grid.attach(&kind_label, 0, row, 1, 1);
+            self.findings_grid.attach(&id_label, 1, row, 1, 1);
+            self.findings_grid.attach(&expander, 2, row, 1, 1);
+        }
+        
+        if report.warnings.is_empty() && report.suggestions.is_empty() {
+            let label = Label::new(Some("✅ Lynis reported no warnings or suggestions"));
+            label.set_xalign(0.0);
+            self.findings_grid.attach(&label, 0, 0, 3, 1);
+        }
+    }
+}
+
+// Browser for the AIDE check logs: changes grouped by directory, each of which can be
+// marked as expected. AIDE can only accept all differences at once, so the database
+// update is offered once every change of the latest check has been reviewed.
+#[derive(Clone)]
+struct AideView {
+    container: Frame,
+    check_selector: DropDown,
+    summary_label: Label,
+    hide_expected: CheckButton,
+    changes_box: Box,
+    review_label: Label,
+    update_btn: Button,
+    // Newest first, as listed in the selector
+    reports: Rc<RefCell<Vec<aide::AideReport>>>,
+    // Paths of the shown check marked as expected
+    expected: Rc<RefCell<std::collections::BTreeSet<String>>>,
+}
+
+impl AideView {
+    fn new(output_area: &OutputPane, job_manager: &JobManager, window: &ApplicationWindow) -> Self {
+        let container = Frame::new(Some("File Integrity (AIDE)"));
+        let content = Box::new(gtk4::Orientation::Vertical, 10);
+        content.set_margin_start(15);
+        content.set_margin_end(15);
+        content.set_margin_top(15);
+        content.set_margin_bottom(15);
+        
+        let toolbar = Box::new(gtk4::Orientation::Horizontal, 5);
+        toolbar.append(&Label::new(Some("Check:")));
+        let check_selector = DropDown::from_strings(&[]);
+        toolbar.append(&check_selector);
+        let reload_btn = Button::with_label("🔄 Reload");
+        toolbar.append(&reload_btn);
+        let hide_expected = CheckButton::with_label("Hide expected changes");
+        toolbar.append(&hide_expected);
+        let summary_label = Label::new(None);
+        summary_label.set_xalign(0.0);
+        summary_label.set_hexpand(true);
+        summary_label.set_wrap(true);
+        toolbar.append(&summary_label);
+        content.append(&toolbar);
+        
+        let changes_box = Box::new(gtk4::Orientation::Vertical, 5);
+        let changes_scroll = ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vexpand(true)
+            .child(&changes_box)
+            .build();
+        content.append(&changes_scroll);
+        
+        let review_box = Box::new(gtk4::Orientation::Horizontal, 10);
+        let review_label = Label::new(None);
+        review_label.set_xalign(0.0);
+        review_label.set_hexpand(true);
+        let update_btn = Button::with_label("✅ Accept Changes and Update Database");
+        update_btn.add_css_class("suggested-action");
+        update_btn.set_sensitive(false);
+        review_box.append(&review_label);
+        review_box.append(&update_btn);
+        content.append(&review_box);
+        container.set_child(Some(&content));
+        
+        let view = AideView {
+            container,
+            check_selector,
+            summary_label,
+            hide_expected,
+            changes_box,
+            review_label,
+            update_btn: update_btn.clone(),
+            reports: Rc::new(RefCell::new(Vec::new())),
+            expected: Rc::new(RefCell::new(std::collections::BTreeSet::new())),
+        };
+        
+        let view_clone = view.clone();
+        view.check_selector.connect_selected_notify(move |_| {
+            view_clone.expected.borrow_mut().clear();
+            view_clone.show_changes();
+        });
+        
+        let view_clone = view.clone();
+        view.hide_expected.connect_toggled(move |_| view_clone.show_changes());
+        
+        let view_clone = view.clone();
+        reload_btn.connect_clicked(move |_| view_clone.refresh());
+        
+        let action = HardnAction::tool("Update AIDE database", "aide_update.sh").expected_secs(900).destructive();
+        set_action_tooltip(&update_btn, &action);
+        let output_area = output_area.clone();
+        let job_manager = job_manager.clone();
+        let window_weak = window.downgrade();
+        update_btn.connect_clicked(move |btn| {
+            if let Some(window) = window_weak.upgrade() {
+                dispatch_action(&action, &output_area, btn, &job_manager, &window, None);
+            }
+        });
+        
+        view
+    }
+    
+    fn refresh(&self) {
+        let dir = config::log_dir();
+        let (mut reports, errors) = aide::load_reports(&dir);
+        for error in &errors {
+            eprintln!("Skipping AIDE check log {}", error);
+        }
+        reports.reverse();
+        let names: Vec<String> = reports.iter().map(|report| report.label()).collect();
+        self.reports.replace(reports);
+        self.expected.borrow_mut().clear();
+        
+        // The new model selects the newest check; the selection may not change, so show it explicitly
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        self.check_selector.set_model(Some(&StringList::new(&names)));
+        self.show_changes();
+        if names.is_empty() {
+            let message = match errors.first() {
+                Some(error) => format!("❌ Cannot read the AIDE check logs: {}", error),
+                None => format!("No AIDE check logs in {} yet; run a security audit first", dir.display()),
+            };
+            self.summary_label.set_text(&message);
+        }
+    }
+    
+    fn show_changes(&self) {
+        while let Some(child) = self.changes_box.first_child() {
+            self.changes_box.remove(&child);
+        }
+        
+        let position = self.check_selector.selected() as usize;
+        let reports = self.reports.borrow();
+        let Some(report) = reports.get(position) else {
+            self.summary_label.set_text("");
+            self.update_review();
+            return;
+        };
+        self.summary_label.set_text(&report.summary());
+        
+        let hide_expected = self.hide_expected.is_active();
+        for (directory, changes) in report.by_directory() {
+            let changes: Vec<&aide::FileChange> = changes
+                .into_iter()
+                .filter(|change| !hide_expected || !self.expected.borrow().contains(&change.path))
+                .collect();
+            if changes.is_empty() {
+                continue;
+            }
+            
+            let directory_box = Box::new(gtk4::Orientation::Vertical, 5);
+            directory_box.set_margin_start(20);
+            let directory_expander = Expander::new(Some(&format!("📁 {} ({})", directory, changes.len())));
+            directory_expander.set_expanded(true);
+            
+            let mark_all_btn = Button::with_label("Mark all as expected");
+            mark_all_btn.add_css_class("flat");
+            mark_all_btn.set_halign(gtk4::Align::Start);
+            let paths: Vec<String> = changes.iter().map(|change| change.path.clone()).collect();
+            let view = self.clone();
+            mark_all_btn.connect_clicked(move |_| {
+                view.expected.borrow_mut().extend(paths.iter().cloned());
+                // Rebuilt on idle: this button is among the widgets being replaced
+                let view = view.clone();
+                glib::idle_add_local_once(move || view.show_changes());
+            });
+            directory_box.append(&mark_all_btn);
+            
+            for change in changes {
+                directory_box.append(&self.change_row(change));
+            }
+            directory_expander.set_child(Some(&directory_box));
+            self.changes_box.append(&directory_expander);
+        }
+        
+        if !report.differences_found && report.error.is_none() {
+            let label = Label::new(Some("✅ Every file matches the AIDE database"));
+            label.set_xalign(0.0);
+            self.changes_box.append(&label);
+        }
+        
+        drop(reports);
+        self.update_review();
+    }
+    
+    fn change_row(&self, change: &aide::FileChange) -> Box {
+        let row = Box::new(gtk4::Orientation::Vertical, 3);
+        
+        let header = Box::new(gtk4::Orientation::Horizontal, 10);
+        let expected_check = CheckButton::with_label("Expected");
+        expected_check.set_active(self.expected.borrow().contains(&change.path));
+        let view = self.clone();
+        let path = change.path.clone();
+        expected_check.connect_toggled(move |check| {
+            if check.is_active() {
+                view.expected.borrow_mut().insert(path.clone());
+            } else {
+                view.expected.borrow_mut().remove(&path);
+            }
+            view.update_review();
+        });
+        header.append(&expected_check);
+        
+        let kind_label = Label::new(Some(change.kind.badge()));
+        kind_label.add_css_class(change.kind.css_class());
+        kind_label.set_width_chars(12);
+        kind_label.set_xalign(0.0);
+        header.append(&kind_label);
+        
+        let groups: Vec<&str> = change.groups().iter().map(aide::AttributeGroup::label).collect();
+        let title = match (&change.entry_type, groups.is_empty()) {
+            (Some(entry_type), false) => format!("{} ({}: {})", change.path, entry_type, groups.join(", ")),
+            (Some(entry_type), true) => format!("{} ({})", change.path, entry_type),
+            (None, false) => format!("{} ({})", change.path, groups.join(", ")),
+            (None, true) => change.path.clone(),
+        };
+        let expander = Expander::new(Some(&title));
+        expander.set_hexpand(true);
+        let details = Grid::new();
+        details.set_row_spacing(3);
+        details.set_column_spacing(15);
+        details.set_margin_start(20);
+        for (i, attribute) in change.attributes.iter().enumerate() {
+            let row = i as i32;
+            let name_label = Label::new(Some(&attribute.name));
+            name_label.set_xalign(0.0);
+            name_label.add_css_class("dim-label");
+            let old_label = Label::new(Some(&attribute.old));
+            old_label.set_xalign(0.0);
+            old_label.set_selectable(true);
+            old_label.set_wrap(true);
+            old_label.add_css_class("monospace");
+            let new_label = Label::new(Some(&format!("→ {}", attribute.new)));
+            new_label.set_xalign(0.0);
+            new_label.set_selectable(true);
+            new_label.set_wrap(true);
+            new_label.add_css_class("monospace");
+            details.attach(&name_label, 0, row, 1, 1);
+            details.attach(&old_label, 1, row, 1, 1);
+            details.attach(&new_label, 2, row, 1, 1);
+        }
+        if change.attributes.is_empty() {
+            let text = change.flags.as_deref().map(|flags| format!("AIDE flags: {}", flags));
+            let label = Label::new(Some(text.as_deref().unwrap_or("No further details")));
+            label.set_xalign(0.0);
+            label.add_css_class("dim-label");
+            label.add_css_class("monospace");
+            details.attach(&label, 0, 0, 3, 1);
+        }
+        expander.set_child(Some(&details));
+        header.append(&expander);
+        
+        row.append(&header);
+        row
+    }
+    
+    // Only the newest check reflects the filesystem the update would record
+    fn update_review(&self) {
+        let reports = self.reports.borrow();
+        let position = self.check_selector.selected() as usize;
+        let Some(report) = reports.get(position) else {
+            self.review_label.set_text("");
+            self.update_btn.set_sensitive(false);
+            return;
+        };
+        
+        let total = report.changes.len();
+        let expected = self.expected.borrow();
+        let reviewed = report.changes.iter().filter(|change| expected.contains(&change.path)).count();
+        let (text, ready) = if total == 0 {
+            ("Nothing to accept".to_string(), false)
+        } else if position != 0 {
+            (format!("{} of {} changes marked as expected · only the newest check can be accepted", reviewed, total), false)
+        } else if reviewed < total {
+            (format!("{} of {} changes marked as expected · mark all of them to update the database", reviewed, total), false)
+        } else {
+            (format!("All {} changes marked as expected", total), true)
+        };
+        self.review_label.set_text(&text);
+        self.update_btn.set_sensitive(ready);
+    }
+}
+
+// Combined rkhunter and chkrootkit findings of the latest scans. Whitelisting a finding
+// hides it here and in the dashboard for every later scan reporting the same thing.
+#[derive(Clone)]
+struct RootkitView {
+    container: Frame,
+    summary_label: Label,
+    show_whitelisted: CheckButton,
+    findings_grid: Grid,
+}
+
+impl RootkitView {
+    fn new() -> Self {
+        let container = Frame::new(Some("Rootkit Scans"));
+        let content = Box::new(gtk4::Orientation::Vertical, 10);
+        content.set_margin_start(15);
+        content.set_margin_end(15);
+        content.set_margin_top(15);
+        content.set_margin_bottom(15);
+        
+        let toolbar = Box::new(gtk4::Orientation::Horizontal, 5);
+        let reload_btn = Button::with_label("🔄 Reload");
+        toolbar.append(&reload_btn);
+        let show_whitelisted = CheckButton::with_label("Show whitelisted");
+        toolbar.append(&show_whitelisted);
+        let summary_label = Label::new(None);
+        summary_label.set_xalign(0.0);
+        summary_label.set_hexpand(true);
+        summary_label.set_wrap(true);
+        toolbar.append(&summary_label);
+        content.append(&toolbar);
+        
+        let findings_grid = Grid::new();
+        findings_grid.set_row_spacing(8);
+        findings_grid.set_column_spacing(15);
+        let findings_scroll = ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vexpand(true)
+            .child(&findings_grid)
+            .build();
+        content.append(&findings_scroll);
+        container.set_child(Some(&content));
+        
+        let view = RootkitView {
+            container,
+            summary_label,
+            show_whitelisted,
+            findings_grid,
+        };
+        
+        let view_clone = view.clone();
+        reload_btn.connect_clicked(move |_| view_clone.refresh());
+        
+        let view_clone = view.clone();
+        view.show_whitelisted.connect_toggled(move |_| view_clone.refresh());
+        
+        view
+    }
+    
+    fn refresh(&self) {
+        while let Some(child) = self.findings_grid.first_child() {
+            self.findings_grid.remove(&child);
+        }
+        
+        let dir = config::log_dir();
+        let results = rootkit::load_latest(&dir);
+        for error in &results.errors {
+            eprintln!("Skipping rootkit scan log {}", error);
+        }
+        let whitelist = rootkit::Whitelist::load();
+        let listed = |finding: &rootkit::RootkitFinding| whitelist.as_ref().is_ok_and(|whitelist| whitelist.contains(finding));
+        let whitelisted = results.findings.iter().filter(|finding| listed(finding)).count();
+        
+        let mut summary = if results.sources.is_empty() {
+            match results.errors.first() {
+                Some(error) => format!("❌ Cannot read the rootkit scan logs: {}", error),
+                None => format!("No rkhunter or chkrootkit logs in {} yet; run a security audit first", dir.display()),
+            }
+        } else {
+            let sources: Vec<String> = results.sources.iter().map(|(_, path)| path.display().to_string()).collect();
+            format!("{} findings, {} whitelisted · {}", results.findings.len() - whitelisted, whitelisted, sources.join(", "))
+        };
+        if let Err(e) = &whitelist {
+            summary.push_str(&format!(" · ❌ Whitelist {} unreadable: {}", rootkit::Whitelist::path().display(), e));
+        }
+        self.summary_label.set_text(&summary);
+        
+        let show_whitelisted = self.show_whitelisted.is_active();
+        let mut row = 0;
+        for finding in &results.findings {
+            let is_listed = listed(finding);
+            if is_listed && !show_whitelisted {
+                continue;
+            }
+            
+            let severity_label = Label::new(Some(finding.severity.badge()));
+            severity_label.set_xalign(0.0);
+            severity_label.set_valign(gtk4::Align::Start);
+            severity_label.add_css_class(if is_listed { "dim-label" } else { finding.severity.css_class() });
+            
+            let tool_label = Label::new(Some(finding.tool.label()));
+            tool_label.set_valign(gtk4::Align::Start);
+            tool_label.add_css_class("dim-label");
+            
+            let title = match &finding.target {
+                Some(target) => format!("{}: {}", finding.check, target),
+                None => finding.check.clone(),
+            };
+            let expander = Expander::new(Some(&title));
+            expander.set_hexpand(true);
+            let details = Box::new(gtk4::Orientation::Vertical, 5);
+            details.set_margin_start(20);
+            let result_label = Label::new(Some(&format!("Result: {}", finding.result)));
+            result_label.set_xalign(0.0);
+            result_label.set_wrap(true);
+            details.append(&result_label);
+            for line in &finding.details {
+                let label = Label::new(Some(line));
+                label.set_xalign(0.0);
+                label.set_wrap(true);
+                label.set_selectable(true);
+                label.add_css_class("monospace");
+                details.append(&label);
+            }
+            expander.set_child(Some(&details));
+            
+            let whitelist_btn = Button::with_label(if is_listed { "↩️ Remove from Whitelist" } else { "🙈 Whitelist" });
+            whitelist_btn.set_valign(gtk4::Align::Start);
+            whitelist_btn.set_tooltip_text(Some("Whitelisted findings are known false positives and no longer count as warnings"));
+            whitelist_btn.set_sensitive(whitelist.is_ok());
+            let view = self.clone();
+            let finding = finding.clone();
+            whitelist_btn.connect_clicked(move |_| {
+                // Reload first so edits made since the list was shown are kept
+                let result = rootkit::Whitelist::load().and_then(|mut whitelist| {
+                    if is_listed {
+                        whitelist.remove(&finding);
+                    } else {
+                        whitelist.add(&finding, &glib::user_name().to_string_lossy());
+                    }
+                    whitelist.save()
+                });
+                if let Err(e) = result {
+                    view.summary_label.set_text(&format!("❌ Failed to update the whitelist: {}", e));
+                    return;
+                }
+                // Rebuilt on idle: this button is among the widgets being replaced
+                let view = view.clone();
+                glib::idle_add_local_once(move || view.refresh());
+            });
+            
+            self.findings_grid.attach(&severity_label, 0, row, 1, 1);
+            self.findings_grid.attach(&tool_label, 1, row, 1, 1);
+            self.findings_grid.attach(&expander, 2, row, 1, 1);
+            self.findings_grid.attach(&whitelist_btn, 3, row, 1, 1);
+            row += 1;
+        }
+        
+        if row == 0 && !results.sources.is_empty() {
+            let label = Label::new(Some("✅ No rootkit findings to review"));
+            label.set_xalign(0.0);
+            self.findings_grid.attach(&label, 0, 0, 4, 1);
+        }
+    }
+}
+
+// Hardening index (0-100) per audit, oldest on the left, against the configured minimum
+fn draw_hardening_trend(cr: &gtk4::cairo::Context, width: f64, height: f64, points: &[lynis::TrendPoint], minimum_score: u32) {
+    let (left, right, top, bottom) = (32.0, 12.0, 8.0, 20.0);
+    let plot_width = (width - left - right).max(1.0);
+    let plot_height = (height - top - bottom).max(1.0);
+    let y_of = |index: f64| top + plot_height * (1.0 - index.clamp(0.0, 100.0) / 100.0);
+    let x_of = |i: usize| match points.len() {
+        0 | 1 => left + plot_width / 2.0,
+        n => left + plot_width * i as f64 / (n - 1) as f64,
+    };
+    
+    cr.set_font_size(10.0);
+    cr.set_line_width(1.0);
+    for tick in [0.0, 25.0, 50.0, 75.0, 100.0] {
+        let y = y_of(tick);
+        cr.set_source_rgba(0.5, 0.5, 0.5, 0.3);
+        cr.move_to(left, y);
+        cr.line_to(width - right, y);
+        let _ = cr.stroke();
+        cr.set_source_rgb(0.5, 0.5, 0.5);
+        cr.move_to(4.0, y + 3.0);
+        let _ = cr.show_text(&tick.to_string());
+    }
+    
+    let minimum_y = y_of(f64::from(minimum_score));
+    cr.set_source_rgb(0.88, 0.11, 0.14);
+    cr.set_dash(&[6.0, 4.0], 0.0);
+    cr.move_to(left, minimum_y);
+    cr.line_to(width - right, minimum_y);
+    let _ = cr.stroke();
+    cr.set_dash(&[], 0.0);
+    cr.move_to(left + 4.0, minimum_y - 4.0);
+    let _ = cr.show_text(&format!("minimum {}", minimum_score));
+    
+    if points.is_empty() {
+        return;
+    }
+    
+    cr.set_source_rgb(0.21, 0.52, 0.89);
+    cr.set_line_width(2.0);
+    for (i, point) in points.iter().enumerate() {
+        let (x, y) = (x_of(i), y_of(f64::from(point.hardening_index)));
+        if i == 0 {
+            cr.move_to(x, y);
+        } else {
+            cr.line_to(x, y);
+        }
+    }
+    let _ = cr.stroke();
+    
+    for (i, point) in points.iter().enumerate() {
+        let (x, y) = (x_of(i), y_of(f64::from(point.hardening_index)));
+        if point.hardening_index >= minimum_score {
+            cr.set_source_rgb(0.18, 0.76, 0.49);
+        } else {
+            cr.set_source_rgb(0.88, 0.11, 0.14);
+        }
+        cr.arc(x, y, 3.5, 0.0, 2.0 * std::f64::consts::PI);
+        let _ = cr.fill();
+    }
+    
+    // Dates of the first and last audit below the axis
+    cr.set_source_rgb(0.5, 0.5, 0.5);
+    let date = |point: &lynis::TrendPoint| point.label.split_whitespace().next().unwrap_or_default().to_string();
+    cr.move_to(left, height - 4.0);
+    let _ = cr.show_text(&date(&points[0]));
+    if points.len() > 1 {
+        let last = date(&points[points.len() - 1]);
+        let extents_width = cr.text_extents(&last).map(|extents| extents.width()).unwrap_or(0.0);
+        cr.move_to(width - right - extents_width, height - 4.0);
+        let _ = cr.show_text(&last);
+    }
+}
+
+fn create_stig_tab(notebook: &Notebook, job_manager: &JobManager, window: &ApplicationWindow) {
+    let main_box = Box::new(gtk4::Orientation::Vertical, 10);
+    main_box.set_margin_start(20);
+    main_box.set_margin_end(20);
+    main_box.set_margin_top(20);
+    main_box.set_margin_bottom(20);
+    
+    let control_frame = Frame::new(Some("STIG Compliance"));
+    let control_box = Box::new(gtk4::Orientation::Horizontal, 10);
+    control_box.set_margin_start(15);
+    control_box.set_margin_end(15);
+    control_box.set_margin_top(15);
+    control_box.set_margin_bottom(15);
+    
+    let evaluate_btn = Button::with_label("📋 Evaluate Rules");
+    evaluate_btn.add_css_class("suggested-action");
+    evaluate_btn.set_height_request(50);
+    
+    let progress = ProgressBar::new();
+    progress.set_show_text(true);
+    progress.set_text(Some("Not evaluated yet"));
+    progress.set_hexpand(true);
+    progress.set_valign(gtk4::Align::Center);
+    
+    control_box.append(&evaluate_btn);
+    control_box.append(&progress);
+    
+    control_frame.set_child(Some(&control_box));
+    main_box.append(&control_frame);
+    
+    let summary_label = Label::new(None);
+    summary_label.set_xalign(0.0);
+    summary_label.set_wrap(true);
+    summary_label.add_css_class("dim-label");
+    main_box.append(&summary_label);
+    
+    let results_frame = Frame::new(Some("Rules"));
+    let results_grid = Grid::new();
+    results_grid.set_row_spacing(8);
+    results_grid.set_column_spacing(15);
+    results_grid.set_margin_start(15);
+    results_grid.set_margin_end(15);
+    results_grid.set_margin_top(15);
+    results_grid.set_margin_bottom(15);
+    
+    let results_scroll = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Automatic)
+        .vscrollbar_policy(gtk4::PolicyType::Automatic)
+        .vexpand(true)
+        .child(&results_grid)
+        .build();
+    results_frame.set_child(Some(&results_scroll));
+    main_box.append(&results_frame);
+    
+    let output_frame = Frame::new(Some("Remediation Output"));
+    let output_area = create_output_area();
+    output_area.container.set_height_request(150);
+    output_frame.set_child(Some(&output_area.container));
+    main_box.append(&output_frame);
+    
+    let evaluate = {
+        let results_grid = results_grid.clone();
+        let summary_label = summary_label.clone();
+        let progress = progress.clone();
+        let output_area = output_area.clone();
+        let job_manager = job_manager.clone();
+        let window_weak = window.downgrade();
+        move || {
+            if let Some(window) = window_weak.upgrade() {
+                evaluate_stig_rules(&results_grid, &summary_label, &progress, &output_area, &job_manager, &window);
+            }
+        }
+    };
+    evaluate();
+    evaluate_btn.connect_clicked(move |_| evaluate());
+    
+    notebook.append_page(&main_box, Some(&Label::new(Some("📋 STIG"))));
+}
+
+fn evaluate_stig_rules(
+    results_grid: &Grid,
+    summary_label: &Label,
+    progress: &ProgressBar,
+    output_area: &OutputPane,
+    job_manager: &JobManager,
+    window: &ApplicationWindow,
+) {
+    progress.set_text(Some("Evaluating..."));
+    
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let rule_set = stig::RuleSet::load_default();
+        let _ = tx.send(stig::Evaluator::from_env().evaluate_all(&rule_set));
+    });
+    
+    let results_grid = results_grid.clone();
+    let summary_label = summary_label.clone();
+    let progress = progress.clone();
+    let output_area = output_area.clone();
+    let job_manager = job_manager.clone();
+    let window_weak = window.downgrade();
+    glib::timeout_add_local(Duration::from_millis(100), move || {
+        match rx.try_recv() {
+            Ok(report) => {
+                if let Some(window) = window_weak.upgrade() {
+                    show_compliance_report(&report, &results_grid, &summary_label, &progress, &output_area, &job_manager, &window);
+                }
+                glib::ControlFlow::Break
+            }
+            Err(mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+            Err(mpsc::TryRecvError::Disconnected) => glib::ControlFlow::Break,
+        }
+    });
+}
+
+fn show_compliance_report(
+    report: &stig::ComplianceReport,
+    results_grid: &Grid,
+    summary_label: &Label,
+    progress: &ProgressBar,
+    output_area: &OutputPane,
+    job_manager: &JobManager,
+    window: &ApplicationWindow,
+) {
+    let percent = report.compliance_percent();
+    progress.set_fraction(percent.unwrap_or(0.0) / 100.0);
+    progress.set_text(Some(&report.summary()));
+    
+    let mut summary = format!(
+        "{} rules from {} evaluated as {}",
+        report.results.len(),
+        report.sources.iter().map(|source| source.display().to_string()).collect::<Vec<_>>().join(", "),
+        glib::user_name().to_string_lossy()
+    );
+    for error in &report.load_errors {
+        summary.push_str(&format!("\n⚠️ Could not load rules from {}", error));
+    }
+    summary_label.set_text(&summary);
+    
+    while let Some(child) = results_grid.first_child() {
+        results_grid.remove(&child);
+    }
+    
+    // Open findings first, most severe first
+    let mut results: Vec<&stig::RuleResult> = report.results.iter().collect();
+    results.sort_by_key(|result| {
+        let rank = match result.outcome {
+            stig::Outcome::Fail => 0,
+            stig::Outcome::Error => 1,
+            stig::Outcome::Pass => 2,
+            stig::Outcome::NotApplicable => 3,
+        };
+        (rank, result.rule.severity)
+    });
+    
+    for (i, result) in results.into_iter().enumerate() {
+        let row = i as i32;
+        let rule = &result.rule;
+        
+        let outcome_label = Label::new(Some(result.outcome.badge()));
+        outcome_label.set_xalign(0.0);
+        outcome_label.add_css_class(result.outcome.css_class());
+        
+        let severity_label = Label::new(Some(rule.severity.label()));
+        severity_label.set_xalign(0.0);
+        if rule.severity == stig::Severity::CatI {
+            severity_label.add_css_class("error");
+        }
+        
+        let id_label = Label::new(Some(&rule.id));
+        id_label.set_xalign(0.0);
+        id_label.add_css_class("monospace");
+        
+        let title_label = Label::new(Some(&rule.title));
+        title_label.set_xalign(0.0);
+        title_label.set_hexpand(true);
+        title_label.set_wrap(true);
+        let mut tooltip = format!("Check: {}", rule.check.describe());
+        if let Some(reference) = &rule.reference {
+            tooltip.push_str(&format!("\nReference: {}", reference));
+        }
+        title_label.set_tooltip_text(Some(&tooltip));
+        
+        let evidence_label = Label::new(Some(&result.evidence));
+        evidence_label.set_xalign(0.0);
+        evidence_label.set_wrap(true);
+        evidence_label.add_css_class("dim-label");
+        
+        results_grid.attach(&outcome_label, 0, row, 1, 1);
+        results_grid.attach(&severity_label, 1, row, 1, 1);
+        results_grid.attach(&id_label, 2, row, 1, 1);
+        results_grid.attach(&title_label, 3, row, 1, 1);
+        results_grid.attach(&evidence_label, 4, row, 1, 1);
+        
+        if let (stig::Outcome::Fail, Some(script)) = (result.outcome, &rule.fix) {
+            let fix_btn = Button::with_label("🔧 Fix");
+            fix_btn.add_css_class("flat");
+            let action = HardnAction::tool(&format!("Fix {}", rule.id), script);
+            connect_action(&fix_btn, action, output_area, job_manager, window);
+            results_grid.attach(&fix_btn, 5, row, 1, 1);
+        }
+    }
+}
+
+fn create_sysctl_tab(notebook: &Notebook, job_manager: &JobManager, window: &ApplicationWindow) {
+    let main_box = Box::new(gtk4::Orientation::Vertical, 10);
+    main_box.set_margin_start(20);
+    main_box.set_margin_end(20);
+    main_box.set_margin_top(20);
+    main_box.set_margin_bottom(20);
+    
+    let control_frame = Frame::new(Some("Kernel Parameter Drift"));
+    let control_box = Box::new(gtk4::Orientation::Horizontal, 10);
+    control_box.set_margin_start(15);
+    control_box.set_margin_end(15);
+    control_box.set_margin_top(15);
+    control_box.set_margin_bottom(15);
+    
+    let check_btn = Button::with_label("🧠 Check Drift");
+    check_btn.add_css_class("suggested-action");
+    check_btn.set_height_request(50);
+    
+    let reapply_btn = Button::with_label("♻️ Reapply sysctl.d");
+    reapply_btn.set_height_request(50);
+    
+    let problems_only = CheckButton::with_label("Only drifted and overridden keys");
+    problems_only.set_active(true);
+    
+    control_box.append(&check_btn);
+    control_box.append(&reapply_btn);
+    control_box.append(&problems_only);
+    
+    control_frame.set_child(Some(&control_box));
+    main_box.append(&control_frame);
+    
+    let summary_label = Label::new(None);
+    summary_label.set_xalign(0.0);
+    summary_label.set_wrap(true);
+    main_box.append(&summary_label);
+    
+    let results_frame = Frame::new(Some(&format!("Configured keys (HARDN file: {})", sysctl::HARDN_SYSCTL_FILE)));
+    let results_grid = Grid::new();
+    results_grid.set_row_spacing(8);
+    results_grid.set_column_spacing(20);
+    results_grid.set_margin_start(15);
+    results_grid.set_margin_end(15);
+    results_grid.set_margin_top(15);
+    results_grid.set_margin_bottom(15);
+    
+    let results_scroll = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Automatic)
+        .vscrollbar_policy(gtk4::PolicyType::Automatic)
+        .vexpand(true)
+        .child(&results_grid)
+        .build();
+    results_frame.set_child(Some(&results_scroll));
+    main_box.append(&results_frame);
+    
+    let output_frame = Frame::new(Some("Reapply Output"));
+    let output_area = create_output_area();
+    output_area.container.set_height_request(150);
+    output_frame.set_child(Some(&output_area.container));
+    main_box.append(&output_frame);
+    
+    connect_action(
+        &reapply_btn,
+        HardnAction::privileged("Reapply sysctl settings", protocol::HelperRequest::ReloadSysctl),
+        &output_area,
+        job_manager,
+        window,
+    );
+    
+    let check = {
+        let results_grid = results_grid.clone();
+        let summary_label = summary_label.clone();
+        let problems_only = problems_only.clone();
+        move || check_sysctl_drift(&results_grid, &summary_label, problems_only.is_active())
+    };
+    check();
+    let check_clone = check.clone();
+    check_btn.connect_clicked(move |_| check_clone());
+    problems_only.connect_toggled(move |_| check());
+    
+    notebook.append_page(&main_box, Some(&Label::new(Some("🧠 Kernel"))));
+}
+
+fn check_sysctl_drift(results_grid: &Grid, summary_label: &Label, problems_only: bool) {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(sysctl::SysctlChecker::from_env().check());
+    });
+    
+    let results_grid = results_grid.clone();
+    let summary_label = summary_label.clone();
+    glib::timeout_add_local(Duration::from_millis(100), move || {
+        match rx.try_recv() {
+            Ok(report) => {
+                show_drift_report(&report, &results_grid, &summary_label, problems_only);
+                glib::ControlFlow::Break
+            }
+            Err(mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+            Err(mpsc::TryRecvError::Disconnected) => glib::ControlFlow::Break,
+        }
+    });
+}
+
+fn show_drift_report(report: &sysctl::DriftReport, results_grid: &Grid, summary_label: &Label, problems_only: bool) {
+    let mut summary = report.summary();
+    for error in &report.errors {
+        summary.push_str(&format!("\n⚠️ {}", error));
+    }
+    summary_label.set_text(&summary);
+    for class in ["success", "error"] {
+        summary_label.remove_css_class(class);
+    }
+    let drifted = report.count(sysctl::DriftState::Drifted) + report.count(sysctl::DriftState::Overridden);
+    summary_label.add_css_class(if drifted == 0 { "success" } else { "error" });
+    
+    while let Some(child) = results_grid.first_child() {
+        results_grid.remove(&child);
+    }
+    
+    for (col, heading) in ["State", "Key", "Expected", "Actual", "Winning file"].iter().enumerate() {
+        let label = Label::new(None);
+        label.set_markup(&format!("<b>{}</b>", heading));
+        label.set_xalign(0.0);
+        results_grid.attach(&label, col as i32, 0, 1, 1);
+    }
+    
+    let keys = report.keys.iter().filter(|key| !problems_only || key.state.is_problem());
+    for (i, key) in keys.enumerate() {
+        let row = i as i32 + 1;
+        
+        let state_label = Label::new(Some(key.state.badge()));
+        state_label.set_xalign(0.0);
+        state_label.add_css_class(key.state.css_class());
+        
+        let key_label = Label::new(Some(&key.key));
+        key_label.set_xalign(0.0);
+        key_label.add_css_class("monospace");
+        key_label.set_tooltip_text(Some(&key.details()));
+        
+        let expected_label = Label::new(Some(&key.expected));
+        expected_label.set_xalign(0.0);
+        if let Some(hardn_value) = key.hardn_value().filter(|value| *value != key.expected) {
+            expected_label.set_tooltip_text(Some(&format!("HARDN sets {}", hardn_value)));
+        }
+        
+        let actual_label = Label::new(Some(key.actual.as_deref().unwrap_or("—")));
+        actual_label.set_xalign(0.0);
+        
+        let winner_label = Label::new(Some(&key.winner.location()));
+        winner_label.set_xalign(0.0);
+        winner_label.add_css_class("dim-label");
+        
+        results_grid.attach(&state_label, 0, row, 1, 1);
+        results_grid.attach(&key_label, 1, row, 1, 1);
+        results_grid.attach(&expected_label, 2, row, 1, 1);
+        results_grid.attach(&actual_label, 3, row, 1, 1);
+        results_grid.attach(&winner_label, 4, row, 1, 1);
+    }
+}
+
+fn create_network_tab(notebook: &Notebook, job_manager: &JobManager, window: &ApplicationWindow) {
+    let main_box = Box::new(gtk4::Orientation::Horizontal, 10);
+    main_box.set_margin_start(20);
+    main_box.set_margin_end(20);
+    main_box.set_margin_top(20);
+    main_box.set_margin_bottom(20);
+    
+    let listeners_frame = Frame::new(Some("Listening Services"));
+    let listeners_box = Box::new(gtk4::Orientation::Vertical, 10);
+    listeners_box.set_margin_start(15);
+    listeners_box.set_margin_end(15);
+    listeners_box.set_margin_top(15);
+    listeners_box.set_margin_bottom(15);
+    listeners_box.set_width_request(640);
+    
+    let toolbar = Box::new(gtk4::Orientation::Horizontal, 5);
+    let refresh_btn = Button::with_label("🔄 Refresh");
+    refresh_btn.set_tooltip_text(Some("Read the listening sockets from /proc/net"));
+    let firewall_btn = Button::with_label("🔥 Check Firewall");
+    let firewall_action = HardnAction::privileged("UFW Status", protocol::HelperRequest::UfwStatus);
+    set_action_tooltip(&firewall_btn, &firewall_action);
+    let exposed_only = CheckButton::with_label("Exposed only");
+    exposed_only.set_tooltip_text(Some("Hide services that only listen on loopback"));
+    toolbar.append(&refresh_btn);
+    toolbar.append(&firewall_btn);
+    toolbar.append(&exposed_only);
+    listeners_box.append(&toolbar);
+    
+    let summary_label = Label::new(None);
+    summary_label.set_xalign(0.0);
+    summary_label.set_wrap(true);
+    listeners_box.append(&summary_label);
+    
+    let listeners_grid = Grid::new();
+    listeners_grid.set_row_spacing(8);
+    listeners_grid.set_column_spacing(15);
+    let listeners_scroll = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .vscrollbar_policy(gtk4::PolicyType::Automatic)
+        .vexpand(true)
+        .child(&listeners_grid)
+        .build();
+    listeners_box.append(&listeners_scroll);
+    
+    listeners_frame.set_child(Some(&listeners_box));
+    main_box.append(&listeners_frame);
+    
+    let output_area = create_output_area();
+    main_box.append(&output_area.container);
+    
+    let view = NetworkView {
+        summary_label,
+        exposed_only,
+        listeners_grid,
+        firewall: Rc::new(RefCell::new(None)),
+    };
+    
+    let view_clone = view.clone();
+    refresh_btn.connect_clicked(move |_| view_clone.refresh());
+    
+    let view_clone = view.clone();
+    view.exposed_only.connect_toggled(move |_| view_clone.refresh());
+    
+    let view_clone = view.clone();
+    let job_manager = job_manager.clone();
+    let window_weak = window.downgrade();
+    firewall_btn.connect_clicked(move |btn| {
+        let Some(window) = window_weak.upgrade() else {
+            return;
+        };
+        let (observer, events) = mpsc::channel();
+        dispatch_action(&firewall_action, &output_area, btn, &job_manager, &window, Some(observer));
+        
+        let view = view_clone.clone();
+        let collect = |status_output: &mut String, line: String| {
+            status_output.push_str(&line);
+            status_output.push('\n');
+        };
+        drain_observer(events, String::new(), collect, move |status_output, exit_code| {
+            if exit_code == Some(0) {
+                let profiles = ufw::load_profiles(std::path::Path::new(ufw::APPLICATIONS_DIR));
+                view.firewall.replace(Some(ufw::UfwStatus::parse(&status_output, &profiles)));
+                view.refresh();
+            }
+        });
+    });
+    
+    // Sockets come and go; reread them whenever the tab is shown
+    let view_clone = view.clone();
+    main_box.connect_map(move |_| view_clone.refresh());
+    
+    notebook.append_page(&main_box, Some(&Label::new(Some("🌐 Network"))));
+}
+
+// State of the Network tab
+#[derive(Clone)]
+struct NetworkView {
+    summary_label: Label,
+    exposed_only: CheckButton,
+    listeners_grid: Grid,
+    // Last `ufw status verbose`, once loaded through the helper
+    firewall: Rc<RefCell<Option<ufw::UfwStatus>>>,
+}
+
+impl NetworkView {
+    fn refresh(&self) {
+        while let Some(child) = self.listeners_grid.first_child() {
+            self.listeners_grid.remove(&child);
+        }
+        
+        let scan = network::scan_listeners(std::path::Path::new(network::PROC_DIR));
+        let firewall = self.firewall.borrow();
+        let assessed: Vec<(network::Listener, network::Exposure)> = scan
+            .listeners
+            .into_iter()
+            .map(|listener| {
+                let exposure = network::assess(&listener, firewall.as_ref());
+                (listener, exposure)
+            })
+            .collect();
+        
+        let exposed = assessed.iter().filter(|(listener, _)| !listener.socket.is_loopback()).count();
+        let risky = assessed.iter().filter(|(_, exposure)| exposure.severity == report::Severity::High).count();
+        let mut summary = format!("{} listening sockets, {} on non-loopback addresses", assessed.len(), exposed);
+        if risky > 0 {
+            summary.push_str(&format!(" · ⚠️ {} risky services reachable", risky));
+        }
+        summary.push_str(&match firewall.as_ref() {
+            Some(status) if status.active => format!(
+                " · UFW active, {} rules, default {} incoming",
+                status.rules.len(),
+                status.default_incoming.as_deref().unwrap_or("deny")
+            ),
+            Some(_) => " · ❌ UFW inactive".to_string(),
+            None => " · firewall not checked yet".to_string(),
+        });
+        if scan.hidden_processes > 0 {
+            summary.push_str(&format!(
+                " · {} processes of other users could not be inspected, their sockets show as \"unknown\"",
+                scan.hidden_processes
+            ));
+        }
+        for error in &scan.errors {
+            summary.push_str(&format!(" · ❌ {}", error));
+        }
+        self.summary_label.set_text(&summary);
+        
+        for (column, title) in ["Risk", "Proto", "Address", "Process", "User", "Firewall"].iter().enumerate() {
+            let header = Label::new(Some(title));
+            header.set_xalign(0.0);
+            header.add_css_class("heading");
+            self.listeners_grid.attach(&header, column as i32, 0, 1, 1);
+        }
+        
+        let exposed_only = self.exposed_only.is_active();
+        let mut row = 1;
+        for (listener, exposure) in &assessed {
+            if exposed_only && listener.socket.is_loopback() {
+                continue;
+            }
+            
+            let severity_label = Label::new(Some(exposure.severity.badge()));
+            severity_label.set_xalign(0.0);
+            severity_label.set_valign(gtk4::Align::Start);
+            severity_label.add_css_class(exposure.severity.css_class());
+            
+            let protocol_label = Label::new(Some(listener.socket.protocol.label()));
+            protocol_label.set_valign(gtk4::Align::Start);
+            
+            let expander = Expander::new(Some(&listener.socket.address()));
+            expander.set_hexpand(true);
+            let evidence = Box::new(gtk4::Orientation::Vertical, 5);
+            evidence.set_margin_start(20);
+            for line in &exposure.evidence {
+                let label = Label::new(Some(line));
+                label.set_xalign(0.0);
+                label.set_wrap(true);
+                label.set_selectable(true);
+                evidence.append(&label);
+            }
+            expander.set_child(Some(&evidence));
+            
+            let process_label = Label::new(Some(&listener.process()));
+            process_label.set_xalign(0.0);
+            process_label.set_valign(gtk4::Align::Start);
+            if let Some(owner) = &listener.owner {
+                process_label.set_tooltip_text(Some(&owner.cmdline));
+            }
+            
+            let user_label = Label::new(Some(&listener.user));
+            user_label.set_xalign(0.0);
+            user_label.set_valign(gtk4::Align::Start);
+            
+            let firewall_label = Label::new(Some(&exposure.firewall));
+            firewall_label.set_xalign(0.0);
+            firewall_label.set_valign(gtk4::Align::Start);
+            firewall_label.set_wrap(true);
+            firewall_label.add_css_class("dim-label");
+            
+            self.listeners_grid.attach(&severity_label, 0, row, 1, 1);
+            self.listeners_grid.attach(&protocol_label, 1, row, 1, 1);
+            self.listeners_grid.attach(&expander, 2, row, 1, 1);
+            self.listeners_grid.attach(&process_label, 3, row, 1, 1);
+            self.listeners_grid.attach(&user_label, 4, row, 1, 1);
+            self.listeners_grid.attach(&firewall_label, 5, row, 1, 1);
+            row += 1;
+        }
+        
+        if row == 1 {
+            let label = Label::new(Some("No listening sockets to show"));
+            label.set_xalign(0.0);
+            label.add_css_class("dim-label");
+            self.listeners_grid.attach(&label, 0, 1, 6, 1);
+        }
+    }
+}
+
+fn create_firewall_tab(notebook: &Notebook, job_manager: &JobManager) {
+    let main_box = Box::new(gtk4::Orientation::Horizontal, 10);
+    main_box.set_margin_start(20);
+    main_box.set_margin_end(20);
+    main_box.set_margin_top(20);
+    main_box.set_margin_bottom(20);
+    
+    let rules_frame = Frame::new(Some("UFW Rules"));
+    rules_frame.set_hexpand(true);
+    let rules_box = Box::new(gtk4::Orientation::Vertical, 10);
+    rules_box.set_margin_start(15);
+    rules_box.set_margin_end(15);
+    rules_box.set_margin_top(15);
+    rules_box.set_margin_bottom(15);
+    
+    let toolbar = Box::new(gtk4::Orientation::Horizontal, 5);
+    let load_btn = Button::with_label("🔄 Load Rules");
+    let load_action = HardnAction::privileged("Read UFW Rules", protocol::HelperRequest::ReadUfwRules);
+    set_action_tooltip(&load_btn, &load_action);
+    let status_label = Label::new(None);
+    status_label.set_xalign(0.0);
+    status_label.set_hexpand(true);
+    status_label.set_wrap(true);
+    toolbar.append(&load_btn);
+    toolbar.append(&status_label);
+    rules_box.append(&toolbar);
+    
+    let rules_grid = Grid::new();
+    rules_grid.set_row_spacing(6);
+    rules_grid.set_column_spacing(15);
+    rules_box.append(
+        &ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vexpand(true)
+            .child(&rules_grid)
+            .build(),
+    );
+    
+    rules_box.append(&Separator::new(gtk4::Orientation::Horizontal));
+    
+    // ACTION DIRECTION [proto P] from FROM to TO [port P | app X] [on IFACE] [comment C]
+    let form_title = Label::new(Some("Add rule"));
+    form_title.set_xalign(0.0);
+    form_title.add_css_class("heading");
+    rules_box.append(&form_title);
+    let form = Grid::new();
+    form.set_row_spacing(5);
+    form.set_column_spacing(5);
+    let action_labels: Vec<&str> = firewall::Action::ALL.iter().map(|action| action.keyword()).collect();
+    let action_selector = DropDown::from_strings(&action_labels);
+    let direction_labels: Vec<&str> = firewall::Direction::ALL.iter().map(|direction| direction.keyword()).collect();
+    let direction_selector = DropDown::from_strings(&direction_labels);
+    let protocol_labels: Vec<&str> = firewall::RuleProtocol::ALL.iter().map(|protocol| protocol.keyword()).collect();
+    let protocol_selector = DropDown::from_strings(&protocol_labels);
+    let entry = |placeholder: &str, tooltip: &str| {
+        let entry = gtk4::Entry::new();
+        entry.set_placeholder_text(Some(placeholder));
+        entry.set_tooltip_text(Some(tooltip));
+        entry.set_hexpand(true);
+        entry
+    };
+    let port_entry = entry("port", "Destination port: 22, 80,443 or 6000:6007 (lists and ranges need tcp or udp)");
+    let app_entry = entry("app profile", "Application profile from /etc/ufw/applications.d instead of a port, e.g. OpenSSH");
+    let from_entry = entry("from: any", "Source address or CIDR, e.g. 192.168.1.0/24 or 2001:db8::/32");
+    let to_entry = entry("to: any", "Destination address or CIDR");
+    let interface_entry = entry("interface", "Only match packets on this interface, e.g. eth0");
+    let comment_entry = entry("comment", "Shown by ufw status; up to 128 characters");
+    let position_entry = entry("position", "Insert before this rule number; empty appends");
+    let log_check = CheckButton::with_label("Log");
+    log_check.set_tooltip_text(Some("Log new connections matching this rule"));
+    let add_btn = Button::with_label("➕ Add");
+    form.attach(&action_selector, 0, 0, 1, 1);
+    form.attach(&direction_selector, 1, 0, 1, 1);
+    form.attach(&protocol_selector, 2, 0, 1, 1);
+    form.attach(&port_entry, 3, 0, 1, 1);
+    form.attach(&app_entry, 4, 0, 1, 1);
+    form.attach(&log_check, 5, 0, 1, 1);
+    form.attach(&from_entry, 0, 1, 2, 1);
+    form.attach(&to_entry, 2, 1, 2, 1);
+    form.attach(&interface_entry, 4, 1, 1, 1);
+    form.attach(&comment_entry, 0, 2, 4, 1);
+    form.attach(&position_entry, 4, 2, 1, 1);
+    form.attach(&add_btn, 5, 2, 1, 1);
+    rules_box.append(&form);
+    let form_label = Label::new(None);
+    form_label.set_xalign(0.0);
+    form_label.set_wrap(true);
+    rules_box.append(&form_label);
+    
+    rules_frame.set_child(Some(&rules_box));
+    main_box.append(&rules_frame);
+    
+    let right_box = Box::new(gtk4::Orientation::Vertical, 10);
+    right_box.set_width_request(420);
+    
+    let settings_frame = Frame::new(Some("Defaults"));
+    let settings_grid = Grid::new();
+    settings_grid.set_row_spacing(5);
+    settings_grid.set_column_spacing(10);
+    settings_grid.set_margin_start(15);
+    settings_grid.set_margin_end(15);
+    settings_grid.set_margin_top(15);
+    settings_grid.set_margin_bottom(15);
+    let mut policy_selectors = Vec::new();
+    for (row, chain) in [firewall::PolicyChain::Incoming, firewall::PolicyChain::Outgoing, firewall::PolicyChain::Routed].into_iter().enumerate() {
+        let label = Label::new(Some(&format!("Default {}", chain.keyword())));
+        label.set_xalign(0.0);
+        let selector = DropDown::from_strings(&firewall::POLICIES);
+        selector.set_hexpand(true);
+        settings_grid.attach(&label, 0, row as i32, 1, 1);
+        settings_grid.attach(&selector, 1, row as i32, 1, 1);
+        policy_selectors.push((chain, selector));
+    }
+    let logging_label = Label::new(Some("Logging"));
+    logging_label.set_xalign(0.0);
+    let logging_selector = DropDown::from_strings(&firewall::LOG_LEVELS);
+    settings_grid.attach(&logging_label, 0, 3, 1, 1);
+    settings_grid.attach(&logging_selector, 1, 3, 1, 1);
+    let settings_label = Label::new(None);
+    settings_label.set_xalign(0.0);
+    settings_label.set_wrap(true);
+    settings_label.add_css_class("dim-label");
+    settings_grid.attach(&settings_label, 0, 4, 2, 1);
+    settings_frame.set_child(Some(&settings_grid));
+    right_box.append(&settings_frame);
+    
+    let pending_frame = Frame::new(Some("Pending Commands"));
+    let pending_box = Box::new(gtk4::Orientation::Vertical, 10);
+    pending_box.set_margin_start(15);
+    pending_box.set_margin_end(15);
+    pending_box.set_margin_top(15);
+    pending_box.set_margin_bottom(15);
+    let pending_label = Label::new(None);
+    pending_label.set_xalign(0.0);
+    pending_label.set_yalign(0.0);
+    pending_label.set_wrap(true);
+    pending_label.set_selectable(true);
+    pending_label.add_css_class("monospace");
+    pending_box.append(
+        &ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .min_content_height(120)
+            .vexpand(true)
+            .child(&pending_label)
+            .build(),
+    );
+    let pending_buttons = Box::new(gtk4::Orientation::Horizontal, 5);
+    let apply_btn = Button::with_label("✅ Apply");
+    apply_btn.add_css_class("suggested-action");
+    apply_btn.set_hexpand(true);
+    apply_btn.set_tooltip_text(Some("Run these commands in order as administrator, stopping at the first failure"));
+    let discard_btn = Button::with_label("✖ Discard");
+    pending_buttons.append(&apply_btn);
+    pending_buttons.append(&discard_btn);
+    pending_box.append(&pending_buttons);
+    pending_frame.set_child(Some(&pending_box));
+    right_box.append(&pending_frame);
+    
+    let output_area = create_output_area();
+    output_area.container.set_vexpand(false);
+    output_area.container.set_height_request(200);
+    right_box.append(&output_area.container);
+    main_box.append(&right_box);
+    
+    let view = FirewallView {
+        status_label,
+        rules_grid,
+        loaded: Rc::new(Cell::new(false)),
+        editor: Rc::new(RefCell::new(ufw::RuleEditor::new(Vec::new(), ufw::UfwSettings::load()))),
+        action_selector,
+        direction_selector,
+        protocol_selector,
+        port_entry,
+        app_entry,
+        from_entry,
+        to_entry,
+        interface_entry,
+        comment_entry,
+        position_entry,
+        log_check,
+        form_label,
+        policy_selectors,
+        logging_selector,
+        settings_label,
+        pending_label,
+        apply_btn: apply_btn.clone(),
+        discard_btn: discard_btn.clone(),
+        output_area,
+        job_manager: job_manager.clone(),
+    };
+    view.show_settings();
+    view.show_rules();
+    
+    let view_clone = view.clone();
+    load_btn.connect_clicked(move |btn| view_clone.load_rules(&load_action, btn));
+    
+    let view_clone = view.clone();
+    add_btn.connect_clicked(move |_| view_clone.add_rule());
+    
+    for (chain, selector) in &view.policy_selectors {
+        let view_clone = view.clone();
+        let chain = *chain;
+        selector.connect_selected_notify(move |selector| {
+            let policy = firewall::POLICIES.get(selector.selected() as usize).copied().unwrap_or("deny");
+            view_clone.editor.borrow_mut().set_default(chain, policy);
+            view_clone.show_pending();
+        });
+    }
+    
+    let view_clone = view.clone();
+    view.logging_selector.connect_selected_notify(move |selector| {
+        let level = firewall::LOG_LEVELS.get(selector.selected() as usize).copied().unwrap_or("low");
+        view_clone.editor.borrow_mut().set_logging(level);
+        view_clone.show_pending();
+    });
+    
+    let view_clone = view.clone();
+    apply_btn.connect_clicked(move |btn| view_clone.apply(btn));
+    
+    let view_clone = view.clone();
+    discard_btn.connect_clicked(move |_| view_clone.discard());
+    
+    notebook.append_page(&main_box, Some(&Label::new(Some("🧱 Firewall"))));
+}
+
+// Row buttons of the Firewall tab, by rule number
+type RuleEdit = fn(&mut ufw::RuleEditor, usize) -> Result<(), String>;
+
+// State of the Firewall tab
+#[derive(Clone)]
+struct FirewallView {
+    status_label: Label,
+    rules_grid: Grid,
+    // The rules files are root-only; editing starts once they were read through the helper
+    loaded: Rc<Cell<bool>>,
+    editor: Rc<RefCell<ufw::RuleEditor>>,
+    action_selector: DropDown,
+    direction_selector: DropDown,
+    protocol_selector: DropDown,
+    port_entry: gtk4::Entry,
+    app_entry: gtk4::Entry,
+    from_entry: gtk4::Entry,
+    to_entry: gtk4::Entry,
+    interface_entry: gtk4::Entry,
+    comment_entry: gtk4::Entry,
+    position_entry: gtk4::Entry,
+    log_check: CheckButton,
+    form_label: Label,
+    policy_selectors: Vec<(firewall::PolicyChain, DropDown)>,
+    logging_selector: DropDown,
+    settings_label: Label,
+    pending_label: Label,
+    apply_btn: Button,
+    discard_btn: Button,
+    output_area: OutputPane,
+    job_manager: JobManager,
+}
+
+impl FirewallView {
+    // Selectors follow the current settings; setting them drops any pending change
+    fn show_settings(&self) {
+        let settings = self.editor.borrow().settings.clone();
+        for (chain, selector) in &self.policy_selectors {
+            let position = firewall::POLICIES.iter().position(|policy| *policy == settings.policy(*chain)).unwrap_or(1);
+            selector.set_selected(position as u32);
+        }
+        let position = firewall::LOG_LEVELS.iter().position(|level| *level == settings.log_level).unwrap_or(1);
+        self.logging_selector.set_selected(position as u32);
+        
+        // What hardn applies on its next run, so drift shows up here
+        let mut text = format!("UFW is {}", if settings.enabled { "enabled" } else { "disabled" });
+        if let Ok(hardn_config) = config::HardnConfig::load() {
+            let expected = [
+                ("UFW_DEFAULT_INCOMING", settings.default_incoming.clone()),
+                ("UFW_DEFAULT_OUTGOING", settings.default_outgoing.clone()),
+                ("UFW_ENABLE_LOGGING", (settings.log_level != "off").to_string()),
+            ];
+            for (key, current) in expected {
+                if let Some(value) = hardn_config.get(key) {
+                    let marker = if value == current { "" } else { " ⚠️" };
+                    text.push_str(&format!("\n{}={}{}", key, value, marker));
+                }
+            }
+            text.push_str(&format!("\n(from {}; hardn setup reapplies these)", config::CONFIG_FILE_NAME));
+        }
+        self.settings_label.set_text(&text);
+        self.show_pending();
+    }
+    
+    fn show_rules(&self) {
+        while let Some(child) = self.rules_grid.first_child() {
+            self.rules_grid.remove(&child);
+        }
+        if !self.loaded.get() {
+            let label = Label::new(Some("Load the rules to view and edit them (the rules files are only readable by root)"));
+            label.set_xalign(0.0);
+            label.add_css_class("dim-label");
+            self.rules_grid.attach(&label, 0, 0, 6, 1);
+            return;
+        }
+        
+        for (column, title) in ["#", "To", "Action", "From", "Comment"].iter().enumerate() {
+            let header = Label::new(Some(title));
+            header.set_xalign(0.0);
+            header.add_css_class("heading");
+            self.rules_grid.attach(&header, column as i32, 0, 1, 1);
+        }
+        
+        let entries = self.editor.borrow().entries.clone();
+        for (index, entry) in entries.iter().enumerate() {
+            let number = index + 1;
+            let row = number as i32;
+            let (to, action, from) = entry.columns();
+            let number_text = if entry.pending { format!("{} ✏️", number) } else { number.to_string() };
+            let comment = entry.spec.as_ref().and_then(|spec| spec.comment.clone()).unwrap_or_default();
+            for (column, text) in [number_text, to, action, from, comment].iter().enumerate() {
+                let label = Label::new(Some(text));
+                label.set_xalign(0.0);
+                label.set_selectable(true);
+                if entry.spec.is_none() {
+                    label.add_css_class("dim-label");
+                    label.set_tooltip_text(Some("Not editable here; can be deleted"));
+                }
+                self.rules_grid.attach(&label, column as i32, row, 1, 1);
+            }
+            
+            let buttons = Box::new(gtk4::Orientation::Horizontal, 2);
+            let edits: [(&str, &str, RuleEdit); 3] = [
+                ("▲", "Move up", ufw::RuleEditor::move_up),
+                ("▼", "Move down", ufw::RuleEditor::move_down),
+                ("🗑", "Delete", ufw::RuleEditor::delete),
+            ];
+            for (label, tooltip, edit) in edits {
+                let btn = Button::with_label(label);
+                btn.add_css_class("flat");
+                btn.set_tooltip_text(Some(tooltip));
+                let view = self.clone();
+                btn.connect_clicked(move |_| {
+                    let result = edit(&mut view.editor.borrow_mut(), number);
+                    view.status_label.set_text(&match result {
+                        Ok(()) => String::new(),
+                        Err(e) => format!("❌ {}", e),
+                    });
+                    // Rebuilt on idle: this button is among the widgets being replaced
+                    let view = view.clone();
+                    glib::idle_add_local_once(move || {
+                        view.show_rules();
+                        view.show_pending();
+                    });
+                });
+                buttons.append(&btn);
+            }
+            self.rules_grid.attach(&buttons, 5, row, 1, 1);
+        }
+        
+        if entries.is_empty() {
+            let label = Label::new(Some("No rules; only the default policies apply"));
+            label.set_xalign(0.0);
+            label.add_css_class("dim-label");
+            self.rules_grid.attach(&label, 0, 1, 6, 1);
+        }
+    }
+    
+    fn show_pending(&self) {
+        let editor = self.editor.borrow();
+        let text = editor
+            .commands
+            .iter()
+            .map(|command| command.argv().iter().map(|arg| action::shell_quote(arg)).collect::<Vec<_>>().join(" "))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.pending_label.set_text(if text.is_empty() { "No pending changes" } else { &text });
+        self.apply_btn.set_sensitive(editor.is_dirty());
+        self.discard_btn.set_sensitive(editor.is_dirty());
+    }
+    
+    fn form_rule(&self) -> Result<(firewall::RuleSpec, Option<u32>), String> {
+        let text = |entry: &gtk4::Entry| Some(entry.text().trim().to_string()).filter(|text| !text.is_empty());
+        let action = firewall::Action::ALL[self.action_selector.selected() as usize % firewall::Action::ALL.len()];
+        let direction = firewall::Direction::ALL[self.direction_selector.selected() as usize % firewall::Direction::ALL.len()];
+        let mut rule = firewall::RuleSpec::new(action, direction);
+        rule.protocol = firewall::RuleProtocol::ALL[self.protocol_selector.selected() as usize % firewall::RuleProtocol::ALL.len()];
+        rule.to_port = text(&self.port_entry);
+        rule.to_app = text(&self.app_entry);
+        rule.from = text(&self.from_entry).unwrap_or_else(|| "any".to_string());
+        rule.to = text(&self.to_entry).unwrap_or_else(|| "any".to_string());
+        rule.interface = text(&self.interface_entry);
+        rule.comment = text(&self.comment_entry);
+        rule.log = self.log_check.is_active().then(|| "log".to_string());
+        let position = match text(&self.position_entry) {
+            Some(position) => Some(position.parse().map_err(|_| format!("invalid position: {}", position))?),
+            None => None,
+        };
+        Ok((rule, position))
+    }
+    
+    fn add_rule(&self) {
+        if !self.loaded.get() {
+            self.form_label.set_text("❌ Load the rules first, so that rule numbers are known");
+            return;
+        }
+        let result = self.form_rule().and_then(|(rule, position)| self.editor.borrow_mut().add(rule, position));
+        match result {
+            Ok(()) => {
+                self.form_label.set_text("");
+                for entry in [&self.port_entry, &self.app_entry, &self.comment_entry, &self.position_entry] {
+                    entry.set_text("");
+                }
+                self.show_rules();
+                self.show_pending();
+            }
+            Err(e) => self.form_label.set_text(&format!("❌ {}", e)),
+        }
+    }
+    
+    fn discard(&self) {
+        {
+            let mut editor = self.editor.borrow_mut();
+            // Rule edits already changed the list; it has to be read again
+            let rules_changed = editor
+                .commands
+                .iter()
+                .any(|command| matches!(command, firewall::UfwCommand::Add { .. } | firewall::UfwCommand::Delete { .. }));
+            if rules_changed {
+                self.loaded.set(false);
+            }
+            let entries = if self.loaded.get() { editor.entries.clone() } else { Vec::new() };
+            editor.reset(entries, ufw::UfwSettings::load());
+        }
+        self.status_label.set_text(if self.loaded.get() { "" } else { "Changes discarded; load the rules again" });
+        self.show_settings();
+        self.show_rules();
+    }
+    
+    fn load_rules(&self, action: &HardnAction, btn: &Button) {
+        let view = self.clone();
+        run_helper(&self.output_area, &self.job_manager, action, btn, move |exit_code, lines| {
+            if exit_code != Some(0) {
+                view.status_label.set_text("❌ Could not read the UFW rules");
+                return;
+            }
+            let entries = ufw::parse_rules(&lines.join("\n"));
+            let v6 = entries.iter().filter(|entry| entry.v6).count();
+            view.status_label.set_text(&format!("{} rules ({} IPv4, {} IPv6)", entries.len(), entries.len() - v6, v6));
+            view.editor.borrow_mut().reset(entries, ufw::UfwSettings::load());
+            view.loaded.set(true);
+            view.show_settings();
+            view.show_rules();
+        });
+    }
+    
+    fn apply(&self, btn: &Button) {
+        let commands = self.editor.borrow().commands.clone();
+        if commands.len() > firewall::MAX_COMMANDS {
+            self.status_label.set_text(&format!("❌ At most {} commands at once; apply or discard some", firewall::MAX_COMMANDS));
+            return;
+        }
+        let action = HardnAction::privileged("Apply Firewall Changes", protocol::HelperRequest::UfwApply { commands }).destructive();
+        let view = self.clone();
+        let btn_clone = btn.clone();
+        run_helper(&self.output_area, &self.job_manager, &action, btn, move |exit_code, _| {
+            // Even a failed batch may have changed some rules; start again from what is there
+            view.loaded.set(false);
+            view.editor.borrow_mut().reset(Vec::new(), ufw::UfwSettings::load());
+            view.show_settings();
+            view.show_rules();
+            let load_action = HardnAction::privileged("Read UFW Rules", protocol::HelperRequest::ReadUfwRules);
+            view.load_rules(&load_action, &btn_clone);
+            if exit_code != Some(0) {
+                view.status_label.set_text("❌ Not all commands were applied; see the output");
+            }
+        });
+    }
+}
+
+fn create_fail2ban_tab(notebook: &Notebook, job_manager: &JobManager, window: &ApplicationWindow) {
+    let main_box = Box::new(gtk4::Orientation::Horizontal, 10);
+    main_box.set_margin_start(20);
+    main_box.set_margin_end(20);
+    main_box.set_margin_top(20);
+    main_box.set_margin_bottom(20);
+    
+    let jails_frame = Frame::new(Some("Fail2Ban Jails"));
+    jails_frame.set_hexpand(true);
+    let jails_box = Box::new(gtk4::Orientation::Vertical, 10);
+    jails_box.set_margin_start(15);
+    jails_box.set_margin_end(15);
+    jails_box.set_margin_top(15);
+    jails_box.set_margin_bottom(15);
+    
+    let toolbar = Box::new(gtk4::Orientation::Horizontal, 5);
+    let refresh_btn = Button::with_label("🔄 Refresh");
+    let status_action = HardnAction::privileged("Fail2Ban Status", protocol::HelperRequest::Fail2banStatus);
+    set_action_tooltip(&refresh_btn, &status_action);
+    let summary_label = Label::new(None);
+    summary_label.set_xalign(0.0);
+    summary_label.set_hexpand(true);
+    summary_label.set_wrap(true);
+    toolbar.append(&refresh_btn);
+    toolbar.append(&summary_label);
+    jails_box.append(&toolbar);
+    
+    let jails_list = Box::new(gtk4::Orientation::Vertical, 8);
+    jails_box.append(
+        &ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vexpand(true)
+            .child(&jails_list)
+            .build(),
+    );
+    
+    jails_box.append(&Separator::new(gtk4::Orientation::Horizontal));
+    
+    let ban_box = Box::new(gtk4::Orientation::Horizontal, 5);
+    let ban_jail_selector = DropDown::from_strings(&[]);
+    let ban_entry = gtk4::Entry::new();
+    ban_entry.set_placeholder_text(Some("IP address to ban"));
+    ban_entry.set_hexpand(true);
+    let ban_btn = Button::with_label("🚫 Ban");
+    ban_box.append(&ban_jail_selector);
+    ban_box.append(&ban_entry);
+    ban_box.append(&ban_btn);
+    jails_box.append(&ban_box);
+    
+    jails_frame.set_child(Some(&jails_box));
+    main_box.append(&jails_frame);
+    
+    let right_box = Box::new(gtk4::Orientation::Vertical, 10);
+    right_box.set_width_request(380);
+    
+    let settings_frame = Frame::new(Some("HARDN Jail Settings"));
+    let settings_grid = Grid::new();
+    settings_grid.set_row_spacing(5);
+    settings_grid.set_column_spacing(10);
+    settings_grid.set_margin_start(15);
+    settings_grid.set_margin_end(15);
+    settings_grid.set_margin_top(15);
+    settings_grid.set_margin_bottom(15);
+    let section_selector = DropDown::from_strings(&[fail2ban::DEFAULT_SECTION]);
+    section_selector.set_hexpand(true);
+    let section_label = Label::new(Some("Section"));
+    section_label.set_xalign(0.0);
+    settings_grid.attach(&section_label, 0, 0, 1, 1);
+    settings_grid.attach(&section_selector, 1, 0, 1, 1);
+    let mut setting_entries = Vec::new();
+    for (row, key) in fail2ban::SETTINGS.iter().enumerate() {
+        let label = Label::new(Some(key));
+        label.set_xalign(0.0);
+        let entry = gtk4::Entry::new();
+        entry.set_hexpand(true);
+        settings_grid.attach(&label, 0, row as i32 + 1, 1, 1);
+        settings_grid.attach(&entry, 1, row as i32 + 1, 1, 1);
+        setting_entries.push(entry);
+    }
+    setting_entries[0].set_tooltip_text(Some("Seconds or e.g. 10m, 1h, 1d; -1 bans forever"));
+    setting_entries[1].set_tooltip_text(Some("Window in which maxretry failures lead to a ban: seconds or e.g. 10m, 1h"));
+    setting_entries[2].set_tooltip_text(Some("Failures within findtime before a ban"));
+    let save_btn = Button::with_label("💾 Save and Reload");
+    save_btn.set_tooltip_text(Some(&format!("Writes {} and reloads fail2ban (runs as administrator)", fail2ban::HARDN_JAIL_FILE)));
+    settings_grid.attach(&save_btn, 0, 4, 2, 1);
+    let settings_label = Label::new(None);
+    settings_label.set_xalign(0.0);
+    settings_label.set_wrap(true);
+    settings_label.add_css_class("dim-label");
+    settings_grid.attach(&settings_label, 0, 5, 2, 1);
+    settings_frame.set_child(Some(&settings_grid));
+    right_box.append(&settings_frame);
+    
+    let output_area = create_output_area();
+    right_box.append(&output_area.container);
+    main_box.append(&right_box);
+    
+    let view = Fail2banView {
+        summary_label,
+        jails_list,
+        jails: Rc::new(RefCell::new(Vec::new())),
+        ban_jail_selector,
+        ban_entry,
+        section_selector,
+        setting_entries,
+        settings_label,
+        output_area,
+        job_manager: job_manager.clone(),
+        window: window.downgrade(),
+    };
+    view.show_jails();
+    view.show_settings();
+    
+    let view_clone = view.clone();
+    refresh_btn.connect_clicked(move |btn| view_clone.refresh(&status_action, btn));
+    
+    let view_clone = view.clone();
+    ban_btn.connect_clicked(move |btn| view_clone.ban(btn));
+    let ban_btn_clone = ban_btn.clone();
+    view.ban_entry.connect_activate(move |_| ban_btn_clone.emit_clicked());
+    
+    let view_clone = view.clone();
+    view.section_selector.connect_selected_notify(move |_| view_clone.show_settings());
+    
+    let view_clone = view.clone();
+    save_btn.connect_clicked(move |btn| view_clone.save_settings(btn));
+    
+    notebook.append_page(&main_box, Some(&Label::new(Some("🚫 Fail2Ban"))));
+}
+
+// State of the Fail2Ban tab
+#[derive(Clone)]
+struct Fail2banView {
+    summary_label: Label,
+    jails_list: Box,
+    jails: Rc<RefCell<Vec<fail2ban::JailStatus>>>,
+    ban_jail_selector: DropDown,
+    ban_entry: gtk4::Entry,
+    section_selector: DropDown,
+    // bantime, findtime, maxretry as in fail2ban::SETTINGS
+    setting_entries: Vec<gtk4::Entry>,
+    settings_label: Label,
+    output_area: OutputPane,
+    job_manager: JobManager,
+    window: glib::WeakRef<ApplicationWindow>,
+}
+
+impl Fail2banView {
+    fn refresh(&self, action: &HardnAction, btn: &Button) {
+        let view = self.clone();
+        self.run(action, btn, move |exit_code, lines| {
+            let jails = fail2ban::parse_transcript(&lines.join("\n"));
+            if exit_code != Some(0) {
+                view.summary_label.set_text("❌ Could not read the jail status; is fail2ban running?");
+            } else {
+                let banned: u64 = jails.iter().map(|jail| jail.currently_banned).sum();
+                let failed: u64 = jails.iter().map(|jail| jail.currently_failed).sum();
+                view.summary_label.set_text(&format!("{} jails · {} addresses banned · {} failures pending", jails.len(), banned, failed));
+            }
+            view.jails.replace(jails);
+            view.show_jails();
+            view.show_settings();
+        });
+    }
+    
+    fn show_jails(&self) {
+        while let Some(child) = self.jails_list.first_child() {
+            self.jails_list.remove(&child);
+        }
+        let jails = self.jails.borrow().clone();
+        
+        let names: Vec<&str> = jails.iter().map(|jail| jail.name.as_str()).collect();
+        self.ban_jail_selector.set_model(Some(&gtk4::StringList::new(&names)));
+        let mut sections = vec![fail2ban::DEFAULT_SECTION];
+        sections.extend(names.iter());
+        let selected = self.section_selector.selected();
+        self.section_selector.set_model(Some(&gtk4::StringList::new(&sections)));
+        if (selected as usize) < sections.len() {
+            self.section_selector.set_selected(selected);
+        }
+        
+        if jails.is_empty() {
+            let label = Label::new(Some("No jail status loaded; press Refresh (the fail2ban socket is only accessible to root)"));
+            label.set_xalign(0.0);
+            label.set_wrap(true);
+            label.add_css_class("dim-label");
+            self.jails_list.append(&label);
+            return;
+        }
+        
+        for jail in &jails {
+            let title = format!(
+                "{} · {} banned ({} total) · {} failing ({} total)",
+                jail.name, jail.currently_banned, jail.total_banned, jail.currently_failed, jail.total_failed
+            );
+            let expander = Expander::new(Some(&title));
+            expander.set_expanded(jail.currently_banned > 0);
+            let details = Box::new(gtk4::Orientation::Vertical, 5);
+            details.set_margin_start(20);
+            
+            let mut info = vec![jail.filter_source.clone()];
+            let settings: Vec<String> = jail.settings.iter().map(|(key, value)| format!("{} {}", key, value)).collect();
+            if !settings.is_empty() {
+                info.push(format!("Effective: {}", settings.join(" · ")));
+            }
+            for text in info.iter().filter(|text| !text.is_empty()) {
+                let label = Label::new(Some(text));
+                label.set_xalign(0.0);
+                label.set_wrap(true);
+                label.set_selectable(true);
+                label.add_css_class("dim-label");
+                details.append(&label);
+            }
+            
+            for ip in &jail.banned {
+                let row = Box::new(gtk4::Orientation::Horizontal, 5);
+                let ip_label = Label::new(Some(ip));
+                ip_label.set_xalign(0.0);
+                ip_label.set_hexpand(true);
+                ip_label.set_selectable(true);
+                ip_label.add_css_class("monospace");
+                let unban_btn = Button::with_label("✅ Unban");
+                let command = fail2ban::JailCommand::Unban { jail: jail.name.clone(), ip: ip.clone() };
+                let action = HardnAction::privileged(&format!("Unban {} from {}", ip, jail.name), protocol::HelperRequest::Fail2banJail { command });
+                set_action_tooltip(&unban_btn, &action);
+                let view = self.clone();
+                unban_btn.connect_clicked(move |btn| view.run_jail_command(&action, btn));
+                row.append(&ip_label);
+                row.append(&unban_btn);
+                details.append(&row);
+            }
+            if jail.banned.is_empty() {
+                let label = Label::new(Some("No addresses banned"));
+                label.set_xalign(0.0);
+                details.append(&label);
+            }
+            
+            expander.set_child(Some(&details));
+            self.jails_list.append(&expander);
+        }
+    }
+    
+    fn ban(&self, btn: &Button) {
+        let jail = self.jails.borrow().get(self.ban_jail_selector.selected() as usize).map(|jail| jail.name.clone());
+        let Some(jail) = jail else {
+            self.summary_label.set_text("❌ Refresh to load the jails first");
+            return;
+        };
+        let ip = self.ban_entry.text().trim().to_string();
+        let command = fail2ban::JailCommand::Ban { jail: jail.clone(), ip: ip.clone() };
+        if let Err(e) = command.validate() {
+            self.summary_label.set_text(&format!("❌ {}", e));
+            return;
+        }
+        self.ban_entry.set_text("");
+        let action = HardnAction::privileged(&format!("Ban {} in {}", ip, jail), protocol::HelperRequest::Fail2banJail { command });
+        self.run_jail_command(&action, btn);
+    }
+    
+    // Ban or unban, then reread the status so the lists show the result
+    fn run_jail_command(&self, action: &HardnAction, btn: &Button) {
+        let view = self.clone();
+        let btn_clone = btn.clone();
+        self.run(action, btn, move |_, _| {
+            let status_action = HardnAction::privileged("Fail2Ban Status", protocol::HelperRequest::Fail2banStatus);
+            view.refresh(&status_action, &btn_clone);
+        });
+    }
+    
+    fn selected_section(&self) -> String {
+        self.section_selector
+            .selected_item()
+            .and_downcast::<gtk4::StringObject>()
+            .map(|item| item.string().to_string())
+            .unwrap_or_else(|| fail2ban::DEFAULT_SECTION.to_string())
+    }
+    
+    // Values set in the HARDN jail file for the section, else the jail's effective ones
+    fn show_settings(&self) {
+        let section = self.selected_section();
+        let file = fail2ban::read_jail_file(std::path::Path::new(fail2ban::HARDN_JAIL_FILE));
+        let configured = file.as_ref().ok().and_then(|sections| sections.get(&section).cloned()).unwrap_or_default();
+        let defaults = file.as_ref().ok().and_then(|sections| sections.get(fail2ban::DEFAULT_SECTION).cloned()).unwrap_or_default();
+        let effective = self.jails.borrow().iter().find(|jail| jail.name == section).map(|jail| jail.settings.clone()).unwrap_or_default();
+        for (entry, key) in self.setting_entries.iter().zip(fail2ban::SETTINGS) {
+            let value = configured.get(key).or_else(|| effective.get(key)).or_else(|| defaults.get(key));
+            entry.set_text(value.map(String::as_str).unwrap_or(""));
+        }
+        self.settings_label.set_text(&match &file {
+            Ok(_) if configured.is_empty() => format!("[{}] sets nothing in {} yet; shown are the values in effect", section, fail2ban::HARDN_JAIL_FILE),
+            Ok(_) => format!("From [{}] in {}", section, fail2ban::HARDN_JAIL_FILE),
+            Err(e) => format!("❌ {}: {}", fail2ban::HARDN_JAIL_FILE, e),
+        });
+    }
+    
+    fn save_settings(&self, btn: &Button) {
+        let text: Vec<String> = self.setting_entries.iter().map(|entry| entry.text().trim().to_string()).collect();
+        let Ok(maxretry) = text[2].parse() else {
+            self.settings_label.set_text(&format!("❌ maxretry must be a number, not {}", text[2]));
+            return;
+        };
+        let settings = fail2ban::JailSettings {
+            section: self.selected_section(),
+            bantime: text[0].clone(),
+            findtime: text[1].clone(),
+            maxretry,
+        };
+        if let Err(e) = settings.validate() {
+            self.settings_label.set_text(&format!("❌ {}", e));
+            return;
+        }
+        let action = HardnAction::privileged(
+            &format!("Update Fail2Ban [{}]", settings.section),
+            protocol::HelperRequest::Fail2banSettings { settings },
+        );
+        let view = self.clone();
+        self.run(&action, btn, move |_, _| view.show_settings());
+    }
+    
+    // Through the helper, or straight to HARDN_FAIL2BAN_CLIENT when set; `done` gets
+    // the exit code and output lines either way
+    fn run(&self, action: &HardnAction, btn: &Button, done: impl FnOnce(Option<i32>, Vec<String>) + 'static) {
+        let (observer, events) = mpsc::channel();
+        let request = match &action.kind {
+            action::ActionKind::Privileged(request) => Some(request.clone()),
+            _ => None,
+        };
+        // Output of a fixture client, which no job writes to the pane
+        let mut mirror = None;
+        match (fail2ban::Client::from_env(), request) {
+            (Some(client), Some(request @ (protocol::HelperRequest::Fail2banStatus | protocol::HelperRequest::Fail2banJail { .. }))) => {
+                self.output_area.clear(&format!("{} (HARDN_FAIL2BAN_CLIENT)\n", action.label));
+                mirror = Some(self.output_area.clone());
+                thread::spawn(move || {
+                    let mut send = |line: &str| {
+                        let _ = observer.send(JobEvent::Line(line.to_string()));
+                    };
+                    let result = match &request {
+                        protocol::HelperRequest::Fail2banJail { command } => client.run(&command.args()).map(|output| output.lines().for_each(&mut send)),
+                        _ => client.transcript(&mut send).map(|_| ()),
+                    };
+                    if let Err(e) = &result {
+                        send(&format!("[ERROR] {}", e));
+                    }
+                    let _ = observer.send(JobEvent::Exited(Some(if result.is_ok() { 0 } else { 1 })));
+                });
+            }
+            _ => {
+                let Some(window) = self.window.upgrade() else {
+                    return;
+                };
+                dispatch_action(action, &self.output_area, btn, &self.job_manager, &window, Some(observer));
+            }
+        }
+        
+        let collect = move |lines: &mut Vec<String>, line: String| {
+            if let Some(output_area) = &mirror {
+                output_area.append_raw(output::Stream::Stdout, &line);
+                output_area.scroll_to_end();
+            }
+            lines.push(line);
+        };
+        drain_observer(events, Vec::new(), collect, move |lines, exit_code| {
+            // On idle: the clicked button may be among the widgets `done` replaces
+            glib::idle_add_local_once(move || done(exit_code, lines));
+        });
+    }
+}
+
+fn create_ssh_tab(notebook: &Notebook, job_manager: &JobManager) {
+    let main_box = Box::new(gtk4::Orientation::Horizontal, 10);
+    main_box.set_margin_start(20);
+    main_box.set_margin_end(20);
+    main_box.set_margin_top(20);
+    main_box.set_margin_bottom(20);
+    
+    let findings_frame = Frame::new(Some("sshd Baseline"));
+    findings_frame.set_hexpand(true);
+    let findings_box = Box::new(gtk4::Orientation::Vertical, 10);
+    findings_box.set_margin_start(15);
+    findings_box.set_margin_end(15);
+    findings_box.set_margin_top(15);
+    findings_box.set_margin_bottom(15);
+    
+    let toolbar = Box::new(gtk4::Orientation::Horizontal, 5);
+    let check_btn = Button::with_label("🔄 Check");
+    check_btn.set_tooltip_text(Some(&format!("Read {} and its Include files as the current user", sshd_config::SSHD_CONFIG)));
+    let load_btn = Button::with_label("🔑 Load as Administrator");
+    let load_action = HardnAction::privileged("Read sshd_config", protocol::HelperRequest::ReadSshdConfig);
+    set_action_tooltip(&load_btn, &load_action);
+    let summary_label = Label::new(None);
+    summary_label.set_xalign(0.0);
+    summary_label.set_hexpand(true);
+    summary_label.set_wrap(true);
+    toolbar.append(&check_btn);
+    toolbar.append(&load_btn);
+    toolbar.append(&summary_label);
+    findings_box.append(&toolbar);
+    
+    let findings_grid = Grid::new();
+    findings_grid.set_row_spacing(6);
+    findings_grid.set_column_spacing(15);
+    findings_box.append(
+        &ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vexpand(true)
+            .child(&findings_grid)
+            .build(),
+    );
+    
+    let files_label = Label::new(None);
+    files_label.set_xalign(0.0);
+    files_label.set_wrap(true);
+    files_label.set_selectable(true);
+    files_label.add_css_class("dim-label");
+    findings_box.append(&files_label);
+    
+    findings_frame.set_child(Some(&findings_box));
+    main_box.append(&findings_frame);
+    
+    let right_box = Box::new(gtk4::Orientation::Vertical, 10);
+    right_box.set_width_request(420);
+    
+    let patch_frame = Frame::new(Some("Patch Preview"));
+    let patch_box = Box::new(gtk4::Orientation::Vertical, 10);
+    patch_box.set_margin_start(15);
+    patch_box.set_margin_end(15);
+    patch_box.set_margin_top(15);
+    patch_box.set_margin_bottom(15);
+    let patch_label = Label::new(None);
+    patch_label.set_xalign(0.0);
+    patch_label.set_yalign(0.0);
+    patch_label.set_selectable(true);
+    patch_label.add_css_class("monospace");
+    patch_box.append(
+        &ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .min_content_height(160)
+            .vexpand(true)
+            .child(&patch_label)
+            .build(),
+    );
+    let apply_btn = Button::with_label("✅ Apply Selected Fixes");
+    apply_btn.add_css_class("suggested-action");
+    apply_btn.set_tooltip_text(Some("Change only these lines as administrator, check the result with sshd -t and reload sshd; a configuration sshd rejects is rolled back"));
+    patch_box.append(&apply_btn);
+    patch_frame.set_child(Some(&patch_box));
+    right_box.append(&patch_frame);
+    
+    let output_area = create_output_area();
+    output_area.container.set_vexpand(false);
+    output_area.container.set_height_request(200);
+    right_box.append(&output_area.container);
+    main_box.append(&right_box);
+    
+    let view = SshView {
+        summary_label,
+        findings_grid,
+        files_label,
+        patch_label,
+        apply_btn: apply_btn.clone(),
+        config: Rc::new(RefCell::new(None)),
+        report: Rc::new(RefCell::new(ssh_audit::AuditReport::default())),
+        selected: Rc::new(RefCell::new(Vec::new())),
+        output_area,
+        job_manager: job_manager.clone(),
+    };
+    view.check();
+    
+    let view_clone = view.clone();
+    check_btn.connect_clicked(move |_| view_clone.check());
+    
+    let view_clone = view.clone();
+    load_btn.connect_clicked(move |btn| view_clone.load(&load_action, btn));
+    
+    let view_clone = view.clone();
+    apply_btn.connect_clicked(move |btn| view_clone.apply(btn));
+    
+    notebook.append_page(&main_box, Some(&Label::new(Some("🔐 SSH"))));
+}
+
+// State of the SSH tab
+#[derive(Clone)]
+struct SshView {
+    summary_label: Label,
+    findings_grid: Grid,
+    files_label: Label,
+    patch_label: Label,
+    apply_btn: Button,
+    // None until sshd_config was read, directly or through the helper
+    config: Rc<RefCell<Option<sshd_config::SshdConfig>>>,
+    report: Rc<RefCell<ssh_audit::AuditReport>>,
+    // Per finding: whether its fix goes into the patch
+    selected: Rc<RefCell<Vec<bool>>>,
+    output_area: OutputPane,
+    job_manager: JobManager,
+}
+
+impl SshView {
+    // openssh.sh makes sshd_config readable by root only; then Load as Administrator
+    fn check(&self) {
+        match sshd_config::SshdConfig::load(std::path::Path::new(sshd_config::SSHD_CONFIG), &sshd_config::SystemFs) {
+            Ok(config) => self.show_config(config),
+            Err(e) => {
+                self.config.replace(None);
+                self.report.replace(ssh_audit::AuditReport::default());
+                self.selected.replace(Vec::new());
+                self.summary_label.set_text(&match e.kind() {
+                    std::io::ErrorKind::PermissionDenied => format!("{} is only readable by root; load it as administrator", sshd_config::SSHD_CONFIG),
+                    std::io::ErrorKind::NotFound => "OpenSSH server is not installed".to_string(),
+                    _ => format!("❌ {}: {}", sshd_config::SSHD_CONFIG, e),
+                });
+                self.show_findings();
+                self.show_patch();
+            }
+        }
+    }
+    
+    fn load(&self, action: &HardnAction, btn: &Button) {
+        let view = self.clone();
+        run_helper(&self.output_area, &self.job_manager, action, btn, move |exit_code, lines| {
+            let transcript = sshd_config::Transcript::parse(&lines.join("\n"));
+            match sshd_config::SshdConfig::load(std::path::Path::new(sshd_config::SSHD_CONFIG), &transcript) {
+                Ok(config) if exit_code == Some(0) => view.show_config(config),
+                _ => view.summary_label.set_text("❌ Could not read the sshd configuration; see the output"),
+            }
+        });
+    }
+    
+    fn show_config(&self, config: sshd_config::SshdConfig) {
+        let report = ssh_audit::audit(&config);
+        // The PasswordAuthentication fix is not preselected: it locks out users without keys
+        let selected = report
+            .findings
+            .iter()
+            .map(|finding| finding.fix.is_some() && finding.keyword != "PasswordAuthentication")
+            .collect();
+        self.summary_label.set_text(&report.summary());
+        let mut files = vec![format!("Files: {}", report.files.join(", "))];
+        files.extend(report.errors.iter().map(|error| format!("⚠️ {}", error)));
+        self.files_label.set_text(&files.join("\n"));
+        self.config.replace(Some(config));
+        self.report.replace(report);
+        self.selected.replace(selected);
+        self.show_findings();
+        self.show_patch();
+    }
+    
+    fn show_findings(&self) {
+        while let Some(child) = self.findings_grid.first_child() {
+            self.findings_grid.remove(&child);
+        }
+        let report = self.report.borrow().clone();
+        if report.findings.is_empty() {
+            let label = Label::new(Some("Nothing checked yet"));
+            label.set_xalign(0.0);
+            label.add_css_class("dim-label");
+            self.findings_grid.attach(&label, 0, 0, 7, 1);
+            return;
+        }
+        
+        for (column, title) in ["Fix", "Setting", "Scope", "Current", "Expected", "Source", "Status"].iter().enumerate() {
+            let header = Label::new(Some(title));
+            header.set_xalign(0.0);
+            header.add_css_class("heading");
+            self.findings_grid.attach(&header, column as i32, 0, 1, 1);
+        }
+        
+        for (index, finding) in report.findings.iter().enumerate() {
+            let row = index as i32 + 1;
+            if finding.fix.is_some() {
+                let check = CheckButton::new();
+                check.set_active(self.selected.borrow().get(index).copied().unwrap_or(false));
+                check.set_tooltip_text(finding.note.or(Some("Include this fix in the patch")));
+                let view = self.clone();
+                check.connect_toggled(move |check| {
+                    if let Some(selected) = view.selected.borrow_mut().get_mut(index) {
+                        *selected = check.is_active();
+                    }
+                    view.show_patch();
+                });
+                self.findings_grid.attach(&check, 0, row, 1, 1);
+            }
+            
+            let texts = [
+                finding.keyword.to_string(),
+                finding.scope_label(),
+                finding.current.clone(),
+                finding.expected.clone(),
+                finding.source_label(),
+            ];
+            for (column, text) in texts.iter().enumerate() {
+                let label = Label::new(Some(text));
+                label.set_xalign(0.0);
+                label.set_selectable(true);
+                label.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+                label.set_max_width_chars(40);
+                label.set_tooltip_text(Some(text));
+                self.findings_grid.attach(&label, column as i32 + 1, row, 1, 1);
+            }
+            let status = Label::new(Some(finding.severity.badge()));
+            status.set_xalign(0.0);
+            status.add_css_class(finding.severity.css_class());
+            if let Some(note) = finding.note {
+                status.set_tooltip_text(Some(note));
+            }
+            self.findings_grid.attach(&status, 6, row, 1, 1);
+        }
+    }
+    
+    fn selected_edits(&self) -> Vec<sshd_config::SshdEdit> {
+        let selected = self.selected.borrow();
+        self.report
+            .borrow()
+            .findings
+            .iter()
+            .zip(selected.iter())
+            .filter(|(_, selected)| **selected)
+            .filter_map(|(finding, _)| finding.fix.clone())
+            .collect()
+    }
+    
+    fn show_patch(&self) {
+        let edits = self.selected_edits();
+        self.apply_btn.set_sensitive(!edits.is_empty());
+        if edits.is_empty() {
+            self.patch_label.set_text("No fixes selected");
+        } else {
+            self.patch_label.set_text(&ssh_audit::render_diff(&edits));
+        }
+    }
+    
+    fn apply(&self, btn: &Button) {
+        let edits = self.selected_edits();
+        // Catch overlapping or stale edits here rather than in the helper
+        let checked = self
+            .config
+            .borrow()
+            .as_ref()
+            .ok_or_else(|| "Check or load the configuration first".to_string())
+            .and_then(|config| ssh_audit::patch(config, &edits))
+            .and_then(|_| edits.iter().try_for_each(|edit| edit.validate()));
+        if let Err(e) = checked {
+            self.summary_label.set_text(&format!("❌ {}", e));
+            return;
+        }
+        let action = HardnAction::privileged("Apply sshd_config Fixes", protocol::HelperRequest::SshdApplyPatch { edits }).destructive();
+        let view = self.clone();
+        let btn_clone = btn.clone();
+        run_helper(&self.output_area, &self.job_manager, &action, btn, move |exit_code, _| {
+            view.check();
+            if view.config.borrow().is_none() {
+                let load_action = HardnAction::privileged("Read sshd_config", protocol::HelperRequest::ReadSshdConfig);
+                view.load(&load_action, &btn_clone);
+            }
+            if exit_code != Some(0) {
+                view.summary_label.set_text("❌ The fixes were not applied; see the output");
+            }
+        });
+    }
+}
+
+fn create_apparmor_tab(notebook: &Notebook, job_manager: &JobManager) {
+    let main_box = Box::new(gtk4::Orientation::Horizontal, 10);
+    main_box.set_margin_start(20);
+    main_box.set_margin_end(20);
+    main_box.set_margin_top(20);
+    main_box.set_margin_bottom(20);
+    
+    let profiles_frame = Frame::new(Some("AppArmor Profiles"));
+    profiles_frame.set_hexpand(true);
+    let profiles_box = Box::new(gtk4::Orientation::Vertical, 10);
+    profiles_box.set_margin_start(15);
+    profiles_box.set_margin_end(15);
+    profiles_box.set_margin_top(15);
+    profiles_box.set_margin_bottom(15);
+    
+    let toolbar = Box::new(gtk4::Orientation::Horizontal, 5);
+    let refresh_btn = Button::with_label("🔄 Refresh");
+    refresh_btn.set_tooltip_text(Some("Read the loaded profiles and the audit log as the current user"));
+    let load_btn = Button::with_label("🔑 Load as Administrator");
+    let load_action = HardnAction::privileged("Read AppArmor Status", protocol::HelperRequest::AppArmorStatus);
+    set_action_tooltip(&load_btn, &load_action);
+    let search_entry = SearchEntry::new();
+    search_entry.set_placeholder_text(Some("Filter profiles"));
+    toolbar.append(&refresh_btn);
+    toolbar.append(&load_btn);
+    toolbar.append(&search_entry);
+    profiles_box.append(&toolbar);
+    
+    let summary_label = Label::new(None);
+    summary_label.set_xalign(0.0);
+    summary_label.set_wrap(true);
+    profiles_box.append(&summary_label);
+    
+    let profiles_list = Box::new(gtk4::Orientation::Vertical, 6);
+    profiles_box.append(
+        &ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vexpand(true)
+            .child(&profiles_list)
+            .build(),
+    );
+    
+    profiles_frame.set_child(Some(&profiles_box));
+    main_box.append(&profiles_frame);
+    
+    let right_box = Box::new(gtk4::Orientation::Vertical, 10);
+    right_box.set_width_request(420);
+    
+    let unconfined_frame = Frame::new(Some("Unconfined Network Processes"));
+    let unconfined_box = Box::new(gtk4::Orientation::Vertical, 10);
+    unconfined_box.set_margin_start(15);
+    unconfined_box.set_margin_end(15);
+    unconfined_box.set_margin_top(15);
+    unconfined_box.set_margin_bottom(15);
+    let unconfined_label = Label::new(None);
+    unconfined_label.set_xalign(0.0);
+    unconfined_label.set_wrap(true);
+    unconfined_label.add_css_class("dim-label");
+    unconfined_box.append(&unconfined_label);
+    let unconfined_grid = Grid::new();
+    unconfined_grid.set_row_spacing(6);
+    unconfined_grid.set_column_spacing(15);
+    unconfined_box.append(
+        &ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .min_content_height(160)
+            .vexpand(true)
+            .child(&unconfined_grid)
+            .build(),
+    );
+    unconfined_frame.set_child(Some(&unconfined_box));
+    right_box.append(&unconfined_frame);
+    
+    let output_area = create_output_area();
+    output_area.container.set_vexpand(false);
+    output_area.container.set_height_request(200);
+    right_box.append(&output_area.container);
+    main_box.append(&right_box);
+    
+    let view = AppArmorView {
+        summary_label,
+        search_entry,
+        profiles_list,
+        unconfined_label,
+        unconfined_grid,
+        status: Rc::new(RefCell::new(None)),
+        privileged: Rc::new(Cell::new(false)),
+        output_area,
+        job_manager: job_manager.clone(),
+    };
+    view.refresh();
+    
+    let view_clone = view.clone();
+    refresh_btn.connect_clicked(move |_| view_clone.refresh());
+    
+    let view_clone = view.clone();
+    load_btn.connect_clicked(move |btn| view_clone.load(&load_action, btn));
+    
+    let view_clone = view.clone();
+    view.search_entry.connect_search_changed(move |_| view_clone.show_profiles());
+    
+    notebook.append_page(&main_box, Some(&Label::new(Some("🛡️ AppArmor"))));
+}
+
+// State of the AppArmor tab
+#[derive(Clone)]
+struct AppArmorView {
+    summary_label: Label,
+    search_entry: SearchEntry,
+    profiles_list: Box,
+    unconfined_label: Label,
+    unconfined_grid: Grid,
+    // None until the profile list could be read
+    status: Rc<RefCell<Option<apparmor::Status>>>,
+    // Whether the status came through the helper, so a mode change rereads it that way
+    privileged: Rc<Cell<bool>>,
+    output_area: OutputPane,
+    job_manager: JobManager,
+}
+
+impl AppArmorView {
+    fn refresh(&self) {
+        let apparmor = apparmor::AppArmor::from_env();
+        match apparmor.status() {
+            Ok(status) => {
+                self.privileged.set(false);
+                self.status.replace(Some(status));
+            }
+            Err(e) => {
+                self.status.replace(None);
+                self.summary_label.set_text(&match e.kind() {
+                    std::io::ErrorKind::PermissionDenied => "The profile list is only readable by root; load it as administrator".to_string(),
+                    std::io::ErrorKind::NotFound => "AppArmor is not enabled in the running kernel".to_string(),
+                    _ => format!("❌ AppArmor profiles: {}", e),
+                });
+            }
+        }
+        self.show_profiles();
+        self.show_unconfined(&apparmor);
+    }
+    
+    fn load(&self, action: &HardnAction, btn: &Button) {
+        let view = self.clone();
+        run_helper(&self.output_area, &self.job_manager, action, btn, move |exit_code, lines| {
+            if exit_code != Some(0) {
+                view.summary_label.set_text("❌ Could not read the AppArmor status; see the output");
+                return;
+            }
+            view.privileged.set(true);
+            view.status.replace(Some(apparmor::Status::parse_transcript(&lines.join("\n"))));
+            view.show_profiles();
+        });
+    }
+    
+    fn show_profiles(&self) {
+        while let Some(child) = self.profiles_list.first_child() {
+            self.profiles_list.remove(&child);
+        }
+        let status = self.status.borrow();
+        let Some(status) = status.as_ref() else {
+            return;
+        };
+        
+        let mut summary = format!(
+            "{} profiles · {} enforce, {} complain, {} unconfined",
+            status.profiles.len(),
+            status.count(&apparmor::Mode::Enforce),
+            status.count(&apparmor::Mode::Complain),
+            status.count(&apparmor::Mode::Unconfined)
+        );
+        summary.push_str(&match &status.log {
+            Some(log) => format!(" · {} recent denials in {}", status.denials.len(), log),
+            None if self.privileged.get() => " · no audit or kernel log found".to_string(),
+            None => format!(" · denials not read ({}); load as administrator to include the audit log", status.errors.join("; ")),
+        });
+        self.summary_label.set_text(&summary);
+        
+        let filter = self.search_entry.text().to_lowercase();
+        let mut shown = 0;
+        for profile in status.profiles.iter().filter(|profile| profile.name.to_lowercase().contains(&filter)) {
+            let denials = status.denials_for(&profile.name);
+            self.profiles_list.append(&self.profile_row(profile, &denials));
+            shown += 1;
+        }
+        
+        // Records of profiles that were since unloaded or renamed
+        let orphaned: Vec<&apparmor::Denial> = status
+            .denials
+            .iter()
+            .filter(|denial| !status.profiles.iter().any(|profile| profile.name == denial.top_profile()))
+            .filter(|denial| denial.profile.to_lowercase().contains(&filter))
+            .collect();
+        if !orphaned.is_empty() {
+            let expander = Expander::new(Some(&format!("Denials from profiles that are not loaded ({})", orphaned.len())));
+            expander.set_child(Some(&denial_list(&orphaned, true)));
+            self.profiles_list.append(&expander);
+        }
+        
+        if shown == 0 {
+            let label = Label::new(Some(if status.profiles.is_empty() { "No profiles loaded" } else { "No profile matches the filter" }));
+            label.set_xalign(0.0);
+            label.add_css_class("dim-label");
+            self.profiles_list.append(&label);
+        }
+    }
+    
+    fn profile_row(&self, profile: &apparmor::Profile, denials: &[&apparmor::Denial]) -> Box {
+        let row = Box::new(gtk4::Orientation::Horizontal, 10);
+        
+        let title = if denials.is_empty() {
+            profile.name.clone()
+        } else {
+            format!("{} · ⚠️ {} denials", profile.name, denials.len())
+        };
+        let expander = Expander::new(Some(&title));
+        expander.set_hexpand(true);
+        if denials.is_empty() {
+            let label = Label::new(Some("No recent denials"));
+            label.set_xalign(0.0);
+            label.set_margin_start(20);
+            label.add_css_class("dim-label");
+            expander.set_child(Some(&label));
+        } else {
+            expander.set_child(Some(&denial_list(denials, false)));
+        }
+        row.append(&expander);
+        
+        let mode_label = Label::new(Some(profile.mode.keyword()));
+        mode_label.set_valign(gtk4::Align::Start);
+        mode_label.add_css_class(profile.mode.css_class());
+        row.append(&mode_label);
+        
+        if profile.is_toggleable() {
+            let (label, mode) = match profile.mode {
+                apparmor::Mode::Enforce => ("⚠️ Complain", apparmor::Mode::Complain),
+                _ => ("🔒 Enforce", apparmor::Mode::Enforce),
+            };
+            let btn = Button::with_label(label);
+            btn.set_valign(gtk4::Align::Start);
+            let change = apparmor::ModeChange { profile: profile.name.clone(), mode };
+            let mut action = HardnAction::privileged(
+                &format!("Set {} to {} mode", profile.name, change.mode.keyword()),
+                protocol::HelperRequest::AppArmorMode { change: change.clone() },
+            );
+            // Complain mode only logs what the profile would block
+            if change.mode == apparmor::Mode::Complain {
+                action = action.destructive();
+            }
+            set_action_tooltip(&btn, &action);
+            let view = self.clone();
+            btn.connect_clicked(move |btn| view.set_mode(&action, btn));
+            row.append(&btn);
+        }
+        row
+    }
+    
+    fn set_mode(&self, action: &HardnAction, btn: &Button) {
+        let view = self.clone();
+        let btn_clone = btn.clone();
+        let done = move |exit_code: Option<i32>| {
+            if view.privileged.get() {
+                let load_action = HardnAction::privileged("Read AppArmor Status", protocol::HelperRequest::AppArmorStatus);
+                view.load(&load_action, &btn_clone);
+            } else {
+                view.refresh();
+            }
+            if exit_code != Some(0) {
+                view.summary_label.set_text("❌ The profile mode was not changed; see the output");
+            }
+        };
+        // On idle: the clicked button is among the rows `done` replaces
+        run_helper(&self.output_area, &self.job_manager, action, btn, move |exit_code, _| {
+            glib::idle_add_local_once(move || done(exit_code));
+        });
+    }
+    
+    // Processes with listening sockets that run without a profile, like aa-unconfined
+    fn show_unconfined(&self, apparmor: &apparmor::AppArmor) {
+        while let Some(child) = self.unconfined_grid.first_child() {
+            self.unconfined_grid.remove(&child);
+        }
+        let proc_dir = apparmor.proc_dir();
+        let scan = network::scan_listeners(&proc_dir);
+        let (processes, unknown) = network::unconfined_processes(&proc_dir, &scan);
+        
+        let mut notes = vec![format!("{} of {} listening sockets belong to unconfined processes", processes.iter().map(|process| process.sockets.len()).sum::<usize>(), scan.listeners.len())];
+        if unknown > 0 {
+            notes.push(format!("{} sockets of other users' processes could not be checked", unknown));
+        }
+        notes.extend(scan.errors.iter().map(|error| format!("❌ {}", error)));
+        self.unconfined_label.set_text(&notes.join(" · "));
+        
+        for (column, title) in ["Process", "User", "Sockets"].iter().enumerate() {
+            let header = Label::new(Some(title));
+            header.set_xalign(0.0);
+            header.add_css_class("heading");
+            self.unconfined_grid.attach(&header, column as i32, 0, 1, 1);
+        }
+        for (row, process) in processes.iter().enumerate() {
+            let process_label = Label::new(Some(&format!("{} ({})", process.owner.name, process.owner.pid)));
+            process_label.set_xalign(0.0);
+            process_label.set_valign(gtk4::Align::Start);
+            process_label.set_tooltip_text(Some(&process.owner.cmdline));
+            let user_label = Label::new(Some(&process.user));
+            user_label.set_xalign(0.0);
+            user_label.set_valign(gtk4::Align::Start);
+            let sockets_label = Label::new(Some(&process.sockets.join("\n")));
+            sockets_label.set_xalign(0.0);
+            sockets_label.set_selectable(true);
+            sockets_label.add_css_class("monospace");
+            self.unconfined_grid.attach(&process_label, 0, row as i32 + 1, 1, 1);
+            self.unconfined_grid.attach(&user_label, 1, row as i32 + 1, 1, 1);
+            self.unconfined_grid.attach(&sockets_label, 2, row as i32 + 1, 1, 1);
+        }
+        if processes.is_empty() {
+            let label = Label::new(Some("No unconfined process with a listening socket"));
+            label.set_xalign(0.0);
+            label.add_css_class("dim-label");
+            self.unconfined_grid.attach(&label, 0, 1, 3, 1);
+        }
+    }
+}
+
+// Newest first, with the profile when records of several profiles are mixed
+fn denial_list(denials: &[&apparmor::Denial], with_profile: bool) -> Box {
+    let list = Box::new(gtk4::Orientation::Vertical, 3);
+    list.set_margin_start(20);
+    for denial in denials.iter().rev() {
+        let time = denial
+            .time
+            .and_then(|seconds| glib::DateTime::from_unix_local(seconds).ok())
+            .and_then(|dt| dt.format("%Y-%m-%d %H:%M:%S").ok())
+            .map(|time| time.to_string())
+            .unwrap_or_default();
+        let mut text = format!("{}  {}", time, denial.describe());
+        if with_profile {
+            text.push_str(&format!("  [{}]", denial.profile));
+        }
+        let label = Label::new(Some(text.trim()));
+        label.set_xalign(0.0);
+        label.set_wrap(true);
+        label.set_selectable(true);
+        label.add_css_class("monospace");
+        list.append(&label);
+    }
+    list
+}
+
+fn create_auditd_tab(notebook: &Notebook, job_manager: &JobManager) {
+    let main_box = Box::new(gtk4::Orientation::Horizontal, 10);
+    main_box.set_margin_start(20);
+    main_box.set_margin_end(20);
+    main_box.set_margin_top(20);
+    main_box.set_margin_bottom(20);
+    
+    let rules_frame = Frame::new(Some("Audit Rules"));
+    rules_frame.set_width_request(440);
+    let rules_box = Box::new(gtk4::Orientation::Vertical, 10);
+    rules_box.set_margin_start(15);
+    rules_box.set_margin_end(15);
+    rules_box.set_margin_top(15);
+    rules_box.set_margin_bottom(15);
+    
+    let rules_toolbar = Box::new(gtk4::Orientation::Horizontal, 5);
+    let refresh_btn = Button::with_label("🔄 Refresh");
+    refresh_btn.set_tooltip_text(Some("Read the rules and the audit log as the current user"));
+    let load_rules_btn = Button::with_label("🔑 Load as Administrator");
+    let load_rules_action = HardnAction::privileged("Read Audit Rules", protocol::HelperRequest::ReadAuditRules);
+    set_action_tooltip(&load_rules_btn, &load_rules_action);
+    rules_toolbar.append(&refresh_btn);
+    rules_toolbar.append(&load_rules_btn);
+    rules_box.append(&rules_toolbar);
+    
+    let rules_label = Label::new(None);
+    rules_label.set_xalign(0.0);
+    rules_label.set_wrap(true);
+    rules_box.append(&rules_label);
+    
+    let rules_list = Box::new(gtk4::Orientation::Vertical, 4);
+    rules_box.append(
+        &ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vexpand(true)
+            .child(&rules_list)
+            .build(),
+    );
+    rules_frame.set_child(Some(&rules_box));
+    main_box.append(&rules_frame);
+    
+    let right_box = Box::new(gtk4::Orientation::Vertical, 10);
+    right_box.set_hexpand(true);
+    
+    let events_frame = Frame::new(Some("Audit Events"));
+    events_frame.set_vexpand(true);
+    let events_box = Box::new(gtk4::Orientation::Vertical, 10);
+    events_box.set_margin_start(15);
+    events_box.set_margin_end(15);
+    events_box.set_margin_top(15);
+    events_box.set_margin_bottom(15);
+    
+    let events_toolbar = Box::new(gtk4::Orientation::Horizontal, 5);
+    let range_labels: Vec<&str> = auditd::TIME_RANGES.iter().map(|(label, _)| *label).collect();
+    let range_selector = DropDown::from_strings(&range_labels);
+    range_selector.set_selected(1);
+    let load_events_btn = Button::with_label("🔑 Load Events");
+    let load_events_action = HardnAction::privileged(
+        "Read Audit Events",
+        protocol::HelperRequest::ReadAuditLog { since: None, until: None },
+    );
+    set_action_tooltip(&load_events_btn, &load_events_action);
+    let key_selector = DropDown::from_strings(&[ALL_KEYS]);
+    let user_selector = DropDown::from_strings(&[ALL_USERS]);
+    events_toolbar.append(&Label::new(Some("Time:")));
+    events_toolbar.append(&range_selector);
+    events_toolbar.append(&load_events_btn);
+    events_toolbar.append(&Label::new(Some("Key:")));
+    events_toolbar.append(&key_selector);
+    events_toolbar.append(&Label::new(Some("User:")));
+    events_toolbar.append(&user_selector);
+    events_box.append(&events_toolbar);
+    
+    let events_label = Label::new(None);
+    events_label.set_xalign(0.0);
+    events_label.set_wrap(true);
+    events_box.append(&events_label);
+    
+    let events_list = Box::new(gtk4::Orientation::Vertical, 4);
+    events_box.append(
+        &ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vexpand(true)
+            .child(&events_list)
+            .build(),
+    );
+    events_frame.set_child(Some(&events_box));
+    right_box.append(&events_frame);
+    
+    let output_area = create_output_area();
+    output_area.container.set_vexpand(false);
+    output_area.container.set_height_request(180);
+    right_box.append(&output_area.container);
+    main_box.append(&right_box);
+    
+    let view = AuditdView {
+        rules_label,
+        rules_list,
+        range_selector,
+        key_selector,
+        user_selector,
+        events_label,
+        events_list,
+        rules: Rc::new(RefCell::new(None)),
+        events: Rc::new(RefCell::new(None)),
+        user_names: Rc::new(network::user_names(std::path::Path::new("/etc/passwd"))),
+        output_area,
+        job_manager: job_manager.clone(),
+    };
+    view.refresh();
+    
+    let view_clone = view.clone();
+    refresh_btn.connect_clicked(move |_| view_clone.refresh());
+    
+    let view_clone = view.clone();
+    load_rules_btn.connect_clicked(move |btn| view_clone.load_rules(&load_rules_action, btn));
+    
+    let view_clone = view.clone();
+    load_events_btn.connect_clicked(move |btn| view_clone.load_events(btn));
+    
+    for selector in [&view.key_selector, &view.user_selector] {
+        let view_clone = view.clone();
+        selector.connect_selected_notify(move |_| view_clone.show_events());
+    }
+    
+    notebook.append_page(&main_box, Some(&Label::new(Some("🕵️ auditd"))));
+}
+
+const ALL_KEYS: &str = "All keys";
+const NO_KEY: &str = "(no key)";
+const ALL_USERS: &str = "All users";
+// Rows built at once; the rest stay reachable through the filters
+const MAX_SHOWN_EVENTS: usize = 300;
+
+// State of the auditd tab
+#[derive(Clone)]
+struct AuditdView {
+    rules_label: Label,
+    rules_list: Box,
+    range_selector: DropDown,
+    key_selector: DropDown,
+    user_selector: DropDown,
+    events_label: Label,
+    events_list: Box,
+    // None until the rules or the log could be read
+    rules: Rc<RefCell<Option<auditd::RuleSet>>>,
+    events: Rc<RefCell<Option<Vec<auditd::Event>>>>,
+    user_names: Rc<std::collections::HashMap<u32, String>>,
+    output_area: OutputPane,
+    job_manager: JobManager,
+}
+
+impl AuditdView {
+    // Both directories are root-only on a stock install, so this mostly works as root
+    fn refresh(&self) {
+        let (rules_dir, log_dir) = auditd::local_dirs();
+        let not_read = |what: &str, e: std::io::Error| match e.kind() {
+            std::io::ErrorKind::PermissionDenied => format!("The {} is only readable by root; load it as administrator", what),
+            std::io::ErrorKind::NotFound => "auditd is not installed".to_string(),
+            _ => format!("❌ {}: {}", what, e),
+        };
+        match auditd::RuleSet::load(&rules_dir) {
+            Ok(rules) => {
+                self.rules.replace(Some(rules));
+            }
+            Err(e) => {
+                self.rules.replace(None);
+                self.rules_label.set_text(&not_read("audit rules directory", e));
+            }
+        }
+        self.show_rules();
+        
+        match auditd::read_events(&log_dir, self.since(), None) {
+            Ok(events) => {
+                self.events.replace(Some(events));
+            }
+            Err(e) => {
+                self.events.replace(None);
+                self.events_label.set_text(&not_read("audit log", e));
+            }
+        }
+        self.update_filters();
+        self.show_events();
+    }
+    
+    fn since(&self) -> Option<i64> {
+        let (_, range) = auditd::TIME_RANGES.get(self.range_selector.selected() as usize)?;
+        Some(glib::DateTime::now_utc().ok()?.to_unix() - (*range)?)
+    }
+    
+    fn load_rules(&self, action: &HardnAction, btn: &Button) {
+        let view = self.clone();
+        run_helper(&self.output_area, &self.job_manager, action, btn, move |exit_code, lines| {
+            if exit_code != Some(0) {
+                view.rules_label.set_text("❌ Could not read the audit rules; see the output");
+                return;
+            }
+            view.rules.replace(Some(auditd::RuleSet::parse_transcript(&lines.join("\n"))));
+            view.show_rules();
+            view.update_filters();
+        });
+    }
+    
+    fn load_events(&self, btn: &Button) {
+        let action = HardnAction::privileged(
+            "Read Audit Events",
+            protocol::HelperRequest::ReadAuditLog { since: self.since(), until: None },
+        );
+        let view = self.clone();
+        run_helper(&self.output_area, &self.job_manager, &action, btn, move |exit_code, lines| {
+            if exit_code != Some(0) {
+                view.events_label.set_text("❌ Could not read the audit log; see the output");
+                return;
+            }
+            view.events.replace(Some(auditd::parse_log(&lines.join("\n"))));
+            view.update_filters();
+            view.show_events();
+        });
+    }
+    
+    fn show_rules(&self) {
+        while let Some(child) = self.rules_list.first_child() {
+            self.rules_list.remove(&child);
+        }
+        let rules = self.rules.borrow();
+        let Some(rules) = rules.as_ref() else {
+            return;
+        };
+        
+        let watches = rules.rules.iter().filter(|rule| matches!(rule.kind, auditd::RuleKind::Watch { .. })).count();
+        let syscalls = rules.rules.iter().filter(|rule| matches!(rule.kind, auditd::RuleKind::Syscall { .. })).count();
+        let mut notes = vec![format!("{} watches and {} syscall rules in {} files", watches, syscalls, rules.files.len())];
+        if !rules.files.iter().any(|file| file.ends_with(auditd::HARDN_RULES)) {
+            notes.push(format!("⚠️ {} is not installed", auditd::HARDN_RULES));
+        }
+        match &rules.loaded {
+            Some(loaded) if loaded.is_empty() => notes.push("⚠️ no rules are loaded in the kernel; is auditd running?".to_string()),
+            Some(loaded) => notes.push(format!("{} rules loaded in the kernel", loaded.len())),
+            None => {}
+        }
+        let locked = rules
+            .rules
+            .iter()
+            .any(|rule| rule.kind == auditd::RuleKind::Control { option: "-e".to_string(), value: Some("2".to_string()) });
+        if locked {
+            notes.push("rules are locked: changes take effect after a reboot".to_string());
+        }
+        notes.extend(rules.errors.iter().map(|error| format!("❌ {}", error)));
+        self.rules_label.set_text(&notes.join(" · "));
+        
+        for file in &rules.files {
+            let heading = Label::new(Some(file));
+            heading.set_xalign(0.0);
+            heading.set_margin_top(6);
+            heading.add_css_class("heading");
+            self.rules_list.append(&heading);
+            for rule in rules.rules.iter().filter(|rule| &rule.file == file) {
+                self.rules_list.append(&self.rule_row(rule));
+            }
+        }
+        if rules.files.is_empty() {
+            let label = Label::new(Some("No audit rules files"));
+            label.set_xalign(0.0);
+            label.add_css_class("dim-label");
+            self.rules_list.append(&label);
+        }
+    }
+    
+    fn rule_row(&self, rule: &auditd::Rule) -> Box {
+        let row = Box::new(gtk4::Orientation::Horizontal, 6);
+        let label = Label::new(Some(&rule.describe()));
+        label.set_xalign(0.0);
+        label.set_wrap(true);
+        label.set_hexpand(true);
+        label.set_tooltip_text(Some(&format!("{}:{}  {}", rule.file, rule.line, rule.raw.trim())));
+        if matches!(rule.kind, auditd::RuleKind::Control { .. }) {
+            label.add_css_class("dim-label");
+        }
+        row.append(&label);
+        for key in &rule.keys {
+            let btn = Button::with_label(&format!("🔑 {}", key));
+            btn.set_valign(gtk4::Align::Start);
+            btn.set_tooltip_text(Some("Show the events of this key"));
+            let view = self.clone();
+            let key = key.clone();
+            btn.connect_clicked(move |_| view.select(&view.key_selector, &key));
+            row.append(&btn);
+        }
+        row
+    }
+    
+    // Offer the keys of the rules and the events and the users of the events,
+    // keeping the current choices where they still exist
+    fn update_filters(&self) {
+        let mut keys: Vec<String> = self.rules.borrow().as_ref().map(|rules| rules.keys()).unwrap_or_default();
+        let mut users = Vec::new();
+        if let Some(events) = self.events.borrow().as_ref() {
+            keys.extend(events.iter().flat_map(|event| event.keys()));
+            users.extend(events.iter().map(|event| event.user(&self.user_names)));
+        }
+        for (selector, all, mut values, extra) in
+            [(&self.key_selector, ALL_KEYS, keys, Some(NO_KEY)), (&self.user_selector, ALL_USERS, users, None)]
+        {
+            values.sort();
+            values.dedup();
+            let current = selected_string(selector);
+            let mut names = vec![all];
+            names.extend(values.iter().map(String::as_str));
+            names.extend(extra);
+            selector.set_model(Some(&StringList::new(&names)));
+            if let Some(current) = current {
+                self.select(selector, &current);
+            }
+        }
+    }
+    
+    fn select(&self, selector: &DropDown, value: &str) {
+        let Some(model) = selector.model() else {
+            return;
+        };
+        let position = (0..model.n_items()).find(|&index| {
+            model.item(index).and_downcast::<gtk4::StringObject>().is_some_and(|item| item.string() == value)
+        });
+        if let Some(position) = position {
+            selector.set_selected(position);
+        }
+    }
+    
+    fn show_events(&self) {
+        while let Some(child) = self.events_list.first_child() {
+            self.events_list.remove(&child);
+        }
+        let events = self.events.borrow();
+        let Some(events) = events.as_ref() else {
+            return;
+        };
+        
+        let key = selected_string(&self.key_selector).filter(|key| key != ALL_KEYS);
+        let user = selected_string(&self.user_selector).filter(|user| user != ALL_USERS);
+        let matching: Vec<&auditd::Event> = events
+            .iter()
+            .filter(|event| match key.as_deref() {
+                None => true,
+                Some(NO_KEY) => event.keys().is_empty(),
+                Some(key) => event.keys().iter().any(|event_key| event_key == key),
+            })
+            .filter(|event| match &user {
+                None => true,
+                Some(user) => *user == event.user(&self.user_names),
+            })
+            .collect();
+        
+        let failed = matching.iter().filter(|event| event.success() == Some(false)).count();
+        let mut summary = format!("{} of {} events · {} failed", matching.len(), events.len(), failed);
+        if events.len() >= auditd::MAX_EVENTS {
+            summary.push_str(&format!(" · only the newest {} events were read; narrow the time range", auditd::MAX_EVENTS));
+        }
+        if matching.len() > MAX_SHOWN_EVENTS {
+            summary.push_str(&format!(" · showing the newest {}", MAX_SHOWN_EVENTS));
+        }
+        self.events_label.set_text(&summary);
+        
+        for event in matching.iter().rev().take(MAX_SHOWN_EVENTS) {
+            self.events_list.append(&self.event_row(event));
+        }
+        if matching.is_empty() {
+            let label = Label::new(Some(if events.is_empty() { "No events in this time range" } else { "No event matches the filters" }));
+            label.set_xalign(0.0);
+            label.add_css_class("dim-label");
+            self.events_list.append(&label);
+        }
+    }
+    
+    fn event_row(&self, event: &auditd::Event) -> Expander {
+        let time = glib::DateTime::from_unix_local(event.time)
+            .ok()
+            .and_then(|dt| dt.format("%Y-%m-%d %H:%M:%S").ok())
+            .map(|time| time.to_string())
+            .unwrap_or_default();
+        let keys = event.keys();
+        let mut title = format!("{}  {}  {}", time, event.kind(), event.user(&self.user_names));
+        if !keys.is_empty() {
+            title.push_str(&format!("  [{}]", keys.join(", ")));
+        }
+        title.push_str(&format!("  {}", event.summary()));
+        if event.success() == Some(false) {
+            title = format!("❌ {}", title);
+        }
+        let expander = Expander::new(Some(&title));
+        
+        let records = Box::new(gtk4::Orientation::Vertical, 3);
+        records.set_margin_start(20);
+        for record in &event.records {
+            let fields: Vec<String> = record.fields.iter().map(|(name, value)| format!("{}={}", name, value)).collect();
+            let label = Label::new(Some(&format!("{}: {}", record.kind, fields.join(" "))));
+            label.set_xalign(0.0);
+            label.set_wrap(true);
+            label.set_wrap_mode(gtk4::pango::WrapMode::WordChar);
+            label.set_selectable(true);
+            label.add_css_class("monospace");
+            records.append(&label);
+        }
+        expander.set_child(Some(&records));
+        expander
+    }
+}
+
+fn selected_string(selector: &DropDown) -> Option<String> {
+    selector.selected_item().and_downcast::<gtk4::StringObject>().map(|item| item.string().to_string())
+}
+
+fn create_malware_tab(notebook: &Notebook, job_manager: &JobManager, window: &ApplicationWindow) {
+    let main_box = Box::new(gtk4::Orientation::Horizontal, 10);
+    main_box.set_margin_start(20);
+    main_box.set_margin_end(20);
+    main_box.set_margin_top(20);
+    main_box.set_margin_bottom(20);
+    
+    let scan_frame = Frame::new(Some("ClamAV Scan"));
+    let scan_box = Box::new(gtk4::Orientation::Vertical, 10);
+    scan_box.set_margin_start(15);
+    scan_box.set_margin_end(15);
+    scan_box.set_margin_top(15);
+    scan_box.set_margin_bottom(15);
+    scan_box.set_width_request(320);
+    
+    let targets_label = Label::new(Some("Scan targets"));
+    targets_label.set_xalign(0.0);
+    targets_label.add_css_class("heading");
+    scan_box.append(&targets_label);
+    
+    let targets_box = Box::new(gtk4::Orientation::Vertical, 2);
+    scan_box.append(&targets_box);
+    
+    let add_box = Box::new(gtk4::Orientation::Horizontal, 5);
+    let target_entry = gtk4::Entry::new();
+    target_entry.set_placeholder_text(Some("/path/to/scan"));
+    target_entry.set_hexpand(true);
+    let add_btn = Button::with_label("➕");
+    add_btn.set_tooltip_text(Some("Add this file or directory"));
+    let browse_btn = Button::with_label("📁");
+    browse_btn.set_tooltip_text(Some("Choose a directory to scan"));
+    add_box.append(&target_entry);
+    add_box.append(&add_btn);
+    add_box.append(&browse_btn);
+    scan_box.append(&add_box);
+    
+    let engine_labels: Vec<&str> = clamav::ScanEngine::ALL.iter().map(|engine| engine.label()).collect();
+    let engine_selector = DropDown::from_strings(&engine_labels);
+    engine_selector.set_tooltip_text(Some(
+        "clamscan only sees files you can read; as administrator it sees everything. \
+         clamd scans through the running daemon without reloading the signatures.",
+    ));
+    scan_box.append(&engine_selector);
+    let clamd_label = Label::new(None);
+    clamd_label.set_xalign(0.0);
+    clamd_label.set_wrap(true);
+    clamd_label.add_css_class("dim-label");
+    scan_box.append(&clamd_label);
+    
+    let buttons = Box::new(gtk4::Orientation::Horizontal, 5);
+    let scan_btn = Button::with_label("🦠 Start Scan");
+    scan_btn.add_css_class("suggested-action");
+    scan_btn.set_hexpand(true);
+    let stop_btn = Button::with_label("⏹️ Stop");
+    stop_btn.set_sensitive(false);
+    buttons.append(&scan_btn);
+    buttons.append(&stop_btn);
+    scan_box.append(&buttons);
+    
+    let current_label = Label::new(None);
+    current_label.set_xalign(0.0);
+    current_label.set_ellipsize(gtk4::pango::EllipsizeMode::Middle);
+    current_label.add_css_class("monospace");
+    scan_box.append(&current_label);
+    let tally_label = Label::new(None);
+    tally_label.set_xalign(0.0);
+    tally_label.set_wrap(true);
+    scan_box.append(&tally_label);
+    
+    scan_box.append(&Separator::new(gtk4::Orientation::Horizontal));
+    
+    let schedule_title = Label::new(Some("Scheduled scan of these targets"));
+    schedule_title.set_xalign(0.0);
+    schedule_title.add_css_class("heading");
+    scan_box.append(&schedule_title);
+    let schedule_box = Box::new(gtk4::Orientation::Horizontal, 5);
+    let frequency_labels: Vec<&str> = malware::ScanFrequency::ALL.iter().map(|frequency| frequency.label()).collect();
+    let schedule_selector = DropDown::from_strings(&frequency_labels);
+    schedule_selector.set_hexpand(true);
+    let schedule_btn = Button::with_label("📅 Apply");
+    schedule_btn.set_tooltip_text(Some(&format!("Writes {} (runs as administrator)", malware::SCHEDULE_FILE)));
+    schedule_box.append(&schedule_selector);
+    schedule_box.append(&schedule_btn);
+    scan_box.append(&schedule_box);
+    let schedule_label = Label::new(None);
+    schedule_label.set_xalign(0.0);
+    schedule_label.set_wrap(true);
+    schedule_label.add_css_class("dim-label");
+    scan_box.append(&schedule_label);
+    
+    scan_frame.set_child(Some(&scan_box));
+    main_box.append(&scan_frame);
+    
+    // Detections and the quarantine above the job output
+    let right_box = Box::new(gtk4::Orientation::Vertical, 10);
+    right_box.set_hexpand(true);
+    let stack = Stack::new();
+    stack.set_vexpand(true);
+    let switcher = StackSwitcher::new();
+    switcher.set_stack(Some(&stack));
+    right_box.append(&switcher);
+    right_box.append(&stack);
+    
+    let detections_page = Box::new(gtk4::Orientation::Vertical, 10);
+    let detections_toolbar = Box::new(gtk4::Orientation::Horizontal, 5);
+    let load_log_btn = Button::with_label("📄 Last Scan Log");
+    load_log_btn.set_tooltip_text(Some("Show the detections of the newest scan log (security audit or scheduled scan)"));
+    let detections_label = Label::new(None);
+    detections_label.set_xalign(0.0);
+    detections_label.set_hexpand(true);
+    detections_label.set_wrap(true);
+    detections_toolbar.append(&load_log_btn);
+    detections_toolbar.append(&detections_label);
+    detections_page.append(&detections_toolbar);
+    let detections_grid = Grid::new();
+    detections_grid.set_row_spacing(8);
+    detections_grid.set_column_spacing(15);
+    detections_page.append(
+        &ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vexpand(true)
+            .child(&detections_grid)
+            .build(),
+    );
+    stack.add_titled(&detections_page, Some("detections"), "Detections");
+    
+    let quarantine_page = Box::new(gtk4::Orientation::Vertical, 10);
+    let quarantine_toolbar = Box::new(gtk4::Orientation::Horizontal, 5);
+    let reload_quarantine_btn = Button::with_label("🔄 Reload");
+    let quarantine_label = Label::new(None);
+    quarantine_label.set_xalign(0.0);
+    quarantine_label.set_hexpand(true);
+    quarantine_label.set_wrap(true);
+    quarantine_toolbar.append(&reload_quarantine_btn);
+    quarantine_toolbar.append(&quarantine_label);
+    quarantine_page.append(&quarantine_toolbar);
+    let quarantine_grid = Grid::new();
+    quarantine_grid.set_row_spacing(8);
+    quarantine_grid.set_column_spacing(15);
+    quarantine_page.append(
+        &ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vexpand(true)
+            .child(&quarantine_grid)
+            .build(),
+    );
+    stack.add_titled(&quarantine_page, Some("quarantine"), "Quarantine");
+    
+    let output_area = create_output_area();
+    output_area.container.set_vexpand(false);
+    output_area.container.set_height_request(220);
+    right_box.append(&output_area.container);
+    main_box.append(&right_box);
+    
+    // Start from the scheduled targets when there are any, else what the audit scans
+    let schedule = malware::ScanSchedule::load(std::path::Path::new(malware::SCHEDULE_FILE));
+    let targets = if schedule.paths.is_empty() {
+        clamav::DEFAULT_SCAN_TARGETS.iter().map(|path| path.to_string()).collect()
+    } else {
+        schedule.paths.clone()
+    };
+    
+    let view = MalwareView {
+        targets_box,
+        targets: Rc::new(RefCell::new(targets)),
+        engine_selector,
+        clamd_label,
+        scan_btn: scan_btn.clone(),
+        stop_btn: stop_btn.clone(),
+        current_label,
+        tally_label,
+        schedule_selector,
+        schedule_label,
+        detections_label,
+        detections_grid,
+        detections: Rc::new(RefCell::new(Vec::new())),
+        quarantine_label,
+        quarantine_grid,
+        clamd_cancel: Rc::new(RefCell::new(None)),
+        output_area,
+        job_manager: job_manager.clone(),
+        window: window.downgrade(),
+    };
+    view.show_targets();
+    
+    let view_clone = view.clone();
+    let target_entry_clone = target_entry.clone();
+    add_btn.connect_clicked(move |_| {
+        if view_clone.add_target(target_entry_clone.text().trim()) {
+            target_entry_clone.set_text("");
+        }
+    });
+    let add_btn_clone = add_btn.clone();
+    target_entry.connect_activate(move |_| add_btn_clone.emit_clicked());
+    
+    let view_clone = view.clone();
+    browse_btn.connect_clicked(move |btn| {
+        let window = btn.root().and_downcast::<gtk4::Window>();
+        let dialog = FileChooserNative::new(
+            Some("Add Scan Target"),
+            window.as_ref(),
+            gtk4::FileChooserAction::SelectFolder,
+            Some("Add"),
+            Some("Cancel"),
+        );
+        let view = view_clone.clone();
+        // Native dialogs are not kept alive by GTK; drop our reference once answered
+        let keep_alive = RefCell::new(Some(dialog.clone()));
+        dialog.connect_response(move |dialog, response| {
+            if response == ResponseType::Accept {
+                if let Some(path) = dialog.file().and_then(|file| file.path()) {
+                    view.add_target(&path.to_string_lossy());
+                }
+            }
+            keep_alive.take();
+        });
+        dialog.show();
     });
     
+    let view_clone = view.clone();
+    view.engine_selector.connect_selected_notify(move |_| view_clone.probe_clamd());
+    
+    let view_clone = view.clone();
+    scan_btn.connect_clicked(move |btn| view_clone.start_scan(btn));
+    
+    let view_clone = view.clone();
+    stop_btn.connect_clicked(move |_| view_clone.stop_scan());
+    
+    let view_clone = view.clone();
+    load_log_btn.connect_clicked(move |_| view_clone.load_scan_log());
+    
+    let view_clone = view.clone();
+    reload_quarantine_btn.connect_clicked(move |_| view_clone.refresh_quarantine());
+    
+    let view_clone = view.clone();
+    schedule_btn.connect_clicked(move |btn| view_clone.apply_schedule(btn));
+    
+    // Pick up changes made outside the tab (audits, scheduled scans, other users)
+    let view_clone = view.clone();
+    main_box.connect_map(move |_| {
+        view_clone.probe_clamd();
+        view_clone.refresh_schedule();
+        view_clone.refresh_quarantine();
+        if view_clone.detections.borrow().is_empty() && view_clone.scan_btn.is_sensitive() {
+            view_clone.load_scan_log();
+        }
+    });
+    
+    notebook.append_page(&main_box, Some(&Label::new(Some("🦠 Malware"))));
+}
+
+// State of the Malware tab
+#[derive(Clone)]
+struct MalwareView {
+    targets_box: Box,
+    targets: Rc<RefCell<Vec<String>>>,
+    engine_selector: DropDown,
+    clamd_label: Label,
+    scan_btn: Button,
+    stop_btn: Button,
+    current_label: Label,
+    tally_label: Label,
+    schedule_selector: DropDown,
+    schedule_label: Label,
+    detections_label: Label,
+    detections_grid: Grid,
+    detections: Rc<RefCell<Vec<clamav::Detection>>>,
+    quarantine_label: Label,
+    quarantine_grid: Grid,
+    // Set while a clamd scan runs; storing true stops it
+    clamd_cancel: Rc<RefCell<Option<Arc<AtomicBool>>>>,
+    output_area: OutputPane,
+    job_manager: JobManager,
+    window: glib::WeakRef<ApplicationWindow>,
+}
+
+// Label of clamscan jobs, which Stop looks for in the job list
+const CLAMSCAN_JOB_LABEL: &str = "ClamAV Scan";
+
+impl MalwareView {
+    fn engine(&self) -> clamav::ScanEngine {
+        clamav::ScanEngine::ALL
+            .get(self.engine_selector.selected() as usize)
+            .copied()
+            .unwrap_or(clamav::ScanEngine::Clamscan)
+    }
+    
+    fn add_target(&self, path: &str) -> bool {
+        let path = path.trim_end_matches('/');
+        let path = if path.is_empty() { "/" } else { path };
+        if !malware::valid_scan_path(path) {
+            self.tally_label.set_text(&format!("❌ Not a usable scan target: {} (absolute path, no \"..\" or \"%\")", path));
+            return false;
+        }
+        {
+            let mut targets = self.targets.borrow_mut();
+            if targets.iter().any(|target| target == path) {
+                return true;
+            }
+            if targets.len() >= malware::MAX_SCAN_PATHS {
+                self.tally_label.set_text(&format!("❌ At most {} scan targets", malware::MAX_SCAN_PATHS));
+                return false;
+            }
+            targets.push(path.to_string());
+        }
+        self.show_targets();
+        true
+    }
+    
+    fn show_targets(&self) {
+        while let Some(child) = self.targets_box.first_child() {
+            self.targets_box.remove(&child);
+        }
+        for target in self.targets.borrow().iter() {
+            let row = Box::new(gtk4::Orientation::Horizontal, 5);
+            let label = Label::new(Some(target));
+            label.set_xalign(0.0);
+            label.set_hexpand(true);
+            label.set_ellipsize(gtk4::pango::EllipsizeMode::Middle);
+            label.add_css_class("monospace");
+            let remove_btn = Button::with_label("✖");
+            remove_btn.add_css_class("flat");
+            remove_btn.set_tooltip_text(Some("Remove this target"));
+            let view = self.clone();
+            let target = target.clone();
+            remove_btn.connect_clicked(move |_| {
+                view.targets.borrow_mut().retain(|existing| existing != &target);
+                // Rebuilt on idle: this button is among the widgets being replaced
+                let view = view.clone();
+                glib::idle_add_local_once(move || view.show_targets());
+            });
+            row.append(&label);
+            row.append(&remove_btn);
+            self.targets_box.append(&row);
+        }
+        if self.targets.borrow().is_empty() {
+            let label = Label::new(Some("No targets; add a file or directory below"));
+            label.set_xalign(0.0);
+            label.add_css_class("dim-label");
+            self.targets_box.append(&label);
+        }
+    }
+    
+    // clamd status, probed off the main loop since the socket may hang
+    fn probe_clamd(&self) {
+        if self.engine() != clamav::ScanEngine::Clamd {
+            self.clamd_label.set_text("");
+            return;
+        }
+        self.clamd_label.set_text("Contacting clamd...");
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let client = clamav::ClamdClient::from_env();
+            let status = client
+                .ping()
+                .and_then(|_| client.version())
+                .map(|version| format!("✅ {} on {}", version, client.socket().display()))
+                .unwrap_or_else(|e| format!("❌ clamd not reachable: {}", e));
+            let _ = tx.send(status);
+        });
+        let clamd_label = self.clamd_label.clone();
+        glib::timeout_add_local(Duration::from_millis(100), move || match rx.try_recv() {
+            Ok(status) => {
+                clamd_label.set_text(&status);
+                glib::ControlFlow::Break
+            }
+            Err(mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+            Err(mpsc::TryRecvError::Disconnected) => glib::ControlFlow::Break,
+        });
+    }
+    
+    fn start_scan(&self, btn: &Button) {
+        let paths = self.targets.borrow().clone();
+        if paths.is_empty() {
+            self.tally_label.set_text("Add at least one scan target");
+            return;
+        }
+        let Some(window) = self.window.upgrade() else {
+            return;
+        };
+        
+        self.detections.borrow_mut().clear();
+        self.show_detections();
+        self.detections_label.set_text("Live scan");
+        self.current_label.set_text("Starting scan...");
+        self.tally_label.set_text("");
+        
+        let (observer, events) = mpsc::channel();
+        match self.engine() {
+            engine @ (clamav::ScanEngine::Clamscan | clamav::ScanEngine::ClamscanAsRoot) => {
+                let action = HardnAction::privileged(CLAMSCAN_JOB_LABEL, protocol::HelperRequest::ClamScan { paths }).expected_secs(1800);
+                let action = if engine == clamav::ScanEngine::Clamscan { action.unprivileged() } else { action };
+                dispatch_action(&action, &self.output_area, btn, &self.job_manager, &window, Some(observer));
+            }
+            clamav::ScanEngine::Clamd => {
+                let cancel = Arc::new(AtomicBool::new(false));
+                self.clamd_cancel.replace(Some(cancel.clone()));
+                self.scan_btn.set_sensitive(false);
+                self.output_area.clear(&format!("Scanning {} through clamd...\n", paths.join(" ")));
+                thread::spawn(move || {
+                    let client = clamav::ClamdClient::from_env();
+                    if let Err(e) = client.ping() {
+                        let _ = observer.send(JobEvent::Line(format!("[ERROR] clamd not reachable: {}", e)));
+                        let _ = observer.send(JobEvent::Exited(Some(2)));
+                        return;
+                    }
+                    let mut found = false;
+                    let finished = clamav::scan_with_clamd(&client, &paths, &cancel, |line| {
+                        found |= matches!(line, clamav::ScanLine::Found(_));
+                        let _ = observer.send(JobEvent::Line(line.to_line()));
+                    });
+                    // Same exit codes as clamscan
+                    let code = if !finished { None } else if found { Some(1) } else { Some(0) };
+                    let _ = observer.send(JobEvent::Exited(code));
+                });
+            }
+        }
+        self.stop_btn.set_sensitive(true);
+        
+        let view = self.clone();
+        let clamd = self.engine() == clamav::ScanEngine::Clamd;
+        let scan_line = move |tally: &mut clamav::ScanTally, line: String| match clamav::parse_line(&line) {
+            Some(result) => {
+                tally.record(&result);
+                view.current_label.set_text(result.path());
+                view.tally_label.set_text(&tally.summary());
+                if clamd {
+                    view.output_area.append_raw(output::Stream::Stdout, &line);
+                }
+                if let clamav::ScanLine::Found(detection) = result {
+                    view.detections.borrow_mut().push(detection.clone());
+                    view.add_detection_row(&detection, view.detections.borrow().len() as i32 - 1);
+                }
+            }
+            None if clamd => view.output_area.append_raw(output::Stream::Stderr, &line),
+            None => {}
+        };
+        let view = self.clone();
+        drain_observer(events, clamav::ScanTally::default(), scan_line, move |tally, exit_code| {
+            // clamscan: 0 clean, 1 something found, anything else an error
+            let outcome = match exit_code {
+                Some(0) | Some(1) => "Scan finished",
+                Some(_) => "⚠️ Scan ended with errors",
+                None => "Scan stopped",
+            };
+            view.current_label.set_text(outcome);
+            view.tally_label.set_text(&tally.summary());
+            if clamd {
+                view.output_area.append_line(&output::Line::plain(output::Level::Info, &format!("{}: {}", outcome, tally.summary())), None);
+                view.output_area.scroll_to_end();
+                view.scan_btn.set_sensitive(true);
+            }
+            view.clamd_cancel.replace(None);
+            view.stop_btn.set_sensitive(false);
+            if tally.detections.is_empty() {
+                view.show_detections();
+            }
+        });
+    }
+    
+    fn stop_scan(&self) {
+        if let Some(cancel) = self.clamd_cancel.borrow().as_ref() {
+            cancel.store(true, Ordering::Relaxed);
+            return;
+        }
+        for job in self.job_manager.jobs() {
+            if job.label == CLAMSCAN_JOB_LABEL && job.state.is_running() {
+                self.job_manager.cancel(job.id);
+            }
+        }
+    }
+    
+    fn load_scan_log(&self) {
+        let dir = config::log_dir();
+        let (text, detections) = match clamav::load_latest_log(&dir) {
+            Ok(Some((path, detections))) => (format!("From {}", path.display()), detections),
+            Ok(None) => (format!("No ClamAV scan logs in {} yet; start a scan or run a security audit", dir.display()), Vec::new()),
+            Err(e) => (format!("❌ Cannot read the ClamAV scan logs: {}", e), Vec::new()),
+        };
+        self.detections_label.set_text(&text);
+        self.detections.replace(detections);
+        self.show_detections();
+    }
+    
+    fn show_detections(&self) {
+        while let Some(child) = self.detections_grid.first_child() {
+            self.detections_grid.remove(&child);
+        }
+        let detections = self.detections.borrow().clone();
+        for (row, detection) in detections.iter().enumerate() {
+            self.add_detection_row(detection, row as i32);
+        }
+        if detections.is_empty() {
+            let label = Label::new(Some("✅ No detections"));
+            label.set_xalign(0.0);
+            self.detections_grid.attach(&label, 0, 0, 3, 1);
+        }
+    }
+    
+    fn add_detection_row(&self, detection: &clamav::Detection, row: i32) {
+        // The "No detections" placeholder goes once the first one comes in
+        if row == 0 {
+            while let Some(child) = self.detections_grid.first_child() {
+                self.detections_grid.remove(&child);
+            }
+        }
+        
+        let signature_label = Label::new(Some(&format!("🦠 {}", detection.signature)));
+        signature_label.set_xalign(0.0);
+        signature_label.add_css_class("error");
+        signature_label.set_selectable(true);
+        
+        let path_label = Label::new(Some(&detection.path));
+        path_label.set_xalign(0.0);
+        path_label.set_hexpand(true);
+        path_label.set_wrap(true);
+        path_label.set_selectable(true);
+        path_label.add_css_class("monospace");
+        
+        let quarantine_btn = Button::with_label("🔒 Quarantine");
+        quarantine_btn.set_valign(gtk4::Align::Center);
+        let action = HardnAction::privileged(
+            &format!("Quarantine {}", detection.path),
+            protocol::HelperRequest::Quarantine { path: detection.path.clone(), signature: detection.signature.clone() },
+        );
+        set_action_tooltip(&quarantine_btn, &action);
+        let view = self.clone();
+        let quarantined = detection.clone();
+        quarantine_btn.connect_clicked(move |btn| {
+            let view_clone = view.clone();
+            let quarantined = quarantined.clone();
+            view.run_helper(&action, btn, move || {
+                view_clone.detections.borrow_mut().retain(|detection| detection != &quarantined);
+                view_clone.show_detections();
+            });
+        });
+        
+        self.detections_grid.attach(&signature_label, 0, row, 1, 1);
+        self.detections_grid.attach(&path_label, 1, row, 1, 1);
+        self.detections_grid.attach(&quarantine_btn, 2, row, 1, 1);
+    }
+    
+    fn refresh_quarantine(&self) {
+        while let Some(child) = self.quarantine_grid.first_child() {
+            self.quarantine_grid.remove(&child);
+        }
+        
+        let store = malware::QuarantineStore::system();
+        let (items, errors) = store.list();
+        for error in &errors {
+            eprintln!("Skipping quarantine entry {}", error);
+        }
+        let mut summary = format!("{} files in {}", items.len(), store.dir().display());
+        if !errors.is_empty() {
+            summary.push_str(&format!(" · {} unreadable", errors.len()));
+        }
+        self.quarantine_label.set_text(&summary);
+        
+        if items.is_empty() {
+            let label = Label::new(Some("Nothing in quarantine"));
+            label.set_xalign(0.0);
+            label.add_css_class("dim-label");
+            self.quarantine_grid.attach(&label, 0, 0, 4, 1);
+        }
+        
+        for (row, item) in items.iter().enumerate() {
+            let row = row as i32;
+            
+            let text_box = Box::new(gtk4::Orientation::Vertical, 2);
+            text_box.set_hexpand(true);
+            let path_label = Label::new(Some(&item.original_path));
+            path_label.set_xalign(0.0);
+            path_label.set_wrap(true);
+            path_label.set_selectable(true);
+            path_label.add_css_class("monospace");
+            let details = Label::new(Some(&format!(
+                "🦠 {} · {} bytes · quarantined {} by uid {}",
+                item.signature,
+                item.size,
+                report::format_timestamp(item.quarantined_at),
+                item.quarantined_by
+            )));
+            details.set_xalign(0.0);
+            details.set_wrap(true);
+            details.add_css_class("dim-label");
+            text_box.append(&path_label);
+            text_box.append(&details);
+            self.quarantine_grid.attach(&text_box, 0, row, 1, 1);
+            
+            let restore = HardnAction::privileged(
+                &format!("Restore {}", item.original_path),
+                protocol::HelperRequest::RestoreQuarantined { id: item.id.clone() },
+            );
+            let delete = HardnAction::privileged(
+                &format!("Delete quarantined {}", item.original_path),
+                protocol::HelperRequest::DeleteQuarantined { id: item.id.clone() },
+            )
+            .destructive();
+            for (column, (label, action)) in [("↩️ Restore", restore), ("🗑️ Delete", delete)].into_iter().enumerate() {
+                let btn = Button::with_label(label);
+                btn.set_valign(gtk4::Align::Center);
+                set_action_tooltip(&btn, &action);
+                let view = self.clone();
+                btn.connect_clicked(move |btn| {
+                    let view_clone = view.clone();
+                    view.run_helper(&action, btn, move || view_clone.refresh_quarantine());
+                });
+                self.quarantine_grid.attach(&btn, column as i32 + 1, row, 1, 1);
+            }
+        }
+    }
+    
+    fn refresh_schedule(&self) {
+        let schedule = malware::ScanSchedule::load(std::path::Path::new(malware::SCHEDULE_FILE));
+        let position = malware::ScanFrequency::ALL
+            .iter()
+            .position(|frequency| *frequency == schedule.frequency)
+            .unwrap_or(0);
+        self.schedule_selector.set_selected(position as u32);
+        self.schedule_label.set_text(&format!("Current: {}", schedule.describe()));
+    }
+    
+    fn apply_schedule(&self, btn: &Button) {
+        let frequency = malware::ScanFrequency::ALL
+            .get(self.schedule_selector.selected() as usize)
+            .copied()
+            .unwrap_or(malware::ScanFrequency::Off);
+        let paths = if frequency == malware::ScanFrequency::Off { Vec::new() } else { self.targets.borrow().clone() };
+        let schedule = malware::ScanSchedule { frequency, paths };
+        if let Err(e) = schedule.validate() {
+            self.schedule_label.set_text(&format!("❌ {}", e));
+            return;
+        }
+        let action = HardnAction::privileged("Schedule ClamAV Scan", protocol::HelperRequest::ScheduleScan { schedule });
+        let view = self.clone();
+        self.run_helper(&action, btn, move || view.refresh_schedule());
+    }
+    
+    // Run a helper operation and call `done` once it succeeded
+    fn run_helper(&self, action: &HardnAction, btn: &Button, done: impl FnOnce() + 'static) {
+        run_helper(&self.output_area, &self.job_manager, action, btn, move |exit_code, _| {
+            if exit_code == Some(0) {
+                // On idle: the clicked button may be among the widgets `done` replaces
+                glib::idle_add_local_once(done);
+            }
+        });
+    }
+}
+
+fn create_backup_tab(notebook: &Notebook, job_manager: &JobManager, window: &ApplicationWindow) {
+    let main_box = Box::new(gtk4::Orientation::Vertical, 10);
+    main_box.set_margin_start(20);
+    main_box.set_margin_end(20);
+    main_box.set_margin_top(20);
+    main_box.set_margin_bottom(20);
+    
+    let control_frame = Frame::new(Some("Backup & Restore"));
+    let control_grid = Grid::new();
+    control_grid.set_row_spacing(10);
+    control_grid.set_column_spacing(10);
+    control_grid.set_margin_start(15);
+    control_grid.set_margin_end(15);
+    control_grid.set_margin_top(15);
+    control_grid.set_margin_bottom(15);
+    
+    let backup_btn = Button::with_label("💾 Create Backup");
+    backup_btn.add_css_class("suggested-action");
+    backup_btn.set_height_request(50);
+    
+    let restore_btn = Button::with_label("♻️ Restore from Backup");
+    restore_btn.set_height_request(50);
+    
+    control_grid.attach(&backup_btn, 0, 0, 1, 1);
+    control_grid.attach(&restore_btn, 1, 0, 1, 1);
+    
+    control_frame.set_child(Some(&control_grid));
+    main_box.append(&control_frame);
+    
+    let output_frame = Frame::new(Some("Backup Operations"));
+    let output_area = create_output_area();
+    output_frame.set_child(Some(&output_area.container));
+    main_box.append(&output_frame);
+    
+    connect_action(
+        &backup_btn,
+        HardnAction::hardn("Create Backup", "backup").root().expected_secs(120),
+        &output_area,
+        job_manager,
+        window,
+    );
+    connect_action(
+        &restore_btn,
+        HardnAction::hardn("Restore from Backup", "restore").root().expected_secs(120).destructive(),
+        &output_area,
+        job_manager,
+        window,
+    );
+    
     notebook.append_page(&main_box, Some(&Label::new(Some("💾 Backup"))));
 }
 
-fn create_tools_tab(notebook: &Notebook, process_tracker: &ProcessTracker, window: &ApplicationWindow) {
+fn create_tools_tab(notebook: &Notebook, job_manager: &JobManager, window: &ApplicationWindow) {
+    let main_box = Box::new(gtk4::Orientation::Horizontal, 10);
+    main_box.set_margin_start(20);
+    main_box.set_margin_end(20);
+    main_box.set_margin_top(20);
+    main_box.set_margin_bottom(20);
+    
+    let tools_frame = Frame::new(Some("Additional Tools"));
+    let tools_box = Box::new(gtk4::Orientation::Vertical, 5);
+    tools_box.set_margin_start(15);
+    tools_box.set_margin_end(15);
+    tools_box.set_margin_top(15);
+    tools_box.set_margin_bottom(15);
+    tools_box.set_width_request(250);
+    
+    let output_area = create_output_area();
+    
+    let tools = vec![
+        HardnAction::hardn("🔄 System Update", "update").root().expected_secs(300),
+        HardnAction::hardn("🌐 API Server", "api").arg("--port").arg("8080"),
+        // The GUI asks for confirmation itself, so skip hardn's interactive prompt
+        HardnAction::hardn("❌ Uninstall HARDN", "uninstall").option("--force").root().expected_secs(300).destructive(),
+    ];
+    
+    for action in tools {
+        let btn = Button::with_label(&action.label);
+        btn.set_height_request(40);
+        connect_action(&btn, action, &output_area, job_manager, window);
+        tools_box.append(&btn);
+    }
+    
+    tools_frame.set_child(Some(&tools_box));
+    main_box.append(&tools_frame);
+    main_box.append(&output_area.container);
+    
+    notebook.append_page(&main_box, Some(&Label::new(Some("🔧 Tools"))));
+}
+
+fn create_history_tab(notebook: &Notebook, job_manager: &JobManager, window: &ApplicationWindow) {
+    let main_box = Box::new(gtk4::Orientation::Horizontal, 10);
+    main_box.set_margin_start(20);
+    main_box.set_margin_end(20);
+    main_box.set_margin_top(20);
+    main_box.set_margin_bottom(20);
+    
+    let history_frame = Frame::new(Some("Job History"));
+    let history_box = Box::new(gtk4::Orientation::Vertical, 10);
+    history_box.set_margin_start(15);
+    history_box.set_margin_end(15);
+    history_box.set_margin_top(15);
+    history_box.set_margin_bottom(15);
+    history_box.set_width_request(560);
+    
+    let toolbar = Box::new(gtk4::Orientation::Horizontal, 5);
+    let refresh_btn = Button::with_label("🔄 Refresh");
+    let compare_btn = Button::with_label("↔️ Compare Selected");
+    compare_btn.set_sensitive(false);
+    compare_btn.set_tooltip_text(Some("Select two runs of the same action to compare their output"));
+    let summary_label = Label::new(None);
+    summary_label.set_xalign(0.0);
+    summary_label.set_hexpand(true);
+    summary_label.add_css_class("dim-label");
+    toolbar.append(&refresh_btn);
+    toolbar.append(&compare_btn);
+    toolbar.append(&summary_label);
+    history_box.append(&toolbar);
+    
+    let history_grid = Grid::new();
+    history_grid.set_row_spacing(8);
+    history_grid.set_column_spacing(12);
+    let history_scroll = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .vscrollbar_policy(gtk4::PolicyType::Automatic)
+        .vexpand(true)
+        .child(&history_grid)
+        .build();
+    history_box.append(&history_scroll);
+    
+    history_frame.set_child(Some(&history_box));
+    main_box.append(&history_frame);
+    
+    let output_area = create_output_area();
+    main_box.append(&output_area.container);
+    
+    let view = HistoryView {
+        grid: history_grid,
+        summary_label,
+        compare_btn: compare_btn.clone(),
+        output_area: output_area.clone(),
+        job_manager: job_manager.clone(),
+        window: window.downgrade(),
+        entries: Rc::new(RefCell::new(Vec::new())),
+        selected: Rc::new(RefCell::new(Vec::new())),
+    };
+    
+    let view_clone = view.clone();
+    refresh_btn.connect_clicked(move |_| view_clone.refresh());
+    
+    let view_clone = view.clone();
+    compare_btn.connect_clicked(move |_| view_clone.compare_selected());
+    
+    // Reload whenever the tab is shown, so runs from other tabs appear
+    let view_clone = view.clone();
+    main_box.connect_map(move |_| view_clone.refresh());
+    
+    notebook.append_page(&main_box, Some(&Label::new(Some("📜 History"))));
+}
+
+// State of the History tab
+#[derive(Clone)]
+struct HistoryView {
+    grid: Grid,
+    summary_label: Label,
+    compare_btn: Button,
+    output_area: OutputPane,
+    job_manager: JobManager,
+    window: glib::WeakRef<ApplicationWindow>,
+    entries: Rc<RefCell<Vec<history::HistoryEntry>>>,
+    // Ids of the runs checked for comparison, in the order they were checked
+    selected: Rc<RefCell<Vec<String>>>,
+}
+
+impl HistoryView {
+    fn refresh(&self) {
+        let store = history::HistoryStore::from_env();
+        let (entries, errors) = store.load_all();
+        for error in &errors {
+            eprintln!("Skipping history entry {}", error);
+        }
+        self.selected.borrow_mut().retain(|id| entries.iter().any(|entry| &entry.id == id));
+        
+        let mut summary = format!("{} runs in {}", entries.len(), store.dir().display());
+        if !errors.is_empty() {
+            summary.push_str(&format!(" · {} unreadable", errors.len()));
+        }
+        self.summary_label.set_text(&summary);
+        
+        while let Some(child) = self.grid.first_child() {
+            self.grid.remove(&child);
+        }
+        
+        if entries.is_empty() {
+            let empty_label = Label::new(Some("No jobs have been run from the GUI yet"));
+            empty_label.add_css_class("dim-label");
+            self.grid.attach(&empty_label, 0, 0, 1, 1);
+        }
+        
+        for (row, entry) in entries.iter().enumerate() {
+            let row = row as i32;
+            
+            let select = CheckButton::new();
+            select.set_active(self.selected.borrow().contains(&entry.id));
+            select.set_valign(gtk4::Align::Center);
+            let view = self.clone();
+            let id = entry.id.clone();
+            select.connect_toggled(move |select| {
+                {
+                    let mut selected = view.selected.borrow_mut();
+                    selected.retain(|selected_id| selected_id != &id);
+                    if select.is_active() {
+                        selected.push(id.clone());
+                    }
+                }
+                view.update_compare_button();
+            });
+            self.grid.attach(&select, 0, row, 1, 1);
+            
+            let text_box = Box::new(gtk4::Orientation::Vertical, 2);
+            text_box.set_hexpand(true);
+            let title = Label::new(Some(&entry.action.label));
+            title.set_xalign(0.0);
+            title.add_css_class("heading");
+            let details = Label::new(Some(&format!(
+                "{} · {}s · {} · {}",
+                report::format_timestamp(entry.started_at),
+                entry.duration_secs(),
+                entry.user,
+                entry.state.label()
+            )));
+            details.set_xalign(0.0);
+            details.add_css_class("dim-label");
+            text_box.append(&title);
+            text_box.append(&details);
+            text_box.set_tooltip_text(Some(&entry.command));
+            self.grid.attach(&text_box, 1, row, 1, 1);
+            
+            let show_btn = Button::with_label("📄 Show");
+            show_btn.set_valign(gtk4::Align::Center);
+            let view = self.clone();
+            let shown = entry.clone();
+            show_btn.connect_clicked(move |_| view.show_entry(&shown));
+            self.grid.attach(&show_btn, 2, row, 1, 1);
+            
+            let rerun_btn = Button::with_label("🔁 Re-run");
+            rerun_btn.set_valign(gtk4::Align::Center);
+            rerun_btn.set_tooltip_text(Some(&entry.command));
+            let view = self.clone();
+            let action = entry.action.clone();
+            rerun_btn.connect_clicked(move |btn| {
+                if let Some(window) = view.window.upgrade() {
+                    dispatch_action(&action, &view.output_area, btn, &view.job_manager, &window, None);
+                }
+            });
+            self.grid.attach(&rerun_btn, 3, row, 1, 1);
+        }
+        
+        self.entries.replace(entries);
+        self.update_compare_button();
+    }
+    
+    fn update_compare_button(&self) {
+        self.compare_btn.set_sensitive(self.selected.borrow().len() == 2);
+    }
+    
+    // Replay the captured output of a run
+    fn show_entry(&self, entry: &history::HistoryEntry) {
+        self.output_area.clear(&format!(
+            "{} · {}\n{}\n{}\n",
+            entry.action.label,
+            report::format_timestamp(entry.started_at),
+            entry.command,
+            "=".repeat(60)
+        ));
+        for record in &entry.output {
+            self.output_area.append_line(&output::Line::plain(record.level, &record.text), record.stream);
+        }
+    }
+    
+    // Diff the output of the two checked runs, older one first
+    fn compare_selected(&self) {
+        let entries = self.entries.borrow();
+        let mut pair: Vec<&history::HistoryEntry> = self
+            .selected
+            .borrow()
+            .iter()
+            .filter_map(|id| entries.iter().find(|entry| &entry.id == id))
+            .collect();
+        if pair.len() != 2 {
+            return;
+        }
+        pair.sort_by_key(|entry| entry.started_at);
+        let (old, new) = (pair[0], pair[1]);
+        
+        if old.command != new.command {
+            self.output_area.clear("");
+            self.output_area.append_line(
+                &output::Line::plain(
+                    output::Level::Warn,
+                    &format!("⚠️ Only runs of the same action can be compared:\n{}\n{}", old.command, new.command),
+                ),
+                None,
+            );
+            return;
+        }
+        
+        self.output_area.clear(&format!(
+            "Comparing {} runs of {}\n- {} ({})\n+ {} ({})\n{}\n",
+            old.action.label,
+            old.command,
+            report::format_timestamp(old.started_at),
+            old.state.label(),
+            report::format_timestamp(new.started_at),
+            new.state.label(),
+            "=".repeat(60)
+        ));
+        let (old_lines, new_lines) = (old.lines(), new.lines());
+        let diff = history::diff_lines(&old_lines, &new_lines);
+        let changes = diff.iter().filter(|(op, _)| *op != history::DiffOp::Same).count();
+        for (op, line) in diff {
+            let level = match op {
+                history::DiffOp::Same => output::Level::Plain,
+                history::DiffOp::Removed => output::Level::Error,
+                history::DiffOp::Added => output::Level::Pass,
+            };
+            self.output_area.append_line(&output::Line::plain(level, &format!("{}{}", op.marker(), line)), None);
+        }
+        self.output_area.append_line(
+            &output::Line::plain(output::Level::Info, &format!("\n{} changed lines", changes)),
+            None,
+        );
+    }
+}
+
+fn create_settings_tab(notebook: &Notebook, job_manager: &JobManager) {
     let main_box = Box::new(gtk4::Orientation::Horizontal, 10);
     main_box.set_margin_start(20);
     main_box.set_margin_end(20);
     main_box.set_margin_top(20);
     main_box.set_margin_bottom(20);
     
-    let tools_frame = Frame::new(Some("Additional Tools"));
-    let tools_box = Box::new(gtk4::Orientation::Vertical, 5);
-    tools_box.set_margin_start(15);
-    tools_box.set_margin_end(15);
-    tools_box.set_margin_top(15);
-    tools_box.set_margin_bottom(15);
-    tools_box.set_width_request(250);
+    let settings_frame = Frame::new(Some("HARDN Settings"));
+    settings_frame.set_hexpand(true);
+    let settings_box = Box::new(gtk4::Orientation::Vertical, 10);
+    settings_box.set_margin_start(15);
+    settings_box.set_margin_end(15);
+    settings_box.set_margin_top(15);
+    settings_box.set_margin_bottom(15);
+    
+    let toolbar = Box::new(gtk4::Orientation::Horizontal, 5);
+    let reload_btn = Button::with_label("🔄 Reload");
+    reload_btn.set_tooltip_text(Some(&format!("Read {} as the current user", config::system_path().display())));
+    let load_btn = Button::with_label("🔑 Load as Administrator");
+    let load_action = HardnAction::privileged("Read hardn.conf", protocol::HelperRequest::ReadHardnConfig);
+    set_action_tooltip(&load_btn, &load_action);
+    let summary_label = Label::new(None);
+    summary_label.set_xalign(0.0);
+    summary_label.set_hexpand(true);
+    summary_label.set_wrap(true);
+    toolbar.append(&reload_btn);
+    toolbar.append(&load_btn);
+    toolbar.append(&summary_label);
+    settings_box.append(&toolbar);
+    
+    let settings_grid = Grid::new();
+    settings_grid.set_row_spacing(6);
+    settings_grid.set_column_spacing(15);
+    settings_box.append(
+        &ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vexpand(true)
+            .child(&settings_grid)
+            .build(),
+    );
+    
+    settings_frame.set_child(Some(&settings_box));
+    main_box.append(&settings_frame);
+    
+    let right_box = Box::new(gtk4::Orientation::Vertical, 10);
+    right_box.set_width_request(420);
+    
+    let diff_frame = Frame::new(Some("Change Preview"));
+    let diff_box = Box::new(gtk4::Orientation::Vertical, 10);
+    diff_box.set_margin_start(15);
+    diff_box.set_margin_end(15);
+    diff_box.set_margin_top(15);
+    diff_box.set_margin_bottom(15);
+    let diff_label = Label::new(None);
+    diff_label.set_xalign(0.0);
+    diff_label.set_yalign(0.0);
+    diff_label.set_selectable(true);
+    diff_label.add_css_class("monospace");
+    diff_box.append(
+        &ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .min_content_height(160)
+            .vexpand(true)
+            .child(&diff_label)
+            .build(),
+    );
+    let buttons = Box::new(gtk4::Orientation::Horizontal, 5);
+    let revert_btn = Button::with_label("↩️ Revert");
+    revert_btn.set_tooltip_text(Some("Drop the changes that were not saved"));
+    let apply_btn = Button::with_label("💾 Save as Administrator");
+    apply_btn.add_css_class("suggested-action");
+    apply_btn.set_hexpand(true);
+    apply_btn.set_tooltip_text(Some(&format!(
+        "Replace only these values in {} as administrator; comments and the order of the file are kept",
+        config::system_path().display()
+    )));
+    buttons.append(&revert_btn);
+    buttons.append(&apply_btn);
+    diff_box.append(&buttons);
+    diff_frame.set_child(Some(&diff_box));
+    right_box.append(&diff_frame);
+    
+    let output_area = create_output_area();
+    output_area.container.set_vexpand(false);
+    output_area.container.set_height_request(200);
+    right_box.append(&output_area.container);
+    main_box.append(&right_box);
+    
+    let view = SettingsView {
+        summary_label,
+        settings_grid,
+        diff_label,
+        apply_btn: apply_btn.clone(),
+        revert_btn: revert_btn.clone(),
+        file: Rc::new(RefCell::new(None)),
+        edits: Rc::new(RefCell::new(std::collections::HashMap::new())),
+        output_area,
+        job_manager: job_manager.clone(),
+    };
+    view.reload();
+    
+    let view_clone = view.clone();
+    reload_btn.connect_clicked(move |_| view_clone.reload());
+    
+    let view_clone = view.clone();
+    load_btn.connect_clicked(move |btn| view_clone.load(&load_action, btn));
+    
+    let view_clone = view.clone();
+    revert_btn.connect_clicked(move |_| view_clone.revert());
+    
+    let view_clone = view.clone();
+    apply_btn.connect_clicked(move |btn| view_clone.apply(btn));
+    
+    notebook.append_page(&main_box, Some(&Label::new(Some("⚙️ Settings"))));
+}
+
+// State of the Settings tab
+#[derive(Clone)]
+struct SettingsView {
+    summary_label: Label,
+    settings_grid: Grid,
+    diff_label: Label,
+    apply_btn: Button,
+    revert_btn: Button,
+    // The file as read and where it was read from; None until it was read
+    file: Rc<RefCell<Option<(std::path::PathBuf, config::ConfigFile)>>>,
+    // Values that differ from the file, valid or not, by key
+    edits: Rc<RefCell<std::collections::HashMap<String, String>>>,
+    output_area: OutputPane,
+    job_manager: JobManager,
+}
+
+impl SettingsView {
+    // debian/postinst installs hardn.conf as root:hardn 0640; until hardn setup ran
+    // there is only the template. Same file the helper saves to, so the old values a save
+    // carries are the ones it compares against
+    fn reload(&self) {
+        let path = config::system_path();
+        let read = match std::fs::read_to_string(&path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => std::fs::read_to_string(config::TEMPLATE_FILE)
+                .map(|content| (std::path::PathBuf::from(config::TEMPLATE_FILE), content))
+                .map_err(|_| e),
+            result => result.map(|content| (path.clone(), content)),
+        };
+        match read {
+            Ok((source, content)) => self.show_file(source, config::ConfigFile::parse(&content)),
+            Err(e) => {
+                self.file.replace(None);
+                self.edits.borrow_mut().clear();
+                self.summary_label.set_text(&match e.kind() {
+                    std::io::ErrorKind::PermissionDenied => format!("{} is only readable by root and the hardn group; load it as administrator", path.display()),
+                    std::io::ErrorKind::NotFound => format!("❌ Neither {} nor {} exists; is HARDN installed?", path.display(), config::TEMPLATE_FILE),
+                    _ => format!("❌ {}: {}", path.display(), e),
+                });
+                self.show_settings();
+                self.show_preview();
+            }
+        }
+    }
+    
+    fn load(&self, action: &HardnAction, btn: &Button) {
+        let view = self.clone();
+        run_helper(&self.output_area, &self.job_manager, action, btn, move |exit_code, lines| {
+            match config::ConfigFile::parse_transcript(&lines.join("\n")) {
+                Some((source, file)) if exit_code == Some(0) => view.show_file(source, file),
+                _ => view.summary_label.set_text("❌ Could not read hardn.conf; see the output"),
+            }
+        });
+    }
+    
+    fn show_file(&self, source: std::path::PathBuf, file: config::ConfigFile) {
+        if source == std::path::Path::new(config::TEMPLATE_FILE) {
+            self.summary_label.set_text(&format!("{} does not exist yet; saving creates it from the template", config::system_path().display()));
+        } else {
+            self.summary_label.set_text(&format!("{} settings in {}", file.settings().len(), source.display()));
+        }
+        self.file.replace(Some((source, file)));
+        self.edits.borrow_mut().clear();
+        self.show_settings();
+        self.show_preview();
+    }
+    
+    fn show_settings(&self) {
+        while let Some(child) = self.settings_grid.first_child() {
+            self.settings_grid.remove(&child);
+        }
+        let settings = match self.file.borrow().as_ref() {
+            Some((_, file)) => file.settings(),
+            None => {
+                let label = Label::new(Some("Nothing loaded yet"));
+                label.set_xalign(0.0);
+                label.add_css_class("dim-label");
+                self.settings_grid.attach(&label, 0, 0, 4, 1);
+                return;
+            }
+        };
+        
+        let mut row = 0;
+        let mut section = "";
+        for spec in config::SCHEMA {
+            if spec.section != section {
+                section = spec.section;
+                self.attach_heading(section, &mut row);
+            }
+            let current = settings.iter().find(|setting| setting.key == spec.key).map(|setting| setting.value.as_str());
+            let status = Label::new(None);
+            status.set_xalign(0.0);
+            match current.map(|value| spec.kind.check(value)) {
+                None => {
+                    status.set_text("not set");
+                    status.add_css_class("dim-label");
+                }
+                Some(Err(e)) if spec.kind != config::Kind::ReadOnly => {
+                    status.set_text(&format!("⚠️ {}", e));
+                    status.add_css_class("error");
+                }
+                Some(_) => {}
+            }
+            let editor = self.editor(spec, current, &status);
+            self.attach_row(spec.key, &editor, &status, spec.description, row);
+            row += 1;
+        }
+        
+        // Kept as they are; the hardn scripts may still read them
+        let unknown: Vec<&config::Setting> = settings.iter().filter(|setting| config::find_spec(&setting.key).is_none()).collect();
+        if !unknown.is_empty() {
+            self.attach_heading("Other", &mut row);
+            for setting in unknown {
+                let value = Label::new(Some(&setting.value));
+                value.set_xalign(0.0);
+                value.set_selectable(true);
+                self.attach_row(&setting.key, &value, &Label::new(None), &format!("Line {}, not edited here", setting.line), row);
+                row += 1;
+            }
+        }
+    }
+    
+    fn attach_heading(&self, title: &str, row: &mut i32) {
+        let heading = Label::new(Some(title));
+        heading.set_xalign(0.0);
+        heading.add_css_class("heading");
+        if *row > 0 {
+            heading.set_margin_top(10);
+        }
+        self.settings_grid.attach(&heading, 0, *row, 4, 1);
+        *row += 1;
+    }
+    
+    fn attach_row(&self, key: &str, editor: &impl IsA<gtk4::Widget>, status: &Label, description: &str, row: i32) {
+        let key_label = Label::new(Some(key));
+        key_label.set_xalign(0.0);
+        key_label.set_selectable(true);
+        key_label.add_css_class("monospace");
+        key_label.set_tooltip_text(Some(description));
+        editor.set_halign(gtk4::Align::Start);
+        let description_label = Label::new(Some(description));
+        description_label.set_xalign(0.0);
+        description_label.set_wrap(true);
+        description_label.add_css_class("dim-label");
+        self.settings_grid.attach(&key_label, 0, row, 1, 1);
+        self.settings_grid.attach(editor, 1, row, 1, 1);
+        self.settings_grid.attach(status, 2, row, 1, 1);
+        self.settings_grid.attach(&description_label, 3, row, 1, 1);
+    }
+    
+    // A widget for the kind of value; every change is checked against the schema
+    fn editor(&self, spec: &'static config::Spec, current: Option<&str>, status: &Label) -> gtk4::Widget {
+        let value = current.unwrap_or("");
+        let view = self.clone();
+        let status = status.clone();
+        match spec.kind {
+            config::Kind::Bool => {
+                let check = CheckButton::new();
+                check.set_active(value == "true");
+                check.connect_toggled(move |check| view.edit(spec, if check.is_active() { "true" } else { "false" }, &status));
+                check.upcast()
+            }
+            config::Kind::Choice(options) => {
+                // A missing or unknown value stays selectable, so that it shows as it is
+                let mut items = options.to_vec();
+                if !options.contains(&value) {
+                    items.insert(0, value);
+                }
+                let selector = DropDown::from_strings(&items);
+                selector.set_selected(items.iter().position(|item| *item == value).unwrap_or(0) as u32);
+                selector.connect_selected_notify(move |selector| {
+                    if let Some(selected) = selected_string(selector) {
+                        view.edit(spec, &selected, &status);
+                    }
+                });
+                selector.upcast()
+            }
+            config::Kind::ReadOnly => {
+                let label = Label::new(Some(value));
+                label.set_selectable(true);
+                label.upcast()
+            }
+            config::Kind::Integer { .. } | config::Kind::Size | config::Kind::IpAddress => {
+                let entry = gtk4::Entry::new();
+                entry.set_width_chars(18);
+                entry.set_text(value);
+                entry.connect_changed(move |entry| view.edit(spec, entry.text().trim(), &status));
+                entry.upcast()
+            }
+        }
+    }
+    
+    fn edit(&self, spec: &config::Spec, value: &str, status: &Label) {
+        let current = self.file.borrow().as_ref().and_then(|(_, file)| file.get(spec.key)).map(|setting| setting.value);
+        status.remove_css_class("error");
+        status.remove_css_class("dim-label");
+        if current.as_deref().unwrap_or("") == value {
+            self.edits.borrow_mut().remove(spec.key);
+            status.set_text(if current.is_none() { "not set" } else { "" });
+        } else {
+            self.edits.borrow_mut().insert(spec.key.to_string(), value.to_string());
+            match spec.kind.check(value) {
+                Ok(()) => status.set_text("✏️ changed"),
+                Err(e) => {
+                    status.set_text(&format!("❌ {}", e));
+                    status.add_css_class("error");
+                }
+            }
+        }
+        self.show_preview();
+    }
+    
+    // In SCHEMA order; the first invalid value is the error
+    fn changes(&self) -> Result<Vec<config::SettingChange>, String> {
+        let file = self.file.borrow();
+        let Some((_, file)) = file.as_ref() else {
+            return Err("Load hardn.conf first".to_string());
+        };
+        let edits = self.edits.borrow();
+        let mut changes = Vec::new();
+        for spec in config::SCHEMA {
+            let Some(value) = edits.get(spec.key) else {
+                continue;
+            };
+            config::validate_setting(spec.key, value)?;
+            changes.push(config::SettingChange {
+                key: spec.key.to_string(),
+                old: file.get(spec.key).map(|setting| setting.value),
+                value: value.clone(),
+            });
+        }
+        Ok(changes)
+    }
+    
+    fn show_preview(&self) {
+        self.revert_btn.set_sensitive(!self.edits.borrow().is_empty());
+        let preview = self.changes().and_then(|changes| {
+            if changes.is_empty() {
+                return Ok(None);
+            }
+            let file = self.file.borrow();
+            let Some((_, file)) = file.as_ref() else {
+                return Ok(None);
+            };
+            let mut changed = file.clone();
+            changed.apply(&changes)?;
+            Ok(Some(history::unified_diff(&config::system_path(), &file.content(), &changed.content())))
+        });
+        self.apply_btn.set_sensitive(matches!(preview, Ok(Some(_))));
+        match preview {
+            Ok(Some(diff)) => self.diff_label.set_text(&diff),
+            Ok(None) => self.diff_label.set_text("No changes"),
+            Err(e) => self.diff_label.set_text(&format!("❌ {}\nCorrect the value to see the change", e)),
+        }
+    }
+    
+    fn revert(&self) {
+        self.edits.borrow_mut().clear();
+        self.show_settings();
+        self.show_preview();
+    }
+    
+    fn apply(&self, btn: &Button) {
+        let changes = match self.changes() {
+            Ok(changes) if !changes.is_empty() => changes,
+            Ok(_) => return,
+            Err(e) => {
+                self.summary_label.set_text(&format!("❌ {}", e));
+                return;
+            }
+        };
+        let action = HardnAction::privileged("Save hardn.conf", protocol::HelperRequest::HardnConfigUpdate { changes });
+        let view = self.clone();
+        let btn_clone = btn.clone();
+        run_helper(&self.output_area, &self.job_manager, &action, btn, move |exit_code, _| {
+            // Keep the edits when saving failed, so that they can be corrected
+            if exit_code != Some(0) {
+                view.summary_label.set_text("❌ The settings were not saved; see the output");
+                return;
+            }
+            view.reload();
+            if view.file.borrow().is_none() {
+                let load_action = HardnAction::privileged("Read hardn.conf", protocol::HelperRequest::ReadHardnConfig);
+                view.load(&load_action, &btn_clone);
+            }
+        });
+    }
+}
+
+// Output pane for a tab: a toolbar (search, level filters, save) above a TextView.
+// Every line is tagged with its level, so hiding a level only flips that tag's
+// "invisible" property. Rendered lines are kept with timestamps for saving.
+#[derive(Clone)]
+struct OutputPane {
+    container: Box,
+    text_view: TextView,
+    renderer: Rc<RefCell<output::Renderer>>,
+    records: Rc<RefCell<Vec<output::Record>>>,
+    query: Rc<RefCell<String>>,
+    match_count: Rc<Cell<usize>>,
+    match_label: Label,
+}
+
+const SEARCH_TAG: &str = "search-match";
+
+impl OutputPane {
+    fn new() -> Self {
+        let container = Box::new(gtk4::Orientation::Vertical, 5);
+        container.set_hexpand(true);
+        container.set_vexpand(true);
+        
+        let text_view = TextView::builder()
+            .editable(false)
+            .monospace(true)
+            .wrap_mode(gtk4::WrapMode::Word)
+            .build();
+        
+        text_view.set_margin_start(10);
+        text_view.set_margin_end(10);
+        text_view.set_margin_top(10);
+        text_view.set_margin_bottom(10);
+        
+        let buffer = text_view.buffer();
+        buffer.set_text("Ready to execute commands...\n\n");
+        
+        let match_label = Label::new(None);
+        match_label.add_css_class("dim-label");
+        
+        let pane = OutputPane {
+            container: container.clone(),
+            text_view: text_view.clone(),
+            renderer: Rc::new(RefCell::new(output::Renderer::new())),
+            records: Rc::new(RefCell::new(Vec::new())),
+            query: Rc::new(RefCell::new(String::new())),
+            match_count: Rc::new(Cell::new(0)),
+            match_label: match_label.clone(),
+        };
+        
+        // Search row
+        let search_box = Box::new(gtk4::Orientation::Horizontal, 5);
+        search_box.set_margin_start(10);
+        search_box.set_margin_end(10);
+        
+        let search_entry = SearchEntry::new();
+        search_entry.set_placeholder_text(Some("Find in output..."));
+        search_entry.set_hexpand(true);
+        let prev_btn = Button::with_label("▲");
+        prev_btn.set_tooltip_text(Some("Previous match (Shift+Ctrl+G)"));
+        let next_btn = Button::with_label("▼");
+        next_btn.set_tooltip_text(Some("Next match (Ctrl+G)"));
+        
+        let save_box = Box::new(gtk4::Orientation::Vertical, 5);
+        let save_popover = Popover::new();
+        save_popover.set_child(Some(&save_box));
+        let save_btn = MenuButton::new();
+        save_btn.set_label("💾 Save as...");
+        save_btn.set_popover(Some(&save_popover));
+        for (label, format) in [("Text (.log)", output::SaveFormat::Text), ("JSON lines (.jsonl)", output::SaveFormat::JsonLines)] {
+            let btn = Button::with_label(label);
+            btn.add_css_class("flat");
+            let pane = pane.clone();
+            let save_popover = save_popover.clone();
+            btn.connect_clicked(move |_| {
+                save_popover.popdown();
+                pane.save_as(format);
+            });
+            save_box.append(&btn);
+        }
+        
+        search_box.append(&search_entry);
+        search_box.append(&prev_btn);
+        search_box.append(&next_btn);
+        search_box.append(&match_label);
+        search_box.append(&Separator::new(gtk4::Orientation::Vertical));
+        search_box.append(&save_btn);
+        
+        let search_tag = gtk4::TextTag::new(Some(SEARCH_TAG));
+        search_tag.set_background(Some("#f6d32d"));
+        search_tag.set_foreground(Some("#000000"));
+        buffer.tag_table().add(&search_tag);
+        
+        let pane_clone = pane.clone();
+        search_entry.connect_search_changed(move |entry| {
+            pane_clone.query.replace(entry.text().to_string());
+            pane_clone.highlight_matches();
+        });
+        for (btn, forward) in [(&next_btn, true), (&prev_btn, false)] {
+            let pane = pane.clone();
+            btn.connect_clicked(move |_| pane.find(forward));
+        }
+        let pane_clone = pane.clone();
+        search_entry.connect_activate(move |_| pane_clone.find(true));
+        let pane_clone = pane.clone();
+        search_entry.connect_next_match(move |_| pane_clone.find(true));
+        let pane_clone = pane.clone();
+        search_entry.connect_previous_match(move |_| pane_clone.find(false));
+        
+        // Filter row
+        let filter_box = Box::new(gtk4::Orientation::Horizontal, 5);
+        filter_box.set_margin_start(10);
+        filter_box.append(&Label::new(Some("Show:")));
+        let mut level_toggles = Vec::new();
+        for level in output::Level::ALL {
+            let tag = gtk4::TextTag::new(Some(&level_tag_name(level)));
+            if let Some(color) = level.color() {
+                tag.set_foreground(Some(color));
+            }
+            buffer.tag_table().add(&tag);
+            
+            let toggle = CheckButton::with_label(level.label());
+            toggle.set_active(true);
+            let pane = pane.clone();
+            toggle.connect_toggled(move |toggle| {
+                tag.set_invisible(!toggle.is_active());
+                // Hidden lines do not count as matches
+                pane.highlight_matches();
+            });
+            filter_box.append(&toggle);
+            level_toggles.push((level, toggle));
+        }
+        
+        let problems_toggle = CheckButton::with_label("⚠️ Only warnings and errors");
+        problems_toggle.connect_toggled(move |problems_toggle| {
+            let problems_only = problems_toggle.is_active();
+            for (level, toggle) in &level_toggles {
+                toggle.set_active(!problems_only || level.is_problem());
+            }
+        });
+        filter_box.append(&Separator::new(gtk4::Orientation::Vertical));
+        filter_box.append(&problems_toggle);
+        
+        let scrolled = ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .vexpand(true)
+            .child(&text_view)
+            .build();
+        
+        container.append(&search_box);
+        container.append(&filter_box);
+        container.append(&scrolled);
+        
+        pane
+    }
+    
+    fn buffer(&self) -> gtk4::TextBuffer {
+        self.text_view.buffer()
+    }
+    
+    // Start over for a new job
+    fn clear(&self, text: &str) {
+        self.renderer.borrow_mut().reset();
+        self.records.borrow_mut().clear();
+        self.buffer().set_text(text);
+        for line in text.lines() {
+            self.records
+                .borrow_mut()
+                .push(output::Record::new(&output::Line::plain(output::Level::Plain, line), None));
+        }
+        self.highlight_matches();
+    }
+    
+    fn append_raw(&self, stream: output::Stream, raw: &str) {
+        let line = self.renderer.borrow_mut().render(stream, raw);
+        self.append_line(&line, Some(stream));
+    }
+    
+    fn append_line(&self, line: &output::Line, stream: Option<output::Stream>) {
+        let buffer = self.buffer();
+        let start_offset = buffer.end_iter().offset();
+        
+        for span in &line.spans {
+            let mut end = buffer.end_iter();
+            match self.style_tag(&span.style) {
+                Some(tag) => buffer.insert_with_tags(&mut end, &span.text, &[&tag]),
+                None => buffer.insert(&mut end, &span.text),
+            }
+        }
+        let mut end = buffer.end_iter();
+        buffer.insert(&mut end, "\n");
+        
+        let start = buffer.iter_at_offset(start_offset);
+        buffer.apply_tag_by_name(&level_tag_name(line.level), &start, &buffer.end_iter());
+        self.records.borrow_mut().push(output::Record::new(line, stream));
+        
+        let count = self.highlight_range(start, &buffer.end_iter());
+        if count > 0 {
+            self.match_count.set(self.match_count.get() + count);
+            self.show_match_count();
+        }
+    }
+    
+    // One TextTag per distinct SGR style, created on first use
+    fn style_tag(&self, style: &output::Style) -> Option<gtk4::TextTag> {
+        if style.is_plain() {
+            return None;
+        }
+        let tag_table = self.buffer().tag_table();
+        let name = style.tag_name();
+        if let Some(tag) = tag_table.lookup(&name) {
+            return Some(tag);
+        }
+        
+        let tag = gtk4::TextTag::new(Some(&name));
+        if let Some(fg) = style.fg {
+            tag.set_foreground(Some(&fg.hex()));
+        }
+        if let Some(bg) = style.bg {
+            tag.set_background(Some(&bg.hex()));
+        }
+        if style.bold {
+            tag.set_weight(700);
+        }
+        if style.dim && !style.bold {
+            tag.set_weight(300);
+        }
+        if style.italic {
+            tag.set_style(gtk4::pango::Style::Italic);
+        }
+        if style.underline {
+            tag.set_underline(gtk4::pango::Underline::Single);
+        }
+        tag_table.add(&tag);
+        Some(tag)
+    }
+    
+    fn search_flags() -> gtk4::TextSearchFlags {
+        gtk4::TextSearchFlags::CASE_INSENSITIVE | gtk4::TextSearchFlags::VISIBLE_ONLY | gtk4::TextSearchFlags::TEXT_ONLY
+    }
     
-    let output_area = create_output_area();
+    // Highlight matches of the current query between two iters; returns their number
+    fn highlight_range(&self, mut iter: gtk4::TextIter, end: &gtk4::TextIter) -> usize {
+        let query = self.query.borrow();
+        if query.is_empty() {
+            return 0;
+        }
+        let buffer = self.buffer();
+        let mut count = 0;
+        while let Some((match_start, match_end)) = iter.forward_search(&query, Self::search_flags(), Some(end)) {
+            buffer.apply_tag_by_name(SEARCH_TAG, &match_start, &match_end);
+            count += 1;
+            iter = match_end;
+        }
+        count
+    }
     
-    let tools = vec![
-        ("🔄 System Update", "hardn update"),
-        ("🔧 Lynis Audit", "sudo /usr/share/hardn/tools/lynis.sh"),
-        ("🔍 RKHunter Setup", "sudo /usr/share/hardn/tools/rkhunter.sh"),
-        ("🌐 API Server", "hardn api --port 8080"),
-        ("❌ Uninstall HARDN", "hardn uninstall"),
-    ];
+    fn highlight_matches(&self) {
+        let buffer = self.buffer();
+        let (start, end) = buffer.bounds();
+        buffer.remove_tag_by_name(SEARCH_TAG, &start, &end);
+        self.match_count.set(self.highlight_range(start, &end));
+        self.show_match_count();
+    }
     
-    for (name, cmd) in tools {
-        let btn = Button::with_label(name);
-        btn.set_height_request(40);
-        
-        let output_clone = output_area.clone();
-        let cmd_clone = cmd.to_string();
-        let tracker_clone = process_tracker.clone();
-        btn.connect_clicked(move |btn| {
-            if cmd_clone.starts_with("sudo") {
-                show_sudo_message(&output_clone, &cmd_clone, &tracker_clone);
-            } else {
-                run_command_in_output(&cmd_clone, &output_clone, btn, &tracker_clone);
+    fn show_match_count(&self) {
+        if self.query.borrow().is_empty() {
+            self.match_label.set_text("");
+        } else {
+            match self.match_count.get() {
+                0 => self.match_label.set_text("No matches"),
+                1 => self.match_label.set_text("1 match"),
+                count => self.match_label.set_text(&format!("{} matches", count)),
             }
+        }
+    }
+    
+    // Select the next or previous match after the selection, wrapping around
+    fn find(&self, forward: bool) {
+        let query = self.query.borrow().clone();
+        if query.is_empty() {
+            return;
+        }
+        let buffer = self.buffer();
+        let (selection_start, selection_end) = buffer.selection_bounds().unwrap_or_else(|| {
+            let cursor = buffer.get_insert();
+            (buffer.iter_at_mark(&cursor), buffer.iter_at_mark(&cursor))
         });
         
-        tools_box.append(&btn);
+        let found = if forward {
+            selection_end
+                .forward_search(&query, Self::search_flags(), None)
+                .or_else(|| buffer.start_iter().forward_search(&query, Self::search_flags(), None))
+        } else {
+            selection_start
+                .backward_search(&query, Self::search_flags(), None)
+                .or_else(|| buffer.end_iter().backward_search(&query, Self::search_flags(), None))
+        };
+        
+        if let Some((mut match_start, match_end)) = found {
+            buffer.select_range(&match_start, &match_end);
+            self.text_view.scroll_to_iter(&mut match_start, 0.1, false, 0.0, 0.0);
+        }
     }
     
-    tools_frame.set_child(Some(&tools_box));
-    main_box.append(&tools_frame);
-    main_box.append(&output_area);
+    #[allow(deprecated)]
+    fn save_as(&self, format: output::SaveFormat) {
+        let window = self.container.root().and_downcast::<gtk4::Window>();
+        let dialog = FileChooserNative::new(
+            Some("Save Output"),
+            window.as_ref(),
+            gtk4::FileChooserAction::Save,
+            Some("Save"),
+            Some("Cancel"),
+        );
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        dialog.set_current_name(&format!("hardn-output-{}.{}", report::file_timestamp(now), format.extension()));
+        
+        let content = format.format(&self.records.borrow());
+        let match_label = self.match_label.clone();
+        // Native dialogs are not kept alive by GTK; drop our reference once answered
+        let keep_alive = RefCell::new(Some(dialog.clone()));
+        dialog.connect_response(move |dialog, response| {
+            if response == ResponseType::Accept {
+                if let Some(path) = dialog.file().and_then(|file| file.path()) {
+                    match std::fs::write(&path, &content) {
+                        Ok(()) => match_label.set_text(&format!("Saved {}", path.display())),
+                        Err(e) => match_label.set_text(&format!("❌ Save failed: {}", e)),
+                    }
+                }
+            }
+            keep_alive.take();
+        });
+        dialog.show();
+    }
     
-    notebook.append_page(&main_box, Some(&Label::new(Some("🔧 Tools"))));
+    fn scroll_to_end(&self) {
+        let mut end_iter = self.buffer().end_iter();
+        self.text_view.scroll_to_iter(&mut end_iter, 0.0, false, 0.0, 0.0);
+    }
 }
 
-fn create_output_area() -> ScrolledWindow {
-    let scrolled = ScrolledWindow::builder()
-        .hscrollbar_policy(gtk4::PolicyType::Automatic)
-        .vscrollbar_policy(gtk4::PolicyType::Automatic)
-        .build();
+fn level_tag_name(level: output::Level) -> String {
+    format!("level-{}", level.key())
+}
 
-    let text_view = TextView::builder()
-        .editable(false)
-        .monospace(true)
-        .wrap_mode(gtk4::WrapMode::Word)
-        .build();
-    
-    text_view.set_margin_start(10);
-    text_view.set_margin_end(10);
-    text_view.set_margin_top(10);
-    text_view.set_margin_bottom(10);
-    
-    text_view.buffer().set_text("Ready to execute commands...\n\n");
-    
-    scrolled.set_child(Some(&text_view));
-    scrolled
+fn create_output_area() -> OutputPane {
+    OutputPane::new()
 }
 
-fn setup_command_handlers(command: &str, output_area: &ScrolledWindow, button: &Button, process_tracker: &ProcessTracker, window: &ApplicationWindow) {
-    // Check if command requires sudo
-    if command.starts_with("sudo") || command.contains("sudo ") {
-        // Show password dialog for sudo commands
-        let dialog = Dialog::builder()
-            .title("Administrator Password Required")
-            .modal(true)
-            .transient_for(window)
-            .build();
-        
-        dialog.add_button("Cancel", ResponseType::Cancel);
-        dialog.add_button("Execute", ResponseType::Accept);
-        
-        let content_area = dialog.content_area();
-        let vbox = Box::new(gtk4::Orientation::Vertical, 10);
-        vbox.set_margin_start(20);
-        vbox.set_margin_end(20);
-        vbox.set_margin_top(20);
-        vbox.set_margin_bottom(20);
-        
-        let label = Label::new(Some(&format!("This command requires administrator privileges:\n{}", command)));
-        label.set_wrap(true);
-        
-        let password_entry = Entry::builder()
-            .placeholder_text("Enter your password")
-            .visibility(false)
-            .activates_default(true)
-            .build();
+// Progress bar with the list of steps a job announced through the progress protocol
+#[derive(Clone)]
+struct ProgressPanel {
+    container: Box,
+    bar: ProgressBar,
+    steps_box: Box,
+}
+
+impl ProgressPanel {
+    fn new(idle_text: &str) -> Self {
+        let container = Box::new(gtk4::Orientation::Vertical, 5);
         
-        vbox.append(&label);
-        vbox.append(&password_entry);
-        content_area.append(&vbox);
+        let bar = ProgressBar::new();
+        bar.set_show_text(true);
+        bar.set_text(Some(idle_text));
+        bar.set_hexpand(true);
+        container.append(&bar);
         
-        dialog.set_default_response(ResponseType::Accept);
-        password_entry.grab_focus();
+        let steps_box = Box::new(gtk4::Orientation::Vertical, 2);
+        container.append(&steps_box);
         
-        let command_clone = command.to_string();
-        let output_area_clone = output_area.clone();
-        let button_clone = button.clone();
-        let process_tracker_clone = process_tracker.clone();
+        ProgressPanel { container, bar, steps_box }
+    }
+    
+    fn reset(&self, text: &str) {
+        self.bar.set_fraction(0.0);
+        self.bar.set_text(Some(text));
+        while let Some(child) = self.steps_box.first_child() {
+            self.steps_box.remove(&child);
+        }
+    }
+    
+    fn show(&self, steps: &progress::StepTracker) {
+        self.bar.set_fraction(steps.fraction());
+        self.bar.set_text(Some(&steps.text()));
         
-        dialog.connect_response(move |dialog, response| {
-            if response == ResponseType::Accept {
-                let password = password_entry.text();
-                if !password.is_empty() {
-                    execute_command_with_password(&command_clone, &password, &output_area_clone, &button_clone, &process_tracker_clone);
+        while let Some(child) = self.steps_box.first_child() {
+            self.steps_box.remove(&child);
+        }
+        for (label, state) in steps.steps() {
+            let step_label = Label::new(Some(&format!("{} {}", state.badge(), label)));
+            step_label.set_xalign(0.0);
+            if state == progress::StepState::Done {
+                step_label.add_css_class("dim-label");
+            }
+            self.steps_box.append(&step_label);
+        }
+    }
+    
+    fn finish(&self, steps: &progress::StepTracker, exit_code: Option<i32>) {
+        match exit_code {
+            Some(0) => {
+                self.bar.set_fraction(1.0);
+                if steps.is_empty() {
+                    self.bar.set_text(Some("Completed"));
+                }
+            }
+            Some(code) => self.bar.set_text(Some(&format!("Failed with exit code {}", code))),
+            None => self.bar.set_text(Some("Terminated")),
+        }
+    }
+    
+    // Observer for one job run that drives this panel
+    fn observe(&self) -> JobObserver {
+        let (observer, events) = mpsc::channel();
+        let panel = self.clone();
+        let mut steps = progress::StepTracker::default();
+        let mut exit_code = None;
+        panel.reset("Starting...");
+        glib::timeout_add_local(Duration::from_millis(200), move || {
+            loop {
+                match events.try_recv() {
+                    Ok(JobEvent::Progress(progress)) => {
+                        steps.update(progress);
+                        panel.show(&steps);
+                    }
+                    Ok(JobEvent::Line(_)) => {}
+                    Ok(JobEvent::Exited(code)) => exit_code = code,
+                    Err(mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                    Err(mpsc::TryRecvError::Disconnected) => {
+                        panel.finish(&steps, exit_code);
+                        return glib::ControlFlow::Break;
+                    }
                 }
             }
-            dialog.close();
         });
-        
-        dialog.present();
-    } else {
-        // Regular command without sudo
-        run_command_in_output(command, output_area, button, process_tracker);
+        observer
     }
 }
 
-fn execute_command_with_password(command: &str, password: &str, output_area: &ScrolledWindow, button: &Button, process_tracker: &ProcessTracker) {
-    let text_view = output_area.child().unwrap().downcast::<TextView>().unwrap();
-    let buffer = text_view.buffer();
+fn set_action_tooltip(button: &Button, action: &HardnAction) {
+    let privileges = if action.needs_root { "runs as administrator" } else { "runs as current user" };
+    button.set_tooltip_text(Some(&format!(
+        "{}\n{}, takes {}",
+        action.command_line(),
+        privileges,
+        action.expected_duration_text()
+    )));
+}
+
+fn connect_action(button: &Button, action: HardnAction, output_area: &OutputPane, job_manager: &JobManager, window: &ApplicationWindow) {
+    set_action_tooltip(button, &action);
     
-    buffer.set_text(&format!("Running: {}\n", command));
-    buffer.insert_at_cursor("=".repeat(60).as_str());
-    buffer.insert_at_cursor("\n");
+    let output_clone = output_area.clone();
+    let job_manager_clone = job_manager.clone();
+    let window_weak = window.downgrade();
+    button.connect_clicked(move |btn| {
+        if let Some(win) = window_weak.upgrade() {
+            dispatch_action(&action, &output_clone, btn, &job_manager_clone, &win, None);
+        }
+    });
+}
+
+// Like connect_action, with the job's progress shown in `panel`
+fn connect_action_with_progress(
+    button: &Button,
+    action: HardnAction,
+    output_area: &OutputPane,
+    job_manager: &JobManager,
+    window: &ApplicationWindow,
+    panel: &ProgressPanel,
+) {
+    set_action_tooltip(button, &action);
     
-    button.set_sensitive(false);
-    let original_label = button.label().unwrap_or_default();
-    button.set_label("Running...");
+    let output_clone = output_area.clone();
+    let job_manager_clone = job_manager.clone();
+    let window_weak = window.downgrade();
+    let panel = panel.clone();
+    button.connect_clicked(move |btn| {
+        if let Some(win) = window_weak.upgrade() {
+            dispatch_action(&action, &output_clone, btn, &job_manager_clone, &win, Some(panel.observe()));
+        }
+    });
+}
+
+// Single entry point for running a HardnAction from any tab
+fn dispatch_action(
+    action: &HardnAction,
+    output_area: &OutputPane,
+    button: &Button,
+    job_manager: &JobManager,
+    window: &ApplicationWindow,
+    observer: Option<JobObserver>,
+) {
+    println!(
+        "🛡️  Dispatching \"{}\": {} (root: {}, destructive: {})",
+        action.label,
+        action.command_line(),
+        action.needs_root,
+        action.destructive
+    );
     
-    let (tx, rx) = mpsc::channel();
-    let cmd_string = command.to_string();
-    let password_string = password.to_string();
-    let button_clone = button.clone();
-    let original_label_clone = original_label.clone();
-    let process_tracker_clone = process_tracker.clone();
+    if action.destructive {
+        confirm_action(action, output_area, button, job_manager, window, observer);
+    } else {
+        run_action(action, output_area, button, job_manager, observer);
+    }
+}
+
+fn confirm_action(
+    action: &HardnAction,
+    output_area: &OutputPane,
+    button: &Button,
+    job_manager: &JobManager,
+    window: &ApplicationWindow,
+    observer: Option<JobObserver>,
+) {
+    let dialog = Dialog::builder()
+        .title("Confirm Operation")
+        .modal(true)
+        .transient_for(window)
+        .build();
     
-    thread::spawn(move || {
-        execute_sudo_command_streaming(&cmd_string, &password_string, tx, &process_tracker_clone);
-    });
+    dialog.add_button("Cancel", ResponseType::Cancel);
+    let continue_btn = dialog.add_button("Continue", ResponseType::Accept);
+    continue_btn.add_css_class("destructive-action");
+    
+    let vbox = Box::new(gtk4::Orientation::Vertical, 10);
+    vbox.set_margin_start(20);
+    vbox.set_margin_end(20);
+    vbox.set_margin_top(20);
+    vbox.set_margin_bottom(20);
     
+    let label = Label::new(Some(&format!(
+        "⚠️ \"{}\" makes changes that are hard to undo:\n\n{}\n\nDo you want to continue?",
+        action.label,
+        action.command_line()
+    )));
+    label.set_wrap(true);
+    vbox.append(&label);
+    dialog.content_area().append(&vbox);
+    dialog.set_default_response(ResponseType::Cancel);
+    
+    let action_clone = action.clone();
     let output_area_clone = output_area.clone();
-    glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
-        while let Ok(message) = rx.try_recv() {
-            if message == "DONE" {
-                button_clone.set_sensitive(true);
-                button_clone.set_label(&original_label_clone);
-                return glib::ControlFlow::Break;
-            } else {
-                let text_view = output_area_clone.child().unwrap().downcast::<TextView>().unwrap();
-                let buffer = text_view.buffer();
-                buffer.insert_at_cursor(&message);
-                
-                let mut end_iter = buffer.end_iter();
-                text_view.scroll_to_iter(&mut end_iter, 0.0, false, 0.0, 0.0);
-            }
+    let button_clone = button.clone();
+    let job_manager_clone = job_manager.clone();
+    dialog.connect_response(move |dialog, response| {
+        if response == ResponseType::Accept {
+            run_action(&action_clone, &output_area_clone, &button_clone, &job_manager_clone, observer.clone());
+        } else {
+            println!("🛑 \"{}\" cancelled by user", action_clone.label);
         }
-        glib::ControlFlow::Continue
+        dialog.close();
     });
+    
+    dialog.present();
 }
 
-fn run_command_in_output(command: &str, output_area: &ScrolledWindow, button: &Button, process_tracker: &ProcessTracker) {
-    let text_view = output_area.child().unwrap().downcast::<TextView>().unwrap();
-    let buffer = text_view.buffer();
-    
-    buffer.set_text(&format!("Running: {}\n", command));
+fn run_action(action: &HardnAction, output_area: &OutputPane, button: &Button, job_manager: &JobManager, observer: Option<JobObserver>) {
+    // Actions that require root go through the polkit-authorized helper
+    if action.needs_root {
+        execute_elevated(action, output_area, button, job_manager, observer);
+    } else {
+        run_command_in_output(action, output_area, button, job_manager, observer);
+    }
+}
+
+fn write_action_header(output_area: &OutputPane, action: &HardnAction) {
+    if action.needs_root {
+        output_area.clear(&format!("Running as administrator: {}\n", action.command_line()));
+    } else {
+        output_area.clear(&format!("Running: {}\n", action.command_line()));
+    }
+    let buffer = output_area.buffer();
     buffer.insert_at_cursor("=".repeat(60).as_str());
     buffer.insert_at_cursor("\n");
     
-    if command.starts_with("sudo") {
-        buffer.insert_at_cursor("⚠️ This command requires sudo privileges. Use the password dialog instead.\n");
-        return;
+    match (action.subcommand(), action.args.first().map(String::as_str)) {
+        (Some("monitor"), Some("start")) => {
+            buffer.insert_at_cursor("🔍 Starting HARDN monitoring services...\n");
+            buffer.insert_at_cursor("This will start real-time monitoring of your system security.\n\n");
+        }
+        (Some("monitor"), Some("stop")) => {
+            buffer.insert_at_cursor("⏹️  Stopping HARDN monitoring services...\n\n");
+        }
+        (Some("audit"), _) => {
+            buffer.insert_at_cursor("🔍 Running comprehensive security audit...\n\n");
+        }
+        (Some("backup"), _) => {
+            buffer.insert_at_cursor("💾 Creating system configuration backup...\n\n");
+        }
+        _ => {}
     }
     
-    if command.contains("monitor start") {
-        buffer.insert_at_cursor("🔍 Starting HARDN monitoring services...\n");
-        buffer.insert_at_cursor("This will start real-time monitoring of your system security.\n\n");
-    } else if command.contains("monitor stop") {
-        buffer.insert_at_cursor("⏹️  Stopping HARDN monitoring services...\n\n");
-    } else if command.contains("audit") {
-        buffer.insert_at_cursor("🔍 Running comprehensive security audit...\n");
-        buffer.insert_at_cursor("This may take several minutes to complete.\n\n");
-    } else if command.contains("backup") {
-        buffer.insert_at_cursor("💾 Creating system configuration backup...\n\n");
+    if action.expected_duration.as_secs() >= 60 {
+        buffer.insert_at_cursor(&format!("⏱️  This usually takes {} to complete.\n\n", action.expected_duration_text()));
     }
+}
+
+fn execute_elevated(action: &HardnAction, output_area: &OutputPane, button: &Button, job_manager: &JobManager, observer: Option<JobObserver>) {
+    let request = match action.helper_request() {
+        Ok(request) => request,
+        Err(e) => {
+            output_area.clear("");
+            output_area.append_line(
+                &output::Line::plain(output::Level::Error, &format!("❌ Not an allowed privileged HARDN operation: {}", e)),
+                None,
+            );
+            return;
+        }
+    };
     
-    button.set_sensitive(false);
-    let original_label = button.label().unwrap_or_default();
-    button.set_label("Running...");
+    write_action_header(output_area, action);
     
     let (tx, rx) = mpsc::channel();
-    let cmd_string = command.to_string();
-    let button_clone = button.clone();
-    let original_label_clone = original_label.clone();
-    let process_tracker_clone = process_tracker.clone();
+    let label = action.label.clone();
+    let job_manager_clone = job_manager.clone();
     
     thread::spawn(move || {
-        execute_command_streaming(&cmd_string, tx, &process_tracker_clone);
+        match privilege::spawn_elevated(&request) {
+            Ok(child) => stream_child(child, &label, true, tx, &job_manager_clone, observer),
+            Err(e) => {
+                let _ = tx.send(OutputMessage::Status(output::Level::Error, format!("❌ Error starting privileged helper: {}", e)));
+                let _ = tx.send(OutputMessage::Done);
+            }
+        }
+    });
+    
+    stream_to_output(rx, output_area, button, Some(action.clone()));
+}
+
+fn run_command_in_output(action: &HardnAction, output_area: &OutputPane, button: &Button, job_manager: &JobManager, observer: Option<JobObserver>) {
+    write_action_header(output_area, action);
+    
+    let (tx, rx) = mpsc::channel();
+    let cmd = action.to_command();
+    let label = action.label.clone();
+    let job_manager_clone = job_manager.clone();
+    
+    thread::spawn(move || {
+        execute_command_streaming(cmd, &label, tx, &job_manager_clone, observer);
     });
     
+    stream_to_output(rx, output_area, button, Some(action.clone()));
+}
+
+// Render streamed lines into the output area until the worker sends Done.
+// Progress lines are kept out of the output and shown on the button instead.
+// Jobs of an `action` are recorded in the job history with their output.
+fn stream_to_output(rx: mpsc::Receiver<OutputMessage>, output_area: &OutputPane, button: &Button, action: Option<HardnAction>) {
+    button.set_sensitive(false);
+    let original_label = button.label().unwrap_or_default();
+    button.set_label("Running...");
+    
+    let button_clone = button.clone();
     let output_area_clone = output_area.clone();
-    glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+    let mut steps = progress::StepTracker::default();
+    let mut ended = None;
+    glib::timeout_add_local(Duration::from_millis(100), move || {
+        let mut appended = false;
         while let Ok(message) = rx.try_recv() {
-            if message == "DONE" {
-                button_clone.set_sensitive(true);
-                button_clone.set_label(&original_label_clone);
-                return glib::ControlFlow::Break;
-            } else {
-                let text_view = output_area_clone.child().unwrap().downcast::<TextView>().unwrap();
-                let buffer = text_view.buffer();
-                buffer.insert_at_cursor(&message);
-                
-                let mut end_iter = buffer.end_iter();
-                text_view.scroll_to_iter(&mut end_iter, 0.0, false, 0.0, 0.0);
+            match message {
+                OutputMessage::Done => {
+                    button_clone.set_sensitive(true);
+                    button_clone.set_label(&original_label);
+                    output_area_clone.scroll_to_end();
+                    if let (Some(action), Some(info)) = (action.clone(), ended.take()) {
+                        record_history(action, info, output_area_clone.records.borrow().clone());
+                    }
+                    return glib::ControlFlow::Break;
+                }
+                OutputMessage::Ended(info) => ended = Some(info),
+                OutputMessage::Line(stream, line) => match progress::Progress::parse(&line) {
+                    Some(progress) => {
+                        steps.update(progress);
+                        button_clone.set_label(&format!("Running... {:.0}%", steps.fraction() * 100.0));
+                    }
+                    None => {
+                        output_area_clone.append_raw(stream, &line);
+                        appended = true;
+                    }
+                },
+                OutputMessage::Status(level, text) => {
+                    output_area_clone.append_line(&output::Line::plain(level, &text), None);
+                    appended = true;
+                }
             }
         }
+        if appended {
+            output_area_clone.scroll_to_end();
+        }
         glib::ControlFlow::Continue
     });
 }
 
-fn execute_command_streaming(command: &str, tx: mpsc::Sender<String>, process_tracker: &ProcessTracker) {
-    let parts: Vec<&str> = command.split_whitespace().collect();
-        if parts.is_empty() {
-        let _ = tx.send("Error: Empty command\n".to_string());
-        let _ = tx.send("DONE".to_string());
-            return;
-        }
-        
-        let mut cmd = Command::new(parts[0]);
-        if parts.len() > 1 {
-            cmd.args(&parts[1..]);
+fn record_history(action: HardnAction, info: jobs::JobInfo, output: Vec<output::Record>) {
+    // Reads of logs and configuration are not worth an entry and would push out real runs
+    if action.is_read_only() {
+        return;
+    }
+    let unix_secs = |time: std::time::SystemTime| time.duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let started_at = unix_secs(info.started_at);
+    let entry = history::HistoryEntry {
+        id: format!("{}-{}", report::file_timestamp(started_at), info.pid),
+        command: action.command_line(),
+        action,
+        user: glib::user_name().to_string_lossy().to_string(),
+        started_at,
+        ended_at: unix_secs(std::time::SystemTime::now()),
+        state: info.state,
+        output,
+    };
+    // Large outputs take a moment to serialize; keep that off the UI thread
+    thread::spawn(move || {
+        if let Err(e) = history::HistoryStore::from_env().save(&entry) {
+            eprintln!("Failed to record job history: {}", e);
         }
-        
+    });
+}
+
+fn execute_command_streaming(mut cmd: Command, label: &str, tx: mpsc::Sender<OutputMessage>, job_manager: &JobManager, observer: Option<JobObserver>) {
+    // Own process group, so cancelling the job also stops everything it started
+    cmd.process_group(0);
     cmd.env("DEBIAN_FRONTEND", "noninteractive");
     cmd.env("NEEDRESTART_MODE", "a");
+    cmd.env(protocol::PROGRESS_ENV, protocol::PROGRESS_MODE);
     
     let child = cmd
             .stdout(Stdio::piped())
@@ -672,169 +6710,128 @@ fn execute_command_streaming(command: &str, tx: mpsc::Sender<String>, process_tr
         .stdin(Stdio::null())
         .spawn();
         
-    let mut child = match child {
-            Ok(child) => child,
-            Err(e) => {
-            let _ = tx.send(format!("❌ Error starting command: {}\n", e));
-            let _ = tx.send("DONE".to_string());
-                return;
-            }
-        };
-        
-    // Track the process for cleanup
-    let pid = child.id();
-    if let Ok(mut pids) = process_tracker.lock() {
-        pids.insert(pid);
+    match child {
+        Ok(child) => stream_child(child, label, false, tx, job_manager, observer),
+        Err(e) => {
+            let _ = tx.send(OutputMessage::Status(output::Level::Error, format!("❌ Error starting command: {}", e)));
+            let _ = tx.send(OutputMessage::Done);
+        }
     }
-    
-        if let Some(stdout) = child.stdout.take() {
-        let tx_clone = tx.clone();
-        thread::spawn(move || {
-            let reader = BufReader::new(stdout);
-                for line in reader.lines() {
-                if let Ok(line) = line {
-                    if line.trim().is_empty() {
-                        continue;
-                    }
-                    if line.contains("[INFO]") {
-                        let _ = tx_clone.send(format!("ℹ️  {}\n", line.replace("[INFO]", "").trim()));
-                    } else if line.contains("[WARN]") {
-                        let _ = tx_clone.send(format!("⚠️  {}\n", line.replace("[WARN]", "").trim()));
-                    } else if line.contains("[ERROR]") {
-                        let _ = tx_clone.send(format!("❌ {}\n", line.replace("[ERROR]", "").trim()));
-                    } else {
-                        let _ = tx_clone.send(format!("{}\n", line));
-                    }
+}
+
+// Run a helper operation in `output` and hand its exit code and output lines to `done`.
+// Confirmations of destructive operations are parented to the button's window.
+fn run_helper(output: &OutputPane, job_manager: &JobManager, action: &HardnAction, btn: &Button, done: impl FnOnce(Option<i32>, Vec<String>) + 'static) {
+    let Some(window) = btn.root().and_downcast::<ApplicationWindow>() else {
+        return;
+    };
+    let (observer, events) = mpsc::channel();
+    dispatch_action(action, output, btn, job_manager, &window, Some(observer));
+    drain_observer(events, Vec::new(), |lines, line| lines.push(line), move |lines, exit_code| done(exit_code, lines));
+}
+
+// Follow an observed job from the main loop: `line` folds each output line into `state`,
+// and `done` gets the state and the exit code once the job dropped its observer
+fn drain_observer<S: 'static>(
+    events: mpsc::Receiver<JobEvent>,
+    state: S,
+    mut line: impl FnMut(&mut S, String) + 'static,
+    done: impl FnOnce(S, Option<i32>) + 'static,
+) {
+    let mut pending = Some((state, done));
+    let mut exit_code = None;
+    glib::timeout_add_local(Duration::from_millis(200), move || {
+        loop {
+            match events.try_recv() {
+                Ok(JobEvent::Line(text)) => {
+                    if let Some((state, _)) = &mut pending {
+                        line(state, text);
                     }
                 }
-            });
-        }
-        
-        if let Some(stderr) = child.stderr.take() {
-        let tx_clone = tx.clone();
-        thread::spawn(move || {
-            let reader = BufReader::new(stderr);
-                for line in reader.lines() {
-                if let Ok(line) = line {
-                    if line.trim().is_empty() {
-                        continue;
-                    }
-                    if line.contains("error") || line.contains("Error") || line.contains("ERROR") {
-                        let _ = tx_clone.send(format!("❌ {}\n", line));
-                    } else if line.contains("[INFO]") {
-                        let _ = tx_clone.send(format!("ℹ️  {}\n", line.replace("[INFO]", "").trim()));
-                            } else {
-                        let _ = tx_clone.send(format!("⚠️  {}\n", line));
-                    }
+                Ok(JobEvent::Progress(_)) => {}
+                Ok(JobEvent::Exited(code)) => exit_code = code,
+                Err(mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    if let Some((state, done)) = pending.take() {
+                        done(state, exit_code);
                     }
+                    return glib::ControlFlow::Break;
                 }
-            });
-        }
-        
-        match child.wait() {
-            Ok(status) => {
-            println!("Process {} finished", pid);
-                if status.success() {
-                let _ = tx.send("\n✅ Command completed successfully\n".to_string());
-                } else {
-                let _ = tx.send(format!("\n❌ Command failed with exit code: {:?}\n", status.code()));
             }
         }
-        Err(e) => {
-            let _ = tx.send(format!("\n❌ Error waiting for command: {}\n", e));
-        }
-    }
-    
-    let _ = tx.send("DONE".to_string());
+    });
 }
 
-fn execute_sudo_command_streaming(command: &str, password: &str, tx: mpsc::Sender<String>, process_tracker: &ProcessTracker) {
-    // Use echo to pipe password to sudo command
-    let full_command = format!("echo '{}' | {}", password, command);
-    
-    let mut cmd = Command::new("bash");
-    cmd.args(["-c", &full_command]);
-    cmd.env("DEBIAN_FRONTEND", "noninteractive");
-    cmd.env("NEEDRESTART_MODE", "a");
-    
-    let child = cmd
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .stdin(Stdio::null())
-        .spawn();
-        
-    let mut child = match child {
-        Ok(child) => child,
-        Err(e) => {
-            let _ = tx.send(format!("❌ Error starting sudo command: {}\n", e));
-            let _ = tx.send("DONE".to_string());
-            return;
-        }
-    };
-    
-    // Track the process for cleanup
+// Forward a spawned child's stdout/stderr to the output channel and report its exit status.
+// `elevated` children are pkexec'd helpers whose auth failures have dedicated exit codes.
+// An observer additionally receives every line unformatted, followed by the exit code.
+fn stream_child(mut child: Child, label: &str, elevated: bool, tx: mpsc::Sender<OutputMessage>, job_manager: &JobManager, observer: Option<JobObserver>) {
     let pid = child.id();
-    if let Ok(mut pids) = process_tracker.lock() {
-        pids.insert(pid);
-    }
+    let job = job_manager.register(label, &mut child, elevated);
+    let mut readers = Vec::new();
     
     if let Some(stdout) = child.stdout.take() {
         let tx_clone = tx.clone();
-        thread::spawn(move || {
-            let reader = BufReader::new(stdout);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    if line.trim().is_empty() {
-                        continue;
-                    }
-                    if line.contains("[INFO]") {
-                        let _ = tx_clone.send(format!("ℹ️  {}\n", line.replace("[INFO]", "").trim()));
-                    } else if line.contains("[WARN]") {
-                        let _ = tx_clone.send(format!("⚠️  {}\n", line.replace("[WARN]", "").trim()));
-                    } else if line.contains("[ERROR]") {
-                        let _ = tx_clone.send(format!("❌ {}\n", line.replace("[ERROR]", "").trim()));
-                    } else {
-                        let _ = tx_clone.send(format!("{}\n", line));
-                    }
-                }
-            }
-        });
+        let observer_clone = observer.clone();
+        readers.push(thread::spawn(move || forward_lines(stdout, output::Stream::Stdout, tx_clone, observer_clone)));
     }
     
     if let Some(stderr) = child.stderr.take() {
         let tx_clone = tx.clone();
-        thread::spawn(move || {
-            let reader = BufReader::new(stderr);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    if line.trim().is_empty() {
-                        continue;
-                    }
-                    if line.contains("error") || line.contains("Error") || line.contains("ERROR") {
-                        let _ = tx_clone.send(format!("❌ {}\n", line));
-                    } else if line.contains("[INFO]") {
-                        let _ = tx_clone.send(format!("ℹ️  {}\n", line.replace("[INFO]", "").trim()));
-                    } else {
-                        let _ = tx_clone.send(format!("⚠️  {}\n", line));
-                    }
-                }
-            }
-        });
+        let observer_clone = observer.clone();
+        readers.push(thread::spawn(move || forward_lines(stderr, output::Stream::Stderr, tx_clone, observer_clone)));
     }
     
-    match child.wait() {
+    let status = child.wait();
+    // The last lines may still be in the pipes; the status, the recorded output and
+    // the observer's exit code all come after them
+    for reader in readers {
+        let _ = reader.join();
+    }
+    let state = job_manager.finish(job, status.as_ref().ok().copied());
+    if let Some(info) = job_manager.info(job) {
+        let _ = tx.send(OutputMessage::Ended(info));
+    }
+    match status {
         Ok(status) => {
-            println!("Sudo process {} finished", pid);
-            if status.success() {
-                let _ = tx.send("\n✅ Sudo command completed successfully\n".to_string());
-            } else {
-                let _ = tx.send(format!("\n❌ Sudo command failed with exit code: {:?}\n", status.code()));
+            println!("Process {} finished: {}", pid, state.label());
+            if let Some(observer) = &observer {
+                let _ = observer.send(JobEvent::Exited(status.code()));
             }
+            let message = if state == jobs::JobState::Cancelled {
+                (output::Level::Warn, "⏹️  Command cancelled".to_string())
+            } else if status.success() {
+                (output::Level::Pass, "✅ Command completed successfully".to_string())
+            } else if let Some(reason) = privilege::describe_exit_code(status.code()).filter(|_| elevated) {
+                (output::Level::Error, format!("❌ Command failed: {}", reason))
+            } else {
+                (output::Level::Error, format!("❌ Command failed with exit code: {:?}", status.code()))
+            };
+            let _ = tx.send(OutputMessage::Status(output::Level::Plain, String::new()));
+            let _ = tx.send(OutputMessage::Status(message.0, message.1));
         }
         Err(e) => {
-            let _ = tx.send(format!("\n❌ Error waiting for sudo command: {}\n", e));
+            let _ = tx.send(OutputMessage::Status(output::Level::Error, format!("❌ Error waiting for command: {}", e)));
         }
     }
     
-    let _ = tx.send("DONE".to_string());
-}
\ No newline at end of file
+    let _ = tx.send(OutputMessage::Done);
+}
+
+// Pass one output stream of a job on line by line. Progress lines go to the observer
+// as progress and to the output pane unchanged, where stream_to_output consumes them.
+fn forward_lines(reader: impl std::io::Read, stream: output::Stream, tx: mpsc::Sender<OutputMessage>, observer: Option<JobObserver>) {
+    for line in BufReader::new(reader).lines().map_while(Result::ok) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(observer) = &observer {
+            match progress::Progress::parse(&line) {
+                Some(progress) => observer.send(JobEvent::Progress(progress)),
+                None => observer.send(JobEvent::Line(line.clone())),
+            }
+            .ok();
+        }
+        let _ = tx.send(OutputMessage::Line(stream, line));
+    }
+}
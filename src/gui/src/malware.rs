@@ -0,0 +1,598 @@
+// Root-side malware handling: the quarantine store and the scheduled ClamAV scan.
+//
+// Quarantined files live in QUARANTINE_DIR/<id>/, the file itself as `data` with all
+// permission bits cleared and its metadata in `item.json`. The metadata stays world
+// readable so the GUI can list the quarantine without elevation; only the helper
+// moves files in or out. The scheduled scan is a cron.d entry owned by the GUI.
+//
+// This file is compiled into both binaries and each only uses its own side of it.
+#![allow(dead_code)]
+
+use serde::{Deserialize, Serialize};
+use std::ffi::CString;
+use std::fs;
+use std::io::{self, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, OpenOptionsExt, PermissionsExt};
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const QUARANTINE_DIR: &str = "/var/lib/hardn/quarantine";
+pub const SCHEDULE_FILE: &str = "/etc/cron.d/hardn-clamav";
+pub const CLAMSCAN_BIN: &str = "/usr/bin/clamscan";
+
+// Same location and naming as check_malware in modules/audit.sh
+pub const SCAN_LOG_DIR: &str = "/var/log/hardn";
+pub const SCAN_LOG_PREFIX: &str = "clamav-scan-";
+
+const ITEM_FILE: &str = "item.json";
+const DATA_FILE: &str = "data";
+
+// Upper bound for scan targets in one request or schedule
+pub const MAX_SCAN_PATHS: usize = 64;
+
+// Absolute, without `..`, and nothing cron or the request line would mangle
+pub fn valid_scan_path(path: &str) -> bool {
+    path.starts_with('/')
+        && path.len() < 4096
+        && !path.contains(['\0', '\n', '\r', '%'])
+        && !Path::new(path).components().any(|c| c == Component::ParentDir)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuarantineItem {
+    pub id: String,
+    pub original_path: String,
+    pub signature: String,
+    // Seconds since the epoch
+    pub quarantined_at: u64,
+    // uid of the user who asked for it (PKEXEC_UID)
+    pub quarantined_by: String,
+    pub size: u64,
+    pub mode: u32,
+    pub uid: u32,
+    pub gid: u32,
+}
+
+// Quarantine ids are generated by add() and end up in paths, so keep them plain
+pub fn valid_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= 64 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+pub struct QuarantineStore {
+    dir: PathBuf,
+}
+
+impl QuarantineStore {
+    pub fn new(dir: &Path) -> Self {
+        QuarantineStore { dir: dir.to_path_buf() }
+    }
+
+    pub fn system() -> Self {
+        Self::new(Path::new(QUARANTINE_DIR))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    // Newest first, plus one message per unreadable entry. A missing store is empty.
+    pub fn list(&self) -> (Vec<QuarantineItem>, Vec<String>) {
+        let read_dir = match fs::read_dir(&self.dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return (Vec::new(), Vec::new()),
+            Err(e) => return (Vec::new(), vec![format!("{}: {}", self.dir.display(), e)]),
+        };
+        let mut items = Vec::new();
+        let mut errors = Vec::new();
+        for entry in read_dir.flatten() {
+            let id = entry.file_name().to_string_lossy().to_string();
+            if !valid_id(&id) {
+                continue;
+            }
+            match self.item(&id) {
+                Ok(item) => items.push(item),
+                Err(e) => errors.push(format!("{}: {}", entry.path().display(), e)),
+            }
+        }
+        items.sort_by(|a, b| b.quarantined_at.cmp(&a.quarantined_at).then_with(|| b.id.cmp(&a.id)));
+        (items, errors)
+    }
+
+    pub fn item(&self, id: &str) -> io::Result<QuarantineItem> {
+        if !valid_id(id) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("invalid quarantine id: {}", id)));
+        }
+        let content = fs::read_to_string(self.dir.join(id).join(ITEM_FILE))?;
+        serde_json::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    // Move a regular file into the store; symlinks and special files are refused, and
+    // so are files whose directory is reached through a symlink
+    pub fn add(&self, path: &Path, signature: &str, caller: &str) -> io::Result<QuarantineItem> {
+        let parent = ParentDir::open(path)?;
+        let file = parent.open_file().map_err(|e| match e.raw_os_error() {
+            Some(libc::ELOOP) => io::Error::new(io::ErrorKind::InvalidInput, format!("{}: not a regular file", path.display())),
+            _ => io::Error::new(e.kind(), format!("{}: {}", path.display(), e)),
+        })?;
+        let meta = file.metadata()?;
+        if !meta.file_type().is_file() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{}: not a regular file", path.display())));
+        }
+
+        fs::DirBuilder::new().recursive(true).mode(0o755).create(&self.dir)?;
+        let (id, item_dir) = self.create_item_dir()?;
+        let item = QuarantineItem {
+            id,
+            original_path: path.to_string_lossy().to_string(),
+            signature: signature.to_string(),
+            quarantined_at: now(),
+            quarantined_by: caller.to_string(),
+            size: meta.len(),
+            mode: meta.mode() & 0o7777,
+            uid: meta.uid(),
+            gid: meta.gid(),
+        };
+
+        let data = item_dir.join(DATA_FILE);
+        let moved = parent.move_out(&file, &data).and_then(|_| fs::set_permissions(&data, fs::Permissions::from_mode(0o000)));
+        if let Err(e) = moved.and_then(|_| write_item(&item_dir, &item)) {
+            // Put the file back if it already moved, so a failure never loses it
+            if data.exists() {
+                if let Ok(moved_back) = fs::File::open(&data).and_then(|data_file| parent.move_in(&data_file, &data).map(|_| data_file)) {
+                    let _ = moved_back.set_permissions(fs::Permissions::from_mode(item.mode));
+                }
+            }
+            let _ = fs::remove_dir_all(&item_dir);
+            return Err(e);
+        }
+        Ok(item)
+    }
+
+    // Move the file back with its original owner and mode; never overwrites, and never
+    // follows a symlink that replaced the file's directory since it was quarantined
+    pub fn restore(&self, id: &str) -> io::Result<QuarantineItem> {
+        let item = self.item(id)?;
+        let original = Path::new(&item.original_path);
+        let parent = ParentDir::open(original)?;
+
+        let item_dir = self.dir.join(id);
+        let data = item_dir.join(DATA_FILE);
+        let file = fs::OpenOptions::new().read(true).custom_flags(libc::O_NOFOLLOW).open(&data)?;
+        // Owner and mode change while the file is still in the store, which only root can enter
+        std::os::unix::fs::fchown(&file, Some(item.uid), Some(item.gid))?;
+        file.set_permissions(fs::Permissions::from_mode(item.mode))?;
+        if let Err(e) = parent.move_in(&file, &data) {
+            let _ = file.set_permissions(fs::Permissions::from_mode(0o000));
+            return Err(match e.kind() {
+                io::ErrorKind::AlreadyExists => io::Error::new(e.kind(), format!("{} exists, not overwriting it", original.display())),
+                _ => io::Error::new(e.kind(), format!("{}: {}", original.display(), e)),
+            });
+        }
+        fs::remove_dir_all(&item_dir)?;
+        Ok(item)
+    }
+
+    pub fn delete(&self, id: &str) -> io::Result<QuarantineItem> {
+        let item = self.item(id)?;
+        fs::remove_dir_all(self.dir.join(id))?;
+        Ok(item)
+    }
+
+    // "<seconds>-<n>" with the first n that is free
+    fn create_item_dir(&self) -> io::Result<(String, PathBuf)> {
+        let seconds = now();
+        for n in 0..1000 {
+            let id = format!("{}-{}", seconds, n);
+            let item_dir = self.dir.join(&id);
+            match fs::DirBuilder::new().mode(0o755).create(&item_dir) {
+                Ok(()) => return Ok((id, item_dir)),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Err(io::Error::new(io::ErrorKind::AlreadyExists, "no free quarantine id"))
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn write_item(item_dir: &Path, item: &QuarantineItem) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(item).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    write_atomic(&item_dir.join(ITEM_FILE), json.as_bytes(), 0o644)
+}
+
+pub fn write_atomic(path: &Path, content: &[u8], mode: u32) -> io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    let mut file = fs::OpenOptions::new().write(true).create(true).truncate(true).mode(mode).open(&tmp)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)
+}
+
+// The directory of a file the helper moves as root. Users own the directories files
+// are quarantined from, so they could swap one for a symlink to, say, /etc/profile.d
+// while the helper works. The directory is opened once, without following symlinks
+// and only when there is no symlink on its path, and the file is moved relative to
+// that handle with renameat2(), which also refuses to replace an existing file.
+struct ParentDir {
+    dir: fs::File,
+    name: CString,
+}
+
+impl ParentDir {
+    fn open(path: &Path) -> io::Result<Self> {
+        let invalid = |what: &str| io::Error::new(io::ErrorKind::InvalidInput, format!("{}: {}", path.display(), what));
+        let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+            return Err(invalid("not a file path"));
+        };
+        if !path.is_absolute() {
+            return Err(invalid("not an absolute path"));
+        }
+        let through_symlink = || invalid("its directory is reached through a symbolic link");
+        if fs::canonicalize(parent)? != parent {
+            return Err(through_symlink());
+        }
+        let dir = fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_DIRECTORY | libc::O_NOFOLLOW)
+            .open(parent)
+            .map_err(|e| match e.raw_os_error() {
+                Some(libc::ELOOP) | Some(libc::ENOTDIR) => through_symlink(),
+                _ => e,
+            })?;
+        // A directory above it may have been swapped between the two checks
+        if fs::read_link(format!("/proc/self/fd/{}", dir.as_raw_fd()))? != parent {
+            return Err(through_symlink());
+        }
+        Ok(ParentDir { dir, name: c_path(Path::new(name))? })
+    }
+
+    // The file itself, unless it is a symlink; special files do not block the open
+    fn open_file(&self) -> io::Result<fs::File> {
+        let flags = libc::O_RDONLY | libc::O_NOFOLLOW | libc::O_NONBLOCK | libc::O_CLOEXEC;
+        let fd = unsafe { libc::openat(self.dir.as_raw_fd(), self.name.as_ptr(), flags) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(unsafe { fs::File::from_raw_fd(fd) })
+    }
+
+    // Move the opened `file` out of this directory to `to`, copying across file systems
+    fn move_out(&self, file: &fs::File, to: &Path) -> io::Result<()> {
+        let to_name = c_path(to)?;
+        match rename(self.dir.as_raw_fd(), &self.name, libc::AT_FDCWD, &to_name) {
+            Err(e) if e.raw_os_error() == Some(libc::EXDEV) => {
+                let mut copy = fs::OpenOptions::new().write(true).create_new(true).mode(0o000).open(to)?;
+                io::copy(&mut &*file, &mut copy)?;
+                copy.sync_all()?;
+                check(unsafe { libc::unlinkat(self.dir.as_raw_fd(), self.name.as_ptr(), 0) })
+            }
+            // The name may have been pointed at another file after it was opened
+            Ok(()) if !same_file(&fs::symlink_metadata(to)?, &file.metadata()?) => {
+                let _ = rename(libc::AT_FDCWD, &to_name, self.dir.as_raw_fd(), &self.name);
+                Err(io::Error::new(io::ErrorKind::InvalidInput, "the file was replaced while it was being quarantined"))
+            }
+            result => result,
+        }
+    }
+
+    // Move the opened `file` at `from` into this directory, never over another file
+    fn move_in(&self, file: &fs::File, from: &Path) -> io::Result<()> {
+        match rename(libc::AT_FDCWD, &c_path(from)?, self.dir.as_raw_fd(), &self.name) {
+            Err(e) if e.raw_os_error() == Some(libc::EXDEV) => {
+                let flags = libc::O_WRONLY | libc::O_CREAT | libc::O_EXCL | libc::O_NOFOLLOW | libc::O_CLOEXEC;
+                let fd = unsafe { libc::openat(self.dir.as_raw_fd(), self.name.as_ptr(), flags, 0o000 as libc::c_uint) };
+                if fd < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                let mut copy = unsafe { fs::File::from_raw_fd(fd) };
+                io::copy(&mut &*file, &mut copy)?;
+                let meta = file.metadata()?;
+                std::os::unix::fs::fchown(&copy, Some(meta.uid()), Some(meta.gid()))?;
+                copy.set_permissions(meta.permissions())?;
+                copy.sync_all()?;
+                fs::remove_file(from)
+            }
+            result => result,
+        }
+    }
+}
+
+fn rename(from_dir: libc::c_int, from: &CString, to_dir: libc::c_int, to: &CString) -> io::Result<()> {
+    check(unsafe { libc::renameat2(from_dir, from.as_ptr(), to_dir, to.as_ptr(), libc::RENAME_NOREPLACE) })
+}
+
+fn c_path(path: &Path) -> io::Result<CString> {
+    CString::new(path.as_os_str().as_bytes()).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+fn check(result: libc::c_int) -> io::Result<()> {
+    match result {
+        -1 => Err(io::Error::last_os_error()),
+        _ => Ok(()),
+    }
+}
+
+fn same_file(a: &fs::Metadata, b: &fs::Metadata) -> bool {
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanFrequency {
+    Off,
+    Daily,
+    Weekly,
+}
+
+impl ScanFrequency {
+    pub const ALL: [ScanFrequency; 3] = [ScanFrequency::Off, ScanFrequency::Daily, ScanFrequency::Weekly];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScanFrequency::Off => "Off",
+            ScanFrequency::Daily => "Daily at 03:30",
+            ScanFrequency::Weekly => "Weekly, Sunday 03:30",
+        }
+    }
+
+    fn cron_spec(&self) -> Option<&'static str> {
+        match self {
+            ScanFrequency::Off => None,
+            ScanFrequency::Daily => Some("30 3 * * *"),
+            ScanFrequency::Weekly => Some("30 3 * * 0"),
+        }
+    }
+}
+
+// The scheduled scan in SCHEDULE_FILE; it logs like check_malware so the GUI and
+// `hardn audit` find its results in the same place
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanSchedule {
+    pub frequency: ScanFrequency,
+    pub paths: Vec<String>,
+}
+
+impl ScanSchedule {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.frequency == ScanFrequency::Off {
+            return Ok(());
+        }
+        if self.paths.is_empty() || self.paths.len() > MAX_SCAN_PATHS {
+            return Err(format!("a scheduled scan needs 1 to {} targets", MAX_SCAN_PATHS));
+        }
+        match self.paths.iter().find(|path| !valid_scan_path(path)) {
+            Some(path) => Err(format!("scan target not allowed: {}", path)),
+            None => Ok(()),
+        }
+    }
+
+    // Read back what render() wrote; anything else counts as no schedule
+    pub fn load(path: &Path) -> Self {
+        let off = ScanSchedule { frequency: ScanFrequency::Off, paths: Vec::new() };
+        let Ok(content) = fs::read_to_string(path) else {
+            return off;
+        };
+        for line in content.lines().map(str::trim).filter(|line| !line.starts_with('#')) {
+            let Some(frequency) = ScanFrequency::ALL
+                .into_iter()
+                .find(|frequency| frequency.cron_spec().is_some_and(|spec| line.starts_with(spec)))
+            else {
+                continue;
+            };
+            let Some((_, targets)) = line.split_once(" -- ") else {
+                continue;
+            };
+            let targets = targets.strip_suffix(" >/dev/null 2>&1").unwrap_or(targets);
+            return ScanSchedule { frequency, paths: split_quoted(targets) };
+        }
+        off
+    }
+
+    pub fn render(&self) -> Option<String> {
+        let spec = self.frequency.cron_spec()?;
+        let targets: Vec<String> = self.paths.iter().map(|path| format!("'{}'", path.replace('\'', r"'\''"))).collect();
+        Some(format!(
+            "# Scheduled ClamAV scan, managed by the HARDN GUI (Malware tab)\n\
+             SHELL=/bin/bash\n\
+             PATH=/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin\n\
+             {} root {} --recursive --infected --log={}/{}$(date +\\%Y\\%m\\%d_\\%H\\%M\\%S).log -- {} >/dev/null 2>&1\n",
+            spec,
+            CLAMSCAN_BIN,
+            SCAN_LOG_DIR,
+            SCAN_LOG_PREFIX,
+            targets.join(" ")
+        ))
+    }
+
+    // Write the cron.d entry, or remove it when the schedule is off
+    pub fn install(&self, path: &Path) -> io::Result<()> {
+        match self.render() {
+            Some(content) => write_atomic(path, content.as_bytes(), 0o644),
+            None => match fs::remove_file(path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self.frequency {
+            ScanFrequency::Off => "no scheduled scan".to_string(),
+            frequency => format!("{}: {}", frequency.label(), self.paths.join(" ")),
+        }
+    }
+}
+
+// Words of a shell line using only the single quoting render() produces
+fn split_quoted(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quoted = false;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                quoted = !quoted;
+                in_word = true;
+            }
+            '\\' if !quoted => {
+                word.extend(chars.next());
+                in_word = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    // A home directory with a download to quarantine, and the store next to it
+    fn setup(name: &str) -> (PathBuf, QuarantineStore, PathBuf) {
+        let base = fs::canonicalize(std::env::temp_dir()).unwrap().join(format!("hardn-malware-test-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&base);
+        let downloads = base.join("home/Downloads");
+        fs::create_dir_all(&downloads).unwrap();
+        let file = downloads.join("eicar.com");
+        fs::write(&file, "X5O!P%@AP[4\\PZX54(P^)7CC)7}$EICAR").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o640)).unwrap();
+        (base.clone(), QuarantineStore::new(&base.join("quarantine")), file)
+    }
+
+    fn mode(path: &Path) -> u32 {
+        fs::symlink_metadata(path).unwrap().mode() & 0o7777
+    }
+
+    #[test]
+    fn files_go_into_quarantine_and_back() {
+        let (base, store, file) = setup("round-trip");
+        let content = fs::read(&file).unwrap();
+        let item = store.add(&file, "Win.Test.EICAR_HDB-1", "1000").unwrap();
+        let data = store.dir().join(&item.id).join(DATA_FILE);
+        let in_store = (fs::symlink_metadata(&file).is_err(), mode(&data), fs::read(&data).unwrap());
+        let listed = store.list();
+        let restored = store.restore(&item.id).unwrap();
+        let back = (fs::read(&file).unwrap(), mode(&file), store.list().0.len());
+        fs::remove_dir_all(&base).unwrap();
+
+        assert_eq!((item.original_path.as_str(), item.signature.as_str(), item.quarantined_by.as_str()), (file.to_str().unwrap(), "Win.Test.EICAR_HDB-1", "1000"));
+        assert_eq!((item.size, item.mode), (content.len() as u64, 0o640));
+        assert_eq!(in_store, (true, 0o000, content.clone()));
+        assert_eq!((listed.0, listed.1), (vec![item.clone()], Vec::<String>::new()));
+        assert_eq!(restored, item);
+        assert_eq!(back, (content, 0o640, 0));
+    }
+
+    #[test]
+    fn symlinked_files_and_directories_are_refused() {
+        let (base, store, file) = setup("symlinks");
+        let downloads = file.parent().unwrap().to_path_buf();
+        // Another user's file behind a link, and a link to the whole directory
+        symlink(&file, downloads.join("link.com")).unwrap();
+        symlink(&downloads, base.join("home/linked")).unwrap();
+        let link = store.add(&downloads.join("link.com"), "sig", "1000").unwrap_err();
+        let through_dir = store.add(&base.join("home/linked/eicar.com"), "sig", "1000").unwrap_err();
+        let relative = store.add(Path::new("eicar.com"), "sig", "1000").unwrap_err();
+        let untouched = (file.exists(), store.list().0.len());
+
+        // Swap the directory for a link to a system directory before restoring
+        let item = store.add(&file, "sig", "1000").unwrap();
+        let profile_d = base.join("etc/profile.d");
+        fs::create_dir_all(&profile_d).unwrap();
+        fs::rename(&downloads, base.join("home/Downloads.real")).unwrap();
+        symlink(&profile_d, &downloads).unwrap();
+        let swapped = store.restore(&item.id).unwrap_err();
+        let after_swap = (fs::read_dir(&profile_d).unwrap().count(), mode(&store.dir().join(&item.id).join(DATA_FILE)), store.list().0.len());
+        // Once the directory is back the file can be restored
+        fs::remove_file(&downloads).unwrap();
+        fs::rename(base.join("home/Downloads.real"), &downloads).unwrap();
+        let restored = store.restore(&item.id).map(|_| mode(&file));
+        fs::remove_dir_all(&base).unwrap();
+
+        assert_eq!(link.to_string(), format!("{}: not a regular file", downloads.join("link.com").display()));
+        assert_eq!(through_dir.kind(), io::ErrorKind::InvalidInput);
+        assert!(through_dir.to_string().ends_with("home/linked/eicar.com: its directory is reached through a symbolic link"));
+        assert_eq!(relative.to_string(), "eicar.com: not an absolute path");
+        assert_eq!(untouched, (true, 0));
+        assert!(swapped.to_string().ends_with("its directory is reached through a symbolic link"));
+        assert_eq!(after_swap, (0, 0o000, 1));
+        assert_eq!(restored.unwrap(), 0o640);
+    }
+
+    #[test]
+    fn restore_never_overwrites() {
+        let (base, store, file) = setup("overwrite");
+        let item = store.add(&file, "sig", "1000").unwrap();
+        fs::write(&file, "a new download").unwrap();
+        let error = store.restore(&item.id).unwrap_err();
+        let state = (fs::read_to_string(&file).unwrap(), mode(&store.dir().join(&item.id).join(DATA_FILE)), store.list().0.len());
+        let invalid = store.restore("../etc").unwrap_err();
+        fs::remove_dir_all(&base).unwrap();
+
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(error.to_string(), format!("{} exists, not overwriting it", file.display()));
+        assert_eq!(state, ("a new download".to_string(), 0o000, 1));
+        assert_eq!(invalid.to_string(), "invalid quarantine id: ../etc");
+    }
+
+    #[test]
+    fn schedules_are_rendered_and_read_back() {
+        let schedule = ScanSchedule { frequency: ScanFrequency::Weekly, paths: vec!["/home".to_string(), "/srv/it's here".to_string()] };
+        assert_eq!(schedule.validate(), Ok(()));
+        let content = schedule.render().unwrap();
+        assert!(content.ends_with(
+            "30 3 * * 0 root /usr/bin/clamscan --recursive --infected \
+             --log=/var/log/hardn/clamav-scan-$(date +\\%Y\\%m\\%d_\\%H\\%M\\%S).log -- '/home' '/srv/it'\\''s here' >/dev/null 2>&1\n"
+        ));
+
+        let dir = std::env::temp_dir().join(format!("hardn-malware-test-{}-schedule", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("hardn-clamav");
+        schedule.install(&path).unwrap();
+        let loaded = ScanSchedule::load(&path);
+        let daily = ScanSchedule { frequency: ScanFrequency::Daily, ..schedule.clone() };
+        daily.install(&path).unwrap();
+        let loaded_daily = ScanSchedule::load(&path);
+        let off = ScanSchedule { frequency: ScanFrequency::Off, paths: Vec::new() };
+        off.install(&path).unwrap();
+        let removed = !path.exists();
+        off.install(&path).unwrap();
+        fs::write(&path, "# edited by hand\n0 * * * * root true\n").unwrap();
+        let foreign = ScanSchedule::load(&path);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded, schedule);
+        assert_eq!(loaded_daily, daily);
+        assert!(removed);
+        assert_eq!(foreign, off);
+        assert_eq!(ScanSchedule::load(&dir.join("missing")), off);
+
+        let invalid = |paths: &[&str]| ScanSchedule { frequency: ScanFrequency::Daily, paths: paths.iter().map(|path| path.to_string()).collect() }.validate();
+        assert_eq!(invalid(&[]), Err(format!("a scheduled scan needs 1 to {} targets", MAX_SCAN_PATHS)));
+        assert_eq!(invalid(&["home"]), Err("scan target not allowed: home".to_string()));
+        assert_eq!(invalid(&["/home/../etc"]), Err("scan target not allowed: /home/../etc".to_string()));
+        assert_eq!(invalid(&["/home/100%"]), Err("scan target not allowed: /home/100%".to_string()));
+        assert!(invalid(&["/home"; MAX_SCAN_PATHS + 1]).is_err());
+    }
+}
@@ -0,0 +1,547 @@
+// Listening sockets from /proc/net/{tcp,tcp6,udp,udp6}, matched to their processes
+// through the socket inodes in /proc/<pid>/fd. One line per socket:
+//
+//   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+//    0: 00000000:0016 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 20512 1 ...
+//
+// Addresses are hex in network byte order printed as native 32-bit words, ports are
+// plain hex. Only the owner's (or root's) /proc/<pid>/fd is readable, so sockets of
+// other users' processes stay unattributed when the GUI runs unprivileged.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+
+use crate::apparmor;
+use crate::report::Severity;
+use crate::ufw::{UfwStatus, Verdict};
+
+pub const PROC_DIR: &str = "/proc";
+const PASSWD: &str = "/etc/passwd";
+
+const TCP_LISTEN: u8 = 0x0A;
+// UDP sockets have no listen state; a bound socket without a peer is "UNCONN" (TCP_CLOSE)
+const UDP_UNCONNECTED: u8 = 0x07;
+
+// The ports run_network_scan in modules/audit.sh warned about
+pub const RISKY_PORTS: &[RiskyPort] = &[
+    RiskyPort { port: 21, service: "FTP", reason: "sends credentials and data in clear text" },
+    RiskyPort { port: 23, service: "Telnet", reason: "unencrypted remote login" },
+    RiskyPort { port: 135, service: "MS RPC", reason: "Windows RPC endpoint mapper, a common worm vector" },
+    RiskyPort { port: 139, service: "NetBIOS/SMB", reason: "legacy file sharing with a long history of exploits" },
+    RiskyPort { port: 445, service: "SMB", reason: "file sharing; should never face untrusted networks" },
+    RiskyPort { port: 1433, service: "MSSQL", reason: "database server listening for remote clients" },
+    RiskyPort { port: 3389, service: "RDP", reason: "remote desktop, a frequent brute-force target" },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RiskyPort {
+    pub port: u16,
+    pub service: &'static str,
+    pub reason: &'static str,
+}
+
+pub fn risky_port(port: u16) -> Option<&'static RiskyPort> {
+    RISKY_PORTS.iter().find(|risky| risky.port == port)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl Protocol {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Udp => "udp",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Socket {
+    pub protocol: Protocol,
+    pub local: IpAddr,
+    pub port: u16,
+    pub remote: IpAddr,
+    pub remote_port: u16,
+    pub state: u8,
+    pub uid: u32,
+    pub inode: u64,
+}
+
+impl Socket {
+    pub fn is_listening(&self) -> bool {
+        match self.protocol {
+            Protocol::Tcp => self.state == TCP_LISTEN,
+            Protocol::Udp => self.state == UDP_UNCONNECTED && self.remote_port == 0 && self.remote.is_unspecified(),
+        }
+    }
+
+    pub fn is_loopback(&self) -> bool {
+        match self.local {
+            IpAddr::V6(addr) => addr.is_loopback() || addr.to_ipv4_mapped().is_some_and(|v4| v4.is_loopback()),
+            addr => addr.is_loopback(),
+        }
+    }
+
+    // "0.0.0.0:22", "[::]:22"
+    pub fn address(&self) -> String {
+        match self.local {
+            IpAddr::V4(addr) => format!("{}:{}", addr, self.port),
+            IpAddr::V6(addr) => format!("[{}]:{}", addr, self.port),
+        }
+    }
+}
+
+fn parse_address(hex: &str) -> Option<(IpAddr, u16)> {
+    let (addr, port) = hex.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    if addr.len() % 8 != 0 {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(16);
+    for word in 0..addr.len() / 8 {
+        let value = u32::from_str_radix(addr.get(word * 8..word * 8 + 8)?, 16).ok()?;
+        bytes.extend(value.to_ne_bytes());
+    }
+    let addr = match bytes.len() {
+        4 => IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(bytes).ok()?)),
+        16 => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?)),
+        _ => return None,
+    };
+    Some((addr, port))
+}
+
+// One /proc/net table; the header and malformed lines are skipped
+pub fn parse_table(protocol: Protocol, content: &str) -> Vec<Socket> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (local, port) = parse_address(fields.get(1)?)?;
+            let (remote, remote_port) = parse_address(fields.get(2)?)?;
+            Some(Socket {
+                protocol,
+                local,
+                port,
+                remote,
+                remote_port,
+                state: u8::from_str_radix(fields.get(3)?, 16).ok()?,
+                uid: fields.get(7)?.parse().ok()?,
+                inode: fields.get(9)?.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Owner {
+    pub pid: u32,
+    pub name: String,
+    pub cmdline: String,
+}
+
+// Socket inode -> owning process, and how many processes could not be inspected
+pub fn socket_owners(proc_dir: &Path) -> (HashMap<u64, Owner>, usize) {
+    let mut owners = HashMap::new();
+    let mut hidden = 0;
+    let Ok(read_dir) = fs::read_dir(proc_dir) else {
+        return (owners, hidden);
+    };
+    for entry in read_dir.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+            continue;
+        };
+        let process_dir = entry.path();
+        let Ok(fds) = fs::read_dir(process_dir.join("fd")) else {
+            hidden += 1;
+            continue;
+        };
+        let inodes: Vec<u64> = fds
+            .flatten()
+            .filter_map(|fd| fs::read_link(fd.path()).ok())
+            .filter_map(|target| {
+                let target = target.to_str()?;
+                target.strip_prefix("socket:[")?.strip_suffix(']')?.parse().ok()
+            })
+            .collect();
+        if inodes.is_empty() {
+            continue;
+        }
+        let name = fs::read_to_string(process_dir.join("comm")).unwrap_or_default().trim().to_string();
+        let cmdline = fs::read(process_dir.join("cmdline"))
+            .map(|raw| String::from_utf8_lossy(&raw).replace('\0', " ").trim().to_string())
+            .unwrap_or_default();
+        for inode in inodes {
+            owners.entry(inode).or_insert_with(|| Owner { pid, name: name.clone(), cmdline: cmdline.clone() });
+        }
+    }
+    (owners, hidden)
+}
+
+// uid -> user name from /etc/passwd
+pub fn user_names(passwd: &Path) -> HashMap<u32, String> {
+    fs::read_to_string(passwd)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid = fields.nth(1)?.parse().ok()?;
+            Some((uid, name.to_string()))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct Listener {
+    pub socket: Socket,
+    pub owner: Option<Owner>,
+    pub user: String,
+}
+
+impl Listener {
+    pub fn process(&self) -> String {
+        match &self.owner {
+            Some(owner) => format!("{} ({})", owner.name, owner.pid),
+            None => "unknown".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ListenerScan {
+    pub listeners: Vec<Listener>,
+    // Processes whose sockets we could not see (other users' without root)
+    pub hidden_processes: usize,
+    pub errors: Vec<String>,
+}
+
+// Every listening socket, ordered by port, protocol and address
+pub fn scan_listeners(proc_dir: &Path) -> ListenerScan {
+    let mut scan = ListenerScan::default();
+    let mut sockets = Vec::new();
+    for (file, protocol) in [("tcp", Protocol::Tcp), ("tcp6", Protocol::Tcp), ("udp", Protocol::Udp), ("udp6", Protocol::Udp)] {
+        let path = proc_dir.join("net").join(file);
+        match fs::read_to_string(&path) {
+            Ok(content) => sockets.extend(parse_table(protocol, &content).into_iter().filter(Socket::is_listening)),
+            // No IPv6 on this kernel
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => scan.errors.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    let (owners, hidden) = socket_owners(proc_dir);
+    scan.hidden_processes = hidden;
+    let users = user_names(Path::new(PASSWD));
+    scan.listeners = sockets
+        .into_iter()
+        .map(|socket| Listener {
+            owner: owners.get(&socket.inode).cloned(),
+            user: users.get(&socket.uid).cloned().unwrap_or_else(|| socket.uid.to_string()),
+            socket,
+        })
+        .collect();
+    scan.listeners.sort_by_key(|listener| (listener.socket.port, listener.socket.protocol, listener.socket.local));
+    scan
+}
+
+// A process with listening sockets, each as "tcp 0.0.0.0:631"
+#[derive(Debug, Clone)]
+pub struct ProcessSockets {
+    pub owner: Owner,
+    pub user: String,
+    pub sockets: Vec<String>,
+}
+
+// Listening processes that AppArmor labels "unconfined", by pid, and how many
+// sockets had no visible owner to check
+pub fn unconfined_processes(proc_dir: &Path, scan: &ListenerScan) -> (Vec<ProcessSockets>, usize) {
+    let mut processes: BTreeMap<u32, ProcessSockets> = BTreeMap::new();
+    let mut unknown = 0;
+    for listener in &scan.listeners {
+        let Some(owner) = &listener.owner else {
+            unknown += 1;
+            continue;
+        };
+        if apparmor::process_label(proc_dir, owner.pid).as_deref() != Some("unconfined") {
+            continue;
+        }
+        processes
+            .entry(owner.pid)
+            .or_insert_with(|| ProcessSockets { owner: owner.clone(), user: listener.user.clone(), sockets: Vec::new() })
+            .sockets
+            .push(format!("{} {}", listener.socket.protocol.label(), listener.socket.address()));
+    }
+    (processes.into_values().collect(), unknown)
+}
+
+// How reachable a listener is and why
+#[derive(Debug, Clone)]
+pub struct Exposure {
+    pub severity: Severity,
+    pub firewall: String,
+    pub evidence: Vec<String>,
+}
+
+// Without `firewall` (not loaded yet) reachability from outside is unknown
+pub fn assess(listener: &Listener, firewall: Option<&UfwStatus>) -> Exposure {
+    let socket = &listener.socket;
+    let mut evidence = vec![format!(
+        "{} {} listening, socket inode {}, owned by {} as {}",
+        socket.protocol.label(),
+        socket.address(),
+        socket.inode,
+        listener.process(),
+        listener.user
+    )];
+    if let Some(owner) = listener.owner.as_ref().filter(|owner| !owner.cmdline.is_empty()) {
+        evidence.push(format!("command: {}", owner.cmdline));
+    }
+
+    let verdict = firewall.map(|status| {
+        let dual_stack = socket.local == IpAddr::V6(Ipv6Addr::UNSPECIFIED);
+        status.verdict(socket.port, socket.protocol, socket.local.is_ipv6(), dual_stack)
+    });
+    let firewall_text = if socket.is_loopback() {
+        "loopback only".to_string()
+    } else {
+        match &verdict {
+            Some(verdict) => verdict.describe(),
+            None => "not checked".to_string(),
+        }
+    };
+    if let Some(verdict) = verdict.as_ref().filter(|_| !socket.is_loopback()) {
+        evidence.push(format!("UFW: {}", verdict.describe()));
+    }
+
+    let reachable = !socket.is_loopback() && verdict.as_ref().map(Verdict::is_open).unwrap_or(true);
+    let severity = match risky_port(socket.port) {
+        Some(risky) => {
+            evidence.push(format!("port {} is {}: {}", risky.port, risky.service, risky.reason));
+            if socket.is_loopback() {
+                Severity::Low
+            } else if reachable {
+                Severity::High
+            } else {
+                Severity::Medium
+            }
+        }
+        None if reachable && verdict.is_some() => Severity::Low,
+        None => Severity::Pass,
+    };
+    Exposure { severity, firewall: firewall_text, evidence }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // network-root has the four tables with listeners on wildcard, specific, loopback
+    // and v4-mapped addresses, connected sockets and malformed lines, and no processes
+    fn network_root() -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/network-root/proc")
+    }
+
+    fn table(protocol: Protocol, file: &str) -> Vec<Socket> {
+        parse_table(protocol, &fs::read_to_string(network_root().join("net").join(file)).unwrap())
+    }
+
+    fn listener(address: &str, protocol: Protocol) -> Listener {
+        let (local, port) = match address.parse::<std::net::SocketAddr>().unwrap() {
+            std::net::SocketAddr::V4(addr) => (IpAddr::V4(*addr.ip()), addr.port()),
+            std::net::SocketAddr::V6(addr) => (IpAddr::V6(*addr.ip()), addr.port()),
+        };
+        let unspecified = if local.is_ipv4() { IpAddr::V4(Ipv4Addr::UNSPECIFIED) } else { IpAddr::V6(Ipv6Addr::UNSPECIFIED) };
+        let socket = Socket { protocol, local, port, remote: unspecified, remote_port: 0, state: TCP_LISTEN, uid: 0, inode: 4100 };
+        let owner = Owner { pid: 900, name: "smbd".to_string(), cmdline: "/usr/sbin/smbd --foreground".to_string() };
+        Listener { socket, owner: Some(owner), user: "root".to_string() }
+    }
+
+    fn firewall(active: bool) -> UfwStatus {
+        let status = "Status: active\nDefault: deny (incoming), allow (outgoing), disabled (routed)\n\n\
+                      To                         Action      From\n\
+                      --                         ------      ----\n\
+                      22/tcp                     LIMIT IN    Anywhere\n\
+                      445/tcp                    DENY IN     Anywhere\n\
+                      3389/tcp (v6)              ALLOW IN    Anywhere (v6)\n";
+        let status = if active { status.to_string() } else { status.replace("Status: active", "Status: inactive") };
+        UfwStatus::parse(&status, &BTreeMap::new())
+    }
+
+    #[test]
+    fn addresses_are_little_endian_words() {
+        let ip = |text: &str| text.parse::<IpAddr>().unwrap();
+        assert_eq!(parse_address("0100007F:0016"), Some((ip("127.0.0.1"), 22)));
+        assert_eq!(parse_address("0A01A8C0:CB92"), Some((ip("192.168.1.10"), 52114)));
+        assert_eq!(parse_address("00000000:0000"), Some((ip("0.0.0.0"), 0)));
+        assert_eq!(parse_address("00000000000000000000000001000000:0277"), Some((ip("::1"), 631)));
+        assert_eq!(parse_address("0000000000000000FFFF00000100007F:1F90"), Some((ip("::ffff:127.0.0.1"), 8080)));
+        assert_eq!(parse_address("B80D0120000000000000000005000000:FFFF"), Some((ip("2001:db8::5"), 65535)));
+        assert_eq!(parse_address("000080FE00000000FFA42F1C105D3BFE:0222"), Some((ip("fe80::1c2f:a4ff:fe3b:5d10"), 546)));
+
+        for bad in ["", "0100007F", "0100007F:", "0100007F:10000", "0100007G:0016", "100007F:0016", "0100007F0:0016", "0100007F0100007F:0016"] {
+            assert_eq!(parse_address(bad), None, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn proc_net_tables() {
+        let tcp = table(Protocol::Tcp, "tcp");
+        // The header and the garbage line are skipped
+        assert_eq!(tcp.len(), 5);
+        let established = &tcp[4];
+        assert_eq!(established.address(), "192.168.1.10:22");
+        assert_eq!((established.remote, established.remote_port), ("203.0.113.7".parse().unwrap(), 52114));
+        assert_eq!((established.state, established.uid, established.inode), (0x01, 0, 4012));
+        assert!(!established.is_listening());
+        assert_eq!(tcp[2].uid, 1000);
+        assert!(tcp[2].is_loopback() && !tcp[0].is_loopback());
+
+        // The address with a 33rd digit is malformed; TIME_WAIT is no listener
+        let tcp6 = table(Protocol::Tcp, "tcp6");
+        let states: Vec<(String, u8, bool)> = tcp6.iter().map(|socket| (socket.address(), socket.state, socket.is_listening())).collect();
+        assert_eq!(
+            states,
+            [
+                ("[::]:22".to_string(), 0x0A, true),
+                ("[::1]:631".to_string(), 0x0A, true),
+                ("[::ffff:127.0.0.1]:8080".to_string(), 0x0A, true),
+                ("[2001:db8::5]:445".to_string(), 0x06, false),
+            ]
+        );
+        assert!(tcp6[1].is_loopback() && tcp6[2].is_loopback() && !tcp6[0].is_loopback());
+
+        // UDP listens when bound without a peer; a connected DHCP client does not
+        let udp = table(Protocol::Udp, "udp");
+        let listening: Vec<bool> = udp.iter().map(Socket::is_listening).collect();
+        assert_eq!(listening, [true, true, false]);
+        assert_eq!(udp[2].remote_port, 67);
+        let udp6 = table(Protocol::Udp, "udp6");
+        assert_eq!(udp6.iter().map(Socket::address).collect::<Vec<_>>(), ["[::]:5353", "[fe80::1c2f:a4ff:fe3b:5d10]:546"]);
+        assert!(udp6.iter().all(Socket::is_listening));
+
+        assert!(parse_table(Protocol::Tcp, "").is_empty());
+        assert!(parse_table(Protocol::Tcp, "   0: 0100007F:0016 00000000:0000 0A 00000000:00000000 00:00000000 00000000 0 0 1\n").is_empty());
+    }
+
+    #[test]
+    fn listeners_of_all_tables() {
+        let scan = scan_listeners(&network_root());
+        assert!(scan.errors.is_empty(), "{:?}", scan.errors);
+        let listeners: Vec<String> =
+            scan.listeners.iter().map(|listener| format!("{} {}", listener.socket.protocol.label(), listener.socket.address())).collect();
+        assert_eq!(
+            listeners,
+            [
+                "tcp 192.168.1.10:21",
+                "tcp 0.0.0.0:22",
+                "tcp [::]:22",
+                "udp 127.0.0.53:53",
+                "tcp 0.0.0.0:445",
+                "udp [fe80::1c2f:a4ff:fe3b:5d10]:546",
+                "tcp [::1]:631",
+                "tcp 127.0.0.1:3389",
+                "udp 0.0.0.0:5353",
+                "udp [::]:5353",
+                "tcp [::ffff:127.0.0.1]:8080",
+            ]
+        );
+        // No process directories to match the inodes against
+        assert!(scan.listeners.iter().all(|listener| listener.owner.is_none() && listener.process() == "unknown"));
+        assert_eq!(scan.hidden_processes, 0);
+    }
+
+    #[test]
+    fn risky_ports_are_rated_by_reachability() {
+        let rate = |address: &str, firewall: Option<&UfwStatus>| {
+            let exposure = assess(&listener(address, Protocol::Tcp), firewall);
+            (exposure.severity, exposure.firewall)
+        };
+        let active = firewall(true);
+        let inactive = firewall(false);
+
+        // SMB blocked by a rule, also for the IPv6 wildcard which takes IPv4 as well
+        let blocked = assess(&listener("0.0.0.0:445", Protocol::Tcp), Some(&active));
+        assert_eq!(blocked.severity, Severity::Medium);
+        assert_eq!(
+            blocked.evidence,
+            [
+                "tcp 0.0.0.0:445 listening, socket inode 4100, owned by smbd (900) as root",
+                "command: /usr/sbin/smbd --foreground",
+                "UFW: blocked: 445/tcp DENY IN from Anywhere",
+                "port 445 is SMB: file sharing; should never face untrusted networks",
+            ]
+        );
+        assert_eq!(rate("[::]:445", Some(&active)).0, Severity::Medium);
+        assert_eq!(rate("[2001:db8::5]:445", Some(&active)), (Severity::Medium, "default deny (incoming)".to_string()));
+
+        // Reachable: UFW off, no firewall status yet, or allowed by a rule
+        assert_eq!(rate("0.0.0.0:445", Some(&inactive)), (Severity::High, "UFW inactive".to_string()));
+        assert_eq!(rate("192.168.1.10:21", None), (Severity::High, "not checked".to_string()));
+        assert_eq!(rate("[::]:3389", Some(&active)), (Severity::High, "allowed: 3389/tcp (v6) ALLOW IN from Anywhere (v6)".to_string()));
+        // Only the IPv6 rule allows RDP, IPv4 falls to the default
+        assert_eq!(rate("0.0.0.0:3389", Some(&active)).0, Severity::Medium);
+
+        // Loopback is never reachable and the firewall is not consulted
+        let loopback = assess(&listener("127.0.0.1:3389", Protocol::Tcp), Some(&active));
+        assert_eq!((loopback.severity, loopback.firewall.as_str()), (Severity::Low, "loopback only"));
+        assert!(!loopback.evidence.iter().any(|line| line.starts_with("UFW:")));
+        assert_eq!(rate("[::ffff:127.0.0.1]:23", Some(&inactive)).0, Severity::Low);
+
+        // Other ports only matter when the firewall lets them through
+        assert_eq!(rate("0.0.0.0:22", Some(&active)).0, Severity::Low);
+        assert_eq!(rate("0.0.0.0:8080", Some(&active)).0, Severity::Pass);
+        assert_eq!(rate("0.0.0.0:8080", None).0, Severity::Pass);
+        let evidence = assess(&listener("0.0.0.0:8080", Protocol::Tcp), Some(&active)).evidence;
+        assert!(!evidence.iter().any(|line| line.starts_with("port ")));
+
+        assert_eq!(risky_port(23).map(|risky| risky.service), Some("Telnet"));
+        assert_eq!(risky_port(22), None);
+    }
+
+    #[test]
+    fn unconfined_listeners_are_grouped_by_process() {
+        let proc_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/apparmor-root/proc");
+        let scan = scan_listeners(&proc_dir);
+        assert!(scan.errors.is_empty(), "{:?}", scan.errors);
+        // The established connection is not a listener, pid 4000 has no readable fds
+        let listeners: Vec<String> = scan
+            .listeners
+            .iter()
+            .map(|listener| format!("{} {} {}", listener.socket.protocol.label(), listener.socket.address(), listener.process()))
+            .collect();
+        assert_eq!(
+            listeners,
+            [
+                "tcp 0.0.0.0:631 cupsd (812)",
+                "udp 0.0.0.0:5353 avahi-daemon (700)",
+                "tcp 0.0.0.0:5432 unknown",
+                "tcp 127.0.0.1:6379 redis-server (2600)",
+                "tcp 0.0.0.0:8080 python3 (2500)",
+                "tcp [::]:8080 python3 (2500)",
+            ]
+        );
+        assert_eq!(scan.hidden_processes, 1);
+
+        let (processes, unknown) = unconfined_processes(&proc_dir, &scan);
+        let processes: Vec<(u32, &str, Vec<&str>)> = processes
+            .iter()
+            .map(|process| (process.owner.pid, process.owner.cmdline.as_str(), process.sockets.iter().map(String::as_str).collect()))
+            .collect();
+        assert_eq!(
+            processes,
+            [
+                (700, "avahi-daemon: running [host.local]", vec!["udp 0.0.0.0:5353"]),
+                (2500, "python3 -m http.server 8080", vec!["tcp 0.0.0.0:8080", "tcp [::]:8080"]),
+                (2600, "/usr/bin/redis-server 127.0.0.1:6379", vec!["tcp 127.0.0.1:6379"]),
+            ]
+        );
+        assert_eq!(unknown, 1);
+    }
+}
@@ -0,0 +1,638 @@
+// Rendering of command output for the output panes: ANSI SGR escape sequences become
+// styled spans and every line is classified by log level, from HARDN's "[LEVEL]"
+// prefixes (modules/logging.sh, tools/functions.sh), apt's "E:"/"W:" and lynis'
+// "[ WARNING ]" result column. Other escape and control sequences are dropped.
+// Rendered lines are also kept as timestamped Records so a pane can be saved.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::report::format_timestamp;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Debug,
+    Info,
+    Pass,
+    Warn,
+    Error,
+    // Lines without any level marker
+    Plain,
+}
+
+impl Level {
+    pub const ALL: [Level; 6] = [Level::Error, Level::Warn, Level::Pass, Level::Info, Level::Debug, Level::Plain];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Level::Debug => "Debug",
+            Level::Info => "Info",
+            Level::Pass => "OK",
+            Level::Warn => "Warnings",
+            Level::Error => "Errors",
+            Level::Plain => "Other",
+        }
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Level::Debug => "debug",
+            Level::Info => "info",
+            Level::Pass => "pass",
+            Level::Warn => "warn",
+            Level::Error => "error",
+            Level::Plain => "plain",
+        }
+    }
+
+    // Foreground color for the whole line, if the level has one
+    pub fn color(&self) -> Option<&'static str> {
+        match self {
+            Level::Debug => Some("#77767b"),
+            Level::Pass => Some("#26a269"),
+            Level::Warn => Some("#c64600"),
+            Level::Error => Some("#c01c28"),
+            Level::Info | Level::Plain => None,
+        }
+    }
+
+    pub fn is_problem(&self) -> bool {
+        matches!(self, Level::Warn | Level::Error)
+    }
+
+    fn from_name(name: &str) -> Option<Level> {
+        match name.trim().to_ascii_uppercase().as_str() {
+            "DEBUG" => Some(Level::Debug),
+            "INFO" => Some(Level::Info),
+            "PASS" | "OK" | "SUCCESS" => Some(Level::Pass),
+            "WARN" | "WARNING" => Some(Level::Warn),
+            "ERROR" | "FAIL" | "FAILED" | "FATAL" | "CRITICAL" => Some(Level::Error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    // xterm 256-color palette index
+    Palette(u8),
+    Rgb(u8, u8, u8),
+}
+
+// Standard and bright ANSI colors, GNOME terminal palette
+const ANSI_COLORS: [(u8, u8, u8); 16] = [
+    (0x17, 0x14, 0x21),
+    (0xc0, 0x1c, 0x28),
+    (0x26, 0xa2, 0x69),
+    (0xa2, 0x73, 0x4c),
+    (0x12, 0x48, 0x8b),
+    (0xa3, 0x47, 0xba),
+    (0x2a, 0xa1, 0xb3),
+    (0xd0, 0xcf, 0xcc),
+    (0x5e, 0x5c, 0x64),
+    (0xf6, 0x61, 0x51),
+    (0x33, 0xd1, 0x7a),
+    (0xe9, 0xad, 0x0c),
+    (0x2a, 0x7b, 0xde),
+    (0xc0, 0x61, 0xcb),
+    (0x33, 0xc7, 0xde),
+    (0xff, 0xff, 0xff),
+];
+
+impl Color {
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        match *self {
+            Color::Rgb(r, g, b) => (r, g, b),
+            Color::Palette(index @ 0..=15) => ANSI_COLORS[index as usize],
+            // 6x6x6 color cube
+            Color::Palette(index @ 16..=231) => {
+                let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+                let index = index - 16;
+                (level(index / 36), level(index / 6 % 6), level(index % 6))
+            }
+            // Grayscale ramp
+            Color::Palette(index) => {
+                let gray = 8 + (index - 232) * 10;
+                (gray, gray, gray)
+            }
+        }
+    }
+
+    pub fn hex(&self) -> String {
+        let (r, g, b) = self.rgb();
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl Style {
+    pub fn is_plain(&self) -> bool {
+        *self == Style::default()
+    }
+
+    // Stable name, so equal styles share one TextTag
+    pub fn tag_name(&self) -> String {
+        let mut name = String::from("sgr");
+        if let Some(fg) = self.fg {
+            name.push_str(&format!(":fg{}", fg.hex()));
+        }
+        if let Some(bg) = self.bg {
+            name.push_str(&format!(":bg{}", bg.hex()));
+        }
+        for (set, flag) in [(self.bold, ":b"), (self.dim, ":d"), (self.italic, ":i"), (self.underline, ":u")] {
+            if set {
+                name.push_str(flag);
+            }
+        }
+        name
+    }
+
+    // Apply the parameters of one "ESC [ ... m" sequence. Parameters are separated by
+    // ';', a ':' group such as "38:2::r:g:b" or "4:3" is one parameter with its own
+    // sub-parameters.
+    fn apply_sgr(&mut self, params: &str) {
+        let mut groups = params.split(';');
+
+        while let Some(group) = groups.next() {
+            let mut parts = group.split(':').map(|part| part.parse::<u16>().unwrap_or(0));
+            let code = parts.next().unwrap_or(0);
+            let colon = group.contains(':');
+            match code {
+                0 => *self = Style::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                // "4:0" turns underlining off, "4:1" to "4:5" pick its shape
+                4 => self.underline = parts.next() != Some(0),
+                22 => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                23 => self.italic = false,
+                24 => self.underline = false,
+                30..=37 => self.fg = Some(Color::Palette((code - 30) as u8)),
+                38 if colon => self.fg = sub_color(parts),
+                38 => self.fg = extended_color(&mut groups.by_ref().map(|group| group.parse().unwrap_or(0))),
+                39 => self.fg = None,
+                40..=47 => self.bg = Some(Color::Palette((code - 40) as u8)),
+                48 if colon => self.bg = sub_color(parts),
+                48 => self.bg = extended_color(&mut groups.by_ref().map(|group| group.parse().unwrap_or(0))),
+                49 => self.bg = None,
+                90..=97 => self.fg = Some(Color::Palette((code - 90 + 8) as u8)),
+                100..=107 => self.bg = Some(Color::Palette((code - 100 + 8) as u8)),
+                _ => {}
+            }
+        }
+    }
+}
+
+// "38;5;n" or "38;2;r;g;b", after the 38/48 was consumed
+fn extended_color(codes: &mut impl Iterator<Item = u16>) -> Option<Color> {
+    let channel = |codes: &mut dyn Iterator<Item = u16>| codes.next().map(|value| value.min(255) as u8);
+    match codes.next()? {
+        5 => channel(codes).map(Color::Palette),
+        2 => Some(Color::Rgb(channel(codes)?, channel(codes)?, channel(codes)?)),
+        _ => None,
+    }
+}
+
+// "38:5:n", "38:2:r:g:b" or the ITU form "38:2:<color space>:r:g:b"
+fn sub_color(parts: impl Iterator<Item = u16>) -> Option<Color> {
+    let parts: Vec<u16> = parts.collect();
+    match parts.as_slice() {
+        [2, _, r, g, b] | [2, r, g, b] => extended_color(&mut [2, *r, *g, *b].into_iter()),
+        _ => extended_color(&mut parts.into_iter()),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub level: Level,
+    pub spans: Vec<Span>,
+}
+
+impl Line {
+    // A line generated by the GUI itself, e.g. a job's final status
+    pub fn plain(level: Level, text: &str) -> Self {
+        Line {
+            level,
+            spans: vec![Span {
+                text: text.to_string(),
+                style: Style::default(),
+            }],
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+}
+
+// Turns raw output lines into Lines. SGR state carries over from one line to the
+// next like in a terminal, so use one renderer per job.
+#[derive(Debug, Default)]
+pub struct Renderer {
+    style: Style,
+}
+
+impl Renderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reset(&mut self) {
+        self.style = Style::default();
+    }
+
+    pub fn render(&mut self, stream: Stream, raw: &str) -> Line {
+        // A carriage return redraws the line on a terminal (apt, wget progress)
+        let raw = raw.trim_end_matches('\r');
+        let raw = raw.rsplit('\r').next().unwrap_or(raw);
+        let mut line = Line {
+            level: Level::Plain,
+            spans: self.parse(raw),
+        };
+        line.level = detect_level(&line.text()).unwrap_or(match stream {
+            // Unmarked stderr output is usually a diagnostic
+            Stream::Stderr => Level::Warn,
+            Stream::Stdout => Level::Plain,
+        });
+        line
+    }
+
+    fn parse(&mut self, raw: &str) -> Vec<Span> {
+        let mut spans: Vec<Span> = Vec::new();
+        let mut text = String::new();
+        let mut chars = raw.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\u{1b}' => match chars.next() {
+                    // CSI: parameters, intermediates, final byte in '@'..='~'
+                    Some('[') => {
+                        let mut params = String::new();
+                        let mut terminator = None;
+                        for c in chars.by_ref() {
+                            if ('@'..='~').contains(&c) {
+                                terminator = Some(c);
+                                break;
+                            }
+                            params.push(c);
+                        }
+                        if terminator == Some('m') {
+                            flush(&mut spans, &mut text, self.style);
+                            self.style.apply_sgr(&params);
+                        }
+                    }
+                    // OSC (window titles, hyperlinks): up to BEL or ESC '\'
+                    Some(']') => {
+                        while let Some(c) = chars.next() {
+                            if c == '\u{7}' {
+                                break;
+                            }
+                            if c == '\u{1b}' && chars.peek() == Some(&'\\') {
+                                chars.next();
+                                break;
+                            }
+                        }
+                    }
+                    // Charset selection takes one more character
+                    Some('(') | Some(')') => {
+                        chars.next();
+                    }
+                    _ => {}
+                },
+                '\t' => text.push(c),
+                c if c.is_control() => {}
+                c => text.push(c),
+            }
+        }
+        flush(&mut spans, &mut text, self.style);
+
+        if spans.is_empty() {
+            spans.push(Span {
+                text: String::new(),
+                style: Style::default(),
+            });
+        }
+        spans
+    }
+}
+
+fn flush(spans: &mut Vec<Span>, text: &mut String, style: Style) {
+    if text.is_empty() {
+        return;
+    }
+    match spans.last_mut() {
+        Some(last) if last.style == style => last.text.push_str(text),
+        _ => spans.push(Span {
+            text: text.clone(),
+            style,
+        }),
+    }
+    text.clear();
+}
+
+// Level of a line with escape sequences already removed
+pub fn detect_level(text: &str) -> Option<Level> {
+    let trimmed = text.trim();
+
+    // "[INFO] ...", also after a "[2024-01-01 12:00:00]" timestamp
+    let mut rest = trimmed;
+    for _ in 0..2 {
+        let Some(inner) = rest.strip_prefix('[') else {
+            break;
+        };
+        let Some(end) = inner.find(']') else {
+            break;
+        };
+        if let Some(level) = Level::from_name(&inner[..end]) {
+            let message = inner[end + 1..].trim_start();
+            // hardn_status logs passed checks as "[INFO] OK ..."
+            if level == Level::Info && message.starts_with("OK ") {
+                return Some(Level::Pass);
+            }
+            return Some(level);
+        }
+        rest = inner[end + 1..].trim_start();
+    }
+
+    // apt and dpkg
+    if trimmed.starts_with("E: ") {
+        return Some(Level::Error);
+    }
+    if trimmed.starts_with("W: ") {
+        return Some(Level::Warn);
+    }
+
+    // lynis result column, e.g. "- Checking PAM password strength tools   [ WARNING ]"
+    if let Some(inner) = trimmed.strip_suffix(']').and_then(|head| head.rsplit_once('[')).map(|(_, inner)| inner) {
+        if inner.trim() == "WARNING" {
+            return Some(Level::Warn);
+        }
+    }
+
+    None
+}
+
+// A rendered line as saved from a pane; lines from the GUI itself have no stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Record {
+    pub time: String,
+    pub unix_ms: u64,
+    pub level: Level,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<Stream>,
+    pub text: String,
+}
+
+impl Record {
+    pub fn new(line: &Line, stream: Option<Stream>) -> Self {
+        let unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        Record {
+            time: format_timestamp(unix_ms / 1_000),
+            unix_ms,
+            level: line.level,
+            stream,
+            text: line.text(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveFormat {
+    Text,
+    JsonLines,
+}
+
+impl SaveFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SaveFormat::Text => "log",
+            SaveFormat::JsonLines => "jsonl",
+        }
+    }
+
+    pub fn format(&self, records: &[Record]) -> String {
+        let mut content = String::new();
+        for record in records {
+            match self {
+                SaveFormat::Text => {
+                    content.push_str(&format!("{} [{}] {}\n", record.time, record.level.key().to_uppercase(), record.text));
+                }
+                SaveFormat::JsonLines => {
+                    content.push_str(&serde_json::to_string(record).unwrap_or_default());
+                    content.push('\n');
+                }
+            }
+        }
+        content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    // logging-sh.txt and hardn-log.txt were recorded from modules/logging.sh on a
+    // terminal and in its log file, apt-get.txt from `apt-get update` without network
+    // on a terminal (with its carriage return redraws). lynis.txt follows lynis'
+    // Display layout: cursor-forward indents and a colored result column.
+    fn fixture(name: &str) -> String {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/output").join(name);
+        std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+    }
+
+    fn render(stream: Stream, name: &str) -> Vec<(Level, String)> {
+        let mut renderer = Renderer::new();
+        fixture(name)
+            .lines()
+            .map(|raw| renderer.render(stream, raw))
+            .map(|line| (line.level, line.text()))
+            .collect()
+    }
+
+    fn sgr(params: &[&str]) -> Style {
+        let mut style = Style::default();
+        for params in params {
+            style.apply_sgr(params);
+        }
+        style
+    }
+
+    #[test]
+    fn reset_and_attribute_codes() {
+        let style = sgr(&["1;2;3;4;31;42"]);
+        assert!(style.bold && style.dim && style.italic && style.underline);
+        assert_eq!(style.fg, Some(Color::Palette(1)));
+        assert_eq!(style.bg, Some(Color::Palette(2)));
+
+        assert!(sgr(&["1;31", "0"]).is_plain());
+        // An empty parameter list is a reset too
+        assert!(sgr(&["1;31", ""]).is_plain());
+        assert_eq!(sgr(&["1;2;3;4", "22"]), Style { italic: true, underline: true, ..Style::default() });
+        assert_eq!(sgr(&["3;4", "23"]), Style { underline: true, ..Style::default() });
+        assert_eq!(sgr(&["3;4", "24"]), Style { italic: true, ..Style::default() });
+        assert_eq!(sgr(&["31;42", "39"]).fg, None);
+        assert_eq!(sgr(&["31;42", "49"]).bg, None);
+    }
+
+    #[test]
+    fn extended_colors() {
+        assert_eq!(sgr(&["38;5;208"]).fg, Some(Color::Palette(208)));
+        assert_eq!(sgr(&["48;5;21"]).bg, Some(Color::Palette(21)));
+        assert_eq!(sgr(&["38;2;255;128;0"]).fg, Some(Color::Rgb(255, 128, 0)));
+        assert_eq!(sgr(&["48;2;1;2;3"]).bg, Some(Color::Rgb(1, 2, 3)));
+        // The color takes its parameters, the rest still apply
+        assert_eq!(sgr(&["38;5;208;1"]), Style { fg: Some(Color::Palette(208)), bold: true, ..Style::default() });
+        assert_eq!(
+            sgr(&["38;2;10;20;30;4"]),
+            Style { fg: Some(Color::Rgb(10, 20, 30)), underline: true, ..Style::default() }
+        );
+        // Channels are clamped, truncated sequences give no color
+        assert_eq!(sgr(&["38;2;300;0;0"]).fg, Some(Color::Rgb(255, 0, 0)));
+        assert_eq!(sgr(&["31", "38;2;1;2"]).fg, None);
+        assert_eq!(sgr(&["31", "38;7;1"]).fg, None);
+
+        assert_eq!(Color::Palette(208).hex(), "#ff8700");
+        assert_eq!(Color::Palette(16).hex(), "#000000");
+        assert_eq!(Color::Palette(244).hex(), "#808080");
+    }
+
+    #[test]
+    fn bright_colors() {
+        for code in 90..=97u8 {
+            assert_eq!(sgr(&[&code.to_string()]).fg, Some(Color::Palette(code - 90 + 8)));
+        }
+        for code in 100..=107u8 {
+            assert_eq!(sgr(&[&code.to_string()]).bg, Some(Color::Palette(code - 100 + 8)));
+        }
+        assert_eq!(Color::Palette(9).hex(), "#f66151");
+        assert_eq!(Color::Palette(15).hex(), "#ffffff");
+    }
+
+    #[test]
+    fn colon_separated_parameters() {
+        assert_eq!(sgr(&["38:5:208"]).fg, Some(Color::Palette(208)));
+        assert_eq!(sgr(&["38:2:255:128:0"]).fg, Some(Color::Rgb(255, 128, 0)));
+        // ITU T.416 form with an (empty) color space id
+        assert_eq!(sgr(&["38:2::255:128:0"]).fg, Some(Color::Rgb(255, 128, 0)));
+        assert_eq!(sgr(&["48:2:0:1:2:3"]).bg, Some(Color::Rgb(1, 2, 3)));
+        // Sub-parameters stay within their group
+        assert_eq!(sgr(&["38:5:208;1"]), Style { fg: Some(Color::Palette(208)), bold: true, ..Style::default() });
+        assert!(sgr(&["4:3"]).underline);
+        assert!(!sgr(&["4", "4:0"]).underline);
+    }
+
+    #[test]
+    fn spans_follow_the_escape_sequences() {
+        let mut renderer = Renderer::new();
+        let line = renderer.render(Stream::Stderr, fixture("logging-sh.txt").lines().nth(3).unwrap());
+        let yellow = Style { fg: Some(Color::Palette(3)), bold: true, ..Style::default() };
+        assert_eq!(
+            line.spans,
+            vec![
+                Span { text: "[WARN]".to_string(), style: yellow },
+                Span { text: " WARNING Fail2Ban is not installed".to_string(), style: Style::default() },
+            ]
+        );
+
+        // Styles carry over to the next line until reset
+        let mut renderer = Renderer::new();
+        renderer.render(Stream::Stdout, "\u{1b}[1;31mfirst");
+        assert_eq!(renderer.render(Stream::Stdout, "second").spans[0].style.fg, Some(Color::Palette(1)));
+        renderer.reset();
+        assert!(renderer.render(Stream::Stdout, "third").spans[0].style.is_plain());
+
+        // OSC titles and other control sequences are dropped
+        let line = renderer.render(Stream::Stdout, "\u{1b}]0;title\u{7}\u{1b}(Bdone\u{1b}[K\u{8}");
+        assert_eq!(line.text(), "done");
+    }
+
+    #[test]
+    fn logging_sh_levels() {
+        let expected = [
+            (Level::Debug, "[DEBUG] Executing: systemctl is-active auditd"),
+            (Level::Info, "[INFO] Configuring UFW firewall"),
+            (Level::Pass, "[INFO] OK UFW is enabled"),
+            (Level::Warn, "[WARN] WARNING Fail2Ban is not installed"),
+            (Level::Error, "[ERROR] ERROR Failed to load AppArmor profiles"),
+        ];
+        let lines = render(Stream::Stderr, "logging-sh.txt");
+        assert_eq!(lines, expected.map(|(level, text)| (level, text.to_string())));
+
+        // The log file has a timestamp before the level
+        let levels: Vec<Level> = render(Stream::Stdout, "hardn-log.txt").into_iter().map(|(level, _)| level).collect();
+        assert_eq!(levels, expected.map(|(level, _)| level));
+    }
+
+    #[test]
+    fn apt_levels_and_redraws() {
+        let lines = render(Stream::Stdout, "apt-get.txt");
+        assert_eq!(lines.len(), 10);
+        assert_eq!(lines[0], (Level::Plain, "Err:3 http://deb.debian.org/debian bookworm InRelease".to_string()));
+        assert_eq!(lines[1], (Level::Plain, "  Could not resolve 'deb.debian.org'".to_string()));
+        assert_eq!(lines[4], (Level::Plain, "Reading package lists... Done".to_string()));
+        assert_eq!(lines[8], (Level::Plain, "Building dependency tree... Done".to_string()));
+        for index in [5, 6, 7] {
+            assert_eq!(lines[index].0, Level::Warn, "{}", lines[index].1);
+            assert!(lines[index].1.starts_with("W: "));
+        }
+        assert_eq!(lines[9], (Level::Error, "E: Unable to locate package nonexistent-pkg-xyz".to_string()));
+
+        // Unmarked stderr output counts as a warning
+        assert_eq!(Renderer::new().render(Stream::Stderr, "dpkg-preconfigure: unable to re-open stdin").level, Level::Warn);
+    }
+
+    #[test]
+    fn lynis_result_column() {
+        let lines = render(Stream::Stdout, "lynis.txt");
+        let warnings: Vec<&str> = lines
+            .iter()
+            .filter(|(level, _)| *level == Level::Warn)
+            .map(|(_, text)| text.as_str())
+            .collect();
+        assert_eq!(warnings, ["- Checking for empty ruleset [ WARNING ]"]);
+        assert!(lines.iter().any(|(level, text)| *level == Level::Plain && text == "[+] Boot and services"));
+        assert!(lines.iter().any(|(level, text)| *level == Level::Plain && text.ends_with("[ SUGGESTION ]")));
+    }
+
+    #[test]
+    fn level_names() {
+        assert_eq!(detect_level("[ warning ] lowercase"), Some(Level::Warn));
+        assert_eq!(detect_level("  [FATAL] cannot continue"), Some(Level::Error));
+        assert_eq!(detect_level("[PASS] AppArmor loaded"), Some(Level::Pass));
+        assert_eq!(detect_level("[2024-01-01 12:00:00] [SUCCESS] done"), Some(Level::Pass));
+        assert_eq!(detect_level("[sshd] not a level"), None);
+        assert_eq!(detect_level("Error: lowercase words are not markers"), None);
+        assert_eq!(detect_level("E:no space"), None);
+    }
+}
@@ -0,0 +1,46 @@
+use std::io::{self, Write};
+use std::process::{Child, Command, Stdio};
+
+use crate::protocol::{HelperRequest, EXIT_BAD_REQUEST, EXIT_REJECTED};
+
+pub const HELPER_PATH: &str = "/usr/lib/hardn/hardn-gui-helper";
+
+// Override the helper location when running from a development build
+const HELPER_ENV: &str = "HARDN_GUI_HELPER";
+
+fn helper_path() -> String {
+    std::env::var(HELPER_ENV).unwrap_or_else(|_| HELPER_PATH.to_string())
+}
+
+// Start the helper through pkexec and hand it the request. Polkit shows its own
+// authentication prompt; output is read from the returned child like any other job.
+// The child's stdin stays open: it is how the job is cancelled (see jobs.rs).
+pub fn spawn_elevated(request: &HelperRequest) -> io::Result<Child> {
+    request
+        .validate()
+        .map_err(|e| io::Error::new(io::ErrorKind::PermissionDenied, e))?;
+
+    let mut child = Command::new("pkexec")
+        .arg(helper_path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    if let Some(stdin) = child.stdin.as_mut() {
+        writeln!(stdin, "{}", request.encode())?;
+    }
+
+    Ok(child)
+}
+
+// Explain exit codes that come from pkexec or the helper rather than the command itself
+pub fn describe_exit_code(code: Option<i32>) -> Option<&'static str> {
+    match code {
+        Some(126) => Some("authorization was dismissed or denied"),
+        Some(127) => Some("authentication failed or the helper could not be started"),
+        Some(EXIT_REJECTED) => Some("the privileged helper rejected the request"),
+        Some(EXIT_BAD_REQUEST) => Some("the privileged helper could not read the request"),
+        _ => None,
+    }
+}
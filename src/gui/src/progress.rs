@@ -0,0 +1,249 @@
+// Structured progress reported by the hardn scripts (show_progress / progress_step in
+// modules/logging.sh) when HARDN_PROGRESS=structured is set:
+//
+//   @@HARDN-PROGRESS id=main step=2 total=6 label=Applying kernel hardening
+//
+// `step` counts completed units, `label` names the one in progress and takes the rest
+// of the line. The "main" stream is the overall operation; other ids report progress
+// within its current step, e.g. the packages being installed.
+
+pub const PROGRESS_PREFIX: &str = "@@HARDN-PROGRESS";
+pub const MAIN_STREAM: &str = "main";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Progress {
+    pub id: String,
+    pub step: u32,
+    pub total: u32,
+    pub label: String,
+}
+
+impl Progress {
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut rest = line.trim().strip_prefix(PROGRESS_PREFIX)?;
+        if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let mut progress = Progress {
+            id: MAIN_STREAM.to_string(),
+            step: 0,
+            total: 0,
+            label: String::new(),
+        };
+        let mut has_step = false;
+
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+            if let Some(label) = rest.strip_prefix("label=") {
+                progress.label = label.trim().to_string();
+                break;
+            }
+            let (field, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            rest = tail;
+            let (key, value) = field.split_once('=')?;
+            match key {
+                "id" => progress.id = value.to_string(),
+                "step" => {
+                    progress.step = value.parse().ok()?;
+                    has_step = true;
+                }
+                "total" => progress.total = value.parse().ok()?,
+                // Unknown fields are reserved for future versions of the protocol
+                _ => {}
+            }
+        }
+
+        (has_step && progress.total > 0).then_some(progress)
+    }
+
+    pub fn is_main(&self) -> bool {
+        self.id == MAIN_STREAM
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.step >= self.total
+    }
+
+    pub fn fraction(&self) -> f64 {
+        f64::from(self.step.min(self.total)) / f64::from(self.total)
+    }
+
+    pub fn text(&self) -> String {
+        format!("{} ({}/{})", self.label, self.step.min(self.total), self.total)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepState {
+    Done,
+    Running,
+}
+
+impl StepState {
+    pub fn badge(&self) -> &'static str {
+        match self {
+            StepState::Done => "✅",
+            StepState::Running => "⏳",
+        }
+    }
+}
+
+// Progress of one job: the steps announced on the main stream plus the latest detail
+#[derive(Debug, Clone, Default)]
+pub struct StepTracker {
+    labels: Vec<String>,
+    main: Option<Progress>,
+    detail: Option<Progress>,
+}
+
+impl StepTracker {
+    pub fn update(&mut self, progress: Progress) {
+        if progress.is_main() {
+            if !progress.is_complete() && self.labels.last() != Some(&progress.label) {
+                self.labels.push(progress.label.clone());
+            }
+            // Detail progress belongs to the step that was running before
+            self.detail = None;
+            self.main = Some(progress);
+        } else {
+            self.detail = Some(progress);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.main.is_none() && self.detail.is_none()
+    }
+
+    pub fn steps(&self) -> Vec<(&str, StepState)> {
+        let complete = self.main.as_ref().is_some_and(Progress::is_complete);
+        self.labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| {
+                let state = if complete || i + 1 < self.labels.len() { StepState::Done } else { StepState::Running };
+                (label.as_str(), state)
+            })
+            .collect()
+    }
+
+    // Overall fraction; detail progress fills in the share of the running main step
+    pub fn fraction(&self) -> f64 {
+        match (&self.main, &self.detail) {
+            (Some(main), Some(detail)) if !main.is_complete() => {
+                (f64::from(main.step) + detail.fraction()) / f64::from(main.total)
+            }
+            (Some(main), _) => main.fraction(),
+            (None, Some(detail)) => detail.fraction(),
+            (None, None) => 0.0,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        match (&self.main, &self.detail) {
+            (Some(main), Some(detail)) if !main.is_complete() => format!("{} · {}", main.text(), detail.text()),
+            (Some(main), _) => main.text(),
+            (None, Some(detail)) => detail.text(),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(id: &str, step: u32, total: u32, label: &str) -> Progress {
+        Progress {
+            id: id.to_string(),
+            step,
+            total,
+            label: label.to_string(),
+        }
+    }
+
+    #[test]
+    fn well_formed_lines() {
+        assert_eq!(
+            Progress::parse("@@HARDN-PROGRESS id=main step=2 total=6 label=Applying kernel hardening"),
+            Some(progress("main", 2, 6, "Applying kernel hardening"))
+        );
+        // id defaults to the main stream, the label to nothing
+        assert_eq!(Progress::parse("  @@HARDN-PROGRESS step=0 total=3\r\n"), Some(progress("main", 0, 3, "")));
+        assert_eq!(
+            Progress::parse("@@HARDN-PROGRESS\tstep=1  total=4 id=apt  version=2 label=  Installing ufw=0.36 \n"),
+            Some(progress("apt", 1, 4, "Installing ufw=0.36"))
+        );
+    }
+
+    #[test]
+    fn malformed_lines_are_output() {
+        for line in [
+            "",
+            "Applying kernel hardening",
+            "@@HARDN-PROGRESS",
+            "@@HARDN-PROGRESSstep=1 total=2",
+            "[INFO] @@HARDN-PROGRESS step=1 total=2",
+            "@@HARDN-PROGRESS total=6 label=Missing step",
+            "@@HARDN-PROGRESS label=Label first step=1 total=2",
+            "@@HARDN-PROGRESS step=1 label=Missing total",
+            "@@HARDN-PROGRESS step=1 total=2 stray label=Field without value",
+            "@@HARDN-PROGRESS step=one total=2",
+            "@@HARDN-PROGRESS step=1 total=",
+            "@@HARDN-PROGRESS step=-1 total=2",
+            "@@HARDN-PROGRESS step=1 total=4294967296",
+            "@@HARDN-PROGRESS step=1 total=0",
+        ] {
+            assert_eq!(Progress::parse(line), None, "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn steps_beyond_the_total_are_clamped() {
+        let overshoot = Progress::parse("@@HARDN-PROGRESS step=9 total=6 label=Done").unwrap();
+        assert!(overshoot.is_complete());
+        assert_eq!(overshoot.fraction(), 1.0);
+        assert_eq!(overshoot.text(), "Done (6/6)");
+
+        let mut steps = StepTracker::default();
+        steps.update(progress("main", 1, 2, "Installing packages"));
+        steps.update(progress("apt", 7, 4, "Unpacking"));
+        assert_eq!(steps.fraction(), 1.0);
+        assert_eq!(steps.text(), "Installing packages (1/2) · Unpacking (4/4)");
+
+        steps.update(progress("main", u32::MAX, 2, "Finished"));
+        assert_eq!(steps.fraction(), 1.0);
+        assert_eq!(steps.steps(), [("Installing packages", StepState::Done)]);
+    }
+
+    #[test]
+    fn step_tracker() {
+        let mut steps = StepTracker::default();
+        assert!(steps.is_empty());
+        assert_eq!(steps.fraction(), 0.0);
+        assert_eq!(steps.text(), "");
+
+        steps.update(progress("main", 0, 4, "Updating packages"));
+        steps.update(progress("apt", 1, 2, "ufw"));
+        assert_eq!(steps.fraction(), 0.125);
+        assert_eq!(steps.text(), "Updating packages (0/4) · ufw (1/2)");
+
+        // A new main step drops the detail of the previous one; repeats are one step
+        steps.update(progress("main", 1, 4, "Configuring the firewall"));
+        steps.update(progress("main", 1, 4, "Configuring the firewall"));
+        assert_eq!(steps.fraction(), 0.25);
+        assert_eq!(steps.text(), "Configuring the firewall (1/4)");
+        assert_eq!(
+            steps.steps(),
+            [("Updating packages", StepState::Done), ("Configuring the firewall", StepState::Running)]
+        );
+
+        steps.update(progress("main", 4, 4, "Done"));
+        assert_eq!(
+            steps.steps(),
+            [("Updating packages", StepState::Done), ("Configuring the firewall", StepState::Done)]
+        );
+    }
+}
@@ -0,0 +1,610 @@
+// Request protocol between the GUI and the privileged hardn-gui-helper.
+//
+// The GUI writes exactly one JSON-encoded HelperRequest line to the helper's stdin.
+// The helper validates it against the allow-list below, runs the matching command
+// with its stdout/stderr inherited and exits with the command's exit code.
+// The GUI keeps stdin open while the command runs: a CANCEL_REQUEST line, or EOF
+// because the GUI went away, makes the helper terminate the command.
+//
+// This file is compiled into both binaries and each only uses its own side of it.
+#![allow(dead_code)]
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::process::Command;
+
+use crate::apparmor::ModeChange;
+use crate::auditd;
+use crate::authlog::Source;
+use crate::config::{self, SettingChange};
+use crate::fail2ban::{self, JailCommand, JailSettings, FAIL2BAN_CLIENT};
+use crate::firewall::{UfwCommand, MAX_COMMANDS};
+use crate::malware::{self, ScanSchedule, CLAMSCAN_BIN, MAX_SCAN_PATHS};
+use crate::sshd_config::{SshdEdit, MAX_EDITS};
+
+pub const TOOLS_DIR: &str = "/usr/share/hardn/tools";
+pub const HARDN_BIN: &str = "/usr/bin/hardn";
+pub const SYSCTL_BIN: &str = "/usr/sbin/sysctl";
+pub const UFW_BIN: &str = "/usr/sbin/ufw";
+
+// Exit codes used by the helper itself (pkexec uses 126/127 for auth failures)
+pub const EXIT_REJECTED: i32 = 64;
+pub const EXIT_BAD_REQUEST: i32 = 65;
+
+pub const CANCEL_REQUEST: &str = "cancel";
+
+// Makes the hardn scripts report progress as "@@HARDN-PROGRESS" lines (see progress.rs)
+pub const PROGRESS_ENV: &str = "HARDN_PROGRESS";
+pub const PROGRESS_MODE: &str = "structured";
+
+// Global hardn options the GUI may pass before the subcommand
+const ALLOWED_OPTIONS: &[&str] = &["--non-interactive", "--force"];
+
+// hardn subcommands that may be run as root from the GUI, with the arguments each accepts
+const ALLOWED_SUBCOMMANDS: &[(&str, &[&str])] = &[
+    ("setup", &[]),
+    ("audit", &[]),
+    ("backup", &[]),
+    ("restore", &[]),
+    ("update", &[]),
+    ("status", &[]),
+    ("monitor", &["start", "stop", "restart", "status"]),
+    ("uninstall", &[]),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum HelperRequest {
+    // Script path relative to TOOLS_DIR, e.g. "ufw.sh" or "stig/grub.sh"
+    RunTool { script: String },
+    // `hardn <options...> <subcommand> <args...>`
+    Hardn { options: Vec<String>, subcommand: String, args: Vec<String> },
+    // `sysctl --system`: reapply every sysctl.d fragment in precedence order
+    ReloadSysctl,
+    // `ufw status verbose`: read-only, the rules files are only readable by root
+    UfwStatus,
+    // clamscan over files and directories, one result line per file
+    ClamScan { paths: Vec<String> },
+    // The operations below are carried out by the helper itself (see malware.rs)
+    Quarantine { path: String, signature: String },
+    RestoreQuarantined { id: String },
+    DeleteQuarantined { id: String },
+    // Replace the scheduled scan; frequency Off removes it
+    ScheduleScan { schedule: ScanSchedule },
+    // Print user.rules and user6.rules (see firewall.rs)
+    ReadUfwRules,
+    // Run the ufw commands in order, stopping at the first that fails
+    UfwApply { commands: Vec<UfwCommand> },
+    // Transcript of `fail2ban-client status` for the server and every jail
+    Fail2banStatus,
+    // `fail2ban-client set <jail> banip|unbanip <ip>`
+    Fail2banJail { command: JailCommand },
+    // Update the HARDN jail file and reload fail2ban
+    Fail2banSettings { settings: JailSettings },
+    // Print sshd_config and the files it includes (see sshd_config.rs)
+    ReadSshdConfig,
+    // Edit sshd_config lines, check with `sshd -t` and reload sshd
+    SshdApplyPatch { edits: Vec<SshdEdit> },
+    // Print the loaded AppArmor profiles and recent DENIED records (see apparmor.rs)
+    AppArmorStatus,
+    // `aa-enforce` or `aa-complain` for one profile
+    AppArmorMode { change: ModeChange },
+    // Print the audit rules files and `auditctl -l` (see auditd.rs)
+    ReadAuditRules,
+    // Print the audit.log records of the newest events between two Unix times
+    ReadAuditLog { since: Option<i64>, until: Option<i64> },
+    // Print the authentication lines since a Unix time and Fail2Ban's bans (see authlog.rs)
+    ReadAuthLog { source: Source, since: i64 },
+    // Print /etc/hardn/hardn.conf, or the template without it (see config.rs)
+    ReadHardnConfig,
+    // Change values in /etc/hardn/hardn.conf
+    HardnConfigUpdate { changes: Vec<SettingChange> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError(pub String);
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+fn valid_script_name(script: &str) -> bool {
+    let Some(stem) = script.strip_suffix(".sh") else {
+        return false;
+    };
+    let segments: Vec<&str> = stem.split('/').collect();
+    let name_ok = |segment: &str| {
+        !segment.is_empty()
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+    };
+    match segments.as_slice() {
+        [name] => name_ok(name),
+        ["stig", name] => name_ok(name),
+        _ => false,
+    }
+}
+
+impl HelperRequest {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        match self {
+            HelperRequest::RunTool { script } => {
+                if valid_script_name(script) {
+                    Ok(())
+                } else {
+                    Err(ValidationError(format!("tool script not allowed: {}", script)))
+                }
+            }
+            HelperRequest::Hardn { options, subcommand, args } => {
+                if let Some(option) = options.iter().find(|option| !ALLOWED_OPTIONS.contains(&option.as_str())) {
+                    return Err(ValidationError(format!("hardn option not allowed: {}", option)));
+                }
+                let allowed_args = ALLOWED_SUBCOMMANDS
+                    .iter()
+                    .find(|(name, _)| name == subcommand)
+                    .map(|(_, args)| *args)
+                    .ok_or_else(|| ValidationError(format!("hardn subcommand not allowed: {}", subcommand)))?;
+                match args.iter().find(|arg| !allowed_args.contains(&arg.as_str())) {
+                    Some(arg) => Err(ValidationError(format!("argument not allowed for hardn {}: {}", subcommand, arg))),
+                    None => Ok(()),
+                }
+            }
+            HelperRequest::ReloadSysctl | HelperRequest::UfwStatus | HelperRequest::ReadUfwRules
+            | HelperRequest::Fail2banStatus
+            | HelperRequest::ReadSshdConfig
+            | HelperRequest::AppArmorStatus
+            | HelperRequest::ReadAuditRules
+            | HelperRequest::ReadHardnConfig => Ok(()),
+            HelperRequest::ClamScan { paths } => {
+                if paths.is_empty() || paths.len() > MAX_SCAN_PATHS {
+                    return Err(ValidationError(format!("a scan needs 1 to {} targets", MAX_SCAN_PATHS)));
+                }
+                match paths.iter().find(|path| !malware::valid_scan_path(path)) {
+                    Some(path) => Err(ValidationError(format!("scan target not allowed: {}", path))),
+                    None => Ok(()),
+                }
+            }
+            HelperRequest::Quarantine { path, signature } => {
+                if !malware::valid_scan_path(path) {
+                    return Err(ValidationError(format!("path not allowed: {}", path)));
+                }
+                if signature.len() > 256 || signature.chars().any(char::is_control) {
+                    return Err(ValidationError("malformed signature name".to_string()));
+                }
+                Ok(())
+            }
+            HelperRequest::RestoreQuarantined { id } | HelperRequest::DeleteQuarantined { id } => {
+                if malware::valid_id(id) {
+                    Ok(())
+                } else {
+                    Err(ValidationError(format!("invalid quarantine id: {}", id)))
+                }
+            }
+            HelperRequest::ScheduleScan { schedule } => schedule.validate().map_err(ValidationError),
+            HelperRequest::UfwApply { commands } => {
+                if commands.is_empty() || commands.len() > MAX_COMMANDS {
+                    return Err(ValidationError(format!("a firewall change needs 1 to {} commands", MAX_COMMANDS)));
+                }
+                commands.iter().try_for_each(|command| command.validate().map_err(ValidationError))
+            }
+            HelperRequest::Fail2banJail { command } => command.validate().map_err(ValidationError),
+            HelperRequest::Fail2banSettings { settings } => settings.validate().map_err(ValidationError),
+            HelperRequest::SshdApplyPatch { edits } => {
+                if edits.is_empty() || edits.len() > MAX_EDITS {
+                    return Err(ValidationError(format!("an sshd_config change needs 1 to {} edits", MAX_EDITS)));
+                }
+                edits.iter().try_for_each(|edit| edit.validate().map_err(ValidationError))
+            }
+            HelperRequest::AppArmorMode { change } => change.validate().map_err(ValidationError),
+            HelperRequest::ReadAuditLog { since, until } => auditd::validate_range(*since, *until).map_err(ValidationError),
+            HelperRequest::ReadAuthLog { since, .. } => {
+                if *since < 0 {
+                    return Err(ValidationError("times must not be negative".to_string()));
+                }
+                Ok(())
+            }
+            HelperRequest::HardnConfigUpdate { changes } => {
+                if changes.is_empty() || changes.len() > config::MAX_CHANGES {
+                    return Err(ValidationError(format!("a settings change needs 1 to {} values", config::MAX_CHANGES)));
+                }
+                for (index, change) in changes.iter().enumerate() {
+                    if changes[..index].iter().any(|earlier| earlier.key == change.key) {
+                        return Err(ValidationError(format!("{} is changed twice", change.key)));
+                    }
+                }
+                changes.iter().try_for_each(|change| change.validate().map_err(ValidationError))
+            }
+        }
+    }
+
+    // Requests the helper performs in-process instead of running argv()
+    pub fn is_builtin(&self) -> bool {
+        matches!(
+            self,
+            HelperRequest::Quarantine { .. }
+                | HelperRequest::RestoreQuarantined { .. }
+                | HelperRequest::DeleteQuarantined { .. }
+                | HelperRequest::ScheduleScan { .. }
+                | HelperRequest::ReadUfwRules
+                | HelperRequest::UfwApply { .. }
+                | HelperRequest::Fail2banStatus
+                | HelperRequest::Fail2banSettings { .. }
+                | HelperRequest::ReadSshdConfig
+                | HelperRequest::SshdApplyPatch { .. }
+                | HelperRequest::AppArmorStatus
+                | HelperRequest::ReadAuditRules
+                | HelperRequest::ReadAuditLog { .. }
+                | HelperRequest::ReadAuthLog { .. }
+                | HelperRequest::ReadHardnConfig
+                | HelperRequest::HardnConfigUpdate { .. }
+        )
+    }
+
+    // Requests that only print what is on the system and change nothing
+    pub fn is_read_only(&self) -> bool {
+        matches!(
+            self,
+            HelperRequest::UfwStatus
+                | HelperRequest::ReadUfwRules
+                | HelperRequest::Fail2banStatus
+                | HelperRequest::ReadSshdConfig
+                | HelperRequest::AppArmorStatus
+                | HelperRequest::ReadAuditRules
+                | HelperRequest::ReadAuditLog { .. }
+                | HelperRequest::ReadAuthLog { .. }
+                | HelperRequest::ReadHardnConfig
+        )
+    }
+
+    // Human readable form shown in the GUI and the polkit prompt log
+    pub fn describe(&self) -> String {
+        match self {
+            HelperRequest::RunTool { script } => format!("{}/{}", TOOLS_DIR, script),
+            HelperRequest::Hardn { options, subcommand, args } => {
+                let mut parts = vec!["hardn".to_string()];
+                parts.extend(options.iter().cloned());
+                parts.push(subcommand.clone());
+                parts.extend(args.iter().cloned());
+                parts.join(" ")
+            }
+            HelperRequest::Quarantine { path, signature } => format!("quarantine {} ({})", path, signature),
+            HelperRequest::RestoreQuarantined { id } => format!("restore quarantined file {}", id),
+            HelperRequest::DeleteQuarantined { id } => format!("delete quarantined file {}", id),
+            HelperRequest::ScheduleScan { schedule } => format!("schedule ClamAV scan: {}", schedule.describe()),
+            HelperRequest::ReadUfwRules => "read UFW rules".to_string(),
+            HelperRequest::UfwApply { commands } => format!("apply {} UFW commands", commands.len()),
+            HelperRequest::Fail2banStatus => "read Fail2Ban jail status".to_string(),
+            HelperRequest::Fail2banSettings { settings } => format!(
+                "set [{}] bantime={} findtime={} maxretry={} in {}",
+                settings.section,
+                settings.bantime,
+                settings.findtime,
+                settings.maxretry,
+                fail2ban::HARDN_JAIL_FILE
+            ),
+            HelperRequest::ReadSshdConfig => "read sshd_config".to_string(),
+            HelperRequest::SshdApplyPatch { edits } => format!("change {} sshd_config lines and reload sshd", edits.len()),
+            HelperRequest::AppArmorStatus => "read AppArmor profiles and denials".to_string(),
+            HelperRequest::ReadAuditRules => "read audit rules".to_string(),
+            HelperRequest::ReadAuditLog { .. } => format!("read the newest {} audit events", auditd::MAX_EVENTS),
+            HelperRequest::ReadAuthLog { source, .. } => format!("read authentication events from the {}", source.label()),
+            HelperRequest::ReadHardnConfig => format!("read {}", config::system_path().display()),
+            HelperRequest::HardnConfigUpdate { changes } => format!("change {} settings in {}", changes.len(), config::system_path().display()),
+            _ => self.argv().join(" "),
+        }
+    }
+
+    // Program followed by its arguments, as the helper executes them
+    pub fn argv(&self) -> Vec<String> {
+        match self {
+            HelperRequest::RunTool { script } => vec!["/bin/bash".to_string(), format!("{}/{}", TOOLS_DIR, script)],
+            HelperRequest::Hardn { options, subcommand, args } => {
+                let mut argv = vec![HARDN_BIN.to_string()];
+                argv.extend(options.iter().cloned());
+                argv.push(subcommand.clone());
+                argv.extend(args.iter().cloned());
+                argv
+            }
+            HelperRequest::ReloadSysctl => vec![SYSCTL_BIN.to_string(), "--system".to_string()],
+            HelperRequest::UfwStatus => vec![UFW_BIN.to_string(), "status".to_string(), "verbose".to_string()],
+            HelperRequest::ClamScan { paths } => {
+                let mut argv = vec![CLAMSCAN_BIN.to_string(), "--recursive".to_string(), "--".to_string()];
+                argv.extend(paths.iter().cloned());
+                argv
+            }
+            HelperRequest::Fail2banJail { command } => {
+                let mut argv = vec![FAIL2BAN_CLIENT.to_string()];
+                argv.extend(command.args());
+                argv
+            }
+            HelperRequest::AppArmorMode { change } => vec![change.program().to_string(), change.target()],
+            // Builtins have no command; show them as what they do
+            HelperRequest::Quarantine { .. }
+            | HelperRequest::RestoreQuarantined { .. }
+            | HelperRequest::DeleteQuarantined { .. }
+            | HelperRequest::ScheduleScan { .. }
+            | HelperRequest::ReadUfwRules
+            | HelperRequest::UfwApply { .. }
+            | HelperRequest::Fail2banStatus
+            | HelperRequest::Fail2banSettings { .. }
+            | HelperRequest::ReadSshdConfig
+            | HelperRequest::SshdApplyPatch { .. }
+            | HelperRequest::AppArmorStatus
+            | HelperRequest::ReadAuditRules
+            | HelperRequest::ReadAuditLog { .. }
+            | HelperRequest::ReadAuthLog { .. }
+            | HelperRequest::ReadHardnConfig
+            | HelperRequest::HardnConfigUpdate { .. } => vec!["hardn-gui-helper".to_string(), self.describe()],
+        }
+    }
+
+    // Build the command the helper runs; only call after validate() succeeded
+    pub fn to_command(&self) -> Command {
+        let argv = self.argv();
+        let mut cmd = Command::new(&argv[0]);
+        cmd.args(&argv[1..]);
+        cmd
+    }
+
+    pub fn encode(&self) -> String {
+        serde_json::to_string(self).expect("helper requests always serialize")
+    }
+
+    pub fn decode(line: &str) -> Result<Self, ValidationError> {
+        serde_json::from_str(line.trim()).map_err(|e| ValidationError(format!("malformed request: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apparmor::Mode;
+    use crate::fail2ban::JailSettings;
+    use crate::firewall::{Action, Direction, PolicyChain, RuleProtocol, RuleSpec};
+    use crate::malware::ScanFrequency;
+
+    fn run_tool(script: &str) -> HelperRequest {
+        HelperRequest::RunTool { script: script.to_string() }
+    }
+
+    fn hardn(options: &[&str], subcommand: &str, args: &[&str]) -> HelperRequest {
+        HelperRequest::Hardn {
+            options: options.iter().map(|option| option.to_string()).collect(),
+            subcommand: subcommand.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    fn rejected(request: HelperRequest) -> bool {
+        request.validate().is_err()
+    }
+
+    #[test]
+    fn tool_scripts() {
+        for script in ["ufw.sh", "fail2ban.sh", "stig/grub.sh", "stig/sshd_banner-2.sh"] {
+            assert_eq!(run_tool(script).validate(), Ok(()), "{}", script);
+        }
+        for script in
+            ["../x.sh", "stig/../x.sh", "/abs.sh", "UFW.sh", "x", "a/b/c.sh", "other/x.sh", ".sh", "stig/.sh", "./x.sh", "x.sh/", "x .sh", ""]
+        {
+            assert!(rejected(run_tool(script)), "{}", script);
+        }
+    }
+
+    #[test]
+    fn hardn_commands() {
+        assert_eq!(hardn(&[], "audit", &[]).validate(), Ok(()));
+        assert_eq!(hardn(&["--non-interactive", "--force"], "setup", &[]).validate(), Ok(()));
+        assert_eq!(hardn(&[], "monitor", &["restart"]).validate(), Ok(()));
+
+        // Unknown subcommands, also when they only differ in case or spacing
+        for subcommand in ["shell", "Setup", "setup ", "", "--force"] {
+            assert!(rejected(hardn(&[], subcommand, &[])), "{}", subcommand);
+        }
+        for option in ["--config=/tmp/x", "-f", "--force=yes", "setup"] {
+            assert!(rejected(hardn(&[option], "setup", &[])), "{}", option);
+        }
+        // Extra arguments, or arguments another subcommand takes
+        assert!(rejected(hardn(&[], "setup", &["--force"])));
+        assert!(rejected(hardn(&[], "status", &["start"])));
+        assert!(rejected(hardn(&[], "monitor", &["start", "--exec=/bin/sh"])));
+        assert!(rejected(hardn(&[], "monitor", &["Start"])));
+    }
+
+    #[test]
+    fn requests_decode_only_from_known_operations() {
+        let request = hardn(&["--force"], "monitor", &["stop"]);
+        assert_eq!(HelperRequest::decode(&request.encode()), Ok(request));
+        assert_eq!(HelperRequest::decode("{\"op\":\"reload_sysctl\"}\n"), Ok(HelperRequest::ReloadSysctl));
+        for line in ["", "{}", "{\"op\":\"shell\",\"command\":\"id\"}", "{\"op\":\"run_tool\"}", "{\"op\":\"run_tool\",\"script\":7}"] {
+            assert!(HelperRequest::decode(line).is_err(), "{}", line);
+        }
+    }
+
+    #[test]
+    fn scans_and_quarantine() {
+        let scan = |paths: &[&str]| HelperRequest::ClamScan { paths: paths.iter().map(|path| path.to_string()).collect() };
+        assert_eq!(scan(&["/home", "/tmp/a b"]).validate(), Ok(()));
+        assert!(rejected(scan(&[])));
+        assert!(rejected(scan(&["/home"; MAX_SCAN_PATHS + 1])));
+        for path in ["home", "/home/../etc", "/tmp/a\nb", "/tmp/%s", "-r"] {
+            assert!(rejected(scan(&[path])), "{:?}", path);
+        }
+
+        let quarantine = |path: &str, signature: &str| HelperRequest::Quarantine { path: path.to_string(), signature: signature.to_string() };
+        assert_eq!(quarantine("/tmp/eicar.com", "Eicar-Signature").validate(), Ok(()));
+        assert!(rejected(quarantine("/tmp/../etc/shadow", "Eicar-Signature")));
+        assert!(rejected(quarantine("/tmp/eicar.com", "Eicar\nforged line")));
+        assert!(rejected(quarantine("/tmp/eicar.com", &"x".repeat(257))));
+
+        assert_eq!(HelperRequest::RestoreQuarantined { id: "1760781000-3f2a".to_string() }.validate(), Ok(()));
+        for id in ["", "../item", "a/b", "a.b", &"a".repeat(65)] {
+            assert!(rejected(HelperRequest::RestoreQuarantined { id: id.to_string() }), "{}", id);
+            assert!(rejected(HelperRequest::DeleteQuarantined { id: id.to_string() }), "{}", id);
+        }
+
+        let schedule = |frequency, paths: &[&str]| HelperRequest::ScheduleScan {
+            schedule: ScanSchedule { frequency, paths: paths.iter().map(|path| path.to_string()).collect() },
+        };
+        assert_eq!(schedule(ScanFrequency::Off, &[]).validate(), Ok(()));
+        assert_eq!(schedule(ScanFrequency::Daily, &["/home"]).validate(), Ok(()));
+        assert!(rejected(schedule(ScanFrequency::Weekly, &[])));
+        assert!(rejected(schedule(ScanFrequency::Daily, &["/home/%H"])));
+    }
+
+    #[test]
+    fn firewall_commands() {
+        let apply = |commands: Vec<UfwCommand>| HelperRequest::UfwApply { commands };
+        let rule = |edit: &dyn Fn(&mut RuleSpec)| {
+            let mut rule = RuleSpec::new(Action::Allow, Direction::In);
+            edit(&mut rule);
+            UfwCommand::Add { rule: Box::new(rule), position: None }
+        };
+        assert_eq!(apply(vec![rule(&|rule| rule.to_port = Some("22".to_string())), UfwCommand::Delete { number: 3 }]).validate(), Ok(()));
+
+        assert!(rejected(apply(Vec::new())));
+        assert!(rejected(apply(vec![UfwCommand::Delete { number: 1 }; MAX_COMMANDS + 1])));
+        assert!(rejected(apply(vec![UfwCommand::Delete { number: 0 }])));
+        assert!(rejected(apply(vec![UfwCommand::Add { rule: Box::new(RuleSpec::new(Action::Deny, Direction::Out)), position: Some(0) }])));
+        assert!(rejected(apply(vec![UfwCommand::Default { chain: PolicyChain::Incoming, policy: "accept".to_string() }])));
+        assert!(rejected(apply(vec![UfwCommand::Logging { level: "verbose".to_string() }])));
+        assert!(rejected(apply(vec![rule(&|rule| rule.interface = Some("-eth0".to_string()))])));
+        assert!(rejected(apply(vec![rule(&|rule| rule.log = Some("log-none".to_string()))])));
+        assert!(rejected(apply(vec![rule(&|rule| rule.from = "10.0.0.300".to_string())])));
+        assert!(rejected(apply(vec![rule(&|rule| {
+            rule.from = "10.0.0.0/8".to_string();
+            rule.to = "::1".to_string();
+        })])));
+        assert!(rejected(apply(vec![rule(&|rule| {
+            rule.to_port = Some("22".to_string());
+            rule.to_app = Some("OpenSSH".to_string());
+        })])));
+        assert!(rejected(apply(vec![rule(&|rule| rule.to_port = Some("1000:2000".to_string()))])));
+        assert!(rejected(apply(vec![rule(&|rule| {
+            rule.protocol = RuleProtocol::Tcp;
+            rule.to_port = Some("22;id".to_string());
+        })])));
+        assert!(rejected(apply(vec![rule(&|rule| rule.to_app = Some("-OpenSSH".to_string()))])));
+        // One bad command rejects the batch
+        assert!(rejected(apply(vec![UfwCommand::Delete { number: 2 }, UfwCommand::Logging { level: "loud".to_string() }])));
+    }
+
+    #[test]
+    fn fail2ban_commands() {
+        let ban = |jail: &str, ip: &str| HelperRequest::Fail2banJail { command: JailCommand::Ban { jail: jail.to_string(), ip: ip.to_string() } };
+        assert_eq!(ban("sshd", "192.0.2.7").validate(), Ok(()));
+        assert_eq!(ban("sshd", "2001:db8::7").validate(), Ok(()));
+        assert!(rejected(ban("sshd", "192.0.2.0/24")));
+        assert!(rejected(ban("sshd", "example.org")));
+        assert!(rejected(ban("-sshd", "192.0.2.7")));
+        assert!(rejected(ban("ssh d", "192.0.2.7")));
+        assert!(rejected(HelperRequest::Fail2banJail { command: JailCommand::Unban { jail: String::new(), ip: "192.0.2.7".to_string() } }));
+
+        let settings = |section: &str, bantime: &str, findtime: &str, maxretry| HelperRequest::Fail2banSettings {
+            settings: JailSettings { section: section.to_string(), bantime: bantime.to_string(), findtime: findtime.to_string(), maxretry },
+        };
+        assert_eq!(settings("DEFAULT", "1h30m", "600", 5).validate(), Ok(()));
+        assert_eq!(settings("sshd", "-1", "10m", 1).validate(), Ok(()));
+        assert!(rejected(settings("ss]hd", "1h", "10m", 5)));
+        assert!(rejected(settings("sshd", "1y", "10m", 5)));
+        assert!(rejected(settings("sshd", "", "10m", 5)));
+        assert!(rejected(settings("sshd", "1h", "-1", 5)));
+        assert!(rejected(settings("sshd", "1h", "10m\nignoreip = 0.0.0.0/0", 5)));
+        assert!(rejected(settings("sshd", "1h", "10m", 0)));
+        assert!(rejected(settings("sshd", "1h", "10m", 1001)));
+    }
+
+    #[test]
+    fn sshd_edits() {
+        let edit = |path: &str, line, old: Option<&str>, new: &str| SshdEdit {
+            path: path.to_string(),
+            line,
+            old: old.map(str::to_string),
+            new: new.to_string(),
+        };
+        let patch = |edits: Vec<SshdEdit>| HelperRequest::SshdApplyPatch { edits };
+        let config = "/etc/ssh/sshd_config";
+        assert_eq!(patch(vec![edit(config, 12, Some("#PermitRootLogin yes"), "PermitRootLogin no")]).validate(), Ok(()));
+        assert_eq!(patch(vec![edit("/etc/ssh/sshd_config.d/50-hardn.conf", 1, None, "maxauthtries 3")]).validate(), Ok(()));
+
+        assert!(rejected(patch(Vec::new())));
+        assert!(rejected(patch(vec![edit(config, 1, None, "MaxAuthTries 3"); MAX_EDITS + 1])));
+        assert!(rejected(patch(vec![edit("/etc/ssh/../shadow", 1, None, "MaxAuthTries 3")])));
+        assert!(rejected(patch(vec![edit("/etc/sshd_config", 1, None, "MaxAuthTries 3")])));
+        assert!(rejected(patch(vec![edit(config, 0, None, "MaxAuthTries 3")])));
+        assert!(rejected(patch(vec![edit(config, 1, Some("a\nb"), "MaxAuthTries 3")])));
+        assert!(rejected(patch(vec![edit(config, 1, None, "ForceCommand /bin/sh")])));
+        assert!(rejected(patch(vec![edit(config, 1, None, "MaxAuthTries")])));
+        assert!(rejected(patch(vec![edit(config, 1, None, "MaxAuthTries 3\nPermitRootLogin yes")])));
+        assert!(rejected(patch(vec![edit(config, 1, None, "Banner \"/etc/issue net\"")])));
+        assert!(rejected(patch(vec![edit(config, 1, None, "Banner /etc/issue;id")])));
+    }
+
+    #[test]
+    fn apparmor_mode_changes() {
+        let mode = |profile: &str, mode| HelperRequest::AppArmorMode { change: ModeChange { profile: profile.to_string(), mode } };
+        assert_eq!(mode("/usr/sbin/cupsd", Mode::Enforce).validate(), Ok(()));
+        assert_eq!(mode("lsb_release", Mode::Complain).validate(), Ok(()));
+
+        assert!(rejected(mode("/usr/sbin/cupsd", Mode::Unconfined)));
+        assert!(rejected(mode("/usr/sbin/cupsd", Mode::Kill)));
+        assert!(rejected(mode("/usr/sbin/cupsd", Mode::Other("prompt".to_string()))));
+        for profile in ["", "-d", "/usr/bin/../../etc/shadow", "/usr/bin//man", "a b", "usr/bin/man", "x\n", &"a".repeat(257)] {
+            assert!(rejected(mode(profile, Mode::Enforce)), "{:?}", profile);
+        }
+    }
+
+    #[test]
+    fn log_time_ranges() {
+        let audit = |since, until| HelperRequest::ReadAuditLog { since, until };
+        assert_eq!(audit(None, None).validate(), Ok(()));
+        assert_eq!(audit(Some(100), Some(100)).validate(), Ok(()));
+        assert!(rejected(audit(Some(-1), None)));
+        assert!(rejected(audit(None, Some(-1))));
+        assert!(rejected(audit(Some(200), Some(100))));
+
+        assert_eq!(HelperRequest::ReadAuthLog { source: Source::Journal, since: 0 }.validate(), Ok(()));
+        assert!(rejected(HelperRequest::ReadAuthLog { source: Source::AuthLog, since: -1 }));
+    }
+
+    #[test]
+    fn hardn_config_updates() {
+        let change = |key: &str, old: Option<&str>, value: &str| SettingChange { key: key.to_string(), old: old.map(str::to_string), value: value.to_string() };
+        let update = |changes: Vec<SettingChange>| HelperRequest::HardnConfigUpdate { changes };
+        assert_eq!(
+            update(vec![change("LOG_LEVEL", Some("info"), "debug"), change("PRIMARY_DNS", None, "9.9.9.9")]).validate(),
+            Ok(())
+        );
+
+        assert!(rejected(update(Vec::new())));
+        assert!(rejected(update(vec![change("LOG_LEVEL", None, "info"); config::MAX_CHANGES + 1])));
+        assert!(rejected(update(vec![change("LOG_LEVEL", None, "info"), change("LOG_LEVEL", None, "debug")])));
+        assert!(rejected(update(vec![change("PATH", None, "/tmp")])));
+        assert!(rejected(update(vec![change("HARDN_VERSION", None, "9.9")])));
+        assert!(rejected(update(vec![change("LOG_LEVEL", None, "LOUD")])));
+        assert!(rejected(update(vec![change("LOG_LEVEL", Some("info\nPATH=/tmp"), "debug")])));
+        assert!(rejected(update(vec![change("AUTO_BACKUP_ENABLED", None, "yes")])));
+        assert!(rejected(update(vec![change("LOG_RETENTION_DAYS", None, "0")])));
+        assert!(rejected(update(vec![change("PRIMARY_DNS", None, "dns.example")])));
+    }
+
+    #[test]
+    fn read_only_requests() {
+        assert!(HelperRequest::ReadAuditLog { since: None, until: None }.is_read_only());
+        assert!(HelperRequest::ReadAuthLog { source: Source::Journal, since: 0 }.is_read_only());
+        assert!(HelperRequest::AppArmorStatus.is_read_only());
+        assert!(HelperRequest::UfwStatus.is_read_only());
+
+        assert!(!run_tool("ufw.sh").is_read_only());
+        assert!(!hardn(&[], "audit", &[]).is_read_only());
+        assert!(!HelperRequest::ReloadSysctl.is_read_only());
+        assert!(!HelperRequest::ClamScan { paths: vec!["/home".to_string()] }.is_read_only());
+        assert!(!HelperRequest::UfwApply { commands: Vec::new() }.is_read_only());
+        assert!(!HelperRequest::HardnConfigUpdate { changes: Vec::new() }.is_read_only());
+    }
+}
@@ -0,0 +1,573 @@
+// Structured audit report built from the log lines of a `hardn audit` run.
+//
+// modules/logging.sh prints "[LEVEL] message" and hardn_status() prefixes results
+// with "OK", "WARNING" or "ERROR"; each audit step announces itself with a fixed
+// info message, which starts a new section.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+// First info message of each step in modules/audit.sh
+const SECTIONS: &[(&str, &str)] = &[
+    ("Running Lynis security audit", "Lynis Audit"),
+    ("Running network security scan", "Network Scan"),
+    ("Checking system integrity with AIDE", "File Integrity"),
+    ("Checking for rootkits", "Rootkit Detection"),
+    ("Checking for malware with ClamAV", "Malware Scan"),
+    ("Generating comprehensive security audit report", "Security Report"),
+];
+const GENERAL_SECTION: &str = "General";
+
+const REMEDIATION: &[(&str, &str)] = &[
+    ("Lynis Audit", "Review the warnings and suggestions in the Lynis report file and apply the relevant hardening tools."),
+    ("Network Scan", "Stop services that do not need to listen, or restrict the ports with UFW."),
+    ("File Integrity", "Confirm the changed files are expected, then update the AIDE database; investigate anything unexplained."),
+    ("Rootkit Detection", "Investigate the flagged files against package checksums (debsums) before whitelisting them."),
+    ("Malware Scan", "Quarantine the detected files and find out how they got onto the system."),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    High,
+    Medium,
+    Low,
+    Pass,
+}
+
+impl Severity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::High => "High",
+            Severity::Medium => "Medium",
+            Severity::Low => "Low",
+            Severity::Pass => "Passed",
+        }
+    }
+
+    pub fn badge(&self) -> &'static str {
+        match self {
+            Severity::High => "🔴 High",
+            Severity::Medium => "🟠 Medium",
+            Severity::Low => "🟡 Low",
+            Severity::Pass => "🟢 Passed",
+        }
+    }
+
+    // Lowercase name, as serialized
+    pub fn key(&self) -> &'static str {
+        match self {
+            Severity::High => "high",
+            Severity::Medium => "medium",
+            Severity::Low => "low",
+            Severity::Pass => "pass",
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Severity::High => "error",
+            Severity::Medium | Severity::Low => "warning",
+            Severity::Pass => "success",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    pub id: String,
+    pub section: String,
+    pub title: String,
+    pub severity: Severity,
+    pub evidence: Vec<String>,
+    pub remediation: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Section {
+    pub title: String,
+    // Informational output of the step that is not tied to a finding
+    pub notes: Vec<String>,
+    pub findings: Vec<Finding>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditReport {
+    pub command: String,
+    pub generated_at: u64,
+    pub hostname: String,
+    pub os: String,
+    pub kernel: String,
+    pub exit_code: Option<i32>,
+    pub sections: Vec<Section>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Severity,
+    Section,
+    Title,
+}
+
+impl AuditReport {
+    pub fn findings(&self) -> Vec<&Finding> {
+        self.sections.iter().flat_map(|section| section.findings.iter()).collect()
+    }
+
+    pub fn sorted_findings(&self, key: SortKey) -> Vec<&Finding> {
+        let mut findings = self.findings();
+        match key {
+            SortKey::Severity => findings.sort_by(|a, b| a.severity.cmp(&b.severity).then_with(|| a.id.cmp(&b.id))),
+            SortKey::Section => findings.sort_by(|a, b| a.section.cmp(&b.section).then_with(|| a.severity.cmp(&b.severity))),
+            SortKey::Title => findings.sort_by_key(|finding| finding.title.to_lowercase()),
+        }
+        findings
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings().iter().filter(|finding| finding.severity == severity).count()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} findings · {} high, {} medium, {} low, {} passed",
+            self.findings().len(),
+            self.count(Severity::High),
+            self.count(Severity::Medium),
+            self.count(Severity::Low),
+            self.count(Severity::Pass)
+        )
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("audit reports always serialize")
+    }
+
+    // Single HTML file with inline styles, suitable for archiving
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str(&format!("<title>HARDN Security Audit Report - {}</title>\n", escape_html(&self.hostname)));
+        html.push_str(
+            "<style>\n\
+             body { font-family: sans-serif; margin: 2em; color: #222; }\n\
+             table { border-collapse: collapse; width: 100%; margin-bottom: 1.5em; }\n\
+             th, td { border: 1px solid #ccc; padding: 6px 8px; text-align: left; vertical-align: top; }\n\
+             th { background: #f0f0f0; }\n\
+             .high { color: #b00020; font-weight: bold; }\n\
+             .medium { color: #c25e00; font-weight: bold; }\n\
+             .low { color: #8a6d00; }\n\
+             .pass { color: #1b7f3b; }\n\
+             ul { margin: 0; padding-left: 1.2em; }\n\
+             .notes { color: #555; font-size: 0.9em; }\n\
+             </style>\n</head>\n<body>\n",
+        );
+        html.push_str("<h1>HARDN Security Audit Report</h1>\n<table>\n");
+        for (name, value) in [
+            ("Generated", format_timestamp(self.generated_at)),
+            ("Host", self.hostname.clone()),
+            ("Operating system", self.os.clone()),
+            ("Kernel", self.kernel.clone()),
+            ("Command", self.command.clone()),
+            ("Exit code", self.exit_code.map(|code| code.to_string()).unwrap_or_else(|| "unknown".to_string())),
+            ("Summary", self.summary()),
+        ] {
+            html.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", name, escape_html(&value)));
+        }
+        html.push_str("</table>\n");
+
+        for section in &self.sections {
+            html.push_str(&format!("<h2>{}</h2>\n", escape_html(&section.title)));
+            if !section.findings.is_empty() {
+                html.push_str("<table>\n<tr><th>ID</th><th>Severity</th><th>Finding</th><th>Evidence</th><th>Remediation</th></tr>\n");
+                for finding in &section.findings {
+                    let evidence: String = finding
+                        .evidence
+                        .iter()
+                        .map(|line| format!("<li>{}</li>", escape_html(line)))
+                        .collect();
+                    html.push_str(&format!(
+                        "<tr><td>{}</td><td class=\"{}\">{}</td><td>{}</td><td><ul>{}</ul></td><td>{}</td></tr>\n",
+                        escape_html(&finding.id),
+                        finding.severity.key(),
+                        finding.severity.label(),
+                        escape_html(&finding.title),
+                        evidence,
+                        escape_html(finding.remediation.as_deref().unwrap_or(""))
+                    ));
+                }
+                html.push_str("</table>\n");
+            }
+            if !section.notes.is_empty() {
+                html.push_str("<ul class=\"notes\">\n");
+                for note in &section.notes {
+                    html.push_str(&format!("<li>{}</li>\n", escape_html(note)));
+                }
+                html.push_str("</ul>\n");
+            }
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    // "audit-<host>-<YYYYMMDD_HHMMSS>", like the timestamps of the shell reports
+    pub fn file_stem(&self) -> String {
+        format!("audit-{}-{}", self.hostname, file_timestamp(self.generated_at))
+    }
+}
+
+// Feed raw output lines in order, then call finish()
+pub struct ReportBuilder {
+    report: AuditReport,
+    // Info lines since the last finding; they become its evidence
+    pending_evidence: Vec<String>,
+    next_id: usize,
+}
+
+impl ReportBuilder {
+    pub fn new(command: &str) -> Self {
+        let os = fs::read_to_string("/etc/os-release")
+            .ok()
+            .and_then(|content| {
+                content
+                    .lines()
+                    .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+                    .map(|name| name.trim_matches('"').to_string())
+            })
+            .unwrap_or_default();
+        let read_trimmed = |path: &str| fs::read_to_string(path).map(|s| s.trim().to_string()).unwrap_or_default();
+
+        ReportBuilder {
+            report: AuditReport {
+                command: command.to_string(),
+                generated_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+                hostname: read_trimmed("/proc/sys/kernel/hostname"),
+                os,
+                kernel: read_trimmed("/proc/sys/kernel/osrelease"),
+                exit_code: None,
+                sections: Vec::new(),
+            },
+            pending_evidence: Vec::new(),
+            next_id: 1,
+        }
+    }
+
+    pub fn current_section(&self) -> Option<&str> {
+        self.report.sections.last().map(|section| section.title.as_str())
+    }
+
+    // Returns the finding the line produced, if any
+    pub fn push_line(&mut self, line: &str) -> Option<Finding> {
+        let line = strip_ansi(line);
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+        let (level, message) = split_level(line);
+
+        if level == Some("INFO") {
+            if let Some((_, title)) = SECTIONS.iter().find(|(marker, _)| message.starts_with(marker)) {
+                self.start_section(title);
+                return None;
+            }
+        }
+
+        let finding = match (level, message) {
+            (Some("INFO"), message) if message.starts_with("OK ") => Some((Severity::Pass, &message[3..])),
+            (Some("WARN"), message) if message.starts_with("WARNING ") => Some((Severity::Medium, &message[8..])),
+            (Some("ERROR"), message) if message.starts_with("ERROR ") => Some((Severity::High, &message[6..])),
+            (Some("ERROR"), message) => Some((Severity::High, message)),
+            // "Review the ... log: <path>" points at the evidence of the previous warning
+            (Some("WARN"), message) if message.starts_with("Review ") => {
+                if let Some(last) = self.section().findings.last_mut() {
+                    last.evidence.push(message.to_string());
+                } else {
+                    self.section().notes.push(message.to_string());
+                }
+                None
+            }
+            (Some("WARN"), message) => Some((Severity::Low, message)),
+            (Some("DEBUG"), _) => None,
+            (_, message) => {
+                self.section().notes.push(message.to_string());
+                // Progress chatter like "Running ClamAV scan..." is not evidence
+                if !message.ends_with("...") {
+                    self.pending_evidence.push(message.to_string());
+                }
+                None
+            }
+        };
+
+        let (severity, title) = finding?;
+        let section = self.section().title.clone();
+        let remediation = if severity <= Severity::Low {
+            REMEDIATION.iter().find(|(name, _)| *name == section).map(|(_, text)| text.to_string())
+        } else {
+            None
+        };
+        let finding = Finding {
+            id: format!("F-{:03}", self.next_id),
+            section,
+            title: title.trim().to_string(),
+            severity,
+            evidence: std::mem::take(&mut self.pending_evidence),
+            remediation,
+        };
+        self.next_id += 1;
+        self.section().findings.push(finding.clone());
+        Some(finding)
+    }
+
+    pub fn finish(mut self, exit_code: Option<i32>) -> AuditReport {
+        self.report.exit_code = exit_code;
+        self.report.sections.retain(|section| !section.findings.is_empty() || !section.notes.is_empty());
+        self.report
+    }
+
+    fn start_section(&mut self, title: &str) {
+        self.pending_evidence.clear();
+        self.report.sections.push(Section {
+            title: title.to_string(),
+            notes: Vec::new(),
+            findings: Vec::new(),
+        });
+    }
+
+    fn section(&mut self) -> &mut Section {
+        if self.report.sections.is_empty() {
+            self.start_section(GENERAL_SECTION);
+        }
+        self.report.sections.last_mut().expect("a section was just added")
+    }
+}
+
+// "[WARN] message" -> (Some("WARN"), "message")
+fn split_level(line: &str) -> (Option<&str>, &str) {
+    if let Some(rest) = line.strip_prefix('[') {
+        if let Some((level, message)) = rest.split_once(']') {
+            if !level.is_empty() && level.chars().all(|c| c.is_ascii_uppercase()) {
+                return (Some(level), message.trim());
+            }
+        }
+    }
+    (None, line)
+}
+
+// Remove ANSI color sequences, which logging.sh adds around the level tag on a terminal
+pub fn strip_ansi(line: &str) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // CSI sequences end with a letter, e.g. "\x1b[0;31m"
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            output.push(c);
+        }
+    }
+    output
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+// Seconds since the epoch as "YYYY-MM-DD HH:MM:SS UTC"
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let time = secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
+
+// Seconds since the epoch as "YYYYMMDD_HHMMSS", for file names
+pub fn file_timestamp(secs: u64) -> String {
+    format_timestamp(secs)
+        .chars()
+        .take(19)
+        .filter(|c| c.is_ascii_digit() || *c == ' ')
+        .map(|c| if c == ' ' { '_' } else { c })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Html,
+}
+
+// Write the report to <dir>/<stem>.<json|html> and return the path
+pub fn export(report: &AuditReport, dir: &Path, format: ExportFormat) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let (extension, content) = match format {
+        ExportFormat::Json => ("json", report.to_json()),
+        ExportFormat::Html => ("html", report.to_html()),
+    };
+    let path = dir.join(format!("{}.{}", report.file_stem(), extension));
+    fs::write(&path, content)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // fixtures/audit/hardn-audit.txt is a colored `hardn audit` run with one step of
+    // every kind of result, an empty AIDE step and markup in a rootkit finding
+    fn audit_report() -> AuditReport {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/audit/hardn-audit.txt");
+        let mut builder = ReportBuilder::new("hardn audit --tag '<nightly>'");
+        for line in fs::read_to_string(path).unwrap().lines() {
+            builder.push_line(line);
+        }
+        let mut report = builder.finish(Some(1));
+        report.generated_at = 1_792_334_591;
+        report.hostname = "web<01>".to_string();
+        report.os = "Debian GNU/Linux 13 (trixie)".to_string();
+        report.kernel = "6.12.48+deb13-amd64".to_string();
+        report
+    }
+
+    #[test]
+    fn sections_and_findings_of_an_audit_run() {
+        let report = audit_report();
+
+        let titles: Vec<&str> = report.sections.iter().map(|section| section.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            ["General", "Lynis Audit", "Network Scan", "Rootkit Detection", "Malware Scan", "Security Report"]
+        );
+        assert_eq!(report.sections[0].notes, ["Starting HARDN security audit"]);
+        assert_eq!(report.summary(), "5 findings · 2 high, 1 medium, 1 low, 1 passed");
+
+        let lynis = &report.sections[1];
+        assert_eq!(lynis.notes, ["Running Lynis scan...", "Hardening index : 64 [############        ]"]);
+        assert_eq!(
+            lynis.findings[0],
+            Finding {
+                id: "F-001".to_string(),
+                section: "Lynis Audit".to_string(),
+                title: "Lynis reported 3 warnings".to_string(),
+                severity: Severity::Medium,
+                evidence: vec![
+                    "Hardening index : 64 [############        ]".to_string(),
+                    "Review the Lynis log: /var/log/lynis.log".to_string(),
+                ],
+                remediation: Some(REMEDIATION[0].1.to_string()),
+            }
+        );
+        assert_eq!(lynis.findings[1].severity, Severity::Low);
+        assert!(lynis.findings[1].evidence.is_empty());
+
+        let passed = &report.sections[2].findings[0];
+        assert_eq!((passed.id.as_str(), passed.severity), ("F-003", Severity::Pass));
+        assert_eq!(passed.evidence, ["Listening on 0.0.0.0:22 (sshd)"]);
+        assert_eq!(passed.remediation, None);
+
+        let malware = &report.sections[4].findings[0];
+        assert_eq!((malware.title.as_str(), malware.severity), ("clamscan exited with status 2", Severity::High));
+
+        let by_severity: Vec<&str> = report.sorted_findings(SortKey::Severity).iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(by_severity, ["F-004", "F-005", "F-001", "F-002", "F-003"]);
+        let by_title: Vec<&str> = report.sorted_findings(SortKey::Title).iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(by_title, ["F-002", "F-005", "F-001", "F-003", "F-004"]);
+    }
+
+    #[test]
+    fn html_escapes_everything_from_the_run() {
+        let html = audit_report().to_html();
+
+        assert!(html.contains("<title>HARDN Security Audit Report - web&lt;01&gt;</title>"));
+        assert!(html.contains("<tr><th>Generated</th><td>2026-10-18 14:43:11 UTC</td></tr>"));
+        assert!(html.contains("<tr><th>Command</th><td>hardn audit --tag &#39;&lt;nightly&gt;&#39;</td></tr>"));
+        assert!(html.contains("<tr><th>Exit code</th><td>1</td></tr>"));
+        assert!(html.contains(&format!(
+            "<tr><td>F-004</td><td class=\"high\">High</td>\
+             <td>Possible rootkit: &lt;Suckit&gt; in /usr/bin/&quot;ls&quot; &amp; /bin/ps</td>\
+             <td><ul><li>Checking `lkm&#39;... chkproc: nothing detected</li></ul></td><td>{}</td></tr>\n",
+            REMEDIATION[3].1
+        )));
+        assert!(html.contains("<h2>Security Report</h2>\n<ul class=\"notes\">\n<li>Report saved to /var/log/hardn/audit-report.txt</li>\n</ul>\n"));
+        assert!(html.ends_with("</body>\n</html>\n"));
+
+        assert!(!html.contains("<Suckit>"));
+        assert!(!html.contains("<nightly>"));
+        assert!(!html.contains("\x1b"));
+        // The empty AIDE step is dropped
+        assert!(!html.contains("File Integrity"));
+    }
+
+    #[test]
+    fn html_escaping() {
+        assert_eq!(escape_html("<a href=\"x\">Tom & Jerry's</a>"), "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;");
+        // Already escaped text is escaped again, it is output and not markup
+        assert_eq!(escape_html("&lt;"), "&amp;lt;");
+        assert_eq!(escape_html("plain text"), "plain text");
+    }
+
+    #[test]
+    fn lines_and_levels() {
+        assert_eq!(strip_ansi("\x1b[1;33m[WARN]\x1b[0m WARNING x"), "[WARN] WARNING x");
+        assert_eq!(split_level("[WARN]  WARNING x "), (Some("WARN"), "WARNING x"));
+        assert_eq!(split_level("[Warn] x"), (None, "[Warn] x"));
+        assert_eq!(split_level("[] x"), (None, "[] x"));
+        assert_eq!(split_level("[INFO x"), (None, "[INFO x"));
+    }
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_timestamp(951_827_696), "2000-02-29 12:34:56 UTC");
+        assert_eq!(format_timestamp(4_107_542_399), "2100-02-28 23:59:59 UTC");
+        assert_eq!(file_timestamp(1_792_334_591), "20261018_144311");
+    }
+
+    #[test]
+    fn json_and_html_exports() {
+        let mut report = audit_report();
+        report.hostname = "web01".to_string();
+        let dir = std::env::temp_dir().join(format!("hardn-report-test-{}", std::process::id()));
+        let json = export(&report, &dir, ExportFormat::Json);
+        let html = export(&report, &dir, ExportFormat::Html);
+        let read_back = json.as_ref().ok().and_then(|path| fs::read_to_string(path).ok());
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(json.unwrap(), dir.join("audit-web01-20261018_144311.json"));
+        assert_eq!(html.unwrap(), dir.join("audit-web01-20261018_144311.html"));
+        let parsed: AuditReport = serde_json::from_str(&read_back.unwrap()).unwrap();
+        assert_eq!(parsed, report);
+        assert!(report.to_json().contains("\"severity\": \"high\""));
+    }
+}
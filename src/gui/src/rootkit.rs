@@ -0,0 +1,556 @@
+// Rootkit scan logs written by check_rootkits in modules/audit.sh: rkhunter-<ts>.log
+// (`rkhunter --check --report-warnings-only`) and chkrootkit-<ts>.log. Both are reduced
+// to findings with a severity. Known false positives go on a whitelist that persists
+// across runs and is applied wherever the findings are counted.
+//
+// rkhunter reports "Warning: <message>", with indented continuation lines for lists:
+//
+//   Warning: Hidden directory found: /etc/.java
+//   Warning: The file properties have changed:
+//            File: /usr/bin/ls
+//
+// chkrootkit prints one line per test, the result after the dots, and lists files
+// on the following lines:
+//
+//   Checking `bindshell'...                                     INFECTED PORTS: ( 465)
+//   Searching for suspicious files and dirs, it may take a while...
+//   /usr/lib/python3/dist-packages/.coverage /lib/modules/.build
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::report::Severity;
+
+const LOG_EXTENSION: &str = "log";
+const WHITELIST_FILE: &str = "rootkit-whitelist.json";
+
+// chkrootkit results that mean the test passed
+const CHKROOTKIT_PASSED: &[&str] = &[
+    "not infected",
+    "not found",
+    "nothing found",
+    "nothing detected",
+    "nothing deleted",
+    "no suspect files",
+    "no suspicious files",
+    "not tested",
+    "not promisc and no packet sniffer sockets",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tool {
+    Rkhunter,
+    Chkrootkit,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 2] = [Tool::Rkhunter, Tool::Chkrootkit];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Tool::Rkhunter => "rkhunter",
+            Tool::Chkrootkit => "chkrootkit",
+        }
+    }
+
+    fn log_prefix(&self) -> &'static str {
+        match self {
+            Tool::Rkhunter => "rkhunter-",
+            Tool::Chkrootkit => "chkrootkit-",
+        }
+    }
+
+    pub fn parse(&self, content: &str) -> Vec<RootkitFinding> {
+        match self {
+            Tool::Rkhunter => parse_rkhunter(content),
+            Tool::Chkrootkit => parse_chkrootkit(content),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RootkitFinding {
+    pub tool: Tool,
+    pub check: String,
+    pub result: String,
+    // File, directory or process the finding is about, when it names one
+    pub target: Option<String>,
+    pub details: Vec<String>,
+    pub severity: Severity,
+}
+
+// rkhunter warnings, one finding per affected file where the warning lists several
+pub fn parse_rkhunter(content: &str) -> Vec<RootkitFinding> {
+    let mut findings = Vec::new();
+    let mut current: Option<(String, Vec<String>)> = None;
+
+    for line in content.lines() {
+        let line = strip_ansi(line);
+        let trimmed = line.trim();
+
+        if let Some(message) = trimmed.strip_prefix("Warning:") {
+            flush_rkhunter(&mut current, &mut findings);
+            current = Some((message.trim().to_string(), Vec::new()));
+        } else if let Some((check, result)) = bracket_result(trimmed) {
+            // Full (not warnings-only) output: "/usr/bin/ls   [ Warning ]"
+            flush_rkhunter(&mut current, &mut findings);
+            if result.eq_ignore_ascii_case("warning") || result.eq_ignore_ascii_case("found") {
+                findings.push(RootkitFinding {
+                    tool: Tool::Rkhunter,
+                    check: check.to_string(),
+                    result: result.to_string(),
+                    target: check.starts_with('/').then(|| check.to_string()),
+                    details: Vec::new(),
+                    severity: Severity::Medium,
+                });
+            }
+        } else if line.starts_with(char::is_whitespace) && !trimmed.is_empty() {
+            if let Some((_, continuation)) = current.as_mut() {
+                continuation.push(trimmed.to_string());
+            }
+        } else {
+            flush_rkhunter(&mut current, &mut findings);
+        }
+    }
+    flush_rkhunter(&mut current, &mut findings);
+    findings
+}
+
+fn flush_rkhunter(current: &mut Option<(String, Vec<String>)>, findings: &mut Vec<RootkitFinding>) {
+    let Some((message, continuation)) = current.take() else {
+        return;
+    };
+    let severity = rkhunter_severity(&message);
+    let finding = |check: &str, target: Option<String>, details: Vec<String>| RootkitFinding {
+        tool: Tool::Rkhunter,
+        check: check.to_string(),
+        result: "Warning".to_string(),
+        target,
+        details,
+        severity,
+    };
+
+    if let Some(check) = message.strip_suffix(':') {
+        // "The file properties have changed:" followed by "File: /path" and the details
+        if let Some(path) = continuation.iter().find_map(|line| line.strip_prefix("File:")) {
+            findings.push(finding(check, Some(path.trim().to_string()), continuation.clone()));
+            return;
+        }
+        // "Suspicious file types found in /dev:" followed by one "path: type" per line
+        if !continuation.is_empty() && continuation.iter().all(|line| line.starts_with('/')) {
+            for line in &continuation {
+                let path = line.split_once(": ").map_or(line.as_str(), |(path, _)| path);
+                findings.push(finding(check, Some(path.to_string()), vec![line.clone()]));
+            }
+            return;
+        }
+        findings.push(finding(check, None, continuation));
+        return;
+    }
+
+    // "Hidden file found: /dev/.blkid.tab: ASCII text"
+    if let Some((check, rest)) = message.split_once(": /") {
+        let path = format!("/{}", rest.split_once(": ").map_or(rest, |(path, _)| path));
+        let mut details = vec![message.clone()];
+        details.extend(continuation);
+        findings.push(finding(check, Some(path), details));
+        return;
+    }
+
+    let mut details = continuation;
+    if details.is_empty() {
+        details.push(message.clone());
+    }
+    findings.push(finding(&message, first_path(&message), details));
+}
+
+fn rkhunter_severity(message: &str) -> Severity {
+    let message = message.to_ascii_lowercase();
+    if message.contains("rootkit") || message.contains("infected") {
+        Severity::High
+    } else if message.starts_with("hidden") {
+        Severity::Low
+    } else {
+        Severity::Medium
+    }
+}
+
+// chkrootkit tests that did not pass, one finding per listed file
+pub fn parse_chkrootkit(content: &str) -> Vec<RootkitFinding> {
+    let mut findings = Vec::new();
+    let mut current: Option<(String, String, Vec<String>)> = None;
+
+    for line in content.lines() {
+        let line = strip_ansi(line);
+        let trimmed = line.trim();
+
+        if let Some((check, result)) = chkrootkit_test(trimmed) {
+            flush_chkrootkit(&mut current, &mut findings);
+            current = Some((check, result, Vec::new()));
+        } else if !trimmed.is_empty() && !is_passed(trimmed) {
+            // Sub-tests that pass get lines of their own too, e.g. "chkdirs: nothing detected" after lkm
+            if let Some((_, _, details)) = current.as_mut() {
+                details.push(trimmed.to_string());
+            }
+        }
+    }
+    flush_chkrootkit(&mut current, &mut findings);
+    findings
+}
+
+// "Checking `name'... result" or "Searching for something... result"
+fn chkrootkit_test(line: &str) -> Option<(String, String)> {
+    let (check, result) = if let Some(rest) = line.strip_prefix("Checking `") {
+        let (name, rest) = rest.split_once('\'')?;
+        (name.to_string(), rest)
+    } else {
+        let rest = line.strip_prefix("Searching for ")?;
+        let (name, rest) = rest.split_once("...")?;
+        let name = name.trim_end_matches(", it may take a while").trim();
+        (format!("Searching for {}", name), rest)
+    };
+    let result = result.trim_start_matches('.').trim().to_string();
+    Some((check, result))
+}
+
+fn flush_chkrootkit(current: &mut Option<(String, String, Vec<String>)>, findings: &mut Vec<RootkitFinding>) {
+    let Some((check, result, details)) = current.take() else {
+        return;
+    };
+    let text = format!("{} {}", result, details.join(" ")).to_ascii_lowercase();
+    let passed = result.is_empty() || is_passed(&result);
+    let severity = if text.contains("infected") && !text.contains("not infected") {
+        Severity::High
+    } else if text.contains("warning") || text.contains("suspicious") || text.contains("vulnerable") || text.contains("sniffer(") || text.contains("possible") {
+        Severity::Medium
+    } else if passed && details.is_empty() {
+        return;
+    } else if passed {
+        // e.g. a "nothing found" test that still printed something
+        Severity::Low
+    } else {
+        Severity::Medium
+    };
+
+    let finding = |target: Option<String>, details: Vec<String>| RootkitFinding {
+        tool: Tool::Chkrootkit,
+        check: check.clone(),
+        result: if result.is_empty() { "Warning".to_string() } else { result.clone() },
+        target,
+        details,
+        severity,
+    };
+
+    // Lines listing files, several per line for the suspicious files search
+    let paths: Vec<&str> = details
+        .iter()
+        .filter(|line| line.starts_with('/'))
+        .flat_map(|line| line.split_whitespace())
+        .filter(|token| token.starts_with('/'))
+        .collect();
+    if paths.is_empty() {
+        findings.push(finding(first_path(&result), details));
+        return;
+    }
+    let notes: Vec<String> = details.iter().filter(|line| !line.starts_with('/')).cloned().collect();
+    for path in paths {
+        findings.push(finding(Some(path.to_string()), notes.clone()));
+    }
+}
+
+// Results may name what was tested first, e.g. "lo: not promisc and no packet sniffer sockets"
+fn is_passed(result: &str) -> bool {
+    let result = result.to_ascii_lowercase();
+    CHKROOTKIT_PASSED.iter().any(|passed| result.ends_with(passed))
+}
+
+// "name   [ Result ]"
+fn bracket_result(line: &str) -> Option<(&str, &str)> {
+    let rest = line.strip_suffix(']')?;
+    let (check, result) = rest.rsplit_once('[')?;
+    let check = check.trim();
+    (!check.is_empty()).then_some((check, result.trim()))
+}
+
+fn first_path(text: &str) -> Option<String> {
+    text.split_whitespace()
+        .map(|token| token.trim_matches(|c: char| c == '\'' || c == '"' || c == ',' || c == '.' || c == ':' || c == '(' || c == ')'))
+        .find(|token| token.starts_with('/') && token.len() > 1)
+        .map(str::to_string)
+}
+
+fn strip_ansi(line: &str) -> String {
+    let mut plain = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // CSI sequences end with a letter
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScanResults {
+    pub findings: Vec<RootkitFinding>,
+    // Log each tool's findings came from
+    pub sources: Vec<(Tool, PathBuf)>,
+    pub errors: Vec<String>,
+}
+
+// Findings of the newest log of each tool
+pub fn load_latest(dir: &Path) -> ScanResults {
+    let mut results = ScanResults::default();
+    for tool in Tool::ALL {
+        let latest = match config::timestamped_files(dir, tool.log_prefix(), LOG_EXTENSION) {
+            Ok(files) => files.into_iter().last(),
+            Err(e) => {
+                results.errors.push(e);
+                continue;
+            }
+        };
+        let Some(path) = latest else {
+            continue;
+        };
+        match fs::read_to_string(&path) {
+            Ok(content) => {
+                results.findings.extend(tool.parse(&content));
+                results.sources.push((tool, path));
+            }
+            Err(e) => results.errors.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+    results.findings.sort_by(|a, b| a.severity.cmp(&b.severity).then_with(|| a.tool.cmp(&b.tool)));
+    results
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WhitelistEntry {
+    pub tool: Tool,
+    pub check: String,
+    pub target: Option<String>,
+    pub user: String,
+    pub added_at: u64,
+}
+
+impl WhitelistEntry {
+    pub fn matches(&self, finding: &RootkitFinding) -> bool {
+        self.tool == finding.tool && self.check == finding.check && self.target == finding.target
+    }
+}
+
+pub struct Whitelist {
+    path: PathBuf,
+    entries: Vec<WhitelistEntry>,
+}
+
+impl Whitelist {
+    pub fn path() -> PathBuf {
+        config::user_data_dir().join(WHITELIST_FILE)
+    }
+
+    // A missing file is an empty whitelist
+    pub fn load() -> io::Result<Self> {
+        Self::load_from(Self::path())
+    }
+
+    pub fn load_from(path: PathBuf) -> io::Result<Self> {
+        let entries = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(Whitelist { path, entries })
+    }
+
+    pub fn contains(&self, finding: &RootkitFinding) -> bool {
+        self.entries.iter().any(|entry| entry.matches(finding))
+    }
+
+    pub fn add(&mut self, finding: &RootkitFinding, user: &str) {
+        if self.contains(finding) {
+            return;
+        }
+        self.entries.push(WhitelistEntry {
+            tool: finding.tool,
+            check: finding.check.clone(),
+            target: finding.target.clone(),
+            user: user.to_string(),
+            added_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+        });
+    }
+
+    pub fn remove(&mut self, finding: &RootkitFinding) {
+        self.entries.retain(|entry| !entry.matches(finding));
+    }
+
+    // Write through a temporary file so a crash never leaves a truncated whitelist
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        let json = serde_json::to_string_pretty(&self.entries).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, &self.path)
+    }
+
+    // Findings not on the whitelist
+    pub fn active<'a>(&self, findings: &'a [RootkitFinding]) -> Vec<&'a RootkitFinding> {
+        findings.iter().filter(|finding| !self.contains(finding)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Logs as check_rootkits writes them: two rkhunter runs, --report-warnings-only
+    // and partly coloured, and a chkrootkit run
+    fn dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/rootkit")
+    }
+
+    fn parse(tool: Tool, name: &str) -> Vec<RootkitFinding> {
+        tool.parse(&fs::read_to_string(dir().join(name)).unwrap())
+    }
+
+    fn summary(findings: &[RootkitFinding]) -> Vec<(&str, Option<&str>, Severity)> {
+        findings.iter().map(|finding| (finding.check.as_str(), finding.target.as_deref(), finding.severity)).collect()
+    }
+
+    #[test]
+    fn rkhunter_warnings() {
+        let findings = parse(Tool::Rkhunter, "rkhunter-20251018_040001.log");
+        assert_eq!(
+            summary(&findings),
+            [
+                ("The command '/usr/bin/lwp-request' has been replaced by a script", Some("/usr/bin/lwp-request"), Severity::Medium),
+                ("The file properties have changed", Some("/usr/bin/ls"), Severity::Medium),
+                ("Hidden directory found", Some("/etc/.java"), Severity::Low),
+                ("Hidden file found", Some("/dev/.blkid.tab"), Severity::Low),
+                ("Suspicious file types found in /dev", Some("/dev/shm/PostgreSQL.1804289383"), Severity::Medium),
+                ("Suspicious file types found in /dev", Some("/dev/shm/.x"), Severity::Medium),
+                ("Possible Ebury rootkit: found file /lib/x86_64-linux-gnu/libns2.so", Some("/lib/x86_64-linux-gnu/libns2.so"), Severity::High),
+                ("Found preloaded shared library", Some("/usr/lib/x86_64-linux-gnu/libfakeroot.so"), Severity::Medium),
+            ]
+        );
+        assert!(findings.iter().all(|finding| finding.tool == Tool::Rkhunter && finding.result == "Warning"));
+        // Continuation lines are kept as details
+        assert_eq!(findings[1].details.len(), 4);
+        assert_eq!(findings[1].details[0], "File: /usr/bin/ls");
+        assert_eq!(findings[5].details, ["/dev/shm/.x: ELF 64-bit LSB executable, x86-64"]);
+        assert_eq!(findings[3].details, ["Hidden file found: /dev/.blkid.tab: ASCII text"]);
+    }
+
+    #[test]
+    fn rkhunter_full_output() {
+        let content = "Checking for rootkits...\n  Suckit Rootkit                                    [ Not found ]\n  Xzibit Rootkit                                    [ Found ]\n  /usr/bin/ls                                       [ Warning ]\n  /usr/bin/cat                                      [ OK ]\n";
+        assert_eq!(
+            summary(&parse_rkhunter(content)),
+            [("Xzibit Rootkit", None, Severity::Medium), ("/usr/bin/ls", Some("/usr/bin/ls"), Severity::Medium)]
+        );
+        assert_eq!(strip_ansi("\x1b[1;33mWarning\x1b[0m: x"), "Warning: x");
+        assert!(parse_rkhunter("").is_empty());
+    }
+
+    #[test]
+    fn chkrootkit_results() {
+        let findings = parse(Tool::Chkrootkit, "chkrootkit-20251018_040001.log");
+        // Tests that passed are dropped, also when a sub-test passes on its own line
+        assert_eq!(
+            summary(&findings),
+            [
+                ("bindshell", None, Severity::High),
+                ("sniffer", None, Severity::Medium),
+                ("Searching for suspicious files and dirs", Some("/usr/lib/python3/dist-packages/.coverage"), Severity::Medium),
+                ("Searching for suspicious files and dirs", Some("/lib/modules/6.1.0-13-amd64/.build"), Severity::Medium),
+                ("Searching for Linux.Xor.DDoS", Some("/tmp/.x/sshd"), Severity::High),
+            ]
+        );
+        assert_eq!(findings[0].result, "INFECTED PORTS: ( 465)");
+        assert_eq!(findings[1].details, ["eth0: PACKET SNIFFER(/usr/sbin/dhclient[612])"]);
+        assert_eq!(findings[4].result, "INFECTED: Possible Malicious Linux.Xor.DDoS installed");
+        assert!(findings[2].details.is_empty());
+
+        assert_eq!(chkrootkit_test("Checking `lkm'...    chkproc: nothing detected"), Some(("lkm".to_string(), "chkproc: nothing detected".to_string())));
+        assert_eq!(chkrootkit_test("ROOTDIR is `/'"), None);
+    }
+
+    #[test]
+    fn newest_log_of_each_tool() {
+        let results = load_latest(&dir());
+        assert!(results.errors.is_empty());
+        let sources: Vec<(Tool, String)> = results
+            .sources
+            .iter()
+            .map(|(tool, path)| (*tool, path.file_name().unwrap().to_string_lossy().to_string()))
+            .collect();
+        assert_eq!(
+            sources,
+            [(Tool::Rkhunter, "rkhunter-20251018_040001.log".to_string()), (Tool::Chkrootkit, "chkrootkit-20251018_040001.log".to_string())]
+        );
+        assert_eq!(results.findings.len(), 13);
+        // Most severe first, rkhunter before chkrootkit within a severity
+        let order: Vec<(Severity, Tool)> = results.findings.iter().map(|finding| (finding.severity, finding.tool)).collect();
+        let mut sorted = order.clone();
+        sorted.sort();
+        assert_eq!(order, sorted);
+        assert_eq!(order[..3], [(Severity::High, Tool::Rkhunter), (Severity::High, Tool::Chkrootkit), (Severity::High, Tool::Chkrootkit)]);
+
+        let missing = load_latest(&dir().join("missing"));
+        assert!(missing.findings.is_empty());
+        assert_eq!(missing.errors.len(), 2);
+    }
+
+    #[test]
+    fn whitelist_round_trip() {
+        let dir = std::env::temp_dir().join(format!("hardn-rootkit-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("hardn").join(WHITELIST_FILE);
+        let findings = parse(Tool::Rkhunter, "rkhunter-20251018_040001.log");
+
+        let mut whitelist = Whitelist::load_from(path.clone()).unwrap();
+        assert!(whitelist.entries.is_empty());
+        whitelist.add(&findings[2], "1000");
+        whitelist.add(&findings[4], "1000");
+        whitelist.add(&findings[2], "1001");
+        whitelist.save().unwrap();
+
+        let loaded = Whitelist::load_from(path.clone()).unwrap();
+        let mut removed = Whitelist::load_from(path.clone()).unwrap();
+        removed.remove(&findings[2]);
+        removed.save().unwrap();
+        let reloaded = Whitelist::load_from(path.clone()).unwrap();
+        let leftovers: Vec<String> = fs::read_dir(path.parent().unwrap()).unwrap().map(|entry| entry.unwrap().file_name().to_string_lossy().to_string()).collect();
+        fs::write(&path, "{\"not\": \"a list\"}").unwrap();
+        let corrupt = Whitelist::load_from(path.clone()).map(|_| ()).map_err(|e| e.kind());
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded.entries, whitelist.entries);
+        assert_eq!(loaded.entries.len(), 2);
+        assert_eq!(loaded.entries[0].user, "1000");
+        assert!(loaded.contains(&findings[2]) && loaded.contains(&findings[4]));
+        // The other file of the same check is a finding of its own
+        assert!(!loaded.contains(&findings[5]));
+        assert_eq!(loaded.active(&findings).len(), findings.len() - 2);
+
+        assert!(!reloaded.contains(&findings[2]));
+        assert!(reloaded.contains(&findings[4]));
+        assert_eq!(leftovers, [WHITELIST_FILE]);
+        assert_eq!(corrupt, Err(io::ErrorKind::InvalidData));
+    }
+}
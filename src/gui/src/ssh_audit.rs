@@ -0,0 +1,266 @@
+// HARDN baseline for sshd: the effective value of each setting, where it comes from and
+// the smallest edit that brings it in line. A setting is changed where sshd reads it
+// from, so a drop-in that wins over the main file is edited in place; settings nobody
+// sets are added to the main file ahead of its first Match block.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use crate::report::Severity;
+use crate::sshd_config::{Directive, SshdConfig, SshdEdit, SystemFs, SSHD_CONFIG};
+
+pub const BANNER_FILE: &str = "/etc/ssh/banner";
+
+// Modern algorithms supported by the OpenSSH releases HARDN targets
+pub const CIPHERS: &str =
+    "chacha20-poly1305@openssh.com,aes256-gcm@openssh.com,aes128-gcm@openssh.com,aes256-ctr,aes192-ctr,aes128-ctr";
+pub const MACS: &str = "hmac-sha2-512-etm@openssh.com,hmac-sha2-256-etm@openssh.com,umac-128-etm@openssh.com,hmac-sha2-512,hmac-sha2-256";
+pub const KEX_ALGORITHMS: &str = "sntrup761x25519-sha512@openssh.com,curve25519-sha256,curve25519-sha256@libssh.org,diffie-hellman-group16-sha512,diffie-hellman-group18-sha512,diffie-hellman-group-exchange-sha256";
+
+// Substrings of algorithm names that are broken or too weak to keep enabled
+const WEAK_ALGORITHMS: &[&str] =
+    &["cbc", "3des", "arcfour", "blowfish", "cast128", "md5", "ripemd", "hmac-sha1", "umac-64", "group1-", "group14-sha1", "group-exchange-sha1"];
+
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub keyword: &'static str,
+    // Match criteria for settings that only apply to some connections
+    pub scope: Option<String>,
+    pub severity: Severity,
+    pub current: String,
+    pub expected: String,
+    // Where the effective value is set; None for sshd's built-in default
+    pub source: Option<String>,
+    pub note: Option<&'static str>,
+    pub fix: Option<SshdEdit>,
+}
+
+impl Finding {
+    pub fn is_deviation(&self) -> bool {
+        self.severity != Severity::Pass
+    }
+
+    pub fn scope_label(&self) -> String {
+        match &self.scope {
+            Some(criteria) => format!("Match {}", criteria),
+            None => "global".to_string(),
+        }
+    }
+
+    pub fn source_label(&self) -> String {
+        self.source.clone().unwrap_or_else(|| "sshd default".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AuditReport {
+    pub findings: Vec<Finding>,
+    pub files: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+impl AuditReport {
+    pub fn deviations(&self) -> usize {
+        self.findings.iter().filter(|finding| finding.is_deviation()).count()
+    }
+
+    pub fn summary(&self) -> String {
+        let count = |severity| self.findings.iter().filter(|finding| finding.severity == severity).count();
+        format!(
+            "{} settings from {} files · {} high, {} medium, {} low, {} meet the baseline",
+            self.findings.len(),
+            self.files.len(),
+            count(Severity::High),
+            count(Severity::Medium),
+            count(Severity::Low),
+            self.findings.len() - self.deviations()
+        )
+    }
+}
+
+// What a baseline setting looks like, with sshd's built-in default
+struct Rule {
+    keyword: &'static str,
+    default: &'static str,
+    expected: &'static str,
+    // Also checked inside Match blocks; algorithms cannot be set there
+    per_match: bool,
+    note: Option<&'static str>,
+    assess: fn(&str) -> Severity,
+}
+
+const RULES: &[Rule] = &[
+    Rule {
+        keyword: "PermitRootLogin",
+        default: "prohibit-password",
+        expected: "no",
+        per_match: true,
+        note: None,
+        assess: |value| match value.to_ascii_lowercase().as_str() {
+            "no" => Severity::Pass,
+            "yes" => Severity::High,
+            _ => Severity::Medium,
+        },
+    },
+    Rule {
+        keyword: "PasswordAuthentication",
+        default: "yes",
+        expected: "no",
+        per_match: true,
+        note: Some("Make sure key based logins work for every administrator before turning passwords off"),
+        assess: |value| if value.eq_ignore_ascii_case("no") { Severity::Pass } else { Severity::Medium },
+    },
+    Rule {
+        keyword: "MaxAuthTries",
+        default: "6",
+        expected: "3",
+        per_match: true,
+        note: None,
+        assess: |value| match value.parse::<u32>() {
+            Ok(tries) if tries <= 3 => Severity::Pass,
+            _ => Severity::Medium,
+        },
+    },
+    Rule {
+        keyword: "Banner",
+        default: "none",
+        expected: BANNER_FILE,
+        per_match: true,
+        note: Some("openssh.sh writes the banner text to /etc/ssh/banner"),
+        assess: |value| if value.eq_ignore_ascii_case("none") { Severity::Low } else { Severity::Pass },
+    },
+    Rule { keyword: "Ciphers", default: "", expected: CIPHERS, per_match: false, note: None, assess: |value| assess_algorithms(value, CIPHERS) },
+    Rule { keyword: "MACs", default: "", expected: MACS, per_match: false, note: None, assess: |value| assess_algorithms(value, MACS) },
+    Rule {
+        keyword: "KexAlgorithms",
+        default: "",
+        expected: KEX_ALGORITHMS,
+        per_match: false,
+        note: None,
+        assess: |value| assess_algorithms(value, KEX_ALGORITHMS),
+    },
+];
+
+// Unset lists are fine: current OpenSSH defaults contain no weak algorithms. A list
+// starting with '-' only removes defaults; '+' and '^' add to them.
+fn assess_algorithms(value: &str, allowed: &str) -> Severity {
+    if value.is_empty() || value.starts_with('-') {
+        return Severity::Pass;
+    }
+    let names: Vec<&str> = value.trim_start_matches(['+', '^']).split(',').filter(|name| !name.is_empty()).collect();
+    if names.iter().any(|name| WEAK_ALGORITHMS.iter().any(|weak| name.contains(weak))) {
+        Severity::High
+    } else if names.iter().any(|name| !allowed.split(',').any(|allowed| allowed == *name)) {
+        Severity::Low
+    } else {
+        Severity::Pass
+    }
+}
+
+// The directive's line with a new value, indentation kept
+fn replacement(directive: &Directive, keyword: &str, value: &str) -> SshdEdit {
+    let indent: String = directive.raw.chars().take_while(|c| c.is_whitespace()).collect();
+    SshdEdit {
+        path: directive.file.display().to_string(),
+        line: directive.line,
+        old: Some(directive.raw.clone()),
+        new: format!("{}{} {}", indent, keyword, value),
+    }
+}
+
+pub fn audit(config: &SshdConfig) -> AuditReport {
+    let main = config.files.first().map(|file| file.path.display().to_string()).unwrap_or_else(|| SSHD_CONFIG.to_string());
+    let insert_line = config.global_insert_line();
+    let mut findings = Vec::new();
+
+    for rule in RULES {
+        let directive = config.global(rule.keyword);
+        let current = directive.map(|directive| directive.value()).unwrap_or_else(|| rule.default.to_string());
+        let severity = (rule.assess)(&current);
+        let fix = (severity != Severity::Pass).then(|| match directive {
+            Some(directive) => replacement(directive, rule.keyword, rule.expected),
+            None => SshdEdit { path: main.clone(), line: insert_line, old: None, new: format!("{} {}", rule.keyword, rule.expected) },
+        });
+        findings.push(Finding {
+            keyword: rule.keyword,
+            scope: None,
+            severity,
+            current: if current.is_empty() { "OpenSSH defaults".to_string() } else { current },
+            expected: rule.expected.to_string(),
+            source: directive.map(Directive::location),
+            note: rule.note,
+            fix,
+        });
+
+        if !rule.per_match {
+            continue;
+        }
+        for directive in config.conditional(rule.keyword) {
+            let severity = (rule.assess)(&directive.value());
+            findings.push(Finding {
+                keyword: rule.keyword,
+                scope: directive.condition.clone(),
+                severity,
+                current: directive.value(),
+                expected: rule.expected.to_string(),
+                source: Some(directive.location()),
+                note: rule.note,
+                fix: (severity != Severity::Pass).then(|| replacement(directive, rule.keyword, rule.expected)),
+            });
+        }
+    }
+
+    AuditReport {
+        findings,
+        files: config.files.iter().map(|file| file.path.display().to_string()).collect(),
+        errors: config.errors.clone(),
+    }
+}
+
+// Patched contents of every file the edits touch
+pub fn patch(config: &SshdConfig, edits: &[SshdEdit]) -> Result<BTreeMap<String, String>, String> {
+    let mut by_file: BTreeMap<&str, Vec<&SshdEdit>> = BTreeMap::new();
+    for edit in edits {
+        by_file.entry(edit.path.as_str()).or_default().push(edit);
+    }
+    by_file
+        .into_iter()
+        .map(|(path, edits)| {
+            let file = config.file(Path::new(path)).ok_or_else(|| format!("{} was not read", path))?;
+            Ok((path.to_string(), crate::sshd_config::patch_content(&file.content, &edits)?))
+        })
+        .collect()
+}
+
+// Unified style preview of the edits, one hunk per changed line
+pub fn render_diff(edits: &[SshdEdit]) -> String {
+    let mut edits: Vec<&SshdEdit> = edits.iter().collect();
+    edits.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    let mut out = String::new();
+    let mut current_path = None;
+    for edit in edits {
+        if current_path != Some(&edit.path) {
+            out.push_str(&format!("--- {}\n+++ {}\n", edit.path, edit.path));
+            current_path = Some(&edit.path);
+        }
+        match &edit.old {
+            Some(old) => out.push_str(&format!("@@ -{0} +{0} @@\n-{1}\n+{2}\n", edit.line, old, edit.new)),
+            None => out.push_str(&format!("@@ -{0},0 +{0} @@\n+{1}\n", edit.line, edit.new)),
+        }
+    }
+    out
+}
+
+// Extra text for the Dashboard's "SSH Hardening" row; None without an sshd_config
+pub fn dashboard_summary() -> Option<String> {
+    match SshdConfig::load(Path::new(SSHD_CONFIG), &SystemFs) {
+        Ok(config) => Some(match audit(&config).deviations() {
+            0 => "HARDN baseline met".to_string(),
+            1 => "1 baseline deviation".to_string(),
+            count => format!("{} baseline deviations", count),
+        }),
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied => Some("audit in the SSH tab".to_string()),
+        Err(_) => None,
+    }
+}
@@ -0,0 +1,679 @@
+// sshd_config the way sshd reads it, and line edits to it.
+//
+// One `Keyword arguments` (or `Keyword=arguments`) per line. `Include` pulls in further
+// files in place, glob patterns sorted and relative paths taken from /etc/ssh. A `Match`
+// line makes the following lines conditional until the next Match or the end of the
+// file; an Include inside a Match block makes the included lines conditional too. For
+// most keywords the first value obtained wins, so with Debian's
+// `Include /etc/ssh/sshd_config.d/*.conf` at the top, drop-ins override the main file.
+//
+// openssh.sh leaves the main file readable by root only. The helper then prints the
+// files as a transcript, "### FILE <lines> <path>" followed by "<number>:<text>" for
+// each non-blank line, which the GUI reads back through the same parser.
+//
+// Edits replace or insert single lines. The helper applies them, checks the result with
+// `sshd -t` and puts the old files back when writing or the check fails, before sshd is
+// reloaded.
+//
+// This file is compiled into both binaries and each only uses its own side of it.
+#![allow(dead_code)]
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::ffi::CString;
+use std::fs;
+use std::io;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::malware::write_atomic;
+
+pub const SSHD_CONFIG: &str = "/etc/ssh/sshd_config";
+pub const SSHD_BIN: &str = "/usr/sbin/sshd";
+pub const SSH_UNIT: &str = "ssh.service";
+const SSH_DIR: &str = "/etc/ssh";
+const FILE_MARKER: &str = "### FILE ";
+// sshd gives up on deeper nesting as well
+const MAX_INCLUDE_DEPTH: usize = 16;
+// One per baseline check, with room for Match blocks
+pub const MAX_EDITS: usize = 64;
+
+// The settings the HARDN baseline covers; edits may only write these
+pub const BASELINE_KEYWORDS: [&str; 7] =
+    ["PermitRootLogin", "PasswordAuthentication", "MaxAuthTries", "Banner", "Ciphers", "MACs", "KexAlgorithms"];
+
+pub trait ConfigFs {
+    fn read(&self, path: &Path) -> io::Result<String>;
+    // Entries of a directory, for Include patterns
+    fn list(&self, dir: &Path) -> io::Result<Vec<PathBuf>>;
+}
+
+pub struct SystemFs;
+
+impl ConfigFs for SystemFs {
+    fn read(&self, path: &Path) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+
+    fn list(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        Ok(fs::read_dir(dir)?.flatten().map(|entry| entry.path()).collect())
+    }
+}
+
+// Files as the helper printed them
+#[derive(Debug, Clone, Default)]
+pub struct Transcript {
+    files: BTreeMap<PathBuf, String>,
+}
+
+impl Transcript {
+    pub fn print(config: &SshdConfig) {
+        for file in &config.files {
+            println!("{}{} {}", FILE_MARKER, file.content.lines().count(), file.path.display());
+            for (index, line) in file.content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+                println!("{}:{}", index + 1, line);
+            }
+        }
+    }
+
+    pub fn parse(output: &str) -> Self {
+        let mut files = BTreeMap::new();
+        let mut current: Option<(PathBuf, Vec<String>)> = None;
+        for line in output.lines() {
+            if let Some(header) = line.strip_prefix(FILE_MARKER) {
+                files.extend(current.take().map(|(path, lines)| (path, lines.join("\n") + "\n")));
+                current = header
+                    .split_once(' ')
+                    .and_then(|(count, path)| Some((PathBuf::from(path), vec![String::new(); count.parse().ok()?])));
+            } else if let Some((_, lines)) = current.as_mut() {
+                let numbered = line.split_once(':').and_then(|(number, text)| Some((number.parse::<usize>().ok()?, text)));
+                if let Some((number, text)) = numbered.filter(|(number, _)| (1..=lines.len()).contains(number)) {
+                    lines[number - 1] = text.to_string();
+                }
+            }
+        }
+        files.extend(current.map(|(path, lines)| (path, lines.join("\n") + "\n")));
+        Transcript { files }
+    }
+}
+
+impl ConfigFs for Transcript {
+    fn read(&self, path: &Path) -> io::Result<String> {
+        self.files.get(path).cloned().ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+    }
+
+    fn list(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        Ok(self.files.keys().filter(|path| path.parent() == Some(dir)).cloned().collect())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Directive {
+    pub file: PathBuf,
+    pub line: usize,
+    // As written; sshd compares keywords case-insensitively
+    pub keyword: String,
+    pub args: Vec<String>,
+    pub raw: String,
+    // Criteria of the enclosing Match block, e.g. "User backup"
+    pub condition: Option<String>,
+}
+
+impl Directive {
+    pub fn is(&self, keyword: &str) -> bool {
+        self.keyword.eq_ignore_ascii_case(keyword)
+    }
+
+    pub fn value(&self) -> String {
+        self.args.join(" ")
+    }
+
+    pub fn location(&self) -> String {
+        format!("{}:{}", self.file.display(), self.line)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SshdConfig {
+    // In the order sshd reads them, the main file first
+    pub files: Vec<SourceFile>,
+    // Match lines included, with their own criteria as condition
+    pub directives: Vec<Directive>,
+    pub errors: Vec<String>,
+}
+
+impl SshdConfig {
+    // Fails only when the main file cannot be read
+    pub fn load(main: &Path, fs: &dyn ConfigFs) -> io::Result<Self> {
+        let content = fs.read(main)?;
+        let mut config = SshdConfig::default();
+        config.parse_file(main, content, None, 0, fs);
+        Ok(config)
+    }
+
+    fn parse_file(&mut self, path: &Path, content: String, inherited: Option<String>, depth: usize, fs: &dyn ConfigFs) {
+        self.files.push(SourceFile { path: path.to_path_buf(), content: content.clone() });
+        let mut condition = inherited;
+        for (index, raw) in content.lines().enumerate() {
+            let Some((keyword, args)) = split_line(raw) else {
+                continue;
+            };
+            let directive = Directive {
+                file: path.to_path_buf(),
+                line: index + 1,
+                keyword,
+                args,
+                raw: raw.to_string(),
+                condition: condition.clone(),
+            };
+            if directive.is("Match") {
+                let criteria = directive.value();
+                // Inside an included file a Match replaces the block the Include was in
+                condition = Some(criteria.clone());
+                self.directives.push(Directive { condition: Some(criteria), ..directive });
+                continue;
+            }
+            if directive.is("Include") {
+                let patterns = directive.args.clone();
+                let included_condition = condition.clone();
+                self.directives.push(directive);
+                for pattern in patterns {
+                    self.include(&pattern, included_condition.clone(), depth, fs);
+                }
+                continue;
+            }
+            self.directives.push(directive);
+        }
+    }
+
+    fn include(&mut self, pattern: &str, condition: Option<String>, depth: usize, fs: &dyn ConfigFs) {
+        if depth + 1 > MAX_INCLUDE_DEPTH {
+            self.errors.push(format!("Include {}: nested too deeply", pattern));
+            return;
+        }
+        let pattern = if pattern.starts_with('/') { PathBuf::from(pattern) } else { Path::new(SSH_DIR).join(pattern) };
+        let paths = match expand(&pattern, fs) {
+            Ok(paths) => paths,
+            Err(e) => {
+                self.errors.push(format!("Include {}: {}", pattern.display(), e));
+                return;
+            }
+        };
+        for path in paths {
+            match fs.read(&path) {
+                Ok(content) => self.parse_file(&path, content, condition.clone(), depth + 1, fs),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => self.errors.push(format!("{}: {}", path.display(), e)),
+            }
+        }
+    }
+
+    // The value sshd uses outside of any Match block
+    pub fn global(&self, keyword: &str) -> Option<&Directive> {
+        self.directives.iter().find(|directive| directive.condition.is_none() && directive.is(keyword))
+    }
+
+    // First occurrence per Match criteria, in the order sshd reads them
+    pub fn conditional(&self, keyword: &str) -> Vec<&Directive> {
+        let mut found: Vec<&Directive> = Vec::new();
+        for directive in self.directives.iter().filter(|directive| !directive.is("Match") && directive.is(keyword)) {
+            if directive.condition.is_some() && !found.iter().any(|seen| seen.condition == directive.condition) {
+                found.push(directive);
+            }
+        }
+        found
+    }
+
+    pub fn file(&self, path: &Path) -> Option<&SourceFile> {
+        self.files.iter().find(|file| file.path == path)
+    }
+
+    // Line number before which a setting for everyone can go in the main file: ahead
+    // of the first Match block, or after the last line
+    pub fn global_insert_line(&self) -> usize {
+        let main = self.files.first().map(|file| file.path.clone()).unwrap_or_else(|| PathBuf::from(SSHD_CONFIG));
+        self.directives
+            .iter()
+            .find(|directive| directive.file == main && directive.is("Match"))
+            .map(|directive| directive.line)
+            .unwrap_or_else(|| self.file(&main).map(|file| file.content.lines().count() + 1).unwrap_or(1))
+    }
+}
+
+// Keyword and arguments; None for blank lines and comments
+fn split_line(line: &str) -> Option<(String, Vec<String>)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let (keyword, rest) = match line.find(|c: char| c.is_whitespace() || c == '=') {
+        Some(end) => (&line[..end], line[end..].trim_start().trim_start_matches('=').trim_start()),
+        None => (line, ""),
+    };
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in rest.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+    Some((keyword.to_string(), args))
+}
+
+// Files an Include pattern names, sorted as glob(3) returns them. Wildcards are
+// supported in the last path component only.
+fn expand(pattern: &Path, fs: &dyn ConfigFs) -> Result<Vec<PathBuf>, String> {
+    let is_glob = |text: &str| text.contains(['*', '?', '[']);
+    let (Some(dir), Some(name)) = (pattern.parent(), pattern.file_name().and_then(|name| name.to_str())) else {
+        return Err("not a file pattern".to_string());
+    };
+    if is_glob(&dir.to_string_lossy()) {
+        return Err("wildcards are only supported in the file name".to_string());
+    }
+    if !is_glob(name) {
+        return Ok(vec![pattern.to_path_buf()]);
+    }
+    let Ok(pattern) = CString::new(name) else {
+        return Err("invalid pattern".to_string());
+    };
+    let mut paths: Vec<PathBuf> = match fs.list(dir) {
+        Ok(paths) => paths,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e.to_string()),
+    };
+    paths.retain(|path| {
+        let Some(name) = path.file_name().and_then(|name| CString::new(name.to_string_lossy().as_bytes()).ok()) else {
+            return false;
+        };
+        // SAFETY: both arguments are valid NUL-terminated strings
+        unsafe { libc::fnmatch(pattern.as_ptr(), name.as_ptr(), libc::FNM_PERIOD) == 0 }
+    });
+    paths.sort();
+    Ok(paths)
+}
+
+// One line change. With `old` the line `line` must still read `old` and is replaced,
+// without it `new` is inserted before `line` (one past the end appends).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SshdEdit {
+    pub path: String,
+    pub line: usize,
+    pub old: Option<String>,
+    pub new: String,
+}
+
+impl SshdEdit {
+    pub fn validate(&self) -> Result<(), String> {
+        let path = Path::new(&self.path);
+        if !path.starts_with(SSH_DIR) || path.components().any(|c| c == Component::ParentDir) || self.path.contains(char::is_control) {
+            return Err(format!("not an sshd configuration file: {}", self.path));
+        }
+        if self.line == 0 {
+            return Err("line numbers start at 1".to_string());
+        }
+        if self.old.as_ref().is_some_and(|old| old.contains(['\n', '\r'])) {
+            return Err("the replaced text must be a single line".to_string());
+        }
+        let Some((keyword, args)) = split_line(&self.new).filter(|_| !self.new.contains(['\n', '\r', '"'])) else {
+            return Err(format!("not a single setting: {}", self.new));
+        };
+        if !BASELINE_KEYWORDS.iter().any(|allowed| allowed.eq_ignore_ascii_case(&keyword)) {
+            return Err(format!("{} is not a setting the GUI edits", keyword));
+        }
+        let value_ok = |arg: &String| arg.chars().all(|c| c.is_ascii_alphanumeric() || "@.,/_:+-^".contains(c));
+        if args.is_empty() || !args.iter().all(value_ok) {
+            return Err(format!("invalid value for {}: {}", keyword, args.join(" ")));
+        }
+        Ok(())
+    }
+
+    pub fn describe(&self) -> String {
+        match &self.old {
+            Some(old) => format!("{}:{}: {} -> {}", self.path, self.line, old.trim(), self.new.trim()),
+            None => format!("{}:{}: insert {}", self.path, self.line, self.new.trim()),
+        }
+    }
+}
+
+// `content` with the edits for its file made; line numbers refer to `content`
+pub fn patch_content(content: &str, edits: &[&SshdEdit]) -> Result<String, String> {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut edits: Vec<(usize, &SshdEdit)> = edits.iter().copied().enumerate().collect();
+    // Bottom up, so that earlier line numbers stay valid; a replacement before an
+    // insertion at the same line, so that the insertion lands above it, and insertions
+    // at the same line last to first, so that they end up in the given order
+    edits.sort_by_key(|(index, edit)| (Reverse(edit.line), edit.old.is_none(), Reverse(*index)));
+    for (_, edit) in edits {
+        match &edit.old {
+            Some(old) => match lines.get_mut(edit.line - 1) {
+                Some(line) if line.trim() == old.trim() => *line = edit.new.clone(),
+                _ => return Err(format!("{} changed since it was read (line {})", edit.path, edit.line)),
+            },
+            None if edit.line <= lines.len() + 1 => lines.insert(edit.line - 1, edit.new.clone()),
+            None => return Err(format!("{} has no line {}", edit.path, edit.line)),
+        }
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+// A file to rewrite, as read and with the edits made
+struct Change<'a> {
+    path: &'a str,
+    original: String,
+    patched: String,
+    mode: u32,
+}
+
+// Puts the files written so far back when dropped, unless the new configuration is
+// kept, so that every early return leaves the previous configuration in place
+struct Rollback<'a> {
+    written: Vec<&'a Change<'a>>,
+    keep: bool,
+}
+
+impl Drop for Rollback<'_> {
+    fn drop(&mut self) {
+        if self.keep {
+            return;
+        }
+        for change in self.written.iter().rev() {
+            if let Err(e) = write_atomic(Path::new(change.path), change.original.as_bytes(), change.mode) {
+                eprintln!("{}: could not restore the previous content: {}", change.path, e);
+            }
+        }
+    }
+}
+
+// Write the patched files and keep them only when `check` passes
+fn write_checked(changes: &[Change], check: impl FnOnce() -> io::Result<()>) -> io::Result<()> {
+    let mut rollback = Rollback { written: Vec::new(), keep: false };
+    for change in changes {
+        write_atomic(Path::new(change.path), change.patched.as_bytes(), change.mode)?;
+        rollback.written.push(change);
+    }
+    check()?;
+    rollback.keep = true;
+    Ok(())
+}
+
+fn sshd_check() -> io::Result<()> {
+    let check = Command::new(SSHD_BIN).arg("-t").env_clear().env("LANG", "C.UTF-8").stdin(Stdio::null()).output()?;
+    if !check.status.success() {
+        return Err(io::Error::other(format!(
+            "sshd -t rejected the change, the previous configuration was restored: {}",
+            String::from_utf8_lossy(&check.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+// Make the edits, check the configuration with `sshd -t` and reload sshd. The previous
+// files are restored when writing or the check fails.
+pub fn apply(edits: &[SshdEdit]) -> io::Result<String> {
+    let mut paths: Vec<&str> = edits.iter().map(|edit| edit.path.as_str()).collect();
+    paths.sort();
+    paths.dedup();
+
+    let mut changes = Vec::new();
+    for path in paths {
+        let meta = fs::symlink_metadata(path)?;
+        if !meta.file_type().is_file() {
+            return Err(io::Error::other(format!("{} is not a regular file", path)));
+        }
+        let original = fs::read_to_string(path)?;
+        let file_edits: Vec<&SshdEdit> = edits.iter().filter(|edit| edit.path == path).collect();
+        let patched = patch_content(&original, &file_edits).map_err(io::Error::other)?;
+        changes.push(Change { path, original, patched, mode: meta.permissions().mode() & 0o7777 });
+    }
+
+    for edit in edits {
+        println!("{}", edit.describe());
+    }
+    write_checked(&changes, sshd_check)?;
+
+    let reload = Command::new("systemctl")
+        .args(["reload", SSH_UNIT])
+        .env_clear()
+        .env("PATH", "/usr/sbin:/usr/bin:/sbin:/bin")
+        .stdin(Stdio::null())
+        .status()?;
+    if !reload.success() {
+        return Err(io::Error::other(format!("the configuration was updated, but reloading {} failed ({})", SSH_UNIT, reload)));
+    }
+    Ok(format!("Changed {} lines in {} files, sshd -t passed and {} was reloaded", edits.len(), changes.len(), SSH_UNIT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Files in memory, for Include patterns the same way SystemFs lists them
+    struct FakeFs(BTreeMap<PathBuf, String>);
+
+    impl FakeFs {
+        fn new(files: &[(&str, &str)]) -> Self {
+            FakeFs(files.iter().map(|(path, content)| (PathBuf::from(path), content.to_string())).collect())
+        }
+    }
+
+    impl ConfigFs for FakeFs {
+        fn read(&self, path: &Path) -> io::Result<String> {
+            self.0.get(path).cloned().ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+        }
+
+        fn list(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+            let paths: Vec<PathBuf> = self.0.keys().filter(|path| path.parent() == Some(dir)).cloned().collect();
+            if paths.is_empty() {
+                return Err(io::Error::from(io::ErrorKind::NotFound));
+            }
+            Ok(paths)
+        }
+    }
+
+    const MAIN: &str = "\
+# Debian keeps the drop-ins first, so that they win
+Include /etc/ssh/sshd_config.d/*.conf
+PermitRootLogin yes
+PasswordAuthentication=yes
+Match User backup
+    PasswordAuthentication no
+    Include match.d/extra.conf
+Match Address 10.0.0.0/8
+    MaxAuthTries 2
+";
+
+    fn load() -> SshdConfig {
+        let fs = FakeFs::new(&[
+            (SSHD_CONFIG, MAIN),
+            ("/etc/ssh/sshd_config.d/50-cloud-init.conf", "PasswordAuthentication no\n"),
+            ("/etc/ssh/sshd_config.d/10-hardn.conf", "# HARDN\nPermitRootLogin no\nMaxAuthTries 3\n"),
+            ("/etc/ssh/sshd_config.d/.hidden.conf", "PermitRootLogin yes\n"),
+            ("/etc/ssh/sshd_config.d/README", "Banner none\n"),
+            ("/etc/ssh/match.d/extra.conf", "Banner \"/etc/issue net\"\nMatch Group admins\nMaxAuthTries 6\n"),
+        ]);
+        SshdConfig::load(Path::new(SSHD_CONFIG), &fs).unwrap()
+    }
+
+    #[test]
+    fn includes_are_read_in_place_and_sorted() {
+        let config = load();
+        let files: Vec<&Path> = config.files.iter().map(|file| file.path.as_path()).collect();
+        assert_eq!(
+            files,
+            [
+                SSHD_CONFIG,
+                "/etc/ssh/sshd_config.d/10-hardn.conf",
+                "/etc/ssh/sshd_config.d/50-cloud-init.conf",
+                "/etc/ssh/match.d/extra.conf",
+            ]
+            .map(Path::new)
+        );
+        assert!(config.errors.is_empty(), "{:?}", config.errors);
+
+        // The first value wins, so the drop-ins override the main file
+        let root = config.global("permitrootlogin").unwrap();
+        assert_eq!((root.value(), root.location()), ("no".to_string(), "/etc/ssh/sshd_config.d/10-hardn.conf:2".to_string()));
+        let password = config.global("PasswordAuthentication").unwrap();
+        assert_eq!(password.file, Path::new("/etc/ssh/sshd_config.d/50-cloud-init.conf"));
+        assert_eq!(config.global("MaxAuthTries").unwrap().value(), "3");
+        assert!(config.global("Banner").is_none());
+    }
+
+    #[test]
+    fn match_blocks_make_directives_conditional() {
+        let config = load();
+        let conditions = |keyword: &str| -> Vec<(String, String)> {
+            config
+                .conditional(keyword)
+                .iter()
+                .map(|directive| (directive.condition.clone().unwrap(), directive.value()))
+                .collect()
+        };
+        assert_eq!(conditions("PasswordAuthentication"), [("User backup".to_string(), "no".to_string())]);
+        // An Include inside a Match block inherits the condition until the file's own Match
+        assert_eq!(conditions("Banner"), [("User backup".to_string(), "/etc/issue net".to_string())]);
+        assert_eq!(
+            conditions("MaxAuthTries"),
+            [("Group admins".to_string(), "6".to_string()), ("Address 10.0.0.0/8".to_string(), "2".to_string())]
+        );
+        // Settings for everyone go in ahead of the first Match in the main file
+        assert_eq!(config.global_insert_line(), 5);
+    }
+
+    #[test]
+    fn include_problems_are_reported() {
+        let fs = FakeFs::new(&[
+            (SSHD_CONFIG, "Include missing.conf\nInclude /etc/ssh/*/x.conf\nInclude loop.conf\nPort 22\n"),
+            ("/etc/ssh/loop.conf", "Include loop.conf\n"),
+        ]);
+        let config = SshdConfig::load(Path::new(SSHD_CONFIG), &fs).unwrap();
+        assert_eq!(
+            config.errors,
+            [
+                "Include /etc/ssh/*/x.conf: wildcards are only supported in the file name",
+                "Include loop.conf: nested too deeply",
+            ]
+        );
+        assert_eq!(config.files.len(), 1 + MAX_INCLUDE_DEPTH);
+        assert_eq!(config.global("Port").unwrap().line, 4);
+        assert!(SshdConfig::load(Path::new("/etc/ssh/none"), &fs).is_err());
+    }
+
+    #[test]
+    fn transcripts_read_back_like_the_files() {
+        let transcript = Transcript::parse(&format!(
+            "{}4 {}\n1:Include /etc/ssh/sshd_config.d/*.conf\n3:PermitRootLogin yes\n{}1 /etc/ssh/sshd_config.d/10-hardn.conf\n1:PermitRootLogin no\n",
+            FILE_MARKER, SSHD_CONFIG, FILE_MARKER
+        ));
+        let config = SshdConfig::load(Path::new(SSHD_CONFIG), &transcript).unwrap();
+        assert_eq!(config.files[0].content, "Include /etc/ssh/sshd_config.d/*.conf\n\nPermitRootLogin yes\n\n");
+        assert_eq!(config.global("PermitRootLogin").unwrap().location(), "/etc/ssh/sshd_config.d/10-hardn.conf:1");
+    }
+
+    fn edit(line: usize, old: Option<&str>, new: &str) -> SshdEdit {
+        SshdEdit { path: SSHD_CONFIG.to_string(), line, old: old.map(str::to_string), new: new.to_string() }
+    }
+
+    #[test]
+    fn patches_keep_line_numbers_of_the_original() {
+        let edits = [
+            edit(3, Some("PermitRootLogin yes"), "PermitRootLogin no"),
+            edit(5, None, "MaxAuthTries 3"),
+            edit(5, None, "Banner /etc/issue.net"),
+            edit(5, Some("Match User backup"), "Match User backup"),
+            edit(10, None, "KexAlgorithms sntrup761x25519-sha512@openssh.com"),
+        ];
+        let patched = patch_content(MAIN, &edits.iter().collect::<Vec<_>>()).unwrap();
+        let lines: Vec<&str> = patched.lines().collect();
+        assert_eq!(lines[2], "PermitRootLogin no");
+        assert_eq!(lines[4..7], ["MaxAuthTries 3", "Banner /etc/issue.net", "Match User backup"]);
+        assert_eq!(lines.last(), Some(&"KexAlgorithms sntrup761x25519-sha512@openssh.com"));
+        assert_eq!(lines.len(), MAIN.lines().count() + 3);
+        // Indentation of the replaced line does not matter
+        let indented = edit(6, Some("PasswordAuthentication no"), "PasswordAuthentication yes");
+        assert!(patch_content(MAIN, &[&indented]).unwrap().contains("\nPasswordAuthentication yes\n"));
+    }
+
+    #[test]
+    fn patches_against_changed_files_fail() {
+        let stale = edit(3, Some("PermitRootLogin prohibit-password"), "PermitRootLogin no");
+        assert_eq!(patch_content(MAIN, &[&stale]).unwrap_err(), "/etc/ssh/sshd_config changed since it was read (line 3)");
+        let past_end = edit(20, Some("Port 22"), "Port 2222");
+        assert!(patch_content(MAIN, &[&past_end]).is_err());
+        let insert_past_end = edit(11, None, "MaxAuthTries 3");
+        assert_eq!(patch_content(MAIN, &[&insert_past_end]).unwrap_err(), "/etc/ssh/sshd_config has no line 11");
+    }
+
+    #[test]
+    fn edits_are_validated() {
+        assert!(edit(3, Some("PermitRootLogin yes"), "PermitRootLogin no").validate().is_ok());
+        assert!(edit(1, None, "Ciphers=aes256-gcm@openssh.com,chacha20-poly1305@openssh.com").validate().is_ok());
+        let bad = [
+            SshdEdit { path: "/etc/passwd".to_string(), ..edit(1, None, "PermitRootLogin no") },
+            SshdEdit { path: "/etc/ssh/../shadow".to_string(), ..edit(1, None, "PermitRootLogin no") },
+            edit(0, None, "PermitRootLogin no"),
+            edit(1, Some("Port 22\nPort 23"), "PermitRootLogin no"),
+            edit(1, None, "# PermitRootLogin no"),
+            edit(1, None, "PermitRootLogin no\nPort 2222"),
+            edit(1, None, "Port 2222"),
+            edit(1, None, "Banner \"/etc/issue net\""),
+            edit(1, None, "PermitRootLogin"),
+            edit(1, None, "PermitRootLogin no;reboot"),
+        ];
+        for edit in bad {
+            assert!(edit.validate().is_err(), "{:?}", edit);
+        }
+    }
+
+    #[test]
+    fn failed_writes_and_checks_restore_the_previous_files() {
+        let dir = std::env::temp_dir().join(format!("hardn-sshd-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join("not-a-file")).unwrap();
+        let paths = ["a.conf", "b.conf", "not-a-file"].map(|name| dir.join(name).to_string_lossy().into_owned());
+        fs::write(&paths[0], "PermitRootLogin yes\n").unwrap();
+        fs::write(&paths[1], "MaxAuthTries 6\n").unwrap();
+        let change = |index: usize, patched: &str| Change {
+            path: &paths[index],
+            original: fs::read_to_string(&paths[index]).unwrap_or_default(),
+            patched: patched.to_string(),
+            mode: 0o600,
+        };
+        let changes = [change(0, "PermitRootLogin no\n"), change(1, "MaxAuthTries 3\n")];
+        let read = || paths[..2].iter().map(|path| fs::read_to_string(path).unwrap()).collect::<Vec<_>>();
+
+        let rejected = write_checked(&changes, || Err(io::Error::other("rejected")));
+        let after_rejection = read();
+        // The second file cannot be written over a directory
+        let unwritable = [change(0, "PermitRootLogin no\n"), change(2, "MaxAuthTries 3\n")];
+        let failed = write_checked(&unwritable, || Ok(()));
+        let after_failure = read();
+        let accepted = write_checked(&changes, || Ok(()));
+        let after_success = read();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(rejected.unwrap_err().to_string(), "rejected");
+        assert_eq!(after_rejection, ["PermitRootLogin yes\n", "MaxAuthTries 6\n"]);
+        assert!(failed.is_err());
+        assert_eq!(after_failure, ["PermitRootLogin yes\n", "MaxAuthTries 6\n"]);
+        assert!(accepted.is_ok());
+        assert_eq!(after_success, ["PermitRootLogin no\n", "MaxAuthTries 3\n"]);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::systemd::SystemdClient;
+
+// Services shown in the dashboard "System Status Overview" grid: (label, icon, systemd unit)
+pub const MONITORED_SERVICES: &[(&str, &str, &str)] = &[
+    ("UFW Firewall", "🔥", "ufw.service"),
+    ("Fail2Ban IPS", "🚫", "fail2ban.service"),
+    ("Audit System", "📊", "auditd.service"),
+    ("AppArmor MAC", "🛡️", "apparmor.service"),
+    ("ClamAV Antivirus", "🦠", "clamav-daemon.service"),
+    ("SSH Hardening", "🔐", "ssh.service"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceState {
+    Active,
+    Activating,
+    Deactivating,
+    Inactive,
+    Failed,
+    NotFound,
+    Unknown,
+}
+
+impl ServiceState {
+    pub fn from_systemd(load_state: &str, active_state: &str) -> Self {
+        if load_state == "not-found" {
+            return ServiceState::NotFound;
+        }
+        match active_state {
+            "active" | "reloading" => ServiceState::Active,
+            "activating" => ServiceState::Activating,
+            "deactivating" => ServiceState::Deactivating,
+            "inactive" => ServiceState::Inactive,
+            "failed" => ServiceState::Failed,
+            _ => ServiceState::Unknown,
+        }
+    }
+
+    pub fn badge(&self) -> &'static str {
+        match self {
+            ServiceState::Active => "🟢 Active",
+            ServiceState::Activating => "🟡 Starting",
+            ServiceState::Deactivating => "🟡 Stopping",
+            ServiceState::Inactive => "⚪ Inactive",
+            ServiceState::Failed => "🔴 Failed",
+            ServiceState::NotFound => "⚫ Not installed",
+            ServiceState::Unknown => "❔ Unknown",
+        }
+    }
+
+    // GTK style class used to color the badge label
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            ServiceState::Active => "success",
+            ServiceState::Activating | ServiceState::Deactivating => "warning",
+            ServiceState::Failed => "error",
+            ServiceState::Inactive | ServiceState::NotFound | ServiceState::Unknown => "dim-label",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ServiceStatus {
+    pub unit: String,
+    pub state: ServiceState,
+    pub sub_state: String,
+    pub enabled: String,
+    pub since_usec: Option<u64>,
+    pub main_pid: Option<u32>,
+}
+
+impl ServiceStatus {
+    fn unknown(unit: &str) -> Self {
+        ServiceStatus {
+            unit: unit.to_string(),
+            state: ServiceState::Unknown,
+            sub_state: String::new(),
+            enabled: String::new(),
+            since_usec: None,
+            main_pid: None,
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let mut text = self.state.badge().to_string();
+        if !self.enabled.is_empty() && self.state != ServiceState::NotFound {
+            text.push_str(&format!(" ({})", self.enabled));
+        }
+        text
+    }
+
+    pub fn details(&self) -> String {
+        let mut lines = vec![format!("Unit: {}", self.unit)];
+        if !self.sub_state.is_empty() {
+            lines.push(format!("State: {}", self.sub_state));
+        }
+        if let Some(pid) = self.main_pid {
+            lines.push(format!("Main PID: {}", pid));
+        }
+        lines.join("\n")
+    }
+}
+
+pub fn probe_all() -> Vec<ServiceStatus> {
+    let client = SystemdClient::connect().ok();
+
+    MONITORED_SERVICES
+        .iter()
+        .map(|(_, _, unit)| {
+            client
+                .as_ref()
+                .and_then(|client| client.unit_status(unit).ok())
+                .unwrap_or_else(|| ServiceStatus::unknown(unit))
+        })
+        .collect()
+}
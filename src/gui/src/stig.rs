@@ -0,0 +1,808 @@
+// STIG rule engine. Rules come from stig/rules.json plus any organization overlays in
+// /etc/hardn/stig.d/*.json and are evaluated read-only against the live system:
+// sysctl values, file permissions, login.defs style files, sshd_config and GRUB.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::sysctl;
+
+pub const RULES_FILE: &str = "/usr/share/hardn/stig/rules.json";
+pub const OVERLAY_DIR: &str = "/etc/hardn/stig.d";
+
+// Evaluate against another root, e.g. a mounted image or a test fixture
+const ROOT_ENV: &str = "HARDN_STIG_ROOT";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+pub enum Severity {
+    #[serde(rename = "CAT I")]
+    CatI,
+    #[serde(rename = "CAT II")]
+    CatII,
+    #[serde(rename = "CAT III")]
+    CatIII,
+}
+
+impl Severity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::CatI => "CAT I",
+            Severity::CatII => "CAT II",
+            Severity::CatIII => "CAT III",
+        }
+    }
+}
+
+// How an observed value is compared against the rule's expected value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    #[default]
+    Eq,
+    Ne,
+    Le,
+    Ge,
+    // Expected value is a whitespace separated list of accepted values
+    OneOf,
+    // Expected value is "<min> <max>", inclusive
+    Between,
+}
+
+impl Comparison {
+    pub fn holds(&self, actual: &str, expected: &str) -> bool {
+        let number = |s: &str| s.trim().parse::<i64>().ok();
+        let same = |a: &str, b: &str| normalize(a).eq_ignore_ascii_case(&normalize(b));
+        match self {
+            Comparison::Eq => same(actual, expected),
+            Comparison::Ne => !same(actual, expected),
+            Comparison::Le => matches!((number(actual), number(expected)), (Some(a), Some(e)) if a <= e),
+            Comparison::Ge => matches!((number(actual), number(expected)), (Some(a), Some(e)) if a >= e),
+            Comparison::OneOf => expected.split_whitespace().any(|accepted| same(actual, accepted)),
+            Comparison::Between => {
+                let bounds: Option<Vec<i64>> = expected.split_whitespace().map(number).collect();
+                match (number(actual), bounds.as_deref()) {
+                    (Some(a), Some([min, max])) => *min <= a && a <= *max,
+                    _ => false,
+                }
+            }
+        }
+    }
+
+    pub fn describe(&self, expected: &str) -> String {
+        match self {
+            Comparison::Eq => expected.to_string(),
+            Comparison::Ne => format!("not {}", expected),
+            Comparison::Le => format!("at most {}", expected),
+            Comparison::Ge => format!("at least {}", expected),
+            Comparison::OneOf => format!("one of {}", expected.split_whitespace().collect::<Vec<_>>().join(", ")),
+            Comparison::Between => format!("between {}", expected.split_whitespace().collect::<Vec<_>>().join(" and ")),
+        }
+    }
+}
+
+// /proc/sys separates multi-value entries with tabs, config files with spaces
+fn normalize(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Check {
+    // Live kernel value from /proc/sys
+    Sysctl {
+        key: String,
+        value: String,
+        #[serde(default)]
+        op: Comparison,
+    },
+    // Permission bits must not exceed max_mode (octal string)
+    FileMode {
+        path: String,
+        max_mode: String,
+        #[serde(default)]
+        owner_uid: Option<u32>,
+    },
+    // Setting in /etc/login.defs
+    LoginDefs {
+        key: String,
+        value: String,
+        #[serde(default)]
+        op: Comparison,
+    },
+    // Setting in a "key = value" or "key value" file, e.g. pwquality.conf
+    KeyValue {
+        path: String,
+        key: String,
+        value: String,
+        #[serde(default)]
+        op: Comparison,
+        #[serde(default)]
+        default: Option<String>,
+    },
+    // Effective global sshd_config keyword; `default` is sshd's built-in value
+    SshdConfig {
+        keyword: String,
+        value: String,
+        #[serde(default)]
+        op: Comparison,
+        #[serde(default)]
+        default: Option<String>,
+    },
+    // Parameter on GRUB_CMDLINE_LINUX or GRUB_CMDLINE_LINUX_DEFAULT
+    GrubCmdline { parameter: String },
+    // Kernel module redirected to /bin/false or /bin/true in modprobe.d
+    ModuleDisabled { module: String },
+    // systemd unit symlinked to /dev/null
+    MaskedUnit { unit: String },
+    // File contains the text, case-insensitively
+    FileContains { path: String, text: String },
+}
+
+impl Check {
+    // One-line summary of what the check expects
+    pub fn describe(&self) -> String {
+        match self {
+            Check::Sysctl { key, value, op } => format!("sysctl {} is {}", key, op.describe(value)),
+            Check::FileMode { path, max_mode, owner_uid } => match owner_uid {
+                Some(uid) => format!("{} has mode {} or stricter and is owned by uid {}", path, max_mode, uid),
+                None => format!("{} has mode {} or stricter", path, max_mode),
+            },
+            Check::LoginDefs { key, value, op } => format!("login.defs {} is {}", key, op.describe(value)),
+            Check::KeyValue { path, key, value, op, .. } => format!("{} {} is {}", path, key, op.describe(value)),
+            Check::SshdConfig { keyword, value, op, .. } => format!("sshd_config {} is {}", keyword, op.describe(value)),
+            Check::GrubCmdline { parameter } => format!("kernel command line contains {}", parameter),
+            Check::ModuleDisabled { module } => format!("kernel module {} cannot be loaded", module),
+            Check::MaskedUnit { unit } => format!("{} is masked", unit),
+            Check::FileContains { path, text } => format!("{} contains \"{}\"", path, text),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    pub id: String,
+    pub title: String,
+    pub severity: Severity,
+    pub check: Check,
+    // Tool script (relative to the tools directory) that remediates the rule
+    #[serde(default)]
+    pub fix: Option<String>,
+    // Free-form reference to an external control, e.g. an organization's STIG mapping
+    #[serde(default)]
+    pub reference: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RuleFile {
+    #[serde(default)]
+    rules: Vec<Rule>,
+    // Rule IDs an overlay switches off
+    #[serde(default)]
+    disable: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    pub rules: Vec<Rule>,
+    pub sources: Vec<PathBuf>,
+    pub errors: Vec<String>,
+}
+
+impl RuleSet {
+    pub fn load_default() -> Self {
+        Self::load(Path::new(RULES_FILE), Path::new(OVERLAY_DIR))
+    }
+
+    // Overlays are applied in file name order: a rule with a known ID replaces the
+    // earlier definition in place, new IDs are appended, `disable` removes rules.
+    pub fn load(rules_file: &Path, overlay_dir: &Path) -> Self {
+        let mut set = RuleSet::default();
+        set.apply_file(rules_file);
+
+        let mut overlays: Vec<PathBuf> = fs::read_dir(overlay_dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        overlays.sort();
+        for overlay in &overlays {
+            set.apply_file(overlay);
+        }
+
+        set
+    }
+
+    fn apply_file(&mut self, path: &Path) {
+        let parsed = fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str::<RuleFile>(&content).map_err(|e| e.to_string()));
+        let file = match parsed {
+            Ok(file) => file,
+            Err(e) => {
+                self.errors.push(format!("{}: {}", path.display(), e));
+                return;
+            }
+        };
+
+        for rule in file.rules {
+            match self.rules.iter_mut().find(|existing| existing.id == rule.id) {
+                Some(existing) => *existing = rule,
+                None => self.rules.push(rule),
+            }
+        }
+        self.rules.retain(|rule| !file.disable.contains(&rule.id));
+        self.sources.push(path.to_path_buf());
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail,
+    NotApplicable,
+    // The check could not be performed, usually because a file is not readable
+    Error,
+}
+
+impl Outcome {
+    pub fn badge(&self) -> &'static str {
+        match self {
+            Outcome::Pass => "✅ Pass",
+            Outcome::Fail => "❌ Fail",
+            Outcome::NotApplicable => "➖ N/A",
+            Outcome::Error => "⚠️ Unknown",
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Outcome::Pass => "success",
+            Outcome::Fail => "error",
+            Outcome::Error => "warning",
+            Outcome::NotApplicable => "dim-label",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RuleResult {
+    pub rule: Rule,
+    pub outcome: Outcome,
+    // What was actually observed on the system
+    pub evidence: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ComplianceReport {
+    pub results: Vec<RuleResult>,
+    pub sources: Vec<PathBuf>,
+    pub load_errors: Vec<String>,
+}
+
+impl ComplianceReport {
+    pub fn count(&self, outcome: Outcome) -> usize {
+        self.results.iter().filter(|result| result.outcome == outcome).count()
+    }
+
+    // Passing rules out of all applicable ones. Checks that could not be performed
+    // count against compliance since nothing shows the control is in place.
+    pub fn compliance_percent(&self) -> Option<f64> {
+        let applicable = self.results.len() - self.count(Outcome::NotApplicable);
+        if applicable == 0 {
+            return None;
+        }
+        Some(self.count(Outcome::Pass) as f64 * 100.0 / applicable as f64)
+    }
+
+    pub fn summary(&self) -> String {
+        let percent = match self.compliance_percent() {
+            Some(percent) => format!("{:.1}% compliant", percent),
+            None => "No applicable rules".to_string(),
+        };
+        format!(
+            "{} · {} passed, {} failed, {} not applicable, {} unknown",
+            percent,
+            self.count(Outcome::Pass),
+            self.count(Outcome::Fail),
+            self.count(Outcome::NotApplicable),
+            self.count(Outcome::Error)
+        )
+    }
+}
+
+type CheckResult = (Outcome, String);
+
+pub struct Evaluator {
+    root: PathBuf,
+}
+
+impl Evaluator {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Evaluator { root: root.into() }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(std::env::var(ROOT_ENV).unwrap_or_else(|_| "/".to_string()))
+    }
+
+    pub fn evaluate_all(&self, rule_set: &RuleSet) -> ComplianceReport {
+        ComplianceReport {
+            results: rule_set.rules.iter().map(|rule| self.evaluate(rule)).collect(),
+            sources: rule_set.sources.clone(),
+            load_errors: rule_set.errors.clone(),
+        }
+    }
+
+    pub fn evaluate(&self, rule: &Rule) -> RuleResult {
+        let (outcome, evidence) = match &rule.check {
+            Check::Sysctl { key, value, op } => self.check_sysctl(key, value, *op),
+            Check::FileMode { path, max_mode, owner_uid } => self.check_file_mode(path, max_mode, *owner_uid),
+            Check::LoginDefs { key, value, op } => self.check_key_value("/etc/login.defs", key, value, *op, None),
+            Check::KeyValue { path, key, value, op, default } => {
+                self.check_key_value(path, key, value, *op, default.as_deref())
+            }
+            Check::SshdConfig { keyword, value, op, default } => {
+                self.check_sshd_config(keyword, value, *op, default.as_deref())
+            }
+            Check::GrubCmdline { parameter } => self.check_grub_cmdline(parameter),
+            Check::ModuleDisabled { module } => self.check_module_disabled(module),
+            Check::MaskedUnit { unit } => self.check_masked_unit(unit),
+            Check::FileContains { path, text } => self.check_file_contains(path, text),
+        };
+        RuleResult {
+            rule: rule.clone(),
+            outcome,
+            evidence,
+        }
+    }
+
+    fn path(&self, path: &str) -> PathBuf {
+        self.root.join(path.trim_start_matches('/'))
+    }
+
+    fn check_sysctl(&self, key: &str, expected: &str, op: Comparison) -> CheckResult {
+        match sysctl::read_live(&self.path("/proc/sys"), key) {
+            Ok(actual) => compare(key, &actual, expected, op),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                (Outcome::NotApplicable, format!("the running kernel has no {}", key))
+            }
+            Err(e) => read_error(&format!("/proc/sys/{}", sysctl::proc_path(key)), e),
+        }
+    }
+
+    fn check_file_mode(&self, path: &str, max_mode: &str, owner_uid: Option<u32>) -> CheckResult {
+        use std::os::unix::fs::MetadataExt;
+
+        let Ok(max) = u32::from_str_radix(max_mode, 8) else {
+            return (Outcome::Error, format!("invalid max_mode {} in rule", max_mode));
+        };
+        let metadata = match fs::metadata(self.path(path)) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return (Outcome::NotApplicable, format!("{} does not exist", path));
+            }
+            Err(e) => return read_error(path, e),
+        };
+
+        let mode = metadata.mode() & 0o7777;
+        let mut problems = Vec::new();
+        if mode & !max != 0 {
+            problems.push(format!("mode {:04o} is more permissive than {:04o}", mode, max));
+        }
+        if let Some(uid) = owner_uid.filter(|uid| *uid != metadata.uid()) {
+            problems.push(format!("owned by uid {} instead of {}", metadata.uid(), uid));
+        }
+
+        if problems.is_empty() {
+            (Outcome::Pass, format!("{} has mode {:04o}, uid {}", path, mode, metadata.uid()))
+        } else {
+            (Outcome::Fail, format!("{}: {}", path, problems.join(", ")))
+        }
+    }
+
+    fn check_key_value(&self, path: &str, key: &str, expected: &str, op: Comparison, default: Option<&str>) -> CheckResult {
+        let content = match fs::read_to_string(self.path(path)) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return (Outcome::Fail, format!("{} does not exist", path));
+            }
+            Err(e) => return read_error(path, e),
+        };
+
+        // The last assignment wins, as with pam_pwquality and shadow-utils
+        let actual = content.lines().filter_map(parse_setting).rfind(|(k, _)| *k == key);
+        match (actual, default) {
+            (Some((_, value)), _) => compare(key, value, expected, op),
+            (None, Some(default)) => {
+                let (outcome, evidence) = compare(key, default, expected, op);
+                (outcome, format!("{} (not set, built-in default)", evidence))
+            }
+            (None, None) => (Outcome::Fail, format!("{} is not set in {}", key, path)),
+        }
+    }
+
+    fn check_sshd_config(&self, keyword: &str, expected: &str, op: Comparison, default: Option<&str>) -> CheckResult {
+        let config = "/etc/ssh/sshd_config";
+        if !self.path(config).exists() {
+            return (Outcome::NotApplicable, "OpenSSH server is not installed".to_string());
+        }
+
+        let mut lines = Vec::new();
+        if let Err((path, e)) = self.read_sshd_lines(config, &mut lines, 0) {
+            return read_error(&path, e);
+        }
+
+        // sshd uses the first value it sees for a keyword; Match blocks are conditional
+        let actual = lines
+            .iter()
+            .map(|line| line.trim())
+            .take_while(|line| !parse_setting(line).is_some_and(|(k, _)| k.eq_ignore_ascii_case("match")))
+            .filter_map(parse_setting)
+            .find(|(k, _)| k.eq_ignore_ascii_case(keyword));
+        match (actual, default) {
+            (Some((_, value)), _) => compare(keyword, value, expected, op),
+            (None, Some(default)) => {
+                let (outcome, evidence) = compare(keyword, default, expected, op);
+                (outcome, format!("{} (not set, sshd default)", evidence))
+            }
+            (None, None) => (Outcome::Fail, format!("{} is not set in sshd_config", keyword)),
+        }
+    }
+
+    // Read sshd_config with its Include directives expanded in place
+    fn read_sshd_lines(&self, path: &str, lines: &mut Vec<String>, depth: usize) -> Result<(), (String, io::Error)> {
+        let content = fs::read_to_string(self.path(path)).map_err(|e| (path.to_string(), e))?;
+        for line in content.lines() {
+            let include = parse_setting(line.trim()).filter(|(k, _)| k.eq_ignore_ascii_case("include"));
+            match include {
+                Some((_, patterns)) if depth < 8 => {
+                    for pattern in patterns.split_whitespace() {
+                        let pattern = if pattern.starts_with('/') {
+                            pattern.to_string()
+                        } else {
+                            format!("/etc/ssh/{}", pattern)
+                        };
+                        for included in self.expand_glob(&pattern) {
+                            self.read_sshd_lines(&included, lines, depth + 1)?;
+                        }
+                    }
+                }
+                _ => lines.push(line.to_string()),
+            }
+        }
+        Ok(())
+    }
+
+    // Supports a single "*" in the last path component, which covers sshd_config.d/*.conf
+    fn expand_glob(&self, pattern: &str) -> Vec<String> {
+        let (dir, file_pattern) = pattern.rsplit_once('/').unwrap_or(("", pattern));
+        let Some((prefix, suffix)) = file_pattern.split_once('*') else {
+            return vec![pattern.to_string()];
+        };
+        let mut matches: Vec<String> = fs::read_dir(self.path(dir))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.file_name().to_string_lossy().to_string())
+                    .filter(|name| name.starts_with(prefix) && name.ends_with(suffix) && name.len() >= prefix.len() + suffix.len())
+                    .map(|name| format!("{}/{}", dir, name))
+                    .collect()
+            })
+            .unwrap_or_default();
+        matches.sort();
+        matches
+    }
+
+    fn check_grub_cmdline(&self, parameter: &str) -> CheckResult {
+        let grub_default = "/etc/default/grub";
+        let mut files = vec![grub_default.to_string()];
+        files.extend(self.expand_glob("/etc/default/grub.d/*.cfg"));
+
+        let mut cmdline = Vec::new();
+        for file in &files {
+            let content = match fs::read_to_string(self.path(file)) {
+                Ok(content) => content,
+                Err(e) if e.kind() == io::ErrorKind::NotFound && file == grub_default => {
+                    return (Outcome::NotApplicable, "GRUB is not installed".to_string());
+                }
+                Err(e) => return read_error(file, e),
+            };
+            for line in content.lines() {
+                let Some((key, value)) = line.trim().split_once('=') else {
+                    continue;
+                };
+                if key == "GRUB_CMDLINE_LINUX" || key == "GRUB_CMDLINE_LINUX_DEFAULT" {
+                    let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+                    cmdline.extend(value.split_whitespace().map(str::to_string));
+                }
+            }
+        }
+
+        if cmdline.iter().any(|token| token == parameter) {
+            (Outcome::Pass, format!("{} is on the kernel command line", parameter))
+        } else {
+            (Outcome::Fail, format!("kernel command line: {}", cmdline.join(" ")))
+        }
+    }
+
+    fn check_module_disabled(&self, module: &str) -> CheckResult {
+        // modprobe treats '-' and '_' in module names as the same character
+        let canonical = |name: &str| name.replace('-', "_");
+        let module_name = canonical(module);
+
+        let loaded = fs::read_to_string(self.path("/proc/modules"))
+            .map(|content| {
+                content
+                    .lines()
+                    .any(|line| line.split_whitespace().next().map(canonical).as_deref() == Some(module_name.as_str()))
+            })
+            .unwrap_or(false);
+        if loaded {
+            return (Outcome::Fail, format!("{} is currently loaded", module));
+        }
+
+        let mut blacklisted = false;
+        for file in self.expand_glob("/etc/modprobe.d/*.conf") {
+            let Ok(content) = fs::read_to_string(self.path(&file)) else {
+                continue;
+            };
+            for line in content.lines() {
+                let words: Vec<&str> = line.split_whitespace().collect();
+                match words.as_slice() {
+                    ["install", name, command, ..] if canonical(name) == module_name && is_noop_command(command) => {
+                        return (Outcome::Pass, format!("{} is redirected to {} in {}", module, command, file));
+                    }
+                    ["blacklist", name] if canonical(name) == module_name => blacklisted = true,
+                    _ => {}
+                }
+            }
+        }
+
+        if blacklisted {
+            (Outcome::Fail, format!("{} is blacklisted but can still be loaded explicitly", module))
+        } else {
+            (Outcome::Fail, format!("no modprobe.d rule prevents loading {}", module))
+        }
+    }
+
+    fn check_masked_unit(&self, unit: &str) -> CheckResult {
+        let path = format!("/etc/systemd/system/{}", unit);
+        match fs::read_link(self.path(&path)) {
+            Ok(target) if target == Path::new("/dev/null") => (Outcome::Pass, format!("{} is masked", unit)),
+            Ok(target) => (Outcome::Fail, format!("{} points to {}", path, target.display())),
+            Err(_) => (Outcome::Fail, format!("{} is not masked", unit)),
+        }
+    }
+
+    fn check_file_contains(&self, path: &str, text: &str) -> CheckResult {
+        match fs::read_to_string(self.path(path)) {
+            Ok(content) if content.to_lowercase().contains(&text.to_lowercase()) => {
+                (Outcome::Pass, format!("{} contains \"{}\"", path, text))
+            }
+            Ok(_) => (Outcome::Fail, format!("{} does not contain \"{}\"", path, text)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (Outcome::Fail, format!("{} does not exist", path)),
+            Err(e) => read_error(path, e),
+        }
+    }
+}
+
+fn compare(key: &str, actual: &str, expected: &str, op: Comparison) -> CheckResult {
+    let outcome = if op.holds(actual, expected) { Outcome::Pass } else { Outcome::Fail };
+    (outcome, format!("{} = {} (expected {})", key, normalize(actual), op.describe(expected)))
+}
+
+fn read_error(path: &str, e: io::Error) -> CheckResult {
+    if e.kind() == io::ErrorKind::PermissionDenied {
+        (Outcome::Error, format!("{} is only readable by root", path))
+    } else {
+        (Outcome::Error, format!("cannot read {}: {}", path, e))
+    }
+}
+
+fn is_noop_command(command: &str) -> bool {
+    matches!(command, "/bin/false" | "/bin/true" | "/usr/bin/false" | "/usr/bin/true")
+}
+
+// "KEY VALUE", "key = value" or "Keyword value"; comments and blank lines yield None
+fn parse_setting(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let split_at = line.find(|c: char| c.is_whitespace() || c == '=')?;
+    let (key, rest) = line.split_at(split_at);
+    let value = rest.trim_start().strip_prefix('=').unwrap_or(rest).trim();
+    Some((key, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // fixtures/stig holds a rule file with overlays, fixtures/stig-root a host with
+    // tab separated /proc/sys values, sshd_config drop-ins and modprobe.d rules
+    fn fixture(path: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(path)
+    }
+
+    fn check(check: Check) -> RuleResult {
+        let rule = Rule {
+            id: "TEST-001".to_string(),
+            title: "test".to_string(),
+            severity: Severity::CatII,
+            check,
+            fix: None,
+            reference: None,
+        };
+        Evaluator::new(fixture("stig-root")).evaluate(&rule)
+    }
+
+    fn sshd(keyword: &str, value: &str, default: Option<&str>) -> RuleResult {
+        check(Check::SshdConfig {
+            keyword: keyword.to_string(),
+            value: value.to_string(),
+            op: Comparison::Eq,
+            default: default.map(str::to_string),
+        })
+    }
+
+    fn module(name: &str) -> RuleResult {
+        check(Check::ModuleDisabled { module: name.to_string() })
+    }
+
+    #[test]
+    fn comparisons() {
+        assert!(Comparison::Eq.holds("4096\t131072  6291456\n", "4096 131072 6291456"));
+        assert!(Comparison::Eq.holds("No", "no"));
+        assert!(Comparison::Ne.holds("yes", "no"));
+        assert!(!Comparison::Ne.holds("NO", "no"));
+
+        assert!(Comparison::Le.holds("3", "4"));
+        assert!(!Comparison::Le.holds("5", "4"));
+        assert!(Comparison::Ge.holds("-1", "-1"));
+        assert!(!Comparison::Ge.holds("unlimited", "0"));
+
+        assert!(Comparison::OneOf.holds("Prohibit-Password", "no prohibit-password"));
+        assert!(Comparison::OneOf.holds("NO", "no forced-commands-only"));
+        assert!(!Comparison::OneOf.holds("yes", "no prohibit-password"));
+        assert!(!Comparison::OneOf.holds("no prohibit-password", "no prohibit-password"));
+
+        assert!(Comparison::Between.holds("3", "1 3"));
+        assert!(Comparison::Between.holds("1", "1 3"));
+        assert!(!Comparison::Between.holds("4", "1 3"));
+        assert!(!Comparison::Between.holds("x", "1 3"));
+        // A malformed bound makes the rule fail rather than shrinking to the valid ones
+        assert!(!Comparison::Between.holds("3", "1 x 5"));
+        assert!(!Comparison::Between.holds("3", "1"));
+        assert!(!Comparison::Between.holds("3", "1 5 9"));
+        assert!(!Comparison::Between.holds("3", ""));
+    }
+
+    #[test]
+    fn sysctl_values_are_tab_normalized() {
+        let sysctl = |key: &str, value: &str, op: Comparison| {
+            check(Check::Sysctl {
+                key: key.to_string(),
+                value: value.to_string(),
+                op,
+            })
+        };
+
+        let result = sysctl("net.ipv4.tcp_rmem", "4096 131072 6291456", Comparison::Eq);
+        assert_eq!(result.outcome, Outcome::Pass);
+        assert_eq!(result.evidence, "net.ipv4.tcp_rmem = 4096 131072 6291456 (expected 4096 131072 6291456)");
+
+        let result = sysctl("net/ipv4/ip_local_port_range", "32768 60999", Comparison::Eq);
+        assert_eq!(result.outcome, Outcome::Pass);
+        assert_eq!(sysctl("kernel.randomize_va_space", "1 2", Comparison::Between).outcome, Outcome::Pass);
+        assert_eq!(sysctl("kernel.randomize_va_space", "0 1", Comparison::OneOf).outcome, Outcome::Fail);
+
+        let result = sysctl("net.ipv6.conf.all.forwarding", "0", Comparison::Eq);
+        assert_eq!(result.outcome, Outcome::NotApplicable);
+        assert_eq!(result.evidence, "the running kernel has no net.ipv6.conf.all.forwarding");
+    }
+
+    #[test]
+    fn overlays_replace_append_and_disable_in_file_name_order() {
+        let set = RuleSet::load(&fixture("stig/rules.json"), &fixture("stig/stig.d"));
+
+        let ids: Vec<&str> = set.rules.iter().map(|rule| rule.id.as_str()).collect();
+        // 10-organization replaces SSH-001 in place, appends ORG-MOD-001 and disables
+        // NET-001; 20-site brings NET-001 back at the end and disables ORG-MOD-001
+        assert_eq!(ids, ["HARDN-KRN-001", "HARDN-SSH-001", "HARDN-NET-001"]);
+
+        let ssh = &set.rules[1];
+        assert_eq!(ssh.title, "Root cannot log in over SSH, not even with a key");
+        assert_eq!(ssh.reference.as_deref(), Some("ORG-AC-17"));
+        assert!(matches!(&ssh.check, Check::SshdConfig { default: None, .. }));
+
+        let forwarding = &set.rules[2];
+        assert_eq!(forwarding.severity, Severity::CatIII);
+        assert!(matches!(&forwarding.check, Check::Sysctl { op: Comparison::OneOf, .. }));
+
+        let sources: Vec<String> = set
+            .sources
+            .iter()
+            .map(|source| source.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(sources, ["rules.json", "10-organization.json", "20-site.json"]);
+
+        assert_eq!(set.errors.len(), 1);
+        assert!(set.errors[0].contains("15-broken.json"), "{}", set.errors[0]);
+    }
+
+    #[test]
+    fn missing_rule_file_is_reported() {
+        let set = RuleSet::load(&fixture("stig/missing.json"), &fixture("stig/missing.d"));
+
+        assert!(set.rules.is_empty());
+        assert!(set.sources.is_empty());
+        assert_eq!(set.errors.len(), 1);
+    }
+
+    #[test]
+    fn sshd_includes_are_expanded_in_place_until_the_first_match() {
+        // Drop-ins come first, so they win over the main file; the first value is used
+        let result = sshd("PermitRootLogin", "no", None);
+        assert_eq!(result.outcome, Outcome::Pass);
+        assert_eq!(result.evidence, "PermitRootLogin = no (expected no)");
+        assert_eq!(sshd("PasswordAuthentication", "no", None).outcome, Outcome::Pass);
+        assert_eq!(sshd("MaxAuthTries", "3", None).outcome, Outcome::Pass);
+
+        // Only *.conf files are included
+        assert_eq!(sshd("X11Forwarding", "no", None).outcome, Outcome::Fail);
+
+        // Settings inside the Match block are conditional and do not count
+        let result = sshd("ClientAliveInterval", "300", Some("0"));
+        assert_eq!(result.outcome, Outcome::Fail);
+        assert_eq!(result.evidence, "ClientAliveInterval = 0 (expected 300) (not set, sshd default)");
+
+        let result = sshd("ClientAliveInterval", "300", None);
+        assert_eq!(result.outcome, Outcome::Fail);
+        assert_eq!(result.evidence, "ClientAliveInterval is not set in sshd_config");
+
+        let without_sshd = Evaluator::new(fixture("stig-root/proc")).evaluate(&Rule {
+            id: "TEST-002".to_string(),
+            title: "test".to_string(),
+            severity: Severity::CatI,
+            check: Check::SshdConfig {
+                keyword: "PermitRootLogin".to_string(),
+                value: "no".to_string(),
+                op: Comparison::Eq,
+                default: None,
+            },
+            fix: None,
+            reference: None,
+        });
+        assert_eq!(without_sshd.outcome, Outcome::NotApplicable);
+    }
+
+    #[test]
+    fn module_names_treat_dashes_and_underscores_alike() {
+        let result = module("firewire-ohci");
+        assert_eq!(result.outcome, Outcome::Pass);
+        assert_eq!(result.evidence, "firewire-ohci is redirected to /bin/false in /etc/modprobe.d/local.conf");
+        assert_eq!(module("cramfs").outcome, Outcome::Pass);
+        assert_eq!(module("dccp").outcome, Outcome::Pass);
+
+        // Loaded as usb_storage, blacklisted as usb-storage
+        let result = module("usb-storage");
+        assert_eq!(result.outcome, Outcome::Fail);
+        assert_eq!(result.evidence, "usb-storage is currently loaded");
+
+        let result = module("firewire-core");
+        assert_eq!(result.outcome, Outcome::Fail);
+        assert_eq!(result.evidence, "firewire-core is blacklisted but can still be loaded explicitly");
+
+        // install lines that still load the module, and files outside *.conf, do not count
+        assert_eq!(module("tipc").evidence, "no modprobe.d rule prevents loading tipc");
+        assert_eq!(module("squashfs").outcome, Outcome::Fail);
+    }
+}
@@ -0,0 +1,395 @@
+// Kernel parameter drift: compare the values configured in sysctl.d fragments with
+// the live values in /proc/sys. Fragments are read in the order `sysctl --system`
+// applies them, so for every key we know which file wins.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Written by apply_kernel_hardening in modules/hardening.sh
+pub const HARDN_SYSCTL_FILE: &str = "/etc/sysctl.d/99-hardn-security.conf";
+
+// Search path of `sysctl --system`; for equal file names the earlier directory wins
+const SYSCTL_DIRS: &[&str] = &[
+    "/etc/sysctl.d",
+    "/run/sysctl.d",
+    "/usr/local/lib/sysctl.d",
+    "/usr/lib/sysctl.d",
+    "/lib/sysctl.d",
+];
+// Applied after all fragments
+const SYSCTL_CONF: &str = "/etc/sysctl.conf";
+
+// Check another root, e.g. a mounted image; HARDN_PROC_SYS points at a fake /proc/sys
+const ROOT_ENV: &str = "HARDN_SYSCTL_ROOT";
+const PROC_SYS_ENV: &str = "HARDN_PROC_SYS";
+
+// "net.ipv4.ip_forward" or "net/ipv4/ip_forward" -> "net/ipv4/ip_forward".
+// With '/' separators a '.' is part of the name, e.g. a VLAN interface "eth0.100".
+pub fn proc_path(key: &str) -> String {
+    let first_separator = key.find(['.', '/']);
+    if first_separator.is_some_and(|i| key[i..].starts_with('/')) {
+        key.to_string()
+    } else {
+        key.replace('.', "/")
+    }
+}
+
+// Live value of a key below a /proc/sys directory, whitespace normalized
+pub fn read_live(proc_sys: &Path, key: &str) -> io::Result<String> {
+    let value = fs::read_to_string(proc_sys.join(proc_path(key)))?;
+    Ok(normalize(&value))
+}
+
+fn normalize(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[derive(Debug, Clone)]
+pub struct Setting {
+    pub key: String,
+    // Path below /proc/sys, which identifies the key regardless of separator style
+    pub path: String,
+    pub value: String,
+    pub file: String,
+    pub line: usize,
+}
+
+impl Setting {
+    pub fn location(&self) -> String {
+        format!("{}:{}", self.file, self.line)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SysctlConfig {
+    // Files in the order they are applied
+    pub files: Vec<String>,
+    pub settings: Vec<Setting>,
+    pub errors: Vec<String>,
+}
+
+impl SysctlConfig {
+    pub fn load(root: &Path) -> Self {
+        let mut config = SysctlConfig::default();
+        let resolve = |path: &str| root.join(path.trim_start_matches('/'));
+
+        let mut fragments: BTreeMap<String, String> = BTreeMap::new();
+        for dir in SYSCTL_DIRS {
+            let Ok(entries) = fs::read_dir(resolve(dir)) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name.ends_with(".conf") {
+                    fragments.entry(name.clone()).or_insert_with(|| format!("{}/{}", dir, name));
+                }
+            }
+        }
+
+        // Debian links /etc/sysctl.d/99-sysctl.conf to /etc/sysctl.conf; read it only once
+        let sysctl_conf = fs::canonicalize(resolve(SYSCTL_CONF)).ok();
+        let mut files: Vec<String> = fragments.into_values().collect();
+        if sysctl_conf.is_some() && !files.iter().any(|file| fs::canonicalize(resolve(file)).ok() == sysctl_conf) {
+            files.push(SYSCTL_CONF.to_string());
+        }
+
+        for file in files {
+            match fs::read_to_string(resolve(&file)) {
+                Ok(content) => config.parse_file(&file, &content),
+                Err(e) => config.errors.push(format!("{}: {}", file, e)),
+            }
+            config.files.push(file);
+        }
+
+        config
+    }
+
+    fn parse_file(&mut self, file: &str, content: &str) {
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                self.errors.push(format!("{}:{}: expected key = value", file, i + 1));
+                continue;
+            };
+            // A leading '-' only silences errors when applying the setting
+            let key = key.trim().trim_start_matches('-');
+            // Glob keys depend on the interfaces present at boot; they are not checked
+            if key.contains('*') || key.contains('?') {
+                continue;
+            }
+            let path = proc_path(key);
+            self.settings.push(Setting {
+                key: path.replace('/', "."),
+                path,
+                value: normalize(value),
+                file: file.to_string(),
+                line: i + 1,
+            });
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DriftState {
+    // Live value differs from the winning configured value
+    Drifted,
+    // The HARDN value is shadowed by a different value from another file
+    Overridden,
+    // The running kernel has no such key, e.g. the module is not loaded
+    Unavailable,
+    // The key exists but is not readable by the current user
+    Unreadable,
+    InSync,
+}
+
+impl DriftState {
+    pub fn badge(&self) -> &'static str {
+        match self {
+            DriftState::Drifted => "❌ Drifted",
+            DriftState::Overridden => "⚠️ Overridden",
+            DriftState::Unavailable => "➖ Unavailable",
+            DriftState::Unreadable => "❔ Unreadable",
+            DriftState::InSync => "✅ In sync",
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            DriftState::Drifted => "error",
+            DriftState::Overridden => "warning",
+            DriftState::Unavailable | DriftState::Unreadable => "dim-label",
+            DriftState::InSync => "success",
+        }
+    }
+
+    pub fn is_problem(&self) -> bool {
+        matches!(self, DriftState::Drifted | DriftState::Overridden)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyDrift {
+    pub key: String,
+    pub state: DriftState,
+    pub expected: String,
+    pub actual: Option<String>,
+    // The assignment that `sysctl --system` leaves in effect
+    pub winner: Setting,
+    // Every assignment of the key, in application order
+    pub assignments: Vec<Setting>,
+}
+
+impl KeyDrift {
+    pub fn hardn_value(&self) -> Option<&str> {
+        self.assignments
+            .iter()
+            .rev()
+            .find(|setting| setting.file == HARDN_SYSCTL_FILE)
+            .map(|setting| setting.value.as_str())
+    }
+
+    pub fn details(&self) -> String {
+        let mut lines: Vec<String> = self
+            .assignments
+            .iter()
+            .map(|setting| format!("{} = {}  ({})", setting.key, setting.value, setting.location()))
+            .collect();
+        lines.push(format!("Winning file: {}", self.winner.location()));
+        lines.join("\n")
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DriftReport {
+    pub keys: Vec<KeyDrift>,
+    pub files: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+impl DriftReport {
+    pub fn count(&self, state: DriftState) -> usize {
+        self.keys.iter().filter(|key| key.state == state).count()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} keys from {} files · {} drifted, {} overridden, {} in sync, {} unavailable",
+            self.keys.len(),
+            self.files.len(),
+            self.count(DriftState::Drifted),
+            self.count(DriftState::Overridden),
+            self.count(DriftState::InSync),
+            self.count(DriftState::Unavailable) + self.count(DriftState::Unreadable)
+        )
+    }
+}
+
+pub struct SysctlChecker {
+    root: PathBuf,
+    proc_sys: PathBuf,
+}
+
+impl SysctlChecker {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        let proc_sys = root.join("proc/sys");
+        SysctlChecker { root, proc_sys }
+    }
+
+    pub fn with_proc_sys(mut self, proc_sys: impl Into<PathBuf>) -> Self {
+        self.proc_sys = proc_sys.into();
+        self
+    }
+
+    pub fn from_env() -> Self {
+        let checker = Self::new(std::env::var(ROOT_ENV).unwrap_or_else(|_| "/".to_string()));
+        match std::env::var(PROC_SYS_ENV) {
+            Ok(proc_sys) => checker.with_proc_sys(proc_sys),
+            Err(_) => checker,
+        }
+    }
+
+    pub fn check(&self) -> DriftReport {
+        let config = SysctlConfig::load(&self.root);
+
+        let mut by_path: BTreeMap<&str, Vec<&Setting>> = BTreeMap::new();
+        for setting in &config.settings {
+            by_path.entry(setting.path.as_str()).or_default().push(setting);
+        }
+
+        let mut keys: Vec<KeyDrift> = by_path
+            .into_iter()
+            .map(|(path, assignments)| {
+                let winner = (*assignments.last().expect("every key has an assignment")).clone();
+                let assignments: Vec<Setting> = assignments.into_iter().cloned().collect();
+                let live = fs::read_to_string(self.proc_sys.join(path)).map(|value| normalize(&value));
+                let (state, actual) = match live {
+                    Ok(actual) if actual != winner.value => (DriftState::Drifted, Some(actual)),
+                    Ok(actual) => (DriftState::InSync, Some(actual)),
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => (DriftState::Unavailable, None),
+                    Err(_) => (DriftState::Unreadable, None),
+                };
+                let mut drift = KeyDrift {
+                    key: winner.key.clone(),
+                    state,
+                    expected: winner.value.clone(),
+                    actual,
+                    winner,
+                    assignments,
+                };
+                let shadowed = drift.hardn_value().is_some_and(|value| value != drift.expected);
+                if drift.state == DriftState::InSync && shadowed {
+                    drift.state = DriftState::Overridden;
+                }
+                drift
+            })
+            .collect();
+        keys.sort_by(|a, b| a.state.cmp(&b.state).then_with(|| a.key.cmp(&b.key)));
+
+        DriftReport {
+            keys,
+            files: config.files,
+            errors: config.errors,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn keys_as_proc_paths() {
+        assert_eq!(proc_path("net.ipv4.ip_forward"), "net/ipv4/ip_forward");
+        assert_eq!(proc_path("net/ipv4/conf/eth0.100/rp_filter"), "net/ipv4/conf/eth0.100/rp_filter");
+        assert_eq!(proc_path("net.ipv4.conf/eth0"), "net/ipv4/conf/eth0");
+    }
+
+    #[test]
+    fn drift_against_a_fake_root() {
+        let root = std::env::temp_dir().join(format!("hardn-sysctl-test-{}", std::process::id()));
+        write(
+            &root,
+            "etc/sysctl.d/99-hardn-security.conf",
+            "# HARDN\nnet.ipv4.ip_forward = 0\nkernel.kptr_restrict = 2\nnet.ipv4.conf.all.rp_filter=1\n\
+             kernel.dmesg_restrict = 1\n-kernel.unprivileged_bpf_disabled = 1\nnet.ipv4.conf.*.log_martians = 1\n",
+        );
+        // Same name further down the search path: never read
+        write(&root, "usr/lib/sysctl.d/99-hardn-security.conf", "kernel.kptr_restrict = 0\n");
+        write(
+            &root,
+            "usr/lib/sysctl.d/50-default.conf",
+            "; defaults\nkernel.kptr_restrict = 1\nnet.ipv4.conf.all.rp_filter = 2\nnot an assignment\nkernel.sysrq = 16\n",
+        );
+        write(&root, "usr/lib/sysctl.d/README", "kernel.sysrq = 1\n");
+        write(&root, "etc/sysctl.d/99-zz-local.conf", "kernel.dmesg_restrict = 0\n");
+        write(&root, "etc/sysctl.conf", "net/ipv4/tcp_syncookies = 1\n");
+        symlink("../sysctl.conf", root.join("etc/sysctl.d/99-sysctl.conf")).unwrap();
+        for (path, value) in [
+            ("net/ipv4/ip_forward", "0\n"),
+            ("kernel/kptr_restrict", "1\n"),
+            ("net/ipv4/conf/all/rp_filter", "1\n"),
+            ("kernel/dmesg_restrict", "0\n"),
+            ("kernel/sysrq", "16\n"),
+            ("net/ipv4/tcp_syncookies", "1\n"),
+        ] {
+            write(&root, &format!("fake-proc/{}", path), value);
+        }
+
+        std::env::set_var(ROOT_ENV, &root);
+        std::env::set_var(PROC_SYS_ENV, root.join("fake-proc"));
+        let report = SysctlChecker::from_env().check();
+        std::env::remove_var(ROOT_ENV);
+        std::env::remove_var(PROC_SYS_ENV);
+        fs::remove_dir_all(&root).unwrap();
+
+        // sysctl.conf is read once, through its link in /etc/sysctl.d
+        assert_eq!(
+            report.files,
+            [
+                "/usr/lib/sysctl.d/50-default.conf",
+                "/etc/sysctl.d/99-hardn-security.conf",
+                "/etc/sysctl.d/99-sysctl.conf",
+                "/etc/sysctl.d/99-zz-local.conf",
+            ]
+        );
+        assert_eq!(report.errors, ["/usr/lib/sysctl.d/50-default.conf:4: expected key = value"]);
+
+        let keys: Vec<(&str, DriftState, Option<&str>, String)> = report
+            .keys
+            .iter()
+            .map(|key| (key.key.as_str(), key.state, key.actual.as_deref(), key.winner.location()))
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                ("kernel.kptr_restrict", DriftState::Drifted, Some("1"), "/etc/sysctl.d/99-hardn-security.conf:3".to_string()),
+                ("kernel.dmesg_restrict", DriftState::Overridden, Some("0"), "/etc/sysctl.d/99-zz-local.conf:1".to_string()),
+                ("kernel.unprivileged_bpf_disabled", DriftState::Unavailable, None, "/etc/sysctl.d/99-hardn-security.conf:6".to_string()),
+                ("kernel.sysrq", DriftState::InSync, Some("16"), "/usr/lib/sysctl.d/50-default.conf:5".to_string()),
+                ("net.ipv4.conf.all.rp_filter", DriftState::InSync, Some("1"), "/etc/sysctl.d/99-hardn-security.conf:4".to_string()),
+                ("net.ipv4.ip_forward", DriftState::InSync, Some("0"), "/etc/sysctl.d/99-hardn-security.conf:2".to_string()),
+                ("net.ipv4.tcp_syncookies", DriftState::InSync, Some("1"), "/etc/sysctl.d/99-sysctl.conf:1".to_string()),
+            ]
+        );
+
+        let kptr = &report.keys[0];
+        assert_eq!(kptr.expected, "2");
+        assert_eq!(kptr.assignments.len(), 2);
+        assert_eq!(kptr.hardn_value(), Some("2"));
+        assert_eq!(report.keys[1].hardn_value(), Some("1"));
+        assert_eq!(report.summary(), "7 keys from 4 files · 1 drifted, 1 overridden, 4 in sync, 1 unavailable");
+    }
+}
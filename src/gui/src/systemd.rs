@@ -0,0 +1,532 @@
+use std::sync::mpsc;
+use std::thread;
+
+use zbus::blocking::Connection;
+use zbus::dbus_proxy;
+use zbus::zvariant::OwnedObjectPath;
+
+use crate::status::{ServiceState, ServiceStatus};
+
+// Units the GUI is allowed to control through systemd
+pub const MANAGED_UNITS: &[&str] = &[
+    "ufw.service",
+    "fail2ban.service",
+    "auditd.service",
+    "apparmor.service",
+    "clamav-daemon.service",
+    "hardn-monitor.service",
+];
+
+// Mirrors start_monitoring_services / stop_monitoring_services in modules/monitor.sh
+pub const MONITORING_START_UNITS: &[&str] = &[
+    "ufw.service",
+    "fail2ban.service",
+    "auditd.service",
+    "apparmor.service",
+    "clamav-daemon.service",
+    "hardn-monitor.service",
+];
+pub const MONITORING_STOP_UNITS: &[&str] = &[
+    "hardn-monitor.service",
+    "fail2ban.service",
+    "clamav-daemon.service",
+];
+
+// (change type, file, destination) as returned by EnableUnitFiles / DisableUnitFiles
+type UnitFileChange = (String, String, String);
+
+// Set HARDN_SYSTEMD_BUS=session to talk to a mock systemd on the session bus
+const BUS_ENV: &str = "HARDN_SYSTEMD_BUS";
+
+#[dbus_proxy(
+    interface = "org.freedesktop.systemd1.Manager",
+    default_service = "org.freedesktop.systemd1",
+    default_path = "/org/freedesktop/systemd1"
+)]
+trait Manager {
+    #[dbus_proxy(allow_interactive_auth)]
+    fn start_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+
+    #[dbus_proxy(allow_interactive_auth)]
+    fn stop_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+
+    #[dbus_proxy(allow_interactive_auth)]
+    fn restart_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+
+    #[dbus_proxy(allow_interactive_auth)]
+    fn enable_unit_files(
+        &self,
+        files: &[&str],
+        runtime: bool,
+        force: bool,
+    ) -> zbus::Result<(bool, Vec<UnitFileChange>)>;
+
+    #[dbus_proxy(allow_interactive_auth)]
+    fn disable_unit_files(
+        &self,
+        files: &[&str],
+        runtime: bool,
+    ) -> zbus::Result<Vec<UnitFileChange>>;
+
+    #[dbus_proxy(allow_interactive_auth)]
+    fn reload(&self) -> zbus::Result<()>;
+
+    fn load_unit(&self, name: &str) -> zbus::Result<OwnedObjectPath>;
+
+    fn subscribe(&self) -> zbus::Result<()>;
+}
+
+#[dbus_proxy(
+    interface = "org.freedesktop.systemd1.Unit",
+    default_service = "org.freedesktop.systemd1"
+)]
+trait Unit {
+    #[dbus_proxy(property)]
+    fn load_state(&self) -> zbus::Result<String>;
+
+    #[dbus_proxy(property)]
+    fn active_state(&self) -> zbus::Result<String>;
+
+    #[dbus_proxy(property)]
+    fn sub_state(&self) -> zbus::Result<String>;
+
+    #[dbus_proxy(property)]
+    fn unit_file_state(&self) -> zbus::Result<String>;
+
+    #[dbus_proxy(property)]
+    fn active_enter_timestamp(&self) -> zbus::Result<u64>;
+
+    #[dbus_proxy(property)]
+    fn inactive_enter_timestamp(&self) -> zbus::Result<u64>;
+}
+
+#[dbus_proxy(
+    interface = "org.freedesktop.systemd1.Service",
+    default_service = "org.freedesktop.systemd1"
+)]
+trait Service {
+    #[dbus_proxy(property, name = "MainPID")]
+    fn main_pid(&self) -> zbus::Result<u32>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitOperation {
+    Start,
+    Stop,
+    Restart,
+    Enable,
+    Disable,
+}
+
+impl UnitOperation {
+    pub fn label(&self) -> &'static str {
+        match self {
+            UnitOperation::Start => "start",
+            UnitOperation::Stop => "stop",
+            UnitOperation::Restart => "restart",
+            UnitOperation::Enable => "enable",
+            UnitOperation::Disable => "disable",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SystemdClient {
+    connection: Connection,
+}
+
+impl SystemdClient {
+    pub fn connect() -> zbus::Result<Self> {
+        let connection = match std::env::var(BUS_ENV).as_deref() {
+            Ok("session") => Connection::session()?,
+            _ => Connection::system()?,
+        };
+        Ok(Self::with_connection(connection))
+    }
+
+    pub fn with_connection(connection: Connection) -> Self {
+        SystemdClient { connection }
+    }
+
+    fn manager(&self) -> zbus::Result<ManagerProxyBlocking<'_>> {
+        ManagerProxyBlocking::new(&self.connection)
+    }
+
+    fn unit_path(&self, unit: &str) -> zbus::Result<OwnedObjectPath> {
+        self.manager()?.load_unit(unit)
+    }
+
+    pub fn unit_status(&self, unit: &str) -> zbus::Result<ServiceStatus> {
+        let path = self.unit_path(unit)?;
+        let unit_proxy = UnitProxyBlocking::builder(&self.connection)
+            .path(path.clone())?
+            .build()?;
+
+        let state = ServiceState::from_systemd(&unit_proxy.load_state()?, &unit_proxy.active_state()?);
+
+        // Report when the unit entered its current state (microseconds since the epoch)
+        let since = match state {
+            ServiceState::Active | ServiceState::Activating => unit_proxy.active_enter_timestamp()?,
+            _ => unit_proxy.inactive_enter_timestamp()?,
+        };
+
+        // Non-service units and unloaded services have no MainPID
+        let main_pid = ServiceProxyBlocking::builder(&self.connection)
+            .path(path)?
+            .build()
+            .and_then(|service| service.main_pid())
+            .unwrap_or(0);
+
+        Ok(ServiceStatus {
+            unit: unit.to_string(),
+            state,
+            sub_state: unit_proxy.sub_state()?,
+            enabled: unit_proxy.unit_file_state().unwrap_or_default(),
+            since_usec: Some(since).filter(|ts| *ts != 0),
+            main_pid: Some(main_pid).filter(|pid| *pid != 0),
+        })
+    }
+
+    // Returns the queued job path, or a description of the unit file changes
+    pub fn run(&self, unit: &str, operation: UnitOperation) -> zbus::Result<String> {
+        if !MANAGED_UNITS.contains(&unit) {
+            return Err(zbus::Error::Failure(format!("{} is not managed by HARDN", unit)));
+        }
+
+        let manager = self.manager()?;
+        match operation {
+            UnitOperation::Start => manager.start_unit(unit, "replace").map(|job| job.to_string()),
+            UnitOperation::Stop => manager.stop_unit(unit, "replace").map(|job| job.to_string()),
+            UnitOperation::Restart => manager.restart_unit(unit, "replace").map(|job| job.to_string()),
+            UnitOperation::Enable => {
+                let (_, changes) = manager.enable_unit_files(&[unit], false, false)?;
+                manager.reload()?;
+                Ok(describe_changes(&changes))
+            }
+            UnitOperation::Disable => {
+                let changes = manager.disable_unit_files(&[unit], false)?;
+                manager.reload()?;
+                Ok(describe_changes(&changes))
+            }
+        }
+    }
+
+    // Push a fresh ServiceStatus whenever systemd reports a state change for one of the units
+    pub fn watch_units(&self, units: &[&str], tx: mpsc::Sender<ServiceStatus>) -> zbus::Result<()> {
+        self.manager()?.subscribe()?;
+
+        for unit in units {
+            let path = self.unit_path(unit)?;
+            let client = self.clone();
+            let unit = unit.to_string();
+            let tx = tx.clone();
+
+            thread::spawn(move || {
+                let unit_proxy = match UnitProxyBlocking::builder(&client.connection)
+                    .path(path)
+                    .and_then(|builder| builder.build())
+                {
+                    Ok(proxy) => proxy,
+                    Err(_) => return,
+                };
+
+                for _ in unit_proxy.receive_active_state_changed() {
+                    if let Ok(status) = client.unit_status(&unit) {
+                        if tx.send(status).is_err() {
+                            return;
+                        }
+                    }
+                }
+            });
+        }
+
+        Ok(())
+    }
+}
+
+fn describe_changes(changes: &[UnitFileChange]) -> String {
+    if changes.is_empty() {
+        return "no changes".to_string();
+    }
+    changes
+        .iter()
+        .map(|(kind, file, dest)| {
+            if dest.is_empty() {
+                format!("{} {}", kind, file)
+            } else {
+                format!("{} {} → {}", kind, file, dest)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::path::PathBuf;
+    use std::process::{Child, Command, Stdio};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use zbus::blocking::ConnectionBuilder;
+    use zbus::{dbus_interface, fdo, ObjectServer};
+
+    const UFW_PATH: &str = "/org/freedesktop/systemd1/unit/ufw_2eservice";
+
+    // A private dbus-daemon, stopped when dropped
+    struct Bus {
+        daemon: Child,
+        dir: PathBuf,
+        address: String,
+    }
+
+    impl Bus {
+        fn start(name: &str) -> Option<Bus> {
+            let dir = std::env::temp_dir().join(format!("hardn-systemd-test-{}-{}", name, std::process::id()));
+            std::fs::create_dir_all(&dir).ok()?;
+            let config = dir.join("bus.conf");
+            std::fs::write(
+                &config,
+                format!(
+                    "<busconfig><type>session</type><listen>unix:path={}</listen><auth>EXTERNAL</auth>\
+                     <policy context=\"default\"><allow send_destination=\"*\" eavesdrop=\"true\"/>\
+                     <allow eavesdrop=\"true\"/><allow own=\"*\"/></policy></busconfig>",
+                    dir.join("bus").display()
+                ),
+            )
+            .ok()?;
+            let mut daemon = Command::new("dbus-daemon")
+                .arg(format!("--config-file={}", config.display()))
+                .args(["--nofork", "--print-address"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .ok()?;
+            let mut address = String::new();
+            BufReader::new(daemon.stdout.take()?).read_line(&mut address).ok()?;
+            Some(Bus { daemon, dir, address: address.trim().to_string() })
+        }
+
+        fn connect(&self) -> zbus::blocking::ConnectionBuilder<'static> {
+            ConnectionBuilder::address(self.address.as_str()).unwrap()
+        }
+    }
+
+    impl Drop for Bus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+            let _ = self.daemon.wait();
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    // The calls the mock systemd received
+    type Calls = Arc<Mutex<Vec<String>>>;
+
+    struct MockManager {
+        calls: Calls,
+    }
+
+    impl MockManager {
+        async fn set_ufw_state(&self, server: &ObjectServer, state: &str) -> fdo::Result<()> {
+            let unit = server.interface::<_, MockUnit>(UFW_PATH).await?;
+            let mut mock = unit.get_mut().await;
+            mock.active_state = state.to_string();
+            mock.active_state_changed(unit.signal_context()).await?;
+            Ok(())
+        }
+
+        fn record(&self, call: String) {
+            self.calls.lock().unwrap().push(call);
+        }
+    }
+
+    #[dbus_interface(name = "org.freedesktop.systemd1.Manager")]
+    impl MockManager {
+        async fn start_unit(
+            &self,
+            name: &str,
+            mode: &str,
+            #[zbus(object_server)] server: &ObjectServer,
+        ) -> fdo::Result<OwnedObjectPath> {
+            self.record(format!("StartUnit {} {}", name, mode));
+            self.set_ufw_state(server, "active").await?;
+            Ok(OwnedObjectPath::try_from("/org/freedesktop/systemd1/job/1").unwrap())
+        }
+
+        async fn stop_unit(
+            &self,
+            name: &str,
+            mode: &str,
+            #[zbus(object_server)] server: &ObjectServer,
+        ) -> fdo::Result<OwnedObjectPath> {
+            self.record(format!("StopUnit {} {}", name, mode));
+            self.set_ufw_state(server, "inactive").await?;
+            Ok(OwnedObjectPath::try_from("/org/freedesktop/systemd1/job/2").unwrap())
+        }
+
+        fn enable_unit_files(&self, files: Vec<String>, runtime: bool, force: bool) -> (bool, Vec<UnitFileChange>) {
+            self.record(format!("EnableUnitFiles {:?} {} {}", files, runtime, force));
+            let link = (
+                "symlink".to_string(),
+                "/etc/systemd/system/multi-user.target.wants/ufw.service".to_string(),
+                "/lib/systemd/system/ufw.service".to_string(),
+            );
+            (true, vec![link])
+        }
+
+        fn reload(&self) {
+            self.record("Reload".to_string());
+        }
+
+        fn load_unit(&self, name: &str) -> fdo::Result<OwnedObjectPath> {
+            match name {
+                "ufw.service" => Ok(OwnedObjectPath::try_from(UFW_PATH).unwrap()),
+                _ => Err(fdo::Error::Failed(format!("Unit {} not found.", name))),
+            }
+        }
+
+        fn subscribe(&self) {
+            self.record("Subscribe".to_string());
+        }
+    }
+
+    struct MockUnit {
+        active_state: String,
+    }
+
+    #[dbus_interface(name = "org.freedesktop.systemd1.Unit")]
+    impl MockUnit {
+        #[dbus_interface(property)]
+        fn load_state(&self) -> &str {
+            "loaded"
+        }
+
+        #[dbus_interface(property)]
+        fn active_state(&self) -> &str {
+            &self.active_state
+        }
+
+        #[dbus_interface(property)]
+        fn sub_state(&self) -> &str {
+            if self.active_state == "active" {
+                "exited"
+            } else {
+                "dead"
+            }
+        }
+
+        #[dbus_interface(property)]
+        fn unit_file_state(&self) -> &str {
+            "enabled"
+        }
+
+        #[dbus_interface(property)]
+        fn active_enter_timestamp(&self) -> u64 {
+            1_760_781_000_000_000
+        }
+
+        #[dbus_interface(property)]
+        fn inactive_enter_timestamp(&self) -> u64 {
+            0
+        }
+    }
+
+    struct MockService;
+
+    #[dbus_interface(name = "org.freedesktop.systemd1.Service")]
+    impl MockService {
+        #[dbus_interface(property, name = "MainPID")]
+        fn main_pid(&self) -> u32 {
+            0
+        }
+    }
+
+    // Serves the mock systemd on a private bus and returns a client connected to it
+    fn mock_systemd(bus: &Bus, calls: &Calls) -> (zbus::blocking::Connection, SystemdClient) {
+        let server = bus
+            .connect()
+            .name("org.freedesktop.systemd1")
+            .unwrap()
+            .serve_at("/org/freedesktop/systemd1", MockManager { calls: calls.clone() })
+            .unwrap()
+            .serve_at(UFW_PATH, MockUnit { active_state: "inactive".to_string() })
+            .unwrap()
+            .serve_at(UFW_PATH, MockService)
+            .unwrap()
+            .build()
+            .unwrap();
+        let client = SystemdClient::with_connection(bus.connect().build().unwrap());
+        (server, client)
+    }
+
+    #[test]
+    fn units_are_controlled_over_dbus() {
+        let Some(bus) = Bus::start("control") else {
+            eprintln!("dbus-daemon is not available, skipping");
+            return;
+        };
+        let calls = Calls::default();
+        let (_server, client) = mock_systemd(&bus, &calls);
+
+        let status = client.unit_status("ufw.service").unwrap();
+        assert_eq!(status.state, ServiceState::Inactive);
+        assert_eq!((status.sub_state.as_str(), status.enabled.as_str()), ("dead", "enabled"));
+        assert_eq!((status.since_usec, status.main_pid), (None, None));
+        assert!(client.unit_status("nonexistent.service").is_err());
+
+        // Units outside MANAGED_UNITS never reach systemd
+        for unit in ["ssh.service", "ufw", "UFW.service", "../ufw.service"] {
+            assert!(matches!(client.run(unit, UnitOperation::Start), Err(zbus::Error::Failure(_))), "{}", unit);
+        }
+        assert!(calls.lock().unwrap().is_empty());
+
+        assert_eq!(client.run("ufw.service", UnitOperation::Start).unwrap(), "/org/freedesktop/systemd1/job/1");
+        let status = client.unit_status("ufw.service").unwrap();
+        assert_eq!((status.state, status.since_usec), (ServiceState::Active, Some(1_760_781_000_000_000)));
+        assert_eq!(
+            client.run("ufw.service", UnitOperation::Enable).unwrap(),
+            "symlink /etc/systemd/system/multi-user.target.wants/ufw.service → /lib/systemd/system/ufw.service"
+        );
+        assert_eq!(
+            *calls.lock().unwrap(),
+            ["StartUnit ufw.service replace", "EnableUnitFiles [\"ufw.service\"] false false", "Reload"]
+        );
+    }
+
+    #[test]
+    fn active_state_changes_are_streamed() {
+        let Some(bus) = Bus::start("watch") else {
+            eprintln!("dbus-daemon is not available, skipping");
+            return;
+        };
+        let calls = Calls::default();
+        let (_server, client) = mock_systemd(&bus, &calls);
+
+        let (tx, rx) = mpsc::channel();
+        client.watch_units(&["ufw.service"], tx).unwrap();
+        assert!(client.watch_units(&["nonexistent.service"], mpsc::channel().0).is_err());
+
+        // The watcher thread subscribes to the property changes in the background, so
+        // keep toggling the unit until a change arrives
+        let mut started = None;
+        for _ in 0..50 {
+            client.run("ufw.service", UnitOperation::Start).unwrap();
+            if let Some(status) = rx.recv_timeout(Duration::from_millis(200)).ok().filter(|s| s.state == ServiceState::Active) {
+                started = Some(status);
+                break;
+            }
+            client.run("ufw.service", UnitOperation::Stop).unwrap();
+        }
+        let started = started.expect("no ActiveState change arrived");
+        assert_eq!((started.unit.as_str(), started.sub_state.as_str()), ("ufw.service", "exited"));
+
+        client.run("ufw.service", UnitOperation::Stop).unwrap();
+        let stopped = std::iter::from_fn(|| rx.recv_timeout(Duration::from_secs(5)).ok())
+            .find(|status| status.state == ServiceState::Inactive)
+            .expect("no change to inactive arrived");
+        assert_eq!(stopped.sub_state, "dead");
+        assert_eq!(calls.lock().unwrap().first().map(String::as_str), Some("Subscribe"));
+    }
+}
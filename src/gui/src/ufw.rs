@@ -0,0 +1,878 @@
+// UFW firewall state from `ufw status verbose` (the rules files are root-only):
+//
+//   Status: active
+//   Logging: on (low)
+//   Default: deny (incoming), allow (outgoing), disabled (routed)
+//   New profiles: skip
+//
+//   To                         Action      From
+//   --                         ------      ----
+//   22/tcp                     ALLOW IN    Anywhere
+//   80,443/tcp                 ALLOW IN    192.168.1.0/24
+//   OpenSSH (v6)               ALLOW IN    Anywhere (v6)
+//
+// Application names in rules are resolved through the profiles in
+// /etc/ufw/applications.d, which are world readable.
+//
+// The rule editor reads the rules files instead, where every rule is a tuple line in
+// the order `ufw status numbered` counts them, IPv4 (user.rules) before IPv6:
+//
+//   ### tuple ### allow tcp 22 0.0.0.0/0 any 0.0.0.0/0 in
+//   ### tuple ### limit any 22 0.0.0.0/0 any 0.0.0.0/0 OpenSSH - in_eth0 comment=7373682066726f6d20776f726b
+//
+// i.e. action[_log|_log-all] protocol dport dst sport src [dapp sapp] direction[_iface],
+// with "%20" for spaces in application names and the comment hex encoded.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::config;
+use crate::firewall::{self, Action, Direction, PolicyChain, RuleProtocol, RuleSpec, UfwCommand};
+use crate::network::Protocol;
+
+pub const APPLICATIONS_DIR: &str = "/etc/ufw/applications.d";
+
+// Ports of one rule or profile, e.g. "80,443/tcp" or "6000:6007/udp"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortSpec {
+    pub ranges: Vec<(u16, u16)>,
+    // None when the rule applies to both
+    pub protocol: Option<Protocol>,
+}
+
+impl PortSpec {
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (ports, protocol) = match spec.split_once('/') {
+            Some((ports, "tcp")) => (ports, Some(Protocol::Tcp)),
+            Some((ports, "udp")) => (ports, Some(Protocol::Udp)),
+            Some(_) => return None,
+            None => (spec, None),
+        };
+        Some(PortSpec { ranges: firewall::parse_ports(ports)?, protocol })
+    }
+
+    pub fn matches(&self, port: u16, protocol: Protocol) -> bool {
+        self.protocol.map(|p| p == protocol).unwrap_or(true) && self.ranges.iter().any(|(low, high)| (*low..=*high).contains(&port))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UfwRule {
+    // As printed, e.g. "22/tcp (v6)"
+    pub to: String,
+    // ALLOW, DENY, REJECT or LIMIT
+    pub action: String,
+    pub incoming: bool,
+    pub from: String,
+    pub v6: bool,
+    // Application profile named in `to`, if any
+    pub application: Option<String>,
+    // None: every port (e.g. "Anywhere on eth0")
+    pub ports: Option<Vec<PortSpec>>,
+}
+
+impl UfwRule {
+    // "To   Action   From" with columns separated by two or more spaces
+    fn parse(line: &str, profiles: &BTreeMap<String, Vec<PortSpec>>) -> Option<Self> {
+        let columns: Vec<&str> = line.split("  ").map(str::trim).filter(|column| !column.is_empty()).collect();
+        // A comment ("# ssh") or the log option may follow
+        let [to, action, from, ..] = columns.as_slice() else {
+            return None;
+        };
+        let mut action_words = action.split_whitespace();
+        let verb = action_words.next()?;
+        if !["ALLOW", "DENY", "REJECT", "LIMIT"].contains(&verb) {
+            return None;
+        }
+        let incoming = action_words.next() != Some("OUT");
+
+        let v6 = to.ends_with("(v6)");
+        let target = to.trim_end_matches("(v6)").trim();
+        // Drop an interface ("on eth0") and the destination address, keep the port part
+        let target = target.split(" on ").next().unwrap_or(target).trim();
+        let last = target.split_whitespace().last().unwrap_or(target);
+        let (application, ports) = if let Some(spec) = PortSpec::parse(last) {
+            (None, Some(vec![spec]))
+        } else if last == "Anywhere" || last.contains(['.', ':']) {
+            (None, None)
+        } else {
+            (Some(target.to_string()), Some(profiles.get(target).cloned().unwrap_or_default()))
+        };
+
+        Some(UfwRule {
+            to: to.to_string(),
+            action: verb.to_string(),
+            incoming,
+            from: from.to_string(),
+            v6,
+            application,
+            ports,
+        })
+    }
+
+    pub fn allows(&self) -> bool {
+        self.action == "ALLOW" || self.action == "LIMIT"
+    }
+
+    pub fn matches(&self, port: u16, protocol: Protocol) -> bool {
+        match &self.ports {
+            Some(specs) => specs.iter().any(|spec| spec.matches(port, protocol)),
+            None => true,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        format!("{} {} IN from {}", self.to, self.action, self.from)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UfwStatus {
+    pub active: bool,
+    // "deny", "reject" or "allow"
+    pub default_incoming: Option<String>,
+    pub rules: Vec<UfwRule>,
+}
+
+// What UFW does with connections to a port
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Inactive,
+    AllowedByRule(String),
+    BlockedByRule(String),
+    DefaultPolicy(String),
+}
+
+impl Verdict {
+    pub fn is_open(&self) -> bool {
+        match self {
+            Verdict::Inactive | Verdict::AllowedByRule(_) => true,
+            Verdict::BlockedByRule(_) => false,
+            Verdict::DefaultPolicy(policy) => policy == "allow",
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Verdict::Inactive => "UFW inactive".to_string(),
+            Verdict::AllowedByRule(rule) => format!("allowed: {}", rule),
+            Verdict::BlockedByRule(rule) => format!("blocked: {}", rule),
+            Verdict::DefaultPolicy(policy) => format!("default {} (incoming)", policy),
+        }
+    }
+}
+
+impl UfwStatus {
+    pub fn parse(output: &str, profiles: &BTreeMap<String, Vec<PortSpec>>) -> Self {
+        let mut status = UfwStatus::default();
+        let mut in_rules = false;
+        for line in output.lines() {
+            let line = line.trim_end();
+            if let Some(state) = line.strip_prefix("Status:") {
+                status.active = state.trim() == "active";
+            } else if let Some(defaults) = line.strip_prefix("Default:") {
+                status.default_incoming = defaults
+                    .split(',')
+                    .map(str::trim)
+                    .find_map(|policy| policy.strip_suffix("(incoming)"))
+                    .map(|policy| policy.trim().to_string());
+            } else if line.starts_with("--") {
+                in_rules = true;
+            } else if in_rules {
+                status.rules.extend(UfwRule::parse(line, profiles));
+            }
+        }
+        status
+    }
+
+    // UFW applies the first matching rule, then the default policy. A socket bound to
+    // "::" also accepts IPv4, so rules of both families apply to it.
+    pub fn verdict(&self, port: u16, protocol: Protocol, v6: bool, dual_stack: bool) -> Verdict {
+        if !self.active {
+            return Verdict::Inactive;
+        }
+        let rule = self
+            .rules
+            .iter()
+            .filter(|rule| rule.incoming && (rule.v6 == v6 || dual_stack))
+            .find(|rule| rule.matches(port, protocol));
+        match rule {
+            Some(rule) if rule.allows() => Verdict::AllowedByRule(rule.describe()),
+            Some(rule) => Verdict::BlockedByRule(rule.describe()),
+            None => Verdict::DefaultPolicy(self.default_incoming.clone().unwrap_or_else(|| "deny".to_string())),
+        }
+    }
+}
+
+// Application profiles: INI files with one section per application and its
+// `ports=80,443/tcp|8080/udp` line
+pub fn load_profiles(dir: &Path) -> BTreeMap<String, Vec<PortSpec>> {
+    let mut profiles = BTreeMap::new();
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return profiles;
+    };
+    for entry in read_dir.flatten() {
+        let Ok(content) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let mut section: Option<String> = None;
+        for line in content.lines().map(str::trim) {
+            if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+                section = Some(name.to_string());
+            } else if let (Some(name), Some(ports)) = (&section, line.strip_prefix("ports=")) {
+                let specs = ports.split('|').filter_map(PortSpec::parse).collect();
+                profiles.insert(name.clone(), specs);
+            }
+        }
+    }
+    profiles
+}
+
+// One line of `ufw status numbered`; rules the editor cannot express (routed rules,
+// other protocols) keep only their tuple and can be deleted but not moved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UfwEntry {
+    pub spec: Option<RuleSpec>,
+    pub tuple: String,
+    pub v6: bool,
+    // Added or moved by pending edits
+    pub pending: bool,
+}
+
+impl UfwEntry {
+    pub fn parse(line: &str) -> Option<Self> {
+        let tuple = line.trim().strip_prefix("### tuple ###")?.trim();
+        let (fields, comment) = match tuple.split_once(" comment=") {
+            Some((fields, comment)) => (fields, decode_hex(comment.trim())),
+            None => (tuple, None),
+        };
+        let fields: Vec<&str> = fields.split_whitespace().collect();
+        let (apps, direction) = match fields.len() {
+            7 => ((None, None), fields[6]),
+            9 => ((app_name(fields[6]), app_name(fields[7])), fields[8]),
+            _ => return None,
+        };
+        let (dst, src) = (fields[3], fields[5]);
+        let v6 = dst.contains(':') || src.contains(':');
+        let spec = Self::spec(&fields, apps, direction, comment);
+        Some(UfwEntry { spec, tuple: tuple.to_string(), v6, pending: false })
+    }
+
+    fn spec(fields: &[&str], (to_app, from_app): (Option<String>, Option<String>), direction: &str, comment: Option<String>) -> Option<RuleSpec> {
+        let (action, log) = match fields[0].split_once('_') {
+            Some((action, log)) => (action, Some(log.to_string())),
+            None => (fields[0], None),
+        };
+        let (direction, interface) = match direction.split_once('_') {
+            Some((direction, interface)) => (direction, Some(interface.to_string())),
+            None => (direction, None),
+        };
+        let direction = Direction::ALL.into_iter().find(|d| d.keyword() == direction)?;
+        let mut protocol = RuleProtocol::ALL.into_iter().find(|p| p.keyword() == fields[1])?;
+        let port = |field: &str, app: &Option<String>| (field != "any" && app.is_none()).then(|| field.to_string());
+        // An application profile implies its protocol
+        if to_app.is_some() || from_app.is_some() {
+            protocol = RuleProtocol::Any;
+        }
+        Some(RuleSpec {
+            action: Action::from_keyword(action)?,
+            direction,
+            interface,
+            log,
+            protocol,
+            from: fields[5].to_string(),
+            from_port: port(fields[4], &from_app),
+            from_app,
+            to: fields[3].to_string(),
+            to_port: port(fields[2], &to_app),
+            to_app,
+            comment,
+        })
+    }
+
+    // The To, Action and From columns the way `ufw status` prints them
+    pub fn columns(&self) -> (String, String, String) {
+        let Some(spec) = &self.spec else {
+            return (self.tuple.clone(), String::new(), String::new());
+        };
+        // Like ufw, mark IPv6 rules on the sides that match any address
+        let v6 = if self.v6 { " (v6)" } else { "" };
+        let side = |address: &str, port: &Option<String>, app: &Option<String>| {
+            let (address, v6) = if address == "any" || address == "0.0.0.0/0" || address == "::/0" { (None, v6) } else { (Some(address), "") };
+            let target = match (app, port) {
+                (Some(app), _) => Some(app.clone()),
+                (None, Some(port)) if spec.protocol == RuleProtocol::Any => Some(port.clone()),
+                (None, Some(port)) => Some(format!("{}/{}", port, spec.protocol.keyword())),
+                (None, None) => None,
+            };
+            let side = match (address, target) {
+                (Some(address), Some(target)) => format!("{} {}", address, target),
+                (Some(address), None) => address.to_string(),
+                (None, Some(target)) => target,
+                (None, None) => "Anywhere".to_string(),
+            };
+            format!("{}{}", side, v6)
+        };
+        let mut to = side(&spec.to, &spec.to_port, &spec.to_app);
+        if let Some(interface) = &spec.interface {
+            to = match to.strip_suffix(" (v6)") {
+                Some(target) => format!("{} on {} (v6)", target, interface),
+                None => format!("{} on {}", to, interface),
+            };
+        }
+        let mut action = format!("{} {}", spec.action.keyword().to_uppercase(), spec.direction.keyword().to_uppercase());
+        if let Some(log) = &spec.log {
+            action.push_str(&format!(" ({})", log));
+        }
+        (to, action, side(&spec.from, &spec.from_port, &spec.from_app))
+    }
+}
+
+fn app_name(field: &str) -> Option<String> {
+    (field != "-").then(|| field.replace("%20", " "))
+}
+
+fn decode_hex(hex: &str) -> Option<String> {
+    let bytes = (0..hex.len() / 2)
+        .map(|i| u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    Some(String::from_utf8_lossy(&bytes).to_string()).filter(|comment| !comment.is_empty())
+}
+
+// Tuples of user.rules followed by user6.rules, numbered as `ufw status numbered` does
+pub fn parse_rules(content: &str) -> Vec<UfwEntry> {
+    content.lines().filter_map(UfwEntry::parse).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UfwSettings {
+    pub enabled: bool,
+    pub ipv6: bool,
+    pub log_level: String,
+    // "allow", "deny" or "reject" per PolicyChain
+    pub default_incoming: String,
+    pub default_outgoing: String,
+    pub default_routed: String,
+}
+
+impl UfwSettings {
+    pub fn load() -> Self {
+        let read = |path: &str| fs::read_to_string(path).unwrap_or_default();
+        Self::parse(&read(firewall::UFW_CONF), &read(firewall::UFW_DEFAULTS))
+    }
+
+    // ufw.conf and /etc/default/ufw, both shell-style assignments like hardn.conf
+    pub fn parse(ufw_conf: &str, defaults: &str) -> Self {
+        let (ufw_conf, defaults) = (config::HardnConfig::parse(ufw_conf), config::HardnConfig::parse(defaults));
+        // iptables targets as ufw names them
+        let policy = |key: &str, default: &str| match defaults.get(key) {
+            Some("ACCEPT") => "allow".to_string(),
+            Some("REJECT") => "reject".to_string(),
+            Some("DROP") => "deny".to_string(),
+            _ => default.to_string(),
+        };
+        UfwSettings {
+            enabled: ufw_conf.get("ENABLED") == Some("yes"),
+            ipv6: defaults.get("IPV6") != Some("no"),
+            log_level: ufw_conf.get("LOGLEVEL").unwrap_or("low").to_string(),
+            default_incoming: policy("DEFAULT_INPUT_POLICY", "deny"),
+            default_outgoing: policy("DEFAULT_OUTPUT_POLICY", "allow"),
+            default_routed: policy("DEFAULT_FORWARD_POLICY", "deny"),
+        }
+    }
+
+    pub fn policy(&self, chain: PolicyChain) -> &str {
+        match chain {
+            PolicyChain::Incoming => &self.default_incoming,
+            PolicyChain::Outgoing => &self.default_outgoing,
+            PolicyChain::Routed => &self.default_routed,
+        }
+    }
+}
+
+// Edits to the rule list, kept as the ufw commands that make them. Each edit is
+// applied to `entries` right away, so the numbers in later commands refer to the
+// rule list as it will be after the earlier commands ran.
+#[derive(Debug, Clone)]
+pub struct RuleEditor {
+    pub entries: Vec<UfwEntry>,
+    pub commands: Vec<UfwCommand>,
+    // What the pending default policy and logging changes are compared against
+    pub settings: UfwSettings,
+}
+
+impl RuleEditor {
+    pub fn new(entries: Vec<UfwEntry>, settings: UfwSettings) -> Self {
+        RuleEditor { entries, commands: Vec::new(), settings }
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        !self.commands.is_empty()
+    }
+
+    fn v4_count(&self) -> usize {
+        self.entries.iter().filter(|entry| !entry.v6).count()
+    }
+
+    fn insert_entry(&mut self, index: usize, rule: RuleSpec, v6: bool) {
+        self.entries.insert(index, UfwEntry { spec: Some(rule), tuple: String::new(), v6, pending: true });
+    }
+
+    // Without `position` ufw appends the rule to each address family it applies to.
+    // With one, the rule goes before that rule number, which has to be of the same
+    // family; a rule for both families is inserted as IPv4 and its IPv6 half appended.
+    pub fn add(&mut self, rule: RuleSpec, position: Option<u32>) -> Result<(), String> {
+        rule.validate()?;
+        let Some(position) = position else {
+            let families = match rule.is_v6() {
+                Some(v6) => vec![v6],
+                None if self.settings.ipv6 => vec![false, true],
+                None => vec![false],
+            };
+            for v6 in families {
+                let index = if v6 { self.entries.len() } else { self.v4_count() };
+                self.insert_entry(index, rule.clone(), v6);
+            }
+            self.commands.push(UfwCommand::Add { rule: Box::new(rule), position: None });
+            return Ok(());
+        };
+
+        let v6 = rule.is_v6().unwrap_or(false);
+        let index = (position as usize).checked_sub(1).ok_or("rule positions start at 1")?;
+        if self.entries.get(index).map(|entry| entry.v6) != Some(v6) {
+            return Err(format!(
+                "there is no {} rule {} to insert before; leave the position empty to append",
+                if v6 { "IPv6" } else { "IPv4" },
+                position
+            ));
+        }
+        let both = rule.is_v6().is_none();
+        let inserted = for_family(&rule, v6);
+        self.insert_entry(index, inserted.clone(), v6);
+        self.commands.push(UfwCommand::Add { rule: Box::new(inserted), position: Some(position) });
+        if both && self.settings.ipv6 {
+            let appended = for_family(&rule, true);
+            let index = self.entries.len();
+            self.insert_entry(index, appended.clone(), true);
+            self.commands.push(UfwCommand::Add { rule: Box::new(appended), position: None });
+        }
+        Ok(())
+    }
+
+    pub fn delete(&mut self, number: usize) -> Result<(), String> {
+        if number == 0 || number > self.entries.len() {
+            return Err(format!("there is no rule {}", number));
+        }
+        self.entries.remove(number - 1);
+        self.commands.push(UfwCommand::Delete { number: number as u32 });
+        Ok(())
+    }
+
+    // Swap rule `number` with the one above it: delete it, then insert it again
+    // before its predecessor. Both have to be of the same address family.
+    pub fn move_up(&mut self, number: usize) -> Result<(), String> {
+        if number < 2 || number > self.entries.len() {
+            return Err(format!("rule {} cannot move up", number));
+        }
+        let entry = &self.entries[number - 1];
+        if self.entries[number - 2].v6 != entry.v6 {
+            return Err(format!("rule {} is the first {} rule", number, if entry.v6 { "IPv6" } else { "IPv4" }));
+        }
+        let Some(spec) = entry.spec.clone() else {
+            return Err(format!("rule {} cannot be recreated by the editor: {}", number, entry.tuple));
+        };
+        let rule = for_family(&spec, entry.v6);
+        rule.validate().map_err(|e| format!("rule {} cannot be recreated: {}", number, e))?;
+        let v6 = entry.v6;
+        self.entries.remove(number - 1);
+        self.insert_entry(number - 2, rule.clone(), v6);
+        self.commands.push(UfwCommand::Delete { number: number as u32 });
+        self.commands.push(UfwCommand::Add { rule: Box::new(rule), position: Some(number as u32 - 1) });
+        Ok(())
+    }
+
+    pub fn move_down(&mut self, number: usize) -> Result<(), String> {
+        self.move_up(number + 1)
+    }
+
+    // Replaces an earlier pending change of the same chain; choosing the current
+    // policy again just drops it
+    pub fn set_default(&mut self, chain: PolicyChain, policy: &str) {
+        self.commands.retain(|command| !matches!(command, UfwCommand::Default { chain: pending, .. } if *pending == chain));
+        if policy != self.settings.policy(chain) {
+            self.commands.push(UfwCommand::Default { chain, policy: policy.to_string() });
+        }
+    }
+
+    pub fn set_logging(&mut self, level: &str) {
+        self.commands.retain(|command| !matches!(command, UfwCommand::Logging { .. }));
+        if level != self.settings.log_level {
+            self.commands.push(UfwCommand::Logging { level: level.to_string() });
+        }
+    }
+
+    pub fn reset(&mut self, entries: Vec<UfwEntry>, settings: UfwSettings) {
+        self.entries = entries;
+        self.commands.clear();
+        self.settings = settings;
+    }
+}
+
+// The rule with "any" addresses spelled out for one family, so that ufw adds it once
+fn for_family(rule: &RuleSpec, v6: bool) -> RuleSpec {
+    if rule.is_v6().is_some() {
+        return rule.clone();
+    }
+    let anywhere = if v6 { "::/0" } else { "0.0.0.0/0" };
+    RuleSpec { from: anywhere.to_string(), to: anywhere.to_string(), ..rule.clone() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firewall::RuleSpec;
+
+    // Captured from a host with the rules below, from `ufw status numbered`/`verbose`
+    // and the files in /etc/ufw and /etc/default
+    fn fixture(name: &str) -> String {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/ufw").join(name);
+        fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+    }
+
+    fn profiles() -> BTreeMap<String, Vec<PortSpec>> {
+        load_profiles(&Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/ufw/applications.d"))
+    }
+
+    fn entries() -> Vec<UfwEntry> {
+        parse_rules(&(fixture("user.rules") + &fixture("user6.rules")))
+    }
+
+    #[test]
+    fn rules_files_are_numbered_like_status_numbered() {
+        let numbered: Vec<(String, String, String)> = fixture("status-numbered.txt")
+            .lines()
+            .filter_map(|line| {
+                let (_, rest) = line.strip_prefix('[')?.split_once(']')?;
+                let columns: Vec<&str> = rest.split("  ").map(str::trim).filter(|column| !column.is_empty()).collect();
+                Some((columns[0].to_string(), columns[1].to_string(), columns[2].to_string()))
+            })
+            .collect();
+        let entries = entries();
+        assert_eq!(entries.len(), 10);
+        assert_eq!(entries.iter().filter(|entry| entry.v6).count(), 3);
+        for (entry, expected) in entries.iter().zip(&numbered) {
+            let (to, action, from) = entry.columns();
+            // ufw prints the log option after the source column
+            let action = action.trim_end_matches(" (log)").to_string();
+            assert_eq!(&(to, action, from), expected, "{}", entry.tuple);
+        }
+    }
+
+    #[test]
+    fn tuples_become_rule_specs() {
+        let entries = entries();
+        let spec = |number: usize| entries[number - 1].spec.clone().unwrap();
+
+        let limit = spec(1);
+        assert_eq!((limit.action, limit.protocol), (Action::Limit, RuleProtocol::Tcp));
+        assert_eq!(limit.to_port.as_deref(), Some("22"));
+        assert_eq!(limit.comment.as_deref(), Some("ssh"));
+
+        let app = spec(3);
+        assert_eq!(app.to_app.as_deref(), Some("OpenSSH"));
+        assert_eq!((app.protocol, app.to_port), (RuleProtocol::Any, None));
+
+        let logged = spec(5);
+        assert_eq!(logged.log.as_deref(), Some("log"));
+        assert_eq!(logged.interface.as_deref(), Some("eth0"));
+        assert_eq!(logged.from, "10.0.0.5");
+
+        assert_eq!(spec(7).direction, Direction::Out);
+        assert_eq!(spec(9).from, "2001:db8::/32");
+        assert!(entries[8].v6 && !entries[6].v6);
+
+        // Rules the editor cannot express keep their tuple only
+        let other = UfwEntry::parse("### tuple ### allow gre any 0.0.0.0/0 any 0.0.0.0/0 in").unwrap();
+        assert!(other.spec.is_none());
+        assert_eq!(other.columns().0, "allow gre any 0.0.0.0/0 any 0.0.0.0/0 in");
+        assert!(UfwEntry::parse("-A ufw-user-input -p tcp --dport 22 -j ACCEPT").is_none());
+        assert!(UfwEntry::parse("### tuple ### allow tcp 22 0.0.0.0/0").is_none());
+    }
+
+    #[test]
+    fn status_verbose_gives_rules_and_verdicts() {
+        let status = UfwStatus::parse(&fixture("status-verbose.txt"), &profiles());
+        assert!(status.active);
+        assert_eq!(status.default_incoming.as_deref(), Some("deny"));
+        assert_eq!(status.rules.len(), 10);
+
+        let ssh = &status.rules[0];
+        assert_eq!((ssh.to.as_str(), ssh.action.as_str(), ssh.from.as_str()), ("22/tcp", "LIMIT", "Anywhere"));
+        assert_eq!(ssh.ports, Some(vec![PortSpec { ranges: vec![(22, 22)], protocol: Some(Protocol::Tcp) }]));
+        assert_eq!(status.rules[2].application.as_deref(), Some("OpenSSH"));
+        assert_eq!(status.rules[2].ports, Some(vec![PortSpec { ranges: vec![(22, 22)], protocol: Some(Protocol::Tcp) }]));
+        assert_eq!(status.rules[5].ports, None);
+        assert!(!status.rules[6].incoming);
+        assert!(status.rules[9].v6);
+
+        assert_eq!(status.verdict(22, Protocol::Tcp, false, false), Verdict::AllowedByRule("22/tcp LIMIT IN from Anywhere".to_string()));
+        assert_eq!(status.verdict(6003, Protocol::Udp, false, false), Verdict::BlockedByRule("6000:6007/udp DENY IN from Anywhere".to_string()));
+        assert_eq!(status.verdict(6003, Protocol::Udp, true, false), Verdict::DefaultPolicy("deny".to_string()));
+        // The IPv4 deny for a whole source network matches every port
+        assert!(!status.verdict(3306, Protocol::Tcp, false, false).is_open());
+        assert_eq!(status.verdict(3306, Protocol::Tcp, true, false), Verdict::DefaultPolicy("deny".to_string()));
+        assert!(status.verdict(443, Protocol::Tcp, true, false).is_open());
+        assert_eq!(UfwStatus::parse("Status: inactive\n", &profiles()).verdict(22, Protocol::Tcp, false, false), Verdict::Inactive);
+    }
+
+    #[test]
+    fn default_policies_come_from_the_config_files() {
+        let settings = UfwSettings::parse(&fixture("ufw.conf"), &fixture("default-ufw"));
+        assert_eq!(
+            settings,
+            UfwSettings {
+                enabled: true,
+                ipv6: true,
+                log_level: "low".to_string(),
+                default_incoming: "deny".to_string(),
+                default_outgoing: "allow".to_string(),
+                default_routed: "deny".to_string(),
+            }
+        );
+        assert_eq!(settings.policy(PolicyChain::Outgoing), "allow");
+
+        let reject = UfwSettings::parse("ENABLED=no\n", "IPV6=no\nDEFAULT_INPUT_POLICY=\"REJECT\"\n");
+        assert!(!reject.enabled && !reject.ipv6);
+        assert_eq!(reject.policy(PolicyChain::Incoming), "reject");
+    }
+
+    fn add(edit: impl FnOnce(&mut RuleSpec)) -> Result<(), String> {
+        let mut rule = RuleSpec::new(Action::Allow, Direction::In);
+        rule.protocol = RuleProtocol::Tcp;
+        rule.to_port = Some("22".to_string());
+        edit(&mut rule);
+        UfwCommand::Add { rule: Box::new(rule), position: None }.validate()
+    }
+
+    #[test]
+    fn commands_with_bad_ports_are_rejected() {
+        assert!(add(|_| {}).is_ok());
+        for port in ["6000:6007", "80,443", "1:2,3:4,5:6,7:8,9:10,11:12,13"] {
+            assert!(add(|rule| rule.to_port = Some(port.to_string())).is_ok(), "{}", port);
+        }
+        for port in ["", "0", "65536", "8000:7000", "22-80", "80,", "http", "1:2,3:4,5:6,7:8,9:10,11:12,13:14,15,16"] {
+            assert!(add(|rule| rule.to_port = Some(port.to_string())).is_err(), "{}", port);
+        }
+        // Lists and ranges need a protocol for iptables multiport
+        assert!(add(|rule| {
+            rule.protocol = RuleProtocol::Any;
+            rule.to_port = Some("80,443".to_string());
+        })
+        .is_err());
+        assert!(add(|rule| rule.to_app = Some("OpenSSH".to_string())).is_err());
+    }
+
+    #[test]
+    fn commands_with_bad_addresses_are_rejected() {
+        for address in ["any", "10.0.0.0/8", "192.168.1.7", "2001:db8::/32", "::/0"] {
+            assert!(add(|rule| rule.from = address.to_string()).is_ok(), "{}", address);
+        }
+        for address in ["10.0.0.0/33", "10.0.0.256", "10.0.0.0/x", "2001:db8::/129", "example.org", "10.0.0.0/8/8", ""] {
+            assert!(add(|rule| rule.from = address.to_string()).is_err(), "{}", address);
+        }
+        assert!(add(|rule| {
+            rule.from = "10.0.0.1".to_string();
+            rule.to = "::1".to_string();
+        })
+        .is_err());
+        assert!(add(|rule| rule.interface = Some("eth0; reboot".to_string())).is_err());
+    }
+
+    #[test]
+    fn commands_with_unknown_protocols_or_values_are_rejected() {
+        let valid = serde_json::to_string(&UfwCommand::Add { rule: Box::new(RuleSpec::new(Action::Deny, Direction::In)), position: Some(1) }).unwrap();
+        assert!(serde_json::from_str::<UfwCommand>(&valid).is_ok());
+        for protocol in ["icmp", "gre", "TCP"] {
+            let json = valid.replace("\"protocol\":\"any\"", &format!("\"protocol\":\"{}\"", protocol));
+            assert_ne!(json, valid);
+            assert!(serde_json::from_str::<UfwCommand>(&json).is_err(), "{}", protocol);
+        }
+        assert!(UfwCommand::Add { rule: Box::new(RuleSpec::new(Action::Deny, Direction::In)), position: Some(0) }.validate().is_err());
+        assert!(UfwCommand::Delete { number: 0 }.validate().is_err());
+        assert!(UfwCommand::Default { chain: PolicyChain::Incoming, policy: "drop".to_string() }.validate().is_err());
+        assert!(UfwCommand::Logging { level: "verbose".to_string() }.validate().is_err());
+        assert!(UfwCommand::Logging { level: "medium".to_string() }.validate().is_ok());
+    }
+
+    fn editor() -> RuleEditor {
+        RuleEditor::new(entries(), UfwSettings::parse(&fixture("ufw.conf"), &fixture("default-ufw")))
+    }
+
+    fn https(from: &str) -> RuleSpec {
+        let mut rule = RuleSpec::new(Action::Allow, Direction::In);
+        rule.protocol = RuleProtocol::Tcp;
+        rule.from = from.to_string();
+        rule.to_port = Some("443".to_string());
+        rule
+    }
+
+    fn add_command(rule: RuleSpec, position: Option<u32>) -> UfwCommand {
+        UfwCommand::Add { rule: Box::new(rule), position }
+    }
+
+    // The rule list ufw ends up with after running the commands in order
+    fn replay(entries: Vec<UfwEntry>, commands: &[UfwCommand], ipv6: bool) -> Vec<(Option<RuleSpec>, bool)> {
+        let mut rules: Vec<(Option<RuleSpec>, bool)> = entries.into_iter().map(|entry| (entry.spec, entry.v6)).collect();
+        for command in commands {
+            match command {
+                UfwCommand::Delete { number } => {
+                    rules.remove(*number as usize - 1);
+                }
+                UfwCommand::Add { rule, position: Some(position) } => {
+                    rules.insert(*position as usize - 1, (Some((**rule).clone()), rule.is_v6() == Some(true)));
+                }
+                UfwCommand::Add { rule, position: None } => {
+                    let families = rule.is_v6().map(|v6| vec![v6]).unwrap_or(if ipv6 { vec![false, true] } else { vec![false] });
+                    for v6 in families {
+                        let index = if v6 { rules.len() } else { rules.iter().filter(|(_, v6)| !v6).count() };
+                        rules.insert(index, (Some((**rule).clone()), v6));
+                    }
+                }
+                _ => {}
+            }
+        }
+        rules
+    }
+
+    fn assert_replays(editor: &RuleEditor) {
+        let expected: Vec<(Option<RuleSpec>, bool)> = editor.entries.iter().map(|entry| (entry.spec.clone(), entry.v6)).collect();
+        assert_eq!(replay(entries(), &editor.commands, editor.settings.ipv6), expected);
+    }
+
+    #[test]
+    fn appended_rules_go_to_the_end_of_their_families() {
+        let mut editor = editor();
+        editor.add(https("any"), None).unwrap();
+        assert_eq!(editor.entries.len(), 12);
+        assert_eq!((editor.entries[7].spec.as_ref(), editor.entries[7].v6), (Some(&https("any")), false));
+        assert_eq!((editor.entries[11].spec.as_ref(), editor.entries[11].v6), (Some(&https("any")), true));
+        assert!(editor.entries[7].pending && !editor.entries[8].pending);
+
+        editor.add(https("2001:db8::/32"), None).unwrap();
+        assert_eq!(editor.entries.len(), 13);
+        assert!(editor.entries[12].v6);
+        assert_eq!(editor.commands, [add_command(https("any"), None), add_command(https("2001:db8::/32"), None)]);
+        assert_replays(&editor);
+
+        let mut v4_only = editor.clone();
+        v4_only.reset(entries(), UfwSettings { ipv6: false, ..editor.settings.clone() });
+        v4_only.add(https("any"), None).unwrap();
+        assert_eq!(v4_only.entries.len(), 11);
+        assert!(!v4_only.entries[7].v6);
+        assert_replays(&v4_only);
+
+        let mut bad = https("any");
+        bad.to_port = Some("0".to_string());
+        assert!(editor.add(bad, None).is_err());
+        assert_eq!(editor.commands.len(), 2);
+    }
+
+    #[test]
+    fn inserted_rules_need_a_rule_of_their_family_at_the_position() {
+        let mut editor = editor();
+        // A rule for both families goes in as IPv4, its IPv6 half is appended
+        editor.add(https("any"), Some(2)).unwrap();
+        assert_eq!(editor.entries[1].spec.as_ref().map(|spec| spec.from.as_str()), Some("0.0.0.0/0"));
+        assert_eq!(editor.entries[11].spec.as_ref().map(|spec| spec.from.as_str()), Some("::/0"));
+        assert_eq!(
+            editor.commands,
+            [add_command(for_family(&https("any"), false), Some(2)), add_command(for_family(&https("any"), true), None)]
+        );
+
+        // Rule 9 is now the first IPv6 rule
+        editor.add(https("2001:db8::/32"), Some(9)).unwrap();
+        assert!(editor.entries[8].v6 && editor.entries[8].pending);
+        assert_replays(&editor);
+
+        assert!(editor.add(https("10.0.0.0/8"), Some(9)).is_err());
+        assert!(editor.add(https("2001:db8::/32"), Some(8)).is_err());
+        assert!(editor.add(https("any"), Some(0)).is_err());
+        assert!(editor.add(https("any"), Some(editor.entries.len() as u32 + 1)).is_err());
+        assert_eq!(editor.commands.len(), 3);
+    }
+
+    #[test]
+    fn deletes_renumber_the_rules_below() {
+        let mut editor = editor();
+        editor.delete(1).unwrap();
+        assert_eq!(editor.entries[0].spec.as_ref().and_then(|spec| spec.to_port.as_deref()), Some("80,443"));
+        // The last rule was 10 and is now 9
+        assert!(editor.delete(10).is_err());
+        editor.delete(9).unwrap();
+        editor.delete(1).unwrap();
+        assert_eq!(editor.commands, [UfwCommand::Delete { number: 1 }, UfwCommand::Delete { number: 9 }, UfwCommand::Delete { number: 1 }]);
+        assert_eq!(editor.entries.len(), 7);
+        assert_replays(&editor);
+
+        assert!(editor.delete(0).is_err());
+        assert!(editor.delete(8).is_err());
+        assert_eq!(editor.commands.len(), 3);
+    }
+
+    #[test]
+    fn moves_stay_within_an_address_family() {
+        let mut editor = editor();
+        editor.move_up(2).unwrap();
+        assert_eq!(editor.entries[0].spec.as_ref().map(|spec| spec.from.as_str()), Some("192.168.1.0/24"));
+        assert!(editor.entries[0].pending);
+        assert_eq!(editor.entries[1].spec.as_ref().and_then(|spec| spec.comment.as_deref()), Some("ssh"));
+        assert_eq!(editor.commands[..2], [UfwCommand::Delete { number: 2 }, add_command(entries()[1].spec.clone().unwrap(), Some(1))]);
+
+        // The OpenSSH rule applies to any address; it is recreated for IPv4 only
+        editor.move_down(2).unwrap();
+        assert_eq!(editor.entries[1].spec.as_ref().map(|spec| spec.to.as_str()), Some("0.0.0.0/0"));
+        assert_eq!(editor.entries[2].spec.as_ref().and_then(|spec| spec.comment.as_deref()), Some("ssh"));
+        editor.move_up(10).unwrap();
+        assert_replays(&editor);
+        let commands = editor.commands.len();
+
+        for number in [0, 1, 8, 11] {
+            assert!(editor.move_up(number).is_err(), "{}", number);
+        }
+        assert!(editor.move_down(7).is_err());
+        assert!(editor.move_down(10).is_err());
+        assert_eq!(editor.commands.len(), commands);
+        assert_eq!(editor.entries.len(), 10);
+
+        let mut other = editor.clone();
+        let gre = UfwEntry::parse("### tuple ### allow gre any 0.0.0.0/0 any 0.0.0.0/0 in").unwrap();
+        other.reset(vec![entries()[0].clone(), gre], editor.settings.clone());
+        assert!(other.move_up(2).unwrap_err().contains("cannot be recreated"));
+        assert!(other.move_down(1).is_err());
+        other.delete(2).unwrap();
+        assert_eq!(other.commands, [UfwCommand::Delete { number: 2 }]);
+    }
+
+    #[test]
+    fn pending_settings_replace_each_other() {
+        let mut editor = editor();
+        editor.set_default(PolicyChain::Incoming, "reject");
+        editor.set_default(PolicyChain::Outgoing, "deny");
+        editor.set_default(PolicyChain::Incoming, "allow");
+        editor.set_logging("medium");
+        assert_eq!(
+            editor.commands,
+            [
+                UfwCommand::Default { chain: PolicyChain::Outgoing, policy: "deny".to_string() },
+                UfwCommand::Default { chain: PolicyChain::Incoming, policy: "allow".to_string() },
+                UfwCommand::Logging { level: "medium".to_string() },
+            ]
+        );
+
+        editor.set_default(PolicyChain::Incoming, "deny");
+        editor.set_default(PolicyChain::Outgoing, "allow");
+        editor.set_logging("low");
+        assert!(!editor.is_dirty());
+    }
+}
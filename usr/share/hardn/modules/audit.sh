@@ -87,53 +87,62 @@ run_lynis_audit() {
 
 # Run network security scan
 run_network_scan() {
-    log_info "Running network security scan..."
+    log_info "Checking listening network services..."
     
-    if ! command -v nmap >/dev/null 2>&1; then
-        if install_package "nmap" "Network mapper"; then
-            log_info "nmap installed successfully"
-        else
-            log_error "Failed to install nmap"
-            return 1
-        fi
+    if ! command -v ss >/dev/null 2>&1; then
+        log_error "ss (iproute2) not available, cannot list listening sockets"
+        return 1
     fi
     
     local timestamp
     local scan_log
     
     timestamp=$(date +%Y%m%d_%H%M%S)
-    scan_log="${HARDN_LOG_DIR}/nmap-scan-${timestamp}.log"
+    scan_log="${HARDN_LOG_DIR}/listeners-${timestamp}.log"
     
     # Create log directory
     mkdir -p "${HARDN_LOG_DIR}"
     
     if is_dry_run; then
-        log_info "[DRY-RUN] Would run network scan"
+        log_info "[DRY-RUN] Would list listening sockets"
         return 0
     fi
     
-    log_info "Scanning localhost for open ports..."
+    # Listening TCP and bound UDP sockets with their processes, read from the kernel
+    # instead of probing ourselves with a port scanner
+    if ! ss -H -tulpn > "${scan_log}" 2>&1; then
+        log_error "Listing listening sockets failed"
+        return 1
+    fi
     
-    # Run localhost scan
-    if nmap -sS -sV -O -p- localhost > "${scan_log}" 2>&1; then
-        log_info "Network scan completed"
-        
-        # Extract open ports
-        local open_ports
-        open_ports=$(grep "^[0-9]" "${scan_log}" | grep -vc "closed")
-        log_info "Open ports detected: ${open_ports}"
-        
-        # Show critical open ports
-        if grep -q "21\|23\|135\|139\|445\|1433\|3389" "${scan_log}"; then
-            hardn_status "warning" "Potentially insecure ports detected"
+    local listeners
+    listeners=$(wc -l < "${scan_log}")
+    log_info "Listening sockets: ${listeners}"
+    
+    # Same list as the GUI's Network tab: FTP, Telnet, MS RPC, NetBIOS, SMB, MSSQL, RDP
+    local risky_ports=(21 23 135 139 445 1433 3389)
+    local port
+    local found=0
+    for port in "${risky_ports[@]}"; do
+        # Column 5 is the local address; match the port exactly, not as a substring
+        local matches
+        matches=$(awk -v port="${port}" '{ n = split($5, a, ":"); if (a[n] == port) print }' "${scan_log}")
+        if [[ -n "${matches}" ]]; then
+            found=1
+            while IFS= read -r line; do
+                log_warn "Port ${port} listening: ${line}"
+            done <<< "${matches}"
         fi
-        
-        log_info "Scan results: ${scan_log}"
-        return 0
+    done
+    
+    if [[ ${found} -eq 1 ]]; then
+        hardn_status "warning" "Potentially insecure ports detected"
     else
-        log_error "Network scan failed"
-        return 1
+        hardn_status "pass" "No insecure services listening"
     fi
+    
+    log_info "Listening sockets: ${scan_log}"
+    return 0
 }
 
 # Check system integrity with AIDE
@@ -278,26 +287,32 @@ check_malware() {
     log_info "Running ClamAV scan on critical directories..."
     log_info "This may take a while depending on system size..."
     
-    # Scan critical directories
-    local scan_dirs="/bin /sbin /usr/bin /usr/sbin /etc /home"
+    # Scan critical directories, one argument each
+    local scan_dirs=(/bin /sbin /usr/bin /usr/sbin /etc /home)
+    local status=0
     
-    if clamscan --recursive --infected --log="${malware_log}" "${scan_dirs}" >/dev/null 2>&1; then
-        local infected_files
-        infected_files=$(grep -c "FOUND" "${malware_log}")
-        
-        if [[ ${infected_files} -gt 0 ]]; then
-            hardn_status "warning" "Malware detected: ${infected_files} infected files"
-            log_warn "Review ClamAV log: ${malware_log}"
-        else
-            hardn_status "pass" "No malware detected by ClamAV"
-        fi
-        
+    clamscan --recursive --infected --log="${malware_log}" -- "${scan_dirs[@]}" >/dev/null 2>&1 || status=$?
+    
+    # clamscan exits 0 when clean, 1 when something was found and 2 on errors
+    if [[ ${status} -gt 1 ]]; then
+        log_error "ClamAV scan failed (exit code ${status})"
         log_info "ClamAV scan log: ${malware_log}"
-        return 0
-    else
-        log_error "ClamAV scan failed"
         return 1
     fi
+    
+    local infected_files
+    infected_files=$(grep -c ": .* FOUND$" "${malware_log}" 2>/dev/null || true)
+    
+    if [[ ${infected_files:-0} -gt 0 ]]; then
+        hardn_status "warning" "Malware detected: ${infected_files} infected files"
+        log_warn "Review ClamAV log: ${malware_log}"
+        log_warn "Detected files can be quarantined from the Malware tab of the HARDN GUI"
+    else
+        hardn_status "pass" "No malware detected by ClamAV"
+    fi
+    
+    log_info "ClamAV scan log: ${malware_log}"
+    return 0
 }
 
 # Generate security report
@@ -399,16 +414,26 @@ run_security_audit() {
     
     case "${audit_type}" in
         "full"|"comprehensive")
+            progress_step 1 6 "Lynis audit"
             run_lynis_audit
+            progress_step 2 6 "Network scan"
             run_network_scan
+            progress_step 3 6 "System integrity"
             check_system_integrity
+            progress_step 4 6 "Rootkit detection"
             check_rootkits
+            progress_step 5 6 "Malware scan"
             check_malware
+            progress_step 6 6 "Security report"
             generate_security_report
+            progress_done 6 "Audit completed"
             ;;
         "quick")
+            progress_step 1 2 "Lynis audit"
             run_lynis_audit
+            progress_step 2 2 "Rootkit detection"
             check_rootkits
+            progress_done 2 "Audit completed"
             ;;
         "lynis")
             run_lynis_audit
@@ -316,8 +316,8 @@ install_security_packages() {
         
         ((current_package++))
         
-        if ! is_non_interactive; then
-            show_progress "${current_package}" "${total_packages}" "Installing packages"
+        if ! is_non_interactive || progress_is_structured; then
+            show_progress "${current_package}" "${total_packages}" "Installing packages" "packages"
         fi
         
         log_debug "Processing package ${current_package}/${total_packages}: ${name}"
@@ -728,9 +728,11 @@ run_hardening_interactive() {
     log_info "Starting interactive system hardening..."
     
     # First, install essential packages and updates
+    progress_step 1 6 "Installing security packages"
     install_security_packages
     
     # Show tools selection menu
+    progress_step 2 6 "Installing security tools"
     if show_tools_menu; then
         log_info "Security tools installation completed"
     else
@@ -738,10 +740,15 @@ run_hardening_interactive() {
     fi
     
     # Apply basic system hardening
+    progress_step 3 6 "Applying kernel hardening"
     apply_kernel_hardening
+    progress_step 4 6 "Configuring secure DNS"
     configure_secure_dns
+    progress_step 5 6 "Configuring logging"
     configure_logging
+    progress_step 6 6 "Disabling unnecessary services"
     disable_unnecessary_services
+    progress_done 6 "Hardening completed"
     
     # Show completion message
     if ! is_dry_run; then
@@ -760,12 +767,19 @@ run_hardening_non_interactive() {
     log_info "Starting non-interactive system hardening..."
     
     # Run complete hardening process
+    progress_step 1 6 "Installing security packages"
     install_security_packages
+    progress_step 2 6 "Installing security tools"
     install_all_security_tools
+    progress_step 3 6 "Applying kernel hardening"
     apply_kernel_hardening
+    progress_step 4 6 "Configuring secure DNS"
     configure_secure_dns
+    progress_step 5 6 "Configuring logging"
     configure_logging
+    progress_step 6 6 "Disabling unnecessary services"
     disable_unnecessary_services
+    progress_done 6 "Hardening completed"
     
     hardn_status "pass" "Non-interactive hardening completed successfully"
     log_info "Please reboot your system to complete the configuration"
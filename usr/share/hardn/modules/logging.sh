@@ -186,12 +186,58 @@ execute_and_log() {
     fi
 }
 
+# Machine-readable progress for the GUI, enabled with HARDN_PROGRESS=structured:
+#   @@HARDN-PROGRESS id=<stream> step=<completed> total=<steps> label=<text>
+# "main" is the overall operation; other ids report progress within its current step.
+progress_is_structured() {
+    [[ "${HARDN_PROGRESS:-}" == "structured" ]]
+}
+
+emit_progress() {
+    local id="$1"
+    local current="$2"
+    local total="$3"
+    local label="$4"
+    
+    echo "@@HARDN-PROGRESS id=${id} step=${current} total=${total} label=${label}"
+}
+
+# Announce a top-level step of a long operation (1-based); silent on a terminal
+progress_step() {
+    local current="$1"
+    local total="$2"
+    local label="$3"
+    
+    if progress_is_structured; then
+        emit_progress "main" "$((current - 1))" "${total}" "${label}"
+    else
+        log_debug "Step ${current}/${total}: ${label}"
+    fi
+}
+
+# Mark a stepped operation as finished
+progress_done() {
+    local total="$1"
+    local label="${2:-Done}"
+    
+    if progress_is_structured; then
+        emit_progress "main" "${total}" "${total}" "${label}"
+    fi
+}
+
 # Function to show progress bar (for long operations)
 show_progress() {
     local current="$1"
     local total="$2"
     local description="${3:-Processing}"
+    local id="${4:-main}"
     local width=50
+    
+    if progress_is_structured; then
+        emit_progress "${id}" "${current}" "${total}" "${description}"
+        return 0
+    fi
+    
     local percentage=$((current * 100 / total))
     local filled=$((current * width / total))
     local empty=$((width - filled))
@@ -245,4 +291,5 @@ log_system_info() {
 # Export functions for use in other modules
 export -f init_logging get_timestamp should_log write_log_file do_log
 export -f log_debug log_info log_warn log_error hardn_status
-export -f log_command execute_and_log show_progress log_separator log_system_info
\ No newline at end of file
+export -f log_command execute_and_log show_progress log_separator log_system_info
+export -f progress_is_structured emit_progress progress_step progress_done
\ No newline at end of file
@@ -0,0 +1,40 @@
+#!/bin/bash
+
+source "$(cd "$(dirname "$0")" && pwd)/functions.sh"
+
+# HARDN Tool: aide_update.sh
+# Purpose: Accept the reviewed filesystem changes as the new AIDE baseline
+# Location: /src/tools/aide_update.sh
+
+check_root
+log_tool_execution "aide_update.sh"
+
+AIDE_CONF="/etc/aide/aide.conf"
+AIDE_DB="/var/lib/aide/aide.db"
+AIDE_DB_NEW="/var/lib/aide/aide.db.new"
+
+if ! command -v aide >/dev/null 2>&1; then
+    HARDN_STATUS "error" "AIDE is not installed; run aide.sh first"
+    exit 1
+fi
+
+if [ ! -f "$AIDE_DB" ]; then
+    HARDN_STATUS "error" "AIDE database $AIDE_DB not found; run aide.sh to initialize it"
+    exit 1
+fi
+
+HARDN_STATUS "info" "Updating the AIDE database with the current filesystem state..."
+rm -f "$AIDE_DB_NEW"
+
+# Exit codes 1-7 flag added, removed and changed entries; 14 and above are errors
+aide --config "$AIDE_CONF" --update
+status=$?
+if [ "$status" -ge 8 ] || [ ! -f "$AIDE_DB_NEW" ]; then
+    HARDN_STATUS "error" "AIDE database update failed (exit code $status)"
+    exit 1
+fi
+
+# Keep the previous baseline so an accidental update can be rolled back
+cp -p "$AIDE_DB" "$AIDE_DB.prev"
+mv "$AIDE_DB_NEW" "$AIDE_DB"
+HARDN_STATUS "pass" "AIDE database updated; previous database kept as $AIDE_DB.prev"